@@ -1,4 +1,41 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+
+use crate::{
+    completions::{complete_hook_events, complete_hook_names},
+    config::{ConfigError, ExecutionStrategy},
+    hooks::TagFilter,
+};
+
+/// Exit code when hooks fail, and for errors outside the categories below
+pub const EXIT_HOOK_FAILURE: i32 = 1;
+/// Exit code for invalid command-line usage, the same code clap uses for
+/// flags it rejects
+pub const EXIT_USAGE_ERROR: i32 = 2;
+/// Exit code when not inside a git repository
+pub const EXIT_NO_GIT_REPO: i32 = 3;
+/// Exit code when there is no config file, or no hook with the requested name
+pub const EXIT_NO_HOOKS: i32 = 4;
+/// Exit code when the hooks configuration is invalid (`EX_CONFIG` from
+/// `sysexits.h`, so it can't be mistaken for a usage error)
+pub const EXIT_CONFIG_ERROR: i32 = 78;
+
+/// Exit code for an error returned by a command
+#[must_use]
+pub fn exit_code_for(error: &anyhow::Error) -> i32 {
+    let not_a_repo = error.chain().any(|cause| {
+        cause.downcast_ref::<git2::Error>().is_some_and(|e| {
+            e.code() == git2::ErrorCode::NotFound && e.class() == git2::ErrorClass::Repository
+        })
+    });
+    if not_a_repo {
+        EXIT_NO_GIT_REPO
+    } else if error.downcast_ref::<ConfigError>().is_some() {
+        EXIT_CONFIG_ERROR
+    } else {
+        EXIT_HOOK_FAILURE
+    }
+}
 
 /// Command-line interface for peter hook manager
 #[derive(Parser)]
@@ -10,6 +47,21 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub debug: bool,
 
+    /// Append a structured (JSON lines) log of resolution and execution to
+    /// this file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<std::path::PathBuf>,
+
+    /// When to use colors and emoji [default: auto, or from the `NO_COLOR`
+    /// and `FORCE_COLOR` environment variables]
+    #[arg(long, global = true, value_name = "WHEN", value_parser = clap::builder::PossibleValuesParser::new(["auto", "always", "never"]))]
+    pub color: Option<String>,
+
+    /// Disable colors and emoji, even on a terminal (same as `--color
+    /// never`)
+    #[arg(long, global = true, conflicts_with = "color")]
+    pub no_color: bool,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Commands,
@@ -17,6 +69,7 @@ pub struct Cli {
 
 /// Available subcommands
 #[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once per process
 pub enum Commands {
     /// Install hooks for the current repository
     Install {
@@ -26,28 +79,156 @@ pub enum Commands {
         /// Worktree hook installation strategy
         #[arg(long, default_value = "shared", value_parser = clap::builder::PossibleValuesParser::new(["shared", "per-worktree", "detect"]))]
         worktree_strategy: String,
+        /// Install hooks in every worktree of the repository
+        #[arg(long)]
+        all_worktrees: bool,
+        /// Run `peter-hook` from PATH in the installed hooks instead of this
+        /// binary's absolute path
+        #[arg(long)]
+        use_path: bool,
     },
     /// Uninstall git-hook-manager managed hooks
     Uninstall {
         /// Remove hooks without prompting for confirmation
         #[arg(long)]
         yes: bool,
+        /// Restore backed-up hooks by copying, keeping the `.backup` files
+        #[arg(long)]
+        keep_backups: bool,
+    },
+    /// Manage backups of hooks replaced by `install --force`
+    Backups {
+        /// Backup management subcommand
+        #[command(subcommand)]
+        subcommand: BackupsCommand,
     },
     /// Run hooks for a specific git event
     Run {
         /// The git hook event (pre-commit, pre-push, etc.)
+        #[arg(add = ArgValueCompleter::new(complete_hook_events))]
         event: String,
         /// Run on all files instead of only changed files
         #[arg(long)]
         all_files: bool,
+        /// Use the paths in FILE (one per line, `-` for stdin) as the changed
+        /// files instead of asking git
+        #[arg(long, value_name = "FILE", conflicts_with = "all_files")]
+        files_from: Option<std::path::PathBuf>,
+        /// Read the changed files from stdin (same as `--files-from -`)
+        #[arg(long, conflicts_with_all = ["all_files", "files_from"])]
+        from_stdin: bool,
+        /// Use the files changed since the last successful run of this event
+        /// (the event's usual changed files if there was none)
+        #[arg(long, conflicts_with_all = ["all_files", "files_from", "from_stdin"])]
+        since_last_run: bool,
+        /// Write the detected changed files to PATH (`-` for stdout), one per
+        /// line, before running hooks
+        #[arg(long, value_name = "PATH", conflicts_with = "all_files")]
+        changed_files_output: Option<std::path::PathBuf>,
+        /// Exit after writing --changed-files-output, without running hooks
+        #[arg(long, requires = "changed_files_output")]
+        changed_files_only: bool,
+        /// Only run the hooks of configs (subprojects) whose hooks match at
+        /// least one changed file, skipping the others entirely
+        #[arg(long, conflicts_with = "all_files")]
+        affected_only: bool,
+        /// Resolve hooks from the repository root instead of the current
+        /// directory, so a subdirectory runs the whole repository's hooks
+        #[arg(long)]
+        root: bool,
+        /// Only read the configs that govern changed files, skipping every
+        /// other config in the repository (for large monorepos)
+        #[arg(long, conflicts_with = "all_files")]
+        only_modified_configs: bool,
+        /// Run an event that isn't a git hook name, i.e. the hooks or group
+        /// named after it
+        #[arg(long)]
+        allow_custom_event: bool,
+        /// Run without checking that the configs are trusted, e.g. in CI
+        /// (see `security.require_trust`)
+        #[arg(long)]
+        no_trust_check: bool,
         /// Show what would run without executing hooks
         #[arg(long)]
         dry_run: bool,
+        /// Show the files each hook would receive, without executing hooks
+        #[arg(long, conflicts_with = "dry_run")]
+        list_files: bool,
+        /// Output the file listing as JSON (use with --list-files)
+        #[arg(long, requires = "list_files")]
+        json: bool,
+        /// Print the environment variables each hook would be given, without
+        /// executing hooks
+        #[arg(long, conflicts_with_all = ["dry_run", "list_files"])]
+        dump_env: bool,
+        /// Show the values of sensitive variables (names containing TOKEN,
+        /// SECRET or PASSWORD) in --dump-env output instead of redacting them
+        #[arg(long, requires = "dump_env")]
+        no_redact: bool,
+        /// Resume a run at this hook, skipping the hooks before it
+        #[arg(long, value_name = "HOOK")]
+        continue_from: Option<String>,
+        /// Set an environment variable for every hook (repeatable); a hook's
+        /// own `env` wins on conflict
+        #[arg(long = "env", value_name = "KEY=VAL", value_parser = parse_env_var)]
+        env: Vec<(String, String)>,
+        /// Hook selection by tag
+        #[command(flatten)]
+        tags: TagArgs,
+        /// Execution strategy override
+        #[command(flatten)]
+        strategy: StrategyArgs,
+        /// Repeated execution for flakiness detection
+        #[command(flatten)]
+        repeat: RepeatArgs,
+        /// Hook output reporting
+        #[command(flatten)]
+        output: OutputArgs,
+        /// Stop starting hooks once N of them have failed; hooks already
+        /// running in parallel finish
+        #[arg(long, value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        bail_after: Option<usize>,
+        /// Write each hook's stdout and stderr to `<DIR>/<hook>.stdout.log`
+        /// and `<DIR>/<hook>.stderr.log`
+        #[arg(long, value_name = "DIR")]
+        output_dir: Option<std::path::PathBuf>,
+        /// Fail any hook whose stdout or stderr has a line matching REGEX,
+        /// even if it exits 0
+        #[arg(long, value_name = "REGEX")]
+        fail_on: Option<String>,
+        /// Ignore output lines matching REGEX when applying `--fail-on` and
+        /// hooks' `fail_on` patterns
+        #[arg(long, value_name = "REGEX")]
+        allow: Option<String>,
         /// Additional arguments passed from git (e.g., commit message file,
         /// refs)
         #[arg(trailing_var_arg = true)]
         git_args: Vec<String>,
     },
+    /// Explain why hooks for an event will or will not run
+    WhySkipped {
+        /// Event to explain, or a hook name when --event is given
+        #[arg(add = ArgValueCompleter::new(complete_hook_events))]
+        name: String,
+        /// Event to resolve; NAME is then the hook to explain
+        #[arg(long)]
+        event: Option<String>,
+        /// Explain as if run with --all-files
+        #[arg(long)]
+        all_files: bool,
+        /// Output the explanation as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show the phases a group or event runs in and its dependencies
+    Graph {
+        /// Group, event or hook name
+        #[arg(add = ArgValueCompleter::new(complete_hook_names))]
+        name: String,
+        /// Output format: an indented tree, or Graphviz DOT
+        #[arg(long, default_value = "tree", value_parser = clap::builder::PossibleValuesParser::new(["tree", "dot"]))]
+        format: String,
+    },
     /// Validate hook configuration
     Validate {
         /// Trace imports and show merge/override diagnostics
@@ -56,24 +237,84 @@ pub enum Commands {
         /// Output diagnostics as JSON (use with --trace-imports)
         #[arg(long)]
         json: bool,
+        /// Print the effective configuration (imports merged, overrides
+        /// applied) as TOML
+        #[arg(long, conflicts_with_all = ["trace_imports", "json"])]
+        explain: bool,
+        /// Report, for each hook, whether PATH matches its `files` patterns
+        /// (repeatable)
+        #[arg(
+            long = "match",
+            value_name = "PATH",
+            conflicts_with_all = ["explain", "json"]
+        )]
+        match_paths: Vec<std::path::PathBuf>,
     },
     /// List installed git hooks
     List,
     /// List worktrees and their hook configuration
     ListWorktrees,
+    /// List the git events the configuration defines hooks for
+    ListEvents {
+        /// Output the events as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the path of the hooks config file that applies to a directory
+    ConfigPath {
+        /// Directory to resolve from [default: current directory]
+        dir: Option<std::path::PathBuf>,
+    },
     /// Manage global configuration
     Config {
         /// Configuration management subcommand
         #[command(subcommand)]
         subcommand: ConfigCommand,
     },
+    /// Trust the hook configs of the current repository as they are now
+    /// (see `security.require_trust`)
+    Trust,
+    /// Forget the trusted hook configs of the current repository
+    Untrust,
     /// Run hooks in lint mode (current directory as root, all matching files)
     Lint {
         /// Name of the hook or group to run
-        hook_name: String,
+        #[arg(add = ArgValueCompleter::new(complete_hook_names), required_unless_present = "list")]
+        hook_name: Option<String>,
         /// Show what would run without executing hooks
         #[arg(long)]
         dry_run: bool,
+        /// Hook selection by tag
+        #[command(flatten)]
+        tags: TagArgs,
+        /// Repeated execution for flakiness detection
+        #[command(flatten)]
+        repeat: RepeatArgs,
+        /// Hook output reporting
+        #[command(flatten)]
+        output: OutputArgs,
+        /// Stop starting hooks once N of them have failed; hooks already
+        /// running in parallel finish
+        #[arg(long, value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+        bail_after: Option<usize>,
+        /// Keep running: re-run the hooks matching each batch of changed
+        /// files until interrupted
+        #[arg(long, conflicts_with_all = ["dry_run", "repeat"])]
+        watch: bool,
+        /// Run each hook's `fix_command` instead of its check command
+        #[arg(long, conflicts_with_all = ["watch", "repeat"])]
+        fix: bool,
+        /// Run without checking that the config is trusted, e.g. in CI
+        /// (see `security.require_trust`)
+        #[arg(long)]
+        no_trust_check: bool,
+        /// List the hooks and groups in the nearest config instead of running
+        /// one
+        #[arg(long, conflicts_with_all = ["hook_name", "dry_run", "repeat", "bail_after", "watch", "fix"])]
+        list: bool,
+        /// Output the list as JSON
+        #[arg(long, requires = "list", conflicts_with = "hook_name")]
+        json: bool,
     },
     /// Show version information
     Version,
@@ -85,7 +326,13 @@ pub enum Commands {
         shell: clap_complete::Shell,
     },
     /// Check health and configuration
-    Doctor,
+    Doctor {
+        /// Output check results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run a trivial hook end-to-end in a throwaway repository
+    Selftest,
     /// Update to latest version
     Update {
         /// Specific version to install
@@ -96,6 +343,18 @@ pub enum Commands {
         /// Custom installation directory
         #[arg(long)]
         install_dir: Option<std::path::PathBuf>,
+        /// Skip checksum verification of the downloaded release (for mirrors)
+        #[arg(long)]
+        skip_verify: bool,
+        /// Release channel to follow
+        #[arg(long, default_value = "stable", value_parser = clap::builder::PossibleValuesParser::new(["stable", "prerelease"]))]
+        channel: String,
+        /// Only report whether an update is available (exit 10 if so)
+        #[arg(long, conflicts_with_all = ["version", "force", "install_dir", "skip_verify"])]
+        check: bool,
+        /// Restore the binary replaced by the last update
+        #[arg(long, conflicts_with_all = ["version", "force", "skip_verify", "channel", "check"])]
+        rollback: bool,
     },
 }
 
@@ -103,7 +362,12 @@ pub enum Commands {
 #[derive(Subcommand)]
 pub enum ConfigCommand {
     /// Show current global configuration
-    Show,
+    Show {
+        /// Output format; `json` prints the effective settings (defaults
+        /// when there is no configuration file)
+        #[arg(long, default_value = "toml", value_parser = clap::builder::PossibleValuesParser::new(["toml", "json"]))]
+        format: String,
+    },
     /// Initialize default global configuration file
     Init {
         /// Overwrite existing configuration file
@@ -112,7 +376,145 @@ pub enum ConfigCommand {
         /// Enable imports from $HOME/.local/peter-hook
         #[arg(long)]
         allow_local: bool,
+        /// Write every setting, commented out where unset, to show what can
+        /// be configured
+        #[arg(long)]
+        global_defaults: bool,
     },
     /// Validate current configuration and check allowlist
     Validate,
+    /// Print one global setting (empty if unset)
+    Get {
+        /// Setting name, e.g. `security.allow_local`
+        key: String,
+    },
+    /// Change one global setting and save the configuration file
+    Set {
+        /// Setting name, e.g. `defaults.remote`
+        key: String,
+        /// New value; empty unsets optional settings, lists are
+        /// comma-separated
+        value: String,
+    },
+}
+
+/// Backup management subcommands
+#[derive(Subcommand)]
+pub enum BackupsCommand {
+    /// List hook backups with their original hook names and timestamps
+    List,
+}
+
+/// `--tag` / `--require-tag` options shared by `run` and `lint`
+#[derive(Args, Debug, Clone, Default)]
+pub struct TagArgs {
+    /// Only run hooks with this tag (repeatable; any tag matches)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub any: Vec<String>,
+    /// Only run hooks with this tag (repeatable; all tags must match)
+    #[arg(long = "require-tag", value_name = "TAG")]
+    pub all: Vec<String>,
+}
+
+impl TagArgs {
+    /// Convert into the resolver's tag filter
+    #[must_use]
+    pub fn into_filter(self) -> TagFilter {
+        TagFilter {
+            any: self.any,
+            all: self.all,
+        }
+    }
+}
+
+/// `--parallel` / `--sequential` / `--force-parallel` options of `run`
+#[derive(Args, Debug, Clone, Copy, Default)]
+#[group(multiple = false)]
+pub struct StrategyArgs {
+    /// Run hooks in parallel where safe, overriding every group's
+    /// `execution`
+    #[arg(long)]
+    pub parallel: bool,
+    /// Run hooks one at a time, overriding every group's `execution`
+    #[arg(long)]
+    pub sequential: bool,
+    /// Run all hooks in parallel, even those with `modifies_repository =
+    /// true`, overriding every group's `execution`
+    #[arg(long)]
+    pub force_parallel: bool,
+}
+
+impl StrategyArgs {
+    /// The strategy to run every group with, if one was requested
+    #[must_use]
+    pub const fn into_strategy(self) -> Option<ExecutionStrategy> {
+        if self.parallel {
+            Some(ExecutionStrategy::Parallel)
+        } else if self.sequential {
+            Some(ExecutionStrategy::Sequential)
+        } else if self.force_parallel {
+            Some(ExecutionStrategy::ForceParallel)
+        } else {
+            None
+        }
+    }
+}
+
+/// `--repeat` / `--repeat-until-fail` options of `run` and `lint`
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct RepeatArgs {
+    /// Execute the resolved hooks N times and report a pass/fail tally per
+    /// hook
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), conflicts_with = "dry_run")]
+    pub repeat: Option<u32>,
+    /// With --repeat, stop after the first run in which a hook fails
+    #[arg(long, requires = "repeat")]
+    pub repeat_until_fail: bool,
+}
+
+/// Hook output options shared by `run` and `lint`
+#[derive(Args, Debug, Clone, Default)]
+pub struct OutputArgs {
+    /// Print at most LINES lines of each hook's stdout and stderr in
+    /// summaries, overriding the global `[output]` line limits (0 disables
+    /// truncation)
+    #[arg(long, value_name = "LINES")]
+    pub max_output: Option<usize>,
+    /// Extra reporting for hook runs: `github` adds GitHub Actions
+    /// annotations for failed hooks [default: github when `GITHUB_ACTIONS`
+    /// is `true`, otherwise text]
+    #[arg(long, value_name = "FORMAT", value_parser = clap::builder::PossibleValuesParser::new(["text", "github"]))]
+    pub report_format: Option<String>,
+    /// List the files each hook ran for under its line in hook run
+    /// summaries
+    #[arg(long)]
+    pub show_files: bool,
+    /// Print hook output live as it is written, each line tagged with the
+    /// hook's name, instead of in summaries
+    #[arg(long)]
+    pub stream: bool,
+}
+
+/// Parse a `KEY=VAL` pair for `run --env`
+///
+/// # Errors
+///
+/// Returns an error if there is no `=` or the key is not a valid
+/// environment variable name (letters, digits and `_`, not starting with a
+/// digit)
+pub fn parse_env_var(input: &str) -> Result<(String, String), String> {
+    let (key, value) = input
+        .split_once('=')
+        .ok_or_else(|| format!("invalid KEY=VAL: no '=' found in '{input}'"))?;
+
+    let valid_key = key
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid_key {
+        return Err(format!("invalid environment variable name '{key}'"));
+    }
+
+    Ok((key.to_string(), value.to_string()))
 }
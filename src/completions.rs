@@ -1,10 +1,10 @@
 //! Shell completion generation module.
 
 use clap::CommandFactory;
-use clap_complete::Shell;
-use std::io;
+use clap_complete::{CompletionCandidate, Shell};
+use std::{env, ffi::OsStr, io};
 
-use crate::cli::Cli;
+use crate::{cli::Cli, config::HookConfig, git::SUPPORTED_HOOKS, hooks::HookResolver};
 
 /// Generate shell completion scripts.
 ///
@@ -23,15 +23,21 @@ pub fn generate_completions(shell: Shell) {
         Shell::Bash => {
             println!("# For bash (~/.bashrc):");
             println!("#   source <({bin_name} completions bash)");
+            println!("#   # Or, to also complete hook names from hooks.toml:");
+            println!("#   source <(COMPLETE=bash {bin_name})");
         }
         Shell::Zsh => {
             println!("# For zsh (~/.zshrc):");
             println!("#   {bin_name} completions zsh > ~/.zsh/completions/_{bin_name}");
             println!("#   # Ensure fpath includes ~/.zsh/completions");
+            println!("#   # Or, to also complete hook names from hooks.toml:");
+            println!("#   source <(COMPLETE=zsh {bin_name})");
         }
         Shell::Fish => {
             println!("# For fish (~/.config/fish/config.fish):");
             println!("#   {bin_name} completions fish | source");
+            println!("#   # Or, to also complete hook names from hooks.toml:");
+            println!("#   COMPLETE=fish {bin_name} | source");
         }
         _ => {
             println!("# For {shell}:");
@@ -45,6 +51,43 @@ pub fn generate_completions(shell: Shell) {
     clap_complete::generate(shell, &mut cmd, bin_name, &mut io::stdout());
 }
 
+/// Complete git hook event names for `peter-hook run`.
+#[must_use]
+pub fn complete_hook_events(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(prefix) = current.to_str() else {
+        return Vec::new();
+    };
+
+    SUPPORTED_HOOKS
+        .iter()
+        .filter(|event| event.starts_with(prefix))
+        .map(|event| CompletionCandidate::new(*event))
+        .collect()
+}
+
+/// Complete hook and group names from the nearest hooks.toml for
+/// `peter-hook lint` and `peter-hook graph`.
+///
+/// Returns no candidates when there is no configuration or it cannot be
+/// parsed, so completion never surfaces errors to the shell.
+#[must_use]
+pub fn complete_hook_names(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(prefix) = current.to_str() else {
+        return Vec::new();
+    };
+
+    env::current_dir()
+        .ok()
+        .and_then(|dir| HookResolver::new(dir).find_config_file().ok().flatten())
+        .and_then(|path| HookConfig::from_file(&path).ok())
+        .map(|config| config.get_hook_names())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|name| name.starts_with(prefix))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,6 +123,24 @@ mod tests {
         generate_completions(Shell::PowerShell);
     }
 
+    #[test]
+    fn test_complete_hook_events_filters_by_prefix() {
+        let candidates = complete_hook_events(OsStr::new("post-c"));
+        let values: Vec<_> = candidates
+            .iter()
+            .map(|c| c.get_value().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(values, vec!["post-commit", "post-checkout"]);
+    }
+
+    #[test]
+    fn test_complete_hook_events_empty_prefix_lists_all() {
+        assert_eq!(
+            complete_hook_events(OsStr::new("")).len(),
+            SUPPORTED_HOOKS.len()
+        );
+    }
+
     #[test]
     fn test_cli_command_factory() {
         // Verify CLI command factory produces valid structure
@@ -0,0 +1,87 @@
+//! Conditions that gate conditional imports (`{ path, when }`)
+//!
+//! A condition is `<kind>:<argument>`, optionally negated with a leading `!`:
+//!
+//! - `exists:<path>`: the path exists, relative to the project (the directory
+//!   of the config file being loaded, not of the file declaring the import)
+//! - `env:<NAME>`: the environment variable is set and not empty;
+//!   `env:<NAME>=<value>` requires that exact value
+//! - `branch:<pattern>`: the project's current branch matches the glob
+//!   pattern (never true on a detached `HEAD`)
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Whether the import condition `when` holds for the project in `project_dir`
+///
+/// # Errors
+///
+/// Returns an error if `when` is not a valid condition
+pub fn condition_met(when: &str, project_dir: &Path) -> Result<bool> {
+    let trimmed = when.trim();
+    let (negated, condition) = trimmed
+        .strip_prefix('!')
+        .map_or((false, trimmed), |rest| (true, rest.trim_start()));
+
+    let met = match condition.split_once(':') {
+        Some(("exists", path)) if !path.is_empty() => project_dir.join(path).exists(),
+        Some(("env", variable)) if !variable.is_empty() => match variable.split_once('=') {
+            Some((name, value)) => std::env::var(name).is_ok_and(|actual| actual == value),
+            None => std::env::var_os(variable).is_some_and(|value| !value.is_empty()),
+        },
+        Some(("branch", pattern)) if !pattern.is_empty() => {
+            let pattern = glob::Pattern::new(pattern)
+                .with_context(|| format!("Invalid branch pattern in import condition: {when}"))?;
+            current_branch(project_dir).is_some_and(|branch| pattern.matches(&branch))
+        }
+        _ => anyhow::bail!(
+            "Invalid import condition '{when}'; expected exists:<path>, env:<NAME>[=<value>] \
+             or branch:<pattern>, optionally negated with '!'"
+        ),
+    };
+    Ok(met != negated)
+}
+
+/// Branch checked out in the repository containing `dir`, including a branch
+/// without commits yet; `None` on a detached `HEAD` or outside a repository
+fn current_branch(dir: &Path) -> Option<String> {
+    let repo = git2::Repository::discover(dir).ok()?;
+    let head = repo.find_reference("HEAD").ok()?;
+    head.symbolic_target()?
+        .strip_prefix("refs/heads/")
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_condition_met() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path();
+        let repo = git2::Repository::init(project).unwrap();
+        repo.set_head("refs/heads/release/1.0").unwrap();
+        std::fs::write(project.join("Cargo.toml"), "").unwrap();
+
+        assert!(condition_met("exists:Cargo.toml", project).unwrap());
+        assert!(!condition_met("exists:package.json", project).unwrap());
+        assert!(condition_met("!exists:package.json", project).unwrap());
+
+        assert!(condition_met("env:PATH", project).unwrap());
+        assert!(!condition_met("env:PETER_HOOK_TEST_UNSET_VARIABLE", project).unwrap());
+        assert!(
+            !condition_met("env:PETER_HOOK_TEST_UNSET_VARIABLE=1", project).unwrap(),
+            "an unset variable has no value"
+        );
+
+        assert!(condition_met("branch:release/*", project).unwrap());
+        assert!(!condition_met("branch:main", project).unwrap());
+        assert!(condition_met("! branch:main", project).unwrap());
+
+        for invalid in ["exists:", "os:linux", "Cargo.toml", "branch:[", ""] {
+            assert!(condition_met(invalid, project).is_err(), "{invalid}");
+        }
+    }
+}
@@ -2,29 +2,129 @@
 //!
 //! Handles user-wide configuration stored in ~/.config/peter-hook/config.toml
 
+use crate::{
+    config::ExecutionStrategy,
+    output::{OutputLimit, OutputLimits},
+};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
 
 /// Global configuration for peter-hook
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct GlobalConfig {
     /// Security settings
+    #[serde(default)]
     pub security: SecurityConfig,
+    /// Execution defaults
+    #[serde(default)]
+    pub execution: ExecutionConfig,
+    /// Hook output settings
+    #[serde(default)]
+    pub output: OutputConfig,
+    /// Defaults for values commands would otherwise guess
+    #[serde(default)]
+    pub defaults: DefaultsConfig,
 }
 
+/// Settings names accepted by [`GlobalConfig::get`] and [`GlobalConfig::set`]
+pub const GLOBAL_CONFIG_KEYS: &[&str] = &[
+    "security.allow_local",
+    "security.trusted_import_dirs",
+    "security.require_trust",
+    "security.allowed_import_hosts",
+    "execution.default_strategy",
+    "execution.timing_history",
+    "output.max_lines",
+    "output.max_bytes",
+    "output.failure_max_lines",
+    "output.failure_max_bytes",
+    "defaults.remote",
+];
+
 /// Security configuration settings
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct SecurityConfig {
     /// Allow imports from $HOME/.local/peter-hook directory
     #[serde(default)]
     pub allow_local: bool,
+    /// Additional directories imports may reference, by absolute path or by
+    /// a relative path that leaves the repository (`~` is expanded). Empty by
+    /// default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_import_dirs: Vec<PathBuf>,
+    /// Ask before running hooks from a config that wasn't trusted with
+    /// `peter-hook trust`, or changed since
+    #[serde(default)]
+    pub require_trust: bool,
+    /// Hosts git imports may be fetched from (`github.com`, or `file` for
+    /// `file://` URLs). Empty by default, which disables git imports.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_import_hosts: Vec<String>,
 }
 
-impl Default for GlobalConfig {
-    fn default() -> Self {
-        Self {
-            security: SecurityConfig { allow_local: false },
+/// Execution configuration settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ExecutionConfig {
+    /// Strategy for groups that don't set `execution` themselves
+    #[serde(default)]
+    pub default_strategy: ExecutionStrategy,
+    /// Runs of each hook whose durations are kept for `doctor` (in the git
+    /// directory); no history is written when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing_history: Option<usize>,
+}
+
+/// Limits on the hook output printed in run summaries; `--output-dir` logs
+/// always get the full output
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct OutputConfig {
+    /// Most lines of each hook's stdout and stderr to print
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_lines: Option<usize>,
+    /// Most bytes of each hook's stdout and stderr to print
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+    /// `max_lines` for failed hooks (defaults to `max_lines`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_max_lines: Option<usize>,
+    /// `max_bytes` for failed hooks (defaults to `max_bytes`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_max_bytes: Option<usize>,
+}
+
+/// Fallback values for commands
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct DefaultsConfig {
+    /// Remote that pushed commits are compared against when running
+    /// `pre-push` hooks by hand (`origin` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+impl OutputConfig {
+    /// The output limits these settings describe
+    ///
+    /// `max_output` is the `--max-output` flag: a line limit for every hook
+    /// that replaces the configured line limits, or with `0`, no truncation
+    /// at all.
+    #[must_use]
+    pub fn limits(&self, max_output: Option<usize>) -> OutputLimits {
+        if max_output == Some(0) {
+            return OutputLimits::default();
+        }
+        OutputLimits {
+            success: OutputLimit {
+                max_lines: max_output.or(self.max_lines),
+                max_bytes: self.max_bytes,
+            },
+            failure: OutputLimit {
+                max_lines: max_output.or(self.failure_max_lines).or(self.max_lines),
+                max_bytes: self.failure_max_bytes.or(self.max_bytes),
+            },
         }
     }
 }
@@ -86,6 +186,245 @@ impl GlobalConfig {
         Ok(())
     }
 
+    /// This configuration as a commented template listing every setting
+    ///
+    /// Settings that are set appear as values; unset optional ones are
+    /// commented out with an example, so the file shows what can be
+    /// configured. It parses back to the same configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a setting cannot be serialized
+    pub fn template(&self) -> Result<String> {
+        let mut out = String::from(
+            "# peter-hook global configuration\n\
+             #\n\
+             # Commented-out settings are unset; uncomment one to change it.\n\
+             # `peter-hook config set` rewrites this file without the comments.\n",
+        );
+
+        out.push_str("\n[security]\n");
+        write_setting(
+            &mut out,
+            "Allow imports from $HOME/.local/peter-hook",
+            "allow_local",
+            Some(toml::Value::Boolean(self.security.allow_local)),
+            "false",
+        );
+        write_setting(
+            &mut out,
+            "Other directories imports may reference, by absolute path or by a\n\
+             relative path that leaves the repository (`~` is expanded)",
+            "trusted_import_dirs",
+            list_setting(&self.security.trusted_import_dirs)?,
+            "[\"~/shared-hooks\"]",
+        );
+        write_setting(
+            &mut out,
+            "Ask before running hooks from configs that weren't trusted with\n\
+             `peter-hook trust`, or changed since",
+            "require_trust",
+            Some(toml::Value::Boolean(self.security.require_trust)),
+            "false",
+        );
+        write_setting(
+            &mut out,
+            "Hosts git imports may be fetched from (`file` allows file:// URLs)",
+            "allowed_import_hosts",
+            list_setting(&self.security.allowed_import_hosts)?,
+            "[\"github.com\"]",
+        );
+
+        out.push_str("\n[execution]\n");
+        write_setting(
+            &mut out,
+            "Strategy for groups that don't set `execution`: sequential,\n\
+             parallel or force-parallel",
+            "default_strategy",
+            Some(
+                toml::Value::try_from(self.execution.default_strategy)
+                    .context("Failed to serialize execution strategy")?,
+            ),
+            "\"sequential\"",
+        );
+        write_setting(
+            &mut out,
+            "Runs of each hook whose durations `peter-hook doctor` ranks hooks\n\
+             by (no history is kept when unset)",
+            "timing_history",
+            number_setting(self.execution.timing_history),
+            "20",
+        );
+
+        out.push_str("\n[output]\n");
+        write_setting(
+            &mut out,
+            "Most lines and bytes of each hook's stdout and stderr printed in\n\
+             run summaries (unlimited when unset; --output-dir logs are never\n\
+             truncated)",
+            "max_lines",
+            number_setting(self.output.max_lines),
+            "100",
+        );
+        write_setting(
+            &mut out,
+            "",
+            "max_bytes",
+            number_setting(self.output.max_bytes),
+            "65536",
+        );
+        write_setting(
+            &mut out,
+            "The same for failed hooks (default to the limits above)",
+            "failure_max_lines",
+            number_setting(self.output.failure_max_lines),
+            "500",
+        );
+        write_setting(
+            &mut out,
+            "",
+            "failure_max_bytes",
+            number_setting(self.output.failure_max_bytes),
+            "262144",
+        );
+
+        out.push_str("\n[defaults]\n");
+        write_setting(
+            &mut out,
+            "Remote that pushed commits are compared against when running\n\
+             pre-push hooks by hand",
+            "remote",
+            self.defaults.remote.clone().map(toml::Value::String),
+            "\"origin\"",
+        );
+
+        Ok(out)
+    }
+
+    /// Write this configuration to the default location as a commented
+    /// [`template`](Self::template)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration cannot be serialized or written
+    pub fn save_template(&self) -> Result<()> {
+        let config_path = Self::config_path()?;
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+
+        std::fs::write(&config_path, self.template()?)
+            .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+
+        Ok(())
+    }
+
+    /// The value of the setting `key` (`section.name`, see
+    /// [`GLOBAL_CONFIG_KEYS`]) as `config get` prints it
+    ///
+    /// Unset optional settings are empty, lists are comma-separated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is not a known setting
+    pub fn get(&self, key: &str) -> Result<String> {
+        let number = |value: Option<usize>| value.map(|n| n.to_string()).unwrap_or_default();
+        Ok(match key {
+            "security.allow_local" => self.security.allow_local.to_string(),
+            "security.trusted_import_dirs" => self
+                .security
+                .trusted_import_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            "security.require_trust" => self.security.require_trust.to_string(),
+            "security.allowed_import_hosts" => self.security.allowed_import_hosts.join(","),
+            "execution.default_strategy" => toml::Value::try_from(self.execution.default_strategy)
+                .context("Failed to serialize execution strategy")?
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            "execution.timing_history" => number(self.execution.timing_history),
+            "output.max_lines" => number(self.output.max_lines),
+            "output.max_bytes" => number(self.output.max_bytes),
+            "output.failure_max_lines" => number(self.output.failure_max_lines),
+            "output.failure_max_bytes" => number(self.output.failure_max_bytes),
+            "defaults.remote" => self.defaults.remote.clone().unwrap_or_default(),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    /// Change the setting `key` from its `config set` text form
+    ///
+    /// Booleans take `true`/`false`, lists take comma-separated values, and an
+    /// empty value unsets an optional setting (or empties a list).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is not a known setting or `value` is not
+    /// valid for it
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let number = |value: &str| -> Result<Option<usize>> {
+            if value.is_empty() {
+                return Ok(None);
+            }
+            value.parse().map(Some).with_context(|| {
+                format!("Invalid value for {key}: expected a number, got '{value}'")
+            })
+        };
+        let text = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+        match key {
+            "security.allow_local" => {
+                self.security.allow_local = value.parse().with_context(|| {
+                    format!("Invalid value for {key}: expected true or false, got '{value}'")
+                })?;
+            }
+            "security.require_trust" => {
+                self.security.require_trust = value.parse().with_context(|| {
+                    format!("Invalid value for {key}: expected true or false, got '{value}'")
+                })?;
+            }
+            "security.trusted_import_dirs" => {
+                self.security.trusted_import_dirs = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from)
+                    .collect();
+            }
+            "security.allowed_import_hosts" => {
+                self.security.allowed_import_hosts = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            "execution.default_strategy" => {
+                self.execution.default_strategy = toml::Value::String(value.to_string())
+                    .try_into()
+                    .map_err(|_| {
+                        anyhow::anyhow!(
+                            "Invalid value for {key}: expected sequential, parallel or \
+                             force-parallel, got '{value}'"
+                        )
+                    })?;
+            }
+            "execution.timing_history" => self.execution.timing_history = number(value)?,
+            "output.max_lines" => self.output.max_lines = number(value)?,
+            "output.max_bytes" => self.output.max_bytes = number(value)?,
+            "output.failure_max_lines" => self.output.failure_max_lines = number(value)?,
+            "output.failure_max_bytes" => self.output.failure_max_bytes = number(value)?,
+            "defaults.remote" => self.defaults.remote = text(value),
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
     /// Get the default configuration file path
     ///
     /// # Errors
@@ -99,15 +438,19 @@ impl GlobalConfig {
 
     /// Check if an absolute path is allowed for import
     ///
-    /// Only allows imports from $HOME/.local/peter-hook if `allow_local` is
-    /// true
+    /// Allows paths inside `trusted_import_dirs`, and imports from
+    /// $HOME/.local/peter-hook if `allow_local` is true
     ///
     /// # Errors
     ///
     /// Returns an error if home directory cannot be determined or path
     /// operations fail
     pub fn is_absolute_path_allowed(&self, path: &Path) -> Result<bool> {
-        // If allow_local is false, reject all absolute paths
+        if self.is_in_trusted_import_dir(path) {
+            return Ok(true);
+        }
+
+        // If allow_local is false, reject all other absolute paths
         if !self.security.allow_local {
             return Ok(false);
         }
@@ -151,6 +494,43 @@ impl GlobalConfig {
         }
     }
 
+    /// Check if a path is inside one of the `trusted_import_dirs`
+    ///
+    /// Existing paths are compared after canonicalizing both sides, so a
+    /// symlink inside a trusted directory cannot point out of it. Entries that
+    /// are not absolute after `~` expansion are ignored.
+    #[must_use]
+    pub fn is_in_trusted_import_dir(&self, path: &Path) -> bool {
+        let canonical_path = path.canonicalize().ok();
+
+        self.security
+            .trusted_import_dirs
+            .iter()
+            .filter_map(|dir| {
+                let expanded = PathBuf::from(&*shellexpand::tilde(&dir.to_string_lossy()));
+                expanded.is_absolute().then_some(expanded)
+            })
+            .any(|dir| match (&canonical_path, dir.canonicalize()) {
+                (Some(canonical_path), Ok(canonical_dir)) => {
+                    canonical_path.starts_with(canonical_dir)
+                }
+                // A path that exists but whose trusted dir doesn't is suspicious
+                (Some(_), Err(_)) => false,
+                // File doesn't exist yet: validate the path as written
+                (None, _) => path.starts_with(&dir),
+            })
+    }
+
+    /// Check if git imports may be fetched from `host` (compared without
+    /// regard to case)
+    #[must_use]
+    pub fn is_import_host_allowed(&self, host: &str) -> bool {
+        self.security
+            .allowed_import_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
     /// Get the peter-hook local directory path
     ///
     /// # Errors
@@ -163,6 +543,53 @@ impl GlobalConfig {
     }
 }
 
+/// Append a setting to a [`GlobalConfig::template`] under its comment lines;
+/// unset (`None`) settings are commented out with `example` as the value
+fn write_setting(
+    out: &mut String,
+    comment: &str,
+    key: &str,
+    value: Option<toml::Value>,
+    example: &str,
+) {
+    for line in comment.lines() {
+        let _ = writeln!(out, "# {line}");
+    }
+    match value {
+        Some(value) => {
+            let _ = writeln!(out, "{key} = {value}");
+        }
+        None => {
+            let _ = writeln!(out, "# {key} = {example}");
+        }
+    }
+}
+
+/// A number setting for [`write_setting`]
+fn number_setting(value: Option<usize>) -> Option<toml::Value> {
+    value
+        .and_then(|n| i64::try_from(n).ok())
+        .map(toml::Value::Integer)
+}
+
+/// A list setting for [`write_setting`]; empty lists are unset
+fn list_setting<T: Serialize>(values: &[T]) -> Result<Option<toml::Value>> {
+    if values.is_empty() {
+        return Ok(None);
+    }
+    toml::Value::try_from(values)
+        .map(Some)
+        .context("Failed to serialize list setting")
+}
+
+/// Error for a `config get`/`config set` key that doesn't exist
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Unknown config key '{key}'. Valid keys: {}",
+        GLOBAL_CONFIG_KEYS.join(", ")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +640,110 @@ mod tests {
         assert!(loaded.security.allow_local);
     }
 
+    #[test]
+    fn test_template_parses_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        let mut config = GlobalConfig::default();
+        config.security.allow_local = true;
+        let template = config.template().unwrap();
+        assert!(template.contains("\n[output]\n"), "{template}");
+        assert!(template.contains("# max_lines = "), "{template}");
+        assert!(template.contains("# remote = \"origin\""), "{template}");
+        assert!(template.contains("\nallow_local = true\n"), "{template}");
+        assert!(
+            template.contains("\ndefault_strategy = \"sequential\"\n"),
+            "{template}"
+        );
+        fs::write(&config_path, &template).unwrap();
+        assert_eq!(GlobalConfig::from_file(&config_path).unwrap(), config);
+
+        // Settings that are set appear uncommented
+        for (key, value) in [
+            ("security.trusted_import_dirs", "~/hooks,/opt/hooks"),
+            ("security.allowed_import_hosts", "github.com"),
+            ("execution.default_strategy", "parallel"),
+            ("execution.timing_history", "20"),
+            ("output.max_lines", "40"),
+            ("output.max_bytes", "2048"),
+            ("output.failure_max_lines", "400"),
+            ("output.failure_max_bytes", "4096"),
+            ("defaults.remote", "upstream"),
+        ] {
+            config.set(key, value).unwrap();
+        }
+        let template = config.template().unwrap();
+        assert!(!template.contains("# max_lines ="), "{template}");
+        fs::write(&config_path, &template).unwrap();
+        assert_eq!(GlobalConfig::from_file(&config_path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_get_set_round_trip() {
+        let mut config = GlobalConfig::default();
+        for (key, value) in [
+            ("security.allow_local", "true"),
+            ("security.trusted_import_dirs", "~/hooks,/opt/hooks"),
+            ("security.require_trust", "true"),
+            ("security.allowed_import_hosts", "github.com,file"),
+            ("execution.default_strategy", "force-parallel"),
+            ("execution.timing_history", "20"),
+            ("output.max_lines", "40"),
+            ("output.failure_max_bytes", "4096"),
+            ("defaults.remote", "upstream"),
+        ] {
+            config.set(key, value).unwrap();
+            assert_eq!(config.get(key).unwrap(), value, "{key}");
+        }
+        assert!(config.security.allow_local);
+        assert_eq!(
+            config.execution.default_strategy,
+            ExecutionStrategy::ForceParallel
+        );
+        assert_eq!(config.defaults.remote.as_deref(), Some("upstream"));
+
+        // The settings survive saving and loading
+        let reparsed: GlobalConfig =
+            toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(reparsed, config);
+
+        // Empty values unset optional settings
+        config.set("output.max_lines", "").unwrap();
+        config.set("defaults.remote", "").unwrap();
+        assert_eq!(config.output.max_lines, None);
+        assert_eq!(config.get("defaults.remote").unwrap(), "");
+    }
+
+    #[test]
+    fn test_get_set_reject_unknown_keys_and_bad_values() {
+        let mut config = GlobalConfig::default();
+
+        let err = config.get("security.allow_everything").unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "Unknown config key 'security.allow_everything'. Valid keys: \
+                 security.allow_local, security.trusted_import_dirs"
+            ),
+            "{err}"
+        );
+        assert!(config.set("remote", "origin").is_err());
+
+        for (key, value) in [
+            ("security.allow_local", "yes"),
+            ("execution.default_strategy", "fast"),
+            ("output.max_bytes", "-1"),
+        ] {
+            let err = config.set(key, value).unwrap_err();
+            assert!(
+                err.to_string()
+                    .starts_with(&format!("Invalid value for {key}")),
+                "{err}"
+            );
+        }
+        assert_eq!(config, GlobalConfig::default());
+    }
+
     #[test]
     fn test_get_local_dir() {
         let local_dir = GlobalConfig::get_local_dir().unwrap();
@@ -250,7 +781,13 @@ mod tests {
         fs::write(&test_file, "test").unwrap();
 
         let config = GlobalConfig {
-            security: SecurityConfig { allow_local: true },
+            security: SecurityConfig {
+                allow_local: true,
+                ..SecurityConfig::default()
+            },
+            execution: ExecutionConfig::default(),
+            output: OutputConfig::default(),
+            defaults: DefaultsConfig::default(),
         };
 
         // Should allow files within peter-hook directory
@@ -268,6 +805,40 @@ mod tests {
         let _ = fs::remove_dir_all(home_dir.join("other-dir"));
     }
 
+    #[test]
+    fn test_trusted_import_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let shared = temp_dir.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        let inside = shared.join("common.toml");
+        fs::write(&inside, "").unwrap();
+        let outside = temp_dir.path().join("other.toml");
+        fs::write(&outside, "").unwrap();
+
+        // Off by default
+        let mut config = GlobalConfig::default();
+        assert!(!config.is_in_trusted_import_dir(&inside));
+        assert!(!config.is_absolute_path_allowed(&inside).unwrap());
+
+        config.security.trusted_import_dirs = vec![shared.clone()];
+        assert!(config.is_in_trusted_import_dir(&inside));
+        assert!(config.is_absolute_path_allowed(&inside).unwrap());
+        assert!(config.is_in_trusted_import_dir(&shared.join("not-yet.toml")));
+        assert!(!config.is_in_trusted_import_dir(&outside));
+        assert!(!config.is_absolute_path_allowed(&outside).unwrap());
+
+        #[cfg(unix)]
+        {
+            let link = shared.join("escape.toml");
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(!config.is_in_trusted_import_dir(&link));
+        }
+
+        // Relative entries are ignored
+        config.security.trusted_import_dirs = vec![PathBuf::from("shared")];
+        assert!(!config.is_in_trusted_import_dir(&inside));
+    }
+
     #[test]
     fn test_symlink_protection() {
         use tempfile::TempDir;
@@ -1,7 +1,13 @@
+pub mod conditions;
 pub mod global;
 pub mod parser;
+pub mod remote;
 pub mod templating;
+pub mod trust;
 
+pub use conditions::*;
 pub use global::*;
 pub use parser::*;
+pub use remote::*;
 pub use templating::*;
+pub use trust::*;
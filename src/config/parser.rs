@@ -2,34 +2,277 @@
 //! Configuration parsing for git hooks
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize, de};
 use shellexpand;
 use std::{
     collections::{HashMap, HashSet},
+    fmt,
     path::{Path, PathBuf},
 };
 
-use crate::config::GlobalConfig;
+use crate::config::{GitImportCache, GlobalConfig, check_import_host, condition_met};
 
-/// Represents a hook configuration file (hooks.toml)
+/// Error loading a hooks config file (unreadable, malformed, bad imports or
+/// failed validation)
+///
+/// Displays exactly like the wrapped error; it only marks the error so the
+/// CLI can report it with its own exit code.
+#[derive(Debug)]
+pub struct ConfigError(anyhow::Error);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl ConfigError {
+    fn wrap(error: anyhow::Error) -> anyhow::Error {
+        anyhow::Error::new(Self(error))
+    }
+}
+
+/// Config file names looked up in each directory, in order of precedence
+pub const CONFIG_FILE_NAMES: [&str; 4] = ["hooks.toml", "hooks.yaml", "hooks.yml", "hooks.json"];
+
+/// Per-developer override file read next to a hooks config; meant to be
+/// gitignored, and never imported
+pub const LOCAL_CONFIG_FILE_NAME: &str = "hooks.local.toml";
+
+/// Find the hooks config file directly inside `dir`, preferring `hooks.toml`
+#[must_use]
+pub fn config_file_in(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.exists())
+}
+
+/// Serialization format of a config file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    /// TOML (the default for any other extension)
+    #[default]
+    Toml,
+    /// YAML (`.yaml` or `.yml`)
+    Yaml,
+    /// JSON (`.json`)
+    Json,
+}
+
+impl ConfigFormat {
+    /// Detect the format of a config file from its extension
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Self::Yaml,
+            Some("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+}
+
+/// Represents a hook configuration file (hooks.toml, hooks.yaml or
+/// hooks.json)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct HookConfig {
-    /// Individual hook definitions
-    pub hooks: Option<HashMap<String, HookDefinition>>,
+    /// Individual hook definitions, in declaration order
+    ///
+    /// Accepts either `[hooks.<name>]` tables or an ordered `[[hooks]]`
+    /// array of tables that each carry a `name` field.
+    #[serde(default, deserialize_with = "deserialize_hooks")]
+    pub hooks: Option<IndexMap<String, HookDefinition>>,
     /// Hook groups that combine multiple hooks
+    #[serde(serialize_with = "serialize_sorted")]
     pub groups: Option<HashMap<String, HookGroup>>,
     /// Optional list of files to import and merge
-    pub imports: Option<Vec<String>>,
+    pub imports: Option<Vec<Import>>,
+    /// Settings applied to every hook that doesn't set them (`[defaults]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<ConfigDefaults>,
+    /// Keys each hook sets itself, recorded while reading files so defaults
+    /// only fill the rest; emptied once defaults are applied
+    #[serde(skip)]
+    hook_keys: HashMap<String, HashSet<String>>,
+}
+
+/// An `imports` entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Import {
+    /// A file that is always imported
+    Path(String),
+    /// A file imported only when a condition holds
+    Conditional(ConditionalImport),
+    /// A file fetched from a git repository
+    Git(GitImport),
+}
+
+/// An import gated by a condition (`{ path = "rust.toml", when =
+/// "exists:Cargo.toml" }`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionalImport {
+    /// File to import, resolved like any other import
+    pub path: String,
+    /// Condition under which the file is imported (see
+    /// [`crate::config::conditions`])
+    pub when: String,
+}
+
+/// An import from a git repository (`{ git =
+/// "https://github.com/org/hooks", ref = "v1", path = "rust.toml" }`), fetched
+/// into a local cache (see [`crate::config::remote`])
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct GitImport {
+    /// Repository URL; its host must be in `security.allowed_import_hosts`
+    pub git: String,
+    /// Tag, branch or full commit hash to import from
+    #[serde(rename = "ref")]
+    pub git_ref: String,
+    /// File to import, relative to the repository root
+    #[serde(default = "default_git_import_path")]
+    pub path: String,
+    /// Condition under which the file is imported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+}
+
+fn default_git_import_path() -> String {
+    "hooks.toml".to_string()
+}
+
+impl fmt::Display for GitImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} from {} at {}", self.path, self.git, self.git_ref)
+    }
+}
+
+impl fmt::Display for Import {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Git(import) => import.fmt(f),
+            _ => f.write_str(self.path()),
+        }
+    }
+}
+
+impl Import {
+    /// The file to import; for git imports, its path in the repository
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // `String` deref isn't const on the MSRV
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Path(path) => path,
+            Self::Conditional(import) => &import.path,
+            Self::Git(import) => &import.path,
+        }
+    }
+
+    /// The condition gating the import, if any
+    #[must_use]
+    #[allow(clippy::missing_const_for_fn)] // `String` deref isn't const on the MSRV
+    pub fn condition(&self) -> Option<&str> {
+        match self {
+            Self::Path(_) => None,
+            Self::Conditional(import) => Some(&import.when),
+            Self::Git(import) => import.when.as_deref(),
+        }
+    }
+}
+
+/// Serialize an optional `HashMap` with sorted keys so output is stable
+#[allow(clippy::ref_option)] // signature required by `serialize_with`
+fn serialize_sorted<S, V>(
+    map: &Option<HashMap<String, V>>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    map.as_ref()
+        .map(|map| map.iter().collect::<std::collections::BTreeMap<_, _>>())
+        .serialize(serializer)
+}
+
+/// A hook declared with the ordered `[[hooks]]` array form
+#[derive(Deserialize)]
+struct NamedHookDefinition {
+    /// Hook name (the key used by the table form)
+    name: String,
+    #[serde(flatten)]
+    definition: HookDefinition,
+}
+
+/// Deserialize `hooks` from either the table form or the `[[hooks]]` array
+/// form, preserving declaration order in both cases
+fn deserialize_hooks<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<IndexMap<String, HookDefinition>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct HooksVisitor;
+
+    impl<'de> de::Visitor<'de> for HooksVisitor {
+        type Value = IndexMap<String, HookDefinition>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a table of hooks or an array of hooks with a `name` field")
+        }
+
+        fn visit_map<A>(self, map: A) -> std::result::Result<Self::Value, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            IndexMap::deserialize(de::value::MapAccessDeserializer::new(map))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+        where
+            A: de::SeqAccess<'de>,
+        {
+            let mut hooks = IndexMap::new();
+            while let Some(hook) = seq.next_element::<NamedHookDefinition>()? {
+                if hooks.contains_key(&hook.name) {
+                    return Err(de::Error::custom(format!(
+                        "duplicate hook name '{}' in [[hooks]]",
+                        hook.name
+                    )));
+                }
+                hooks.insert(hook.name, hook.definition);
+            }
+            Ok(hooks)
+        }
+    }
+
+    deserializer.deserialize_any(HooksVisitor).map(Some)
 }
 
 /// Definition of an individual hook
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // independent TOML flags, not a state machine
 pub struct HookDefinition {
     /// Command to execute (either as string or array)
     pub command: HookCommand,
     /// Working directory override (defaults to config file directory)
     pub workdir: Option<String>,
+    /// Glob of directories to run the hook in, once per match (a matched
+    /// file stands for its directory, e.g. `packages/*/package.json`); each
+    /// run only gets the changed files under its directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir_glob: Option<String>,
     /// Environment variables to set
+    #[serde(serialize_with = "serialize_sorted")]
     pub env: Option<HashMap<String, String>>,
     /// Description of what this hook does
     pub description: Option<String>,
@@ -43,8 +286,24 @@ pub struct HookDefinition {
     /// Run this hook always, regardless of file changes
     #[serde(default)]
     pub run_always: bool,
+    /// Also run this hook, without files, when none of the changed files are
+    /// left for it; with matching files it runs for them as usual
+    #[serde(default)]
+    pub run_always_if_no_changes: bool,
+    /// Pass deleted files to `per-file` and `in-place` hooks instead of
+    /// dropping paths that no longer exist (`other` hooks always get them)
+    #[serde(default)]
+    pub include_deleted: bool,
     /// Hooks that must complete successfully before this hook runs
     pub depends_on: Option<Vec<String>>,
+    /// For `post-checkout`: skip the hook when git reports a file checkout
+    /// (`git checkout -- <file>`) rather than a branch switch
+    #[serde(default)]
+    pub only_on_branch_change: bool,
+    /// For `pre-push`: only run when a pushed remote ref matches one of these
+    /// glob patterns (e.g. `refs/heads/release/*`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_refs: Option<Vec<String>>,
     /// How to execute this hook with respect to changed files
     #[serde(default)]
     pub execution_type: ExecutionType,
@@ -52,6 +311,374 @@ pub struct HookDefinition {
     /// directory
     #[serde(default)]
     pub run_at_root: bool,
+    /// Command to run when this hook fails (e.g. a remediation hint or
+    /// cleanup). The failing hook's output is available through the
+    /// `{FAILED_*}` template variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<HookCommand>,
+    /// Command `lint --fix` runs instead of `command`, for tools with separate
+    /// check and fix invocations (e.g. `cargo fmt --check` / `cargo fmt`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_command: Option<HookCommand>,
+    /// Shell snippet run by `sh` before the command in the same process, to
+    /// activate a toolchain (e.g. `. .venv/bin/activate`); the command
+    /// doesn't run if it fails
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activate: Option<String>,
+    /// Condition command run before the hook; if it exits 0 the hook is
+    /// skipped. Template variables are available to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_if: Option<HookCommand>,
+    /// Whether the hook runs at all; a disabled hook is reported as skipped
+    /// and still satisfies `depends_on`
+    #[serde(default = "enabled_by_default", skip_serializing_if = "is_enabled")]
+    pub enabled: bool,
+    /// Regex matched against each line of the hook's stdout and stderr; a
+    /// matching line fails the hook even when it exits 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<String>,
+    /// Regex for known-noise lines that `fail_on` should ignore
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on_allow: Option<String>,
+    /// Free-form labels for selecting hooks with `run --tag` / `lint --tag`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Run the hook's commands inside a container instead of on the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerSpec>,
+    /// Scheduling hint for parallel execution: within a parallel batch,
+    /// hooks with a higher priority are started first (default 0)
+    #[serde(default)]
+    pub priority: i32,
+    /// Capture stdout and stderr as one stream in the order they were
+    /// written, instead of separately
+    #[serde(default)]
+    pub combined_output: bool,
+    /// Report a failure of this hook as a warning instead of failing the run
+    /// (advisory checks such as a spell-checker)
+    #[serde(default)]
+    pub allow_failure: bool,
+}
+
+/// Hooks are enabled unless they set `enabled = false`
+const fn enabled_by_default() -> bool {
+    true
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // signature required by `skip_serializing_if`
+const fn is_enabled(enabled: &bool) -> bool {
+    *enabled
+}
+
+impl HookDefinition {
+    /// How `other` would run differently from this definition: the files it
+    /// runs for and how it is invoked, one `<field>: <old> -> <new>` entry per
+    /// setting that differs
+    ///
+    /// Used to flag imported hooks that an override turns into something else
+    /// (e.g. `files` patterns dropped in favor of `run_always`).
+    #[must_use]
+    pub fn execution_changes(&self, other: &Self) -> Vec<String> {
+        let describe_files = |files: &Option<Vec<String>>| {
+            files
+                .as_ref()
+                .map_or_else(|| "none".to_string(), |files| format!("{files:?}"))
+        };
+
+        let mut changes = Vec::new();
+        if self.files != other.files {
+            changes.push(format!(
+                "files: {} -> {}",
+                describe_files(&self.files),
+                describe_files(&other.files)
+            ));
+        }
+        for (field, old, new) in [
+            ("run_always", self.run_always, other.run_always),
+            (
+                "run_always_if_no_changes",
+                self.run_always_if_no_changes,
+                other.run_always_if_no_changes,
+            ),
+            ("run_at_root", self.run_at_root, other.run_at_root),
+            (
+                "modifies_repository",
+                self.modifies_repository,
+                other.modifies_repository,
+            ),
+        ] {
+            if old != new {
+                changes.push(format!("{field}: {old} -> {new}"));
+            }
+        }
+        if self.execution_type != other.execution_type {
+            changes.push(format!(
+                "execution_type: {} -> {}",
+                self.execution_type.as_str(),
+                other.execution_type.as_str()
+            ));
+        }
+        changes
+    }
+}
+
+/// Defaults applied across a configuration (`[defaults]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigDefaults {
+    /// Settings for every hook (`[defaults.hook]`)
+    #[serde(default)]
+    pub hook: HookDefaults,
+}
+
+/// Hook settings from `[defaults.hook]`
+///
+/// Each one applies to every hook that doesn't set the same key itself;
+/// `env` is merged instead, with the hook's own values winning. `workdir` and
+/// `run_at_root` skip hooks with `workdir_glob`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HookDefaults {
+    /// Default `workdir`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<String>,
+    /// Environment variables added to every hook's `env`
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_sorted"
+    )]
+    pub env: Option<HashMap<String, String>>,
+    /// Default `modifies_repository`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifies_repository: Option<bool>,
+    /// Default `run_always`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_always: Option<bool>,
+    /// Default `include_deleted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_deleted: Option<bool>,
+    /// Default `execution_type`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_type: Option<ExecutionType>,
+    /// Default `run_at_root`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_at_root: Option<bool>,
+    /// Default `activate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activate: Option<String>,
+    /// Default `on_failure`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<HookCommand>,
+    /// Default `skip_if`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_if: Option<HookCommand>,
+    /// Default `fail_on`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on: Option<String>,
+    /// Default `fail_on_allow`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_on_allow: Option<String>,
+    /// Default `tags`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Default `container`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerSpec>,
+    /// Default `priority`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// Default `combined_output`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combined_output: Option<bool>,
+    /// Default `allow_failure`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_failure: Option<bool>,
+}
+
+impl HookDefaults {
+    /// Layer `other` on top of these defaults: its settings win, and `env`
+    /// is merged
+    fn overlay(&mut self, other: Self) {
+        if let Some(env) = other.env {
+            self.env.get_or_insert_with(HashMap::new).extend(env);
+        }
+        self.workdir = other.workdir.or_else(|| self.workdir.take());
+        self.modifies_repository = other.modifies_repository.or(self.modifies_repository);
+        self.run_always = other.run_always.or(self.run_always);
+        self.include_deleted = other.include_deleted.or(self.include_deleted);
+        self.execution_type = other.execution_type.or(self.execution_type);
+        self.run_at_root = other.run_at_root.or(self.run_at_root);
+        self.activate = other.activate.or_else(|| self.activate.take());
+        self.on_failure = other.on_failure.or_else(|| self.on_failure.take());
+        self.skip_if = other.skip_if.or_else(|| self.skip_if.take());
+        self.fail_on = other.fail_on.or_else(|| self.fail_on.take());
+        self.fail_on_allow = other.fail_on_allow.or_else(|| self.fail_on_allow.take());
+        self.tags = other.tags.or_else(|| self.tags.take());
+        self.container = other.container.or_else(|| self.container.take());
+        self.priority = other.priority.or(self.priority);
+        self.combined_output = other.combined_output.or(self.combined_output);
+        self.allow_failure = other.allow_failure.or(self.allow_failure);
+    }
+
+    /// Fill the settings `hook` doesn't set itself; `keys` are the keys its
+    /// definition spelled out
+    fn apply(&self, hook: &mut HookDefinition, keys: &HashSet<String>) {
+        if let Some(env) = &self.env {
+            let hook_env = hook.env.get_or_insert_with(HashMap::new);
+            for (key, value) in env {
+                hook_env.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+        // A `workdir_glob` hook picks its own working directories
+        if hook.workdir_glob.is_none() {
+            inherit_option(keys, "workdir", self.workdir.as_ref(), &mut hook.workdir);
+            inherit(keys, "run_at_root", self.run_at_root, &mut hook.run_at_root);
+        }
+        inherit(
+            keys,
+            "modifies_repository",
+            self.modifies_repository,
+            &mut hook.modifies_repository,
+        );
+        inherit(keys, "run_always", self.run_always, &mut hook.run_always);
+        inherit(
+            keys,
+            "include_deleted",
+            self.include_deleted,
+            &mut hook.include_deleted,
+        );
+        inherit(
+            keys,
+            "execution_type",
+            self.execution_type,
+            &mut hook.execution_type,
+        );
+        inherit_option(keys, "activate", self.activate.as_ref(), &mut hook.activate);
+        inherit_option(
+            keys,
+            "on_failure",
+            self.on_failure.as_ref(),
+            &mut hook.on_failure,
+        );
+        inherit_option(keys, "skip_if", self.skip_if.as_ref(), &mut hook.skip_if);
+        inherit_option(keys, "fail_on", self.fail_on.as_ref(), &mut hook.fail_on);
+        inherit_option(
+            keys,
+            "fail_on_allow",
+            self.fail_on_allow.as_ref(),
+            &mut hook.fail_on_allow,
+        );
+        inherit_option(keys, "tags", self.tags.as_ref(), &mut hook.tags);
+        inherit_option(
+            keys,
+            "container",
+            self.container.as_ref(),
+            &mut hook.container,
+        );
+        inherit(keys, "priority", self.priority, &mut hook.priority);
+        inherit(
+            keys,
+            "combined_output",
+            self.combined_output,
+            &mut hook.combined_output,
+        );
+        inherit(
+            keys,
+            "allow_failure",
+            self.allow_failure,
+            &mut hook.allow_failure,
+        );
+    }
+}
+
+/// Set a hook setting from its default unless the hook set `key` itself
+fn inherit<T>(keys: &HashSet<String>, key: &str, default: Option<T>, field: &mut T) {
+    if let Some(default) = default {
+        if !keys.contains(key) {
+            *field = default;
+        }
+    }
+}
+
+/// [`inherit`] for settings that are optional on the hook too
+fn inherit_option<T: Clone>(
+    keys: &HashSet<String>,
+    key: &str,
+    default: Option<&T>,
+    field: &mut Option<T>,
+) {
+    inherit(keys, key, default.cloned().map(Some), field);
+}
+
+/// Keys spelled out by each hook of a config file, read without the
+/// defaults serde fills in
+#[derive(Deserialize)]
+struct RawHookKeys {
+    #[serde(default)]
+    hooks: serde_json::Value,
+}
+
+impl RawHookKeys {
+    /// Map each hook name to its keys, for both the table and the
+    /// `[[hooks]]` array form
+    fn into_keys(self) -> HashMap<String, HashSet<String>> {
+        let keys_of = |hook: &serde_json::Map<String, serde_json::Value>| {
+            hook.keys()
+                .filter(|key| *key != "name")
+                .cloned()
+                .collect::<HashSet<_>>()
+        };
+        match self.hooks {
+            serde_json::Value::Object(hooks) => hooks
+                .iter()
+                .filter_map(|(name, hook)| Some((name.clone(), keys_of(hook.as_object()?))))
+                .collect(),
+            serde_json::Value::Array(hooks) => hooks
+                .iter()
+                .filter_map(|hook| {
+                    let hook = hook.as_object()?;
+                    Some((hook.get("name")?.as_str()?.to_string(), keys_of(hook)))
+                })
+                .collect(),
+            _ => HashMap::new(),
+        }
+    }
+}
+
+/// Per-developer changes from a `hooks.local.toml` file, applied on top of
+/// the config next to it
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LocalOverrides {
+    /// Changes per hook name
+    #[serde(default)]
+    pub hooks: IndexMap<String, LocalHookOverride>,
+}
+
+/// Local changes to a single hook
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LocalHookOverride {
+    /// Disable (or re-enable) the hook
+    pub enabled: Option<bool>,
+    /// Environment variables added to the hook's `env`, winning on conflict
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
+}
+
+/// Container a hook runs in (`container = { image = "..." }`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContainerSpec {
+    /// Image to run the hook in
+    pub image: String,
+    /// Extra volumes in `docker run -v` syntax (`host:container[:options]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mounts: Vec<String>,
+    /// Container runtime to use (`docker` or `podman`); detected from `PATH`
+    /// when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
 }
 
 /// How to execute hooks with respect to changed files
@@ -67,6 +694,41 @@ pub enum ExecutionType {
     Other,
 }
 
+impl ExecutionType {
+    /// Name as written in the config (`per-file`, `in-place`, `other`)
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::PerFile => "per-file",
+            Self::InPlace => "in-place",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// Upper bound on the compiled size of a `fail_on` / `fail_on_allow` regex
+const OUTPUT_PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// Compile a `fail_on` / `fail_on_allow` output pattern
+///
+/// The `regex` crate matches in linear time, so no pattern can backtrack
+/// catastrophically; the size limit rejects patterns (e.g. huge bounded
+/// repetitions) that would blow up at compile time instead.
+///
+/// # Errors
+///
+/// Returns an error if the pattern is invalid or too large
+pub fn compile_output_pattern(pattern: &str) -> Result<regex::Regex> {
+    regex::RegexBuilder::new(pattern)
+        .size_limit(OUTPUT_PATTERN_SIZE_LIMIT)
+        .dfa_size_limit(OUTPUT_PATTERN_SIZE_LIMIT)
+        .build()
+        .with_context(|| format!("Invalid output pattern: {pattern}"))
+}
+
+/// Interpreter for inline `script` commands that don't set `shell`
+pub const DEFAULT_SCRIPT_SHELL: &str = "sh";
+
 /// Command specification for a hook
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
@@ -75,6 +737,22 @@ pub enum HookCommand {
     Shell(String),
     /// Execve-style command as array
     Args(Vec<String>),
+    /// Inline multi-line script (`command = { script = """...""" }`), written
+    /// to a temporary file and run with `shell`
+    Script {
+        /// Script body; templates are resolved across the whole script
+        script: String,
+        /// Interpreter plus any arguments, split on whitespace (default `sh`)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shell: Option<String>,
+    },
+    /// Commands run one after another (`command = { steps = [...] }`) in the
+    /// hook's working directory and environment, stopping at the first that
+    /// fails
+    Steps {
+        /// Shell strings, argument arrays or scripts, in order
+        steps: Vec<Self>,
+    },
 }
 
 /// Execution strategy for hook groups
@@ -90,6 +768,15 @@ pub enum ExecutionStrategy {
     ForceParallel,
 }
 
+/// How the hooks of a group are kept apart from each other's changes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Isolation {
+    /// Run each hook in its own temporary git worktree of `HEAD`, with the
+    /// changed files copied in from the working directory
+    Worktree,
+}
+
 /// Group of hooks that run together
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct HookGroup {
@@ -97,9 +784,22 @@ pub struct HookGroup {
     pub includes: Vec<String>,
     /// Description of what this group does
     pub description: Option<String>,
-    /// Execution strategy for this group
-    #[serde(default)]
-    pub execution: ExecutionStrategy,
+    /// Execution strategy for this group (falls back to the global
+    /// `default_strategy` when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<ExecutionStrategy>,
+    /// Setup command run once before the group's hooks; if it fails, the
+    /// group fails without running any hooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<HookCommand>,
+    /// Teardown command run once after the group's hooks, even when setup or
+    /// a hook failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<HookCommand>,
+    /// Run each hook in a separate copy of the repository, so no hook sees
+    /// another's in-progress modifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolation: Option<Isolation>,
     /// Whether this is a placeholder group for hierarchical resolution
     /// Placeholder groups trigger git hook installation but don't run any hooks
     /// at the root level - they only enable subdirectory hooks to be discovered
@@ -113,46 +813,64 @@ pub struct HookGroup {
 
 impl HookGroup {
     /// Get the effective execution strategy, handling backward compatibility
+    ///
+    /// The group's own setting wins; `default` (the global
+    /// `default_strategy`) applies only when the group sets neither
+    /// `execution` nor the deprecated `parallel` flag.
     #[must_use]
-    pub fn get_execution_strategy(&self) -> ExecutionStrategy {
+    pub fn get_execution_strategy(&self, default: ExecutionStrategy) -> ExecutionStrategy {
         // Handle backward compatibility with deprecated `parallel` field
-        self.parallel.map_or_else(
-            || self.execution,
-            |parallel| {
+        self.parallel
+            .map(|parallel| {
                 if parallel {
                     ExecutionStrategy::Parallel
                 } else {
                     ExecutionStrategy::Sequential
                 }
-            },
-        )
+            })
+            .or(self.execution)
+            .unwrap_or(default)
     }
 }
 
 impl HookConfig {
-    /// Parse a hooks.toml file from the given path
+    /// Parse a hooks config file from the given path, in the format given by
+    /// its extension
     ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read or parsed
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
         let mut visited = HashSet::new();
-        Self::from_file_internal(path.as_ref(), &mut visited, None)
+        Self::from_file_internal(path, &mut visited, None)
+            .and_then(|mut config| {
+                config.apply_defaults();
+                config.apply_local_overrides(path)?;
+                config.validate_merged()?;
+                Ok(config)
+            })
+            .map_err(ConfigError::wrap)
     }
 
-    /// Parse a hooks.toml file and collect import diagnostics
+    /// Parse a hooks config file and collect import diagnostics
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The file cannot be read
-    /// - The TOML content is malformed
+    /// - The file content is malformed
     /// - Import cycles are detected
     /// - Required configuration fields are missing
     pub fn from_file_with_trace<P: AsRef<Path>>(path: P) -> Result<(Self, ImportDiagnostics)> {
+        let path = path.as_ref();
         let mut visited = HashSet::new();
         let mut diag = ImportDiagnostics::default();
-        let cfg = Self::from_file_internal(path.as_ref(), &mut visited, Some(&mut diag))?;
+        let mut cfg = Self::from_file_internal(path, &mut visited, Some(&mut diag))
+            .map_err(ConfigError::wrap)?;
+        cfg.apply_defaults();
+        diag.local_override = cfg.apply_local_overrides(path).map_err(ConfigError::wrap)?;
+        cfg.validate_merged().map_err(ConfigError::wrap)?;
         // Compute unused imports: those that were resolved but contributed no names
         let unused: Vec<String> = diag
             .imports
@@ -164,26 +882,64 @@ impl HookConfig {
         Ok((cfg, diag))
     }
 
+    /// Check whether each import of the config file at `path` resolves under
+    /// the import security rules, without loading the imported files. Git
+    /// imports that aren't cached yet are fetched.
+    ///
+    /// Returns every import entry paired with its resolved path or the error
+    /// that would abort loading.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or is not inside
+    /// a git repository
+    pub fn check_imports<P: AsRef<Path>>(path: P) -> Result<Vec<(String, Result<PathBuf>)>> {
+        let path = path.as_ref();
+        let parsed = Self::read_file(path)?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let repo_root = find_git_root_for_config(base_dir)?;
+        let repo_root_real = repo_root.canonicalize().unwrap_or(repo_root);
+        let global_config = GlobalConfig::load().unwrap_or_default();
+
+        Ok(parsed
+            .imports
+            .unwrap_or_default()
+            .into_iter()
+            .map(|imp| {
+                let resolved = match &imp {
+                    Import::Git(git) => fetch_git_import(git, &global_config),
+                    _ => resolve_import(imp.path(), base_dir, &repo_root_real, &global_config)
+                        .map(|(imp_real, _)| imp_real),
+                };
+                (imp.to_string(), resolved)
+            })
+            .collect())
+    }
+
     #[allow(clippy::too_many_lines)]
     fn from_file_internal(
         path: &Path,
         visited: &mut HashSet<PathBuf>,
         diag: Option<&mut ImportDiagnostics>,
     ) -> Result<Self> {
-        Self::from_file_internal_with_options(path, visited, diag, true)
+        let project_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::from_file_internal_with_options(path, visited, diag, true, project_dir)
     }
 
+    /// Read `path` and merge its imports into it
+    ///
+    /// Conditional imports are evaluated against `project_dir`, the directory
+    /// of the config file being loaded, however deeply they are nested.
     #[allow(clippy::too_many_lines)]
     fn from_file_internal_with_options(
         path: &Path,
         visited: &mut HashSet<PathBuf>,
         mut diag: Option<&mut ImportDiagnostics>,
         require_git_root: bool,
+        project_dir: &Path,
     ) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-
-        let parsed: Self = Self::parse(&content)?;
+        let parsed = Self::read_file(path)?;
         let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
 
         // Determine repository root for import security (relative-only, under repo
@@ -208,8 +964,10 @@ impl HookConfig {
         };
 
         // Start with merged result from imports (if any)
-        let mut merged_hooks: HashMap<String, HookDefinition> = HashMap::new();
+        let mut merged_hooks: IndexMap<String, HookDefinition> = IndexMap::new();
         let mut merged_groups: HashMap<String, HookGroup> = HashMap::new();
+        let mut merged_keys: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut merged_defaults: Option<ConfigDefaults> = None;
         // Track sources to produce override diagnostics
         let mut hook_sources: HashMap<String, String> = HashMap::new();
         let mut group_sources: HashMap<String, String> = HashMap::new();
@@ -218,48 +976,19 @@ impl HookConfig {
             // Load global configuration for absolute path validation
             let global_config = GlobalConfig::load().unwrap_or_default();
 
-            for imp in imports {
-                // Expand tilde in the import path
-                let expanded = shellexpand::tilde(imp);
-                let p = Path::new(&*expanded);
-                let (imp_path, is_absolute) = if p.is_absolute() {
-                    // Check if absolute path is allowed via global config
-                    if !global_config.is_absolute_path_allowed(p)? {
-                        return Err(anyhow::anyhow!(
-                            "Absolute import path not allowed: {imp}\nHint: Only imports from \
-                             $HOME/.local/peter-hook are allowed.\nEnable with: peter-hook config \
-                             init --allow-local"
-                        ));
-                    }
-                    (p.to_path_buf(), true)
-                } else {
-                    (base_dir.join(p), false)
+            for import in imports {
+                let Some((imp_real, is_absolute)) = resolve_import_entry(
+                    import,
+                    base_dir,
+                    &repo_root_real,
+                    project_dir,
+                    &global_config,
+                    diag.as_deref_mut(),
+                )?
+                else {
+                    continue;
                 };
 
-                let imp_real = imp_path.canonicalize().with_context(|| {
-                    format!("Failed to resolve import path: {}", imp_path.display())
-                })?;
-
-                // Enforce import stays within repo root (but only for relative imports)
-                if !is_absolute && !imp_real.starts_with(&repo_root_real) {
-                    return Err(anyhow::anyhow!(
-                        "import outside repository root is not allowed: {} (repo root: {})",
-                        imp_real.display(),
-                        repo_root_real.display()
-                    ));
-                }
-
-                // For absolute paths, verify they are still within peter-hook dir after
-                // canonicalization (this protects against symlink attacks)
-                if is_absolute && !global_config.is_absolute_path_allowed(&imp_real)? {
-                    return Err(anyhow::anyhow!(
-                        "Import path resolves outside $HOME/.local/peter-hook (possible symlink): \
-                         {} -> {}",
-                        imp_path.display(),
-                        imp_real.display()
-                    ));
-                }
-
                 // Diagnostics: record import edge
                 if let Some(d) = diag.as_mut() {
                     d.imports.push(ImportRecord {
@@ -283,8 +1012,16 @@ impl HookConfig {
                     visited,
                     diag.as_deref_mut(),
                     !skip_git_for_import,
+                    project_dir,
                 )
-                .with_context(|| format!("Failed to import config: {imp}"))?;
+                .with_context(|| format!("Failed to import config: {import}"))?;
+                let mut imported_keys = imported.hook_keys;
+                if let Some(defaults) = imported.defaults {
+                    merged_defaults
+                        .get_or_insert_with(ConfigDefaults::default)
+                        .hook
+                        .overlay(defaults.hook);
+                }
                 if let Some(h) = imported.hooks {
                     for (k, v) in h {
                         if let Some(d) = diag.as_mut() {
@@ -295,6 +1032,7 @@ impl HookConfig {
                                     name: k.clone(),
                                     previous: prev_src,
                                     new: imp_real.display().to_string(),
+                                    changes: merged_hooks[&k].execution_changes(&v),
                                 });
                             } else {
                                 *d.contributions
@@ -303,6 +1041,7 @@ impl HookConfig {
                             }
                         }
                         hook_sources.insert(k.clone(), imp_real.display().to_string());
+                        merged_keys.insert(k.clone(), imported_keys.remove(&k).unwrap_or_default());
                         merged_hooks.insert(k, v);
                     }
                 }
@@ -316,6 +1055,7 @@ impl HookConfig {
                                     name: k.clone(),
                                     previous: prev_src,
                                     new: imp_real.display().to_string(),
+                                    changes: Vec::new(),
                                 });
                             } else {
                                 *d.contributions
@@ -331,6 +1071,13 @@ impl HookConfig {
         }
 
         // Overlay with local definitions (local overrides imports)
+        let mut local_keys = parsed.hook_keys;
+        if let Some(defaults) = parsed.defaults {
+            merged_defaults
+                .get_or_insert_with(ConfigDefaults::default)
+                .hook
+                .overlay(defaults.hook);
+        }
         if let Some(h) = parsed.hooks {
             for (k, v) in h {
                 if let Some(d) = diag.as_mut() {
@@ -340,6 +1087,7 @@ impl HookConfig {
                             name: k.clone(),
                             previous: prev_src,
                             new: path.display().to_string(),
+                            changes: merged_hooks[&k].execution_changes(&v),
                         });
                     } else {
                         *d.contributions
@@ -348,6 +1096,7 @@ impl HookConfig {
                     }
                 }
                 hook_sources.insert(k.clone(), path.display().to_string());
+                merged_keys.insert(k.clone(), local_keys.remove(&k).unwrap_or_default());
                 merged_hooks.insert(k, v);
             }
         }
@@ -360,6 +1109,7 @@ impl HookConfig {
                             name: k.clone(),
                             previous: prev_src,
                             new: path.display().to_string(),
+                            changes: Vec::new(),
                         });
                     } else {
                         *d.contributions
@@ -384,31 +1134,175 @@ impl HookConfig {
                 Some(merged_groups)
             },
             imports: None,
+            defaults: merged_defaults,
+            hook_keys: merged_keys,
         })
     }
 
-    /// Parse a hooks.toml configuration from a string
+    /// Apply `[defaults.hook]` to every hook
     ///
-    /// # Errors
+    /// Runs once imports are merged, so defaults from any file reach hooks
+    /// from every file. The defaults are consumed; the result reads the same
+    /// without them.
+    fn apply_defaults(&mut self) {
+        let hook_keys = std::mem::take(&mut self.hook_keys);
+        let Some(defaults) = self.defaults.take() else {
+            return;
+        };
+        let no_keys = HashSet::new();
+        for (name, hook) in self.hooks.iter_mut().flatten() {
+            defaults
+                .hook
+                .apply(hook, hook_keys.get(name).unwrap_or(&no_keys));
+        }
+    }
+
+    /// Apply the `hooks.local.toml` next to the config at `path`, if any
     ///
-    /// Returns an error if the TOML content cannot be parsed or validation
-    /// fails
-    pub fn parse(content: &str) -> Result<Self> {
-        let config: Self = toml::from_str(content).context("Failed to parse TOML configuration")?;
-        config.validate()?;
-        Ok(config)
+    /// Runs after imports are merged, so local changes win over every shared
+    /// file. Only the top-level config's own local file is read; imported
+    /// configs never bring theirs along.
+    fn apply_local_overrides(&mut self, path: &Path) -> Result<Option<LocalOverrideRecord>> {
+        let local_path = path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(LOCAL_CONFIG_FILE_NAME);
+        if !local_path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&local_path).with_context(|| {
+            format!(
+                "Failed to read local override file: {}",
+                local_path.display()
+            )
+        })?;
+        let overrides: LocalOverrides = toml::from_str(&content).with_context(|| {
+            format!(
+                "Failed to parse local override file: {}",
+                local_path.display()
+            )
+        })?;
+
+        let mut record = LocalOverrideRecord {
+            path: local_path.display().to_string(),
+            hooks: Vec::new(),
+        };
+        for (name, local) in overrides.hooks {
+            let Some(hook) = self.hooks.as_mut().and_then(|hooks| hooks.get_mut(&name)) else {
+                anyhow::bail!(
+                    "Local override file {} refers to unknown hook '{name}'",
+                    local_path.display()
+                );
+            };
+            if let Some(enabled) = local.enabled {
+                hook.enabled = enabled;
+            }
+            let env_keys = local.env.keys().cloned().collect();
+            hook.env.get_or_insert_with(HashMap::new).extend(local.env);
+            record.hooks.push(LocalHookRecord {
+                name,
+                enabled: local.enabled,
+                env: env_keys,
+            });
+        }
+        Ok(Some(record))
     }
 
-    /// Validate the configuration for consistency
+    /// Serialize the configuration back to TOML, as loaded by `from_file`
+    /// (imports merged and overrides applied)
+    ///
+    /// Groups using the deprecated `parallel` flag are written with the
+    /// equivalent `execution` setting so the output parses back to the same
+    /// behavior.
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - A hook has both `files` and `run_always = true` set (conflicting
-    ///   options)
-    /// - A hook uses `execution_type` = "per-file" or "in-place" with
-    ///   template variables like `{CHANGED_FILES}`
-    pub fn validate(&self) -> Result<()> {
+    /// Returns an error if the configuration cannot be serialized
+    pub fn to_toml(&self) -> Result<String> {
+        let mut config = self.clone();
+        if let Some(groups) = config.groups.as_mut() {
+            for group in groups.values_mut() {
+                if let Some(parallel) = group.parallel.take() {
+                    group.execution = Some(if parallel {
+                        ExecutionStrategy::Parallel
+                    } else {
+                        ExecutionStrategy::Sequential
+                    });
+                }
+            }
+        }
+        toml::to_string_pretty(&config).context("Failed to serialize configuration")
+    }
+
+    /// Parse a hooks.toml configuration from a string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TOML content cannot be parsed or validation
+    /// fails
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::parse_as(content, ConfigFormat::Toml)
+    }
+
+    /// Parse a hooks configuration in the given format from a string
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content is malformed or fails validation
+    pub fn parse_as(content: &str, format: ConfigFormat) -> Result<Self> {
+        let mut config = Self::deserialize_as(content, format)?;
+        config.apply_defaults();
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Deserialize a configuration and record the keys each hook sets,
+    /// leaving `[defaults]` unapplied
+    fn deserialize_as(content: &str, format: ConfigFormat) -> Result<Self> {
+        let (mut config, raw): (Self, RawHookKeys) = match format {
+            ConfigFormat::Toml => (
+                toml::from_str(content).context("Failed to parse TOML configuration")?,
+                toml::from_str(content).context("Failed to parse TOML configuration")?,
+            ),
+            ConfigFormat::Yaml => (
+                serde_yaml_ng::from_str(content).context("Failed to parse YAML configuration")?,
+                serde_yaml_ng::from_str(content).context("Failed to parse YAML configuration")?,
+            ),
+            ConfigFormat::Json => (
+                serde_json::from_str(content).context("Failed to parse JSON configuration")?,
+                serde_json::from_str(content).context("Failed to parse JSON configuration")?,
+            ),
+        };
+        config.hook_keys = raw.into_keys();
+        Ok(config)
+    }
+
+    /// Read and parse a single config file, without resolving its imports
+    /// or applying its defaults
+    fn read_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let config = Self::deserialize_as(&content, ConfigFormat::from_path(path))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Validate the configuration for consistency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A hook has both `files` and `run_always = true` set (conflicting
+    ///   options), or both `run_always` and `run_always_if_no_changes`
+    /// - A hook uses `execution_type` = "per-file" or "in-place" with
+    ///   template variables like `{CHANGED_FILES}`
+    /// - A hook's `container` has an empty `image`
+    /// - A hook's `steps` list is empty or nested, or `steps` is used outside
+    ///   a hook's `command`
+    /// - A hook has an invalid `fail_on`, `fail_on_allow` or `push_refs`
+    ///   pattern
+    pub fn validate(&self) -> Result<()> {
         if let Some(hooks) = &self.hooks {
             for (name, hook) in hooks {
                 // Check for conflicting files and run_always settings
@@ -419,13 +1313,70 @@ impl HookConfig {
                          for unconditional execution."
                     ));
                 }
+                if hook.run_always && hook.run_always_if_no_changes {
+                    anyhow::bail!(
+                        "Hook '{name}' sets both 'run_always' and 'run_always_if_no_changes'"
+                    );
+                }
+
+                if let Some(pattern) = &hook.workdir_glob {
+                    if hook.workdir.is_some() || hook.run_at_root {
+                        return Err(anyhow::anyhow!(
+                            "Hook '{name}' cannot combine 'workdir_glob' with 'workdir' or \
+                             'run_at_root = true'; each match is its working directory"
+                        ));
+                    }
+                    glob::Pattern::new(pattern.trim_start_matches("//")).with_context(|| {
+                        format!("Hook '{name}' has an invalid workdir_glob pattern")
+                    })?;
+                }
+
+                if let Some(container) = &hook.container {
+                    if container.image.trim().is_empty() {
+                        return Err(anyhow::anyhow!(
+                            "Hook '{name}' has a container with an empty image"
+                        ));
+                    }
+                }
+
+                for pattern in hook.push_refs.iter().flatten() {
+                    crate::git::compile_push_ref_pattern(pattern).with_context(|| {
+                        format!("Hook '{name}' has an invalid push_refs pattern")
+                    })?;
+                }
+
+                for (field, pattern) in [
+                    ("fail_on", &hook.fail_on),
+                    ("fail_on_allow", &hook.fail_on_allow),
+                ] {
+                    if let Some(pattern) = pattern {
+                        compile_output_pattern(pattern).with_context(|| {
+                            format!("Hook '{name}' has an invalid {field} pattern")
+                        })?;
+                    }
+                }
+
+                validate_steps(name, hook)?;
 
                 // Check for conflicting execution_type and template variable usage
                 if matches!(
                     hook.execution_type,
                     ExecutionType::PerFile | ExecutionType::InPlace
                 ) {
-                    let command_str = hook.command.to_string();
+                    // Display only shows the first line of a script, so check the
+                    // whole body of every command
+                    let command_str = std::iter::once(&hook.command)
+                        .chain(&hook.fix_command)
+                        .flat_map(|command| match command {
+                            HookCommand::Steps { steps } => steps.iter().collect(),
+                            command => vec![command],
+                        })
+                        .map(|command| match command {
+                            HookCommand::Script { script, .. } => script.clone(),
+                            command => command.to_string(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
                     if command_str.contains("{CHANGED_FILES}") {
                         return Err(anyhow::anyhow!(
                             "Hook '{}' with execution_type = '{}' should not use \
@@ -446,6 +1397,15 @@ impl HookConfig {
         // Validate groups
         if let Some(groups) = &self.groups {
             for (name, group) in groups {
+                for (field, command) in [("before", &group.before), ("after", &group.after)] {
+                    if matches!(command, Some(HookCommand::Steps { .. })) {
+                        return Err(anyhow::anyhow!(
+                            "Group '{name}' cannot use steps in {field}; steps are only supported \
+                             in a hook's command"
+                        ));
+                    }
+                }
+
                 // Check for conflicting placeholder and includes settings
                 if group.placeholder == Some(true) && !group.includes.is_empty() {
                     return Err(anyhow::anyhow!(
@@ -460,6 +1420,15 @@ impl HookConfig {
         Ok(())
     }
 
+    /// Validate the configuration after imports are merged
+    ///
+    /// Each file is validated as it is read; this catches problems only the
+    /// merged result has.
+    fn validate_merged(&self) -> Result<()> {
+        self.validate()
+            .context("Configuration is invalid after merging imports")
+    }
+
     /// Get all hook names defined in this configuration
     #[must_use]
     pub fn get_hook_names(&self) -> Vec<String> {
@@ -496,9 +1465,13 @@ pub struct ImportDiagnostics {
     pub cycles: Vec<String>,
     /// List of unused import declarations
     pub unused: Vec<String>,
+    /// Conditional imports left out because their condition didn't hold
+    pub skipped: Vec<SkippedImportRecord>,
     /// Count of contributions from each configuration source
     #[serde(skip)]
     pub contributions: HashMap<String, usize>,
+    /// Local override file applied last, if one exists
+    pub local_override: Option<LocalOverrideRecord>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -510,6 +1483,17 @@ pub struct ImportRecord {
     pub resolved: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+/// Record of a conditional import whose condition didn't hold
+pub struct SkippedImportRecord {
+    /// Directory of the file declaring the import
+    pub from: String,
+    /// The resolved absolute path of the file that was not imported
+    pub resolved: String,
+    /// The condition that didn't hold
+    pub when: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 /// Record of a configuration override during merging
 pub struct OverrideRecord {
@@ -521,20 +1505,205 @@ pub struct OverrideRecord {
     pub previous: String,
     /// The new configuration source that overrode the previous one
     pub new: String,
+    /// How the overriding hook runs differently from the one it replaced
+    /// (see [`HookDefinition::execution_changes`]); empty for groups
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<String>,
 }
 
-/// Find git repository root by walking up directories for config parsing
-fn find_git_root_for_config(start_dir: &Path) -> Result<PathBuf> {
-    let mut current = start_dir;
-    loop {
-        if current.join(".git").exists() {
-            return Ok(current.to_path_buf());
+#[derive(Debug, Clone, Serialize)]
+/// Record of a `hooks.local.toml` file applied on top of the configuration
+pub struct LocalOverrideRecord {
+    /// Path of the local override file
+    pub path: String,
+    /// Hooks it changed, in file order
+    pub hooks: Vec<LocalHookRecord>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+/// Changes a local override file made to one hook
+pub struct LocalHookRecord {
+    /// Name of the hook
+    pub name: String,
+    /// New `enabled` value, if the file sets one
+    pub enabled: Option<bool>,
+    /// Environment variables the file sets (names only)
+    pub env: Vec<String>,
+}
+
+/// Check a hook's use of `steps`: only its `command` and `fix_command` may be
+/// a non-empty list of steps, and steps can't be nested
+fn validate_steps(name: &str, hook: &HookDefinition) -> Result<()> {
+    for command in std::iter::once(&hook.command).chain(&hook.fix_command) {
+        let HookCommand::Steps { steps } = command else {
+            continue;
+        };
+        if steps.is_empty() {
+            return Err(anyhow::anyhow!("Hook '{name}' has an empty steps list"));
+        }
+        if steps
+            .iter()
+            .any(|step| matches!(step, HookCommand::Steps { .. }))
+        {
+            return Err(anyhow::anyhow!(
+                "Hook '{name}' has a step that is itself a list of steps"
+            ));
+        }
+    }
+    for (field, command) in [("skip_if", &hook.skip_if), ("on_failure", &hook.on_failure)] {
+        if matches!(command, Some(HookCommand::Steps { .. })) {
+            return Err(anyhow::anyhow!(
+                "Hook '{name}' cannot use steps in {field}; steps are only supported \
+                 in a hook's command"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Resolve an import entry to its canonical path, enforcing import security.
+///
+/// Relative imports must stay under `repo_root_real`; absolute imports (after
+/// tilde expansion) must be allowed by the global configuration. Returns the
+/// canonical path and whether the import was absolute.
+fn resolve_import(
+    imp: &str,
+    base_dir: &Path,
+    repo_root_real: &Path,
+    global_config: &GlobalConfig,
+) -> Result<(PathBuf, bool)> {
+    // Expand tilde in the import path
+    let expanded = shellexpand::tilde(imp);
+    let p = Path::new(&*expanded);
+    let (imp_path, is_absolute) = if p.is_absolute() {
+        // Check if absolute path is allowed via global config
+        if !global_config.is_absolute_path_allowed(p)? {
+            return Err(anyhow::anyhow!(
+                "Absolute import path not allowed: {imp}\nHint: Only imports from \
+                 $HOME/.local/peter-hook or security.trusted_import_dirs are allowed.\nEnable \
+                 with: peter-hook config init --allow-local"
+            ));
+        }
+        (p.to_path_buf(), true)
+    } else {
+        (base_dir.join(p), false)
+    };
+
+    let imp_real = imp_path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve import path: {}", imp_path.display()))?;
+
+    // Local overrides belong to one developer's checkout, not to the configs
+    // that might share them
+    if imp_real.file_name() == Some(LOCAL_CONFIG_FILE_NAME.as_ref()) {
+        return Err(anyhow::anyhow!(
+            "{LOCAL_CONFIG_FILE_NAME} holds local overrides and cannot be imported: {imp}"
+        ));
+    }
+
+    // Enforce import stays within repo root (but only for relative imports),
+    // unless it lands in a directory the user explicitly trusts
+    if !is_absolute
+        && !imp_real.starts_with(repo_root_real)
+        && !global_config.is_in_trusted_import_dir(&imp_real)
+    {
+        return Err(anyhow::anyhow!(
+            "import outside repository root is not allowed: {} (repo root: {})",
+            imp_real.display(),
+            repo_root_real.display()
+        ));
+    }
+
+    // For absolute paths, verify they are still within an allowed dir after
+    // canonicalization (this protects against symlink attacks)
+    if is_absolute && !global_config.is_absolute_path_allowed(&imp_real)? {
+        return Err(anyhow::anyhow!(
+            "Import path resolves outside allowed import directories (possible symlink): \
+             {} -> {}",
+            imp_path.display(),
+            imp_real.display()
+        ));
+    }
+
+    Ok((imp_real, is_absolute))
+}
+
+/// Resolve an `imports` entry to the canonical path of the file to load, or
+/// `None` (recorded in `diag`) when its condition doesn't hold
+///
+/// The source must be allowed whether or not the condition holds. Git imports
+/// are only fetched once it does, so skipped ones need no network access; their
+/// checkout is a git repository, which its own imports must stay inside.
+fn resolve_import_entry(
+    import: &Import,
+    base_dir: &Path,
+    repo_root_real: &Path,
+    project_dir: &Path,
+    global_config: &GlobalConfig,
+    diag: Option<&mut ImportDiagnostics>,
+) -> Result<Option<(PathBuf, bool)>> {
+    let skipped = |resolved: String| {
+        if let Some(d) = diag {
+            d.skipped.push(SkippedImportRecord {
+                from: base_dir.display().to_string(),
+                resolved,
+                when: import.condition().unwrap_or_default().to_string(),
+            });
         }
-        match current.parent() {
-            Some(parent) => current = parent,
-            None => return Err(anyhow::anyhow!("Not in a git repository")),
+        Ok(None)
+    };
+
+    if let Import::Git(git) = import {
+        check_import_host(&git.git, global_config)?;
+        if !import_condition_met(import, project_dir)? {
+            return skipped(import.to_string());
         }
+        return Ok(Some((GitImportCache::open_default()?.fetch(git)?, false)));
+    }
+
+    let resolved = resolve_import(import.path(), base_dir, repo_root_real, global_config)?;
+    if !import_condition_met(import, project_dir)? {
+        return skipped(resolved.0.display().to_string());
     }
+    Ok(Some(resolved))
+}
+
+/// Fetch a git import into the default cache (once) and return the local
+/// path of the file it names, after checking its host is allowed
+fn fetch_git_import(import: &GitImport, global_config: &GlobalConfig) -> Result<PathBuf> {
+    check_import_host(&import.git, global_config)?;
+    GitImportCache::open_default()?.fetch(import)
+}
+
+/// Whether `import` applies: unconditional imports always do, conditional
+/// ones when their condition holds for `project_dir`
+///
+/// # Errors
+///
+/// Returns an error if the condition is invalid
+fn import_condition_met(import: &Import, project_dir: &Path) -> Result<bool> {
+    let Some(when) = import.condition() else {
+        return Ok(true);
+    };
+    let met = condition_met(when, project_dir)
+        .with_context(|| format!("Failed to import config: {import}"))?;
+    log::debug!("import condition import={import} when={when} met={met}");
+    Ok(met)
+}
+
+/// Find the repository root that imports must stay inside
+///
+/// Uses git's own discovery so `.git` files (submodules, linked worktrees)
+/// resolve to the checkout containing the config. Bare repositories have no
+/// working tree, so their git directory is the root.
+fn find_git_root_for_config(start_dir: &Path) -> Result<PathBuf> {
+    let start_dir = if start_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        start_dir
+    };
+    let repo = git2::Repository::discover(start_dir).context("Not in a git repository")?;
+    Ok(repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf())
 }
 
 #[cfg(test)]
@@ -565,6 +1734,95 @@ description = "A simple test hook"
         assert!(hook.files.is_none()); // Default should be None
         assert!(!hook.run_always); // Default should be false
         assert!(hook.depends_on.is_none()); // Default should be None
+        assert_eq!(hook.priority, 0); // Default should be 0
+        assert!(!hook.combined_output); // Default should be false
+    }
+
+    #[test]
+    fn test_parse_priority() {
+        let toml = r#"
+[hooks.slow]
+command = "cargo test"
+priority = 10
+
+[hooks.last]
+command = "echo done"
+priority = -5
+"#;
+
+        let hooks = HookConfig::parse(toml).unwrap().hooks.unwrap();
+        assert_eq!(hooks["slow"].priority, 10);
+        assert_eq!(hooks["last"].priority, -5);
+    }
+
+    #[test]
+    fn test_parse_container() {
+        let toml = r#"
+[hooks.fmt]
+command = "cargo fmt --check"
+container = { image = "rust:1.86", mounts = ["/home/me/.cargo:/usr/local/cargo:ro"] }
+
+[hooks.host]
+command = "echo host"
+"#;
+
+        let hooks = HookConfig::parse(toml).unwrap().hooks.unwrap();
+        assert_eq!(
+            hooks["fmt"].container,
+            Some(ContainerSpec {
+                image: "rust:1.86".to_string(),
+                mounts: vec!["/home/me/.cargo:/usr/local/cargo:ro".to_string()],
+                runtime: None,
+            })
+        );
+        assert_eq!(hooks["host"].container, None);
+
+        let err = HookConfig::parse(
+            r#"
+[hooks.fmt]
+command = "cargo fmt --check"
+container = { image = " " }
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("empty image"), "{err}");
+    }
+
+    #[test]
+    fn test_parse_push_refs() {
+        let hooks = HookConfig::parse(
+            r#"
+[hooks.integration]
+command = "make integration"
+push_refs = ["refs/heads/main", "refs/heads/release/*"]
+"#,
+        )
+        .unwrap()
+        .hooks
+        .unwrap();
+        assert_eq!(
+            hooks["integration"].push_refs.as_deref(),
+            Some(
+                &[
+                    "refs/heads/main".to_string(),
+                    "refs/heads/release/*".to_string()
+                ][..]
+            )
+        );
+
+        let err = HookConfig::parse(
+            r#"
+[hooks.integration]
+command = "make integration"
+push_refs = ["refs/heads/[main"]
+"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Hook 'integration' has an invalid push_refs pattern"),
+            "{err}"
+        );
     }
 
     #[test]
@@ -591,6 +1849,106 @@ command = ["cargo", "clippy", "--all-targets", "--", "-D", "warnings"]
         );
     }
 
+    #[test]
+    fn test_parse_script_command() {
+        let toml = r#"
+[hooks.setup]
+command = { script = """
+set -e
+echo one
+echo two
+""" }
+
+[hooks.setup-bash.command]
+script = "echo bash"
+shell = "bash -eu"
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let hooks = config.hooks.unwrap();
+
+        assert_eq!(
+            hooks["setup"].command,
+            HookCommand::Script {
+                script: "set -e\necho one\necho two\n".to_string(),
+                shell: None,
+            }
+        );
+        assert_eq!(hooks["setup"].command.to_string(), "sh script: set -e ...");
+        assert_eq!(
+            hooks["setup-bash"].command,
+            HookCommand::Script {
+                script: "echo bash".to_string(),
+                shell: Some("bash -eu".to_string()),
+            }
+        );
+        assert_eq!(
+            hooks["setup-bash"].command.to_string(),
+            "bash -eu script: echo bash"
+        );
+    }
+
+    #[test]
+    fn test_validation_rejects_per_file_script_with_changed_files_template() {
+        let toml = r#"
+[hooks.bad-hook]
+command = { script = """
+echo start
+eslint {CHANGED_FILES}
+""" }
+files = ["**/*.js"]
+"#;
+
+        let err = HookConfig::parse(toml).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("should not use {CHANGED_FILES} template variables")
+        );
+    }
+
+    #[test]
+    fn test_to_toml_round_trips() {
+        let toml = r#"
+[hooks.lint]
+command = ["cargo", "clippy"]
+env = { B = "2", A = "1" }
+on_failure = "echo fix it"
+
+[hooks.setup]
+command = { script = "echo one\necho two\n", shell = "bash" }
+execution_type = "in-place"
+
+[groups.legacy]
+includes = ["lint"]
+parallel = true
+
+[groups.check]
+includes = ["setup", "lint"]
+before = "echo start"
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let explained = config.to_toml().unwrap();
+        let reparsed = HookConfig::parse(&explained).unwrap();
+
+        assert_eq!(reparsed.hooks, config.hooks);
+        let groups = config.groups.as_ref().unwrap();
+        let reparsed_groups = reparsed.groups.as_ref().unwrap();
+        for (name, group) in groups {
+            let reparsed_group = &reparsed_groups[name];
+            assert_eq!(reparsed_group.includes, group.includes);
+            assert_eq!(reparsed_group.before, group.before);
+            assert_eq!(
+                reparsed_group.get_execution_strategy(ExecutionStrategy::Sequential),
+                group.get_execution_strategy(ExecutionStrategy::Sequential)
+            );
+        }
+        // Maps are written in sorted order, so the output is stable
+        assert!(explained.find("[groups.check]") < explained.find("[groups.legacy]"));
+        assert!(explained.contains("[hooks.lint.env]\nA = \"1\"\nB = \"2\"\n"));
+        assert_eq!(reparsed.to_toml().unwrap(), explained);
+    }
+
     #[test]
     fn test_parse_hook_group() {
         let toml = r#"
@@ -608,7 +1966,10 @@ parallel = true
 
         let group = &groups["python-lint"];
         assert_eq!(group.includes, vec!["python.ruff", "python.type-check"]);
-        assert_eq!(group.get_execution_strategy(), ExecutionStrategy::Parallel);
+        assert_eq!(
+            group.get_execution_strategy(ExecutionStrategy::default()),
+            ExecutionStrategy::Parallel
+        );
     }
 
     #[test]
@@ -648,32 +2009,176 @@ modifies_repository = true
     }
 
     #[test]
-    fn test_imports_merge_and_override() {
+    fn test_yaml_and_json_match_toml() {
+        let toml = r#"
+[hooks.lint]
+command = ["cargo", "clippy"]
+description = "Lint"
+files = ["**/*.rs"]
+execution_type = "in-place"
+env = { RUST_LOG = "warn" }
+tags = ["rust"]
+
+[hooks.check]
+command = { script = "set -e\ncargo check", shell = "bash" }
+depends_on = ["lint"]
+
+[groups.pre-commit]
+includes = ["lint", "check"]
+execution = "parallel"
+"#;
+        let yaml = r#"
+hooks:
+  lint:
+    command: [cargo, clippy]
+    description: Lint
+    files: ["**/*.rs"]
+    execution_type: in-place
+    env:
+      RUST_LOG: warn
+    tags: [rust]
+  check:
+    command:
+      script: |-
+        set -e
+        cargo check
+      shell: bash
+    depends_on: [lint]
+groups:
+  pre-commit:
+    includes: [lint, check]
+    execution: parallel
+"#;
+        let json = r#"{
+  "hooks": {
+    "lint": {
+      "command": ["cargo", "clippy"],
+      "description": "Lint",
+      "files": ["**/*.rs"],
+      "execution_type": "in-place",
+      "env": { "RUST_LOG": "warn" },
+      "tags": ["rust"]
+    },
+    "check": {
+      "command": { "script": "set -e\ncargo check", "shell": "bash" },
+      "depends_on": ["lint"]
+    }
+  },
+  "groups": {
+    "pre-commit": { "includes": ["lint", "check"], "execution": "parallel" }
+  }
+}"#;
+
+        let expected = HookConfig::parse(toml).unwrap();
+        assert_eq!(
+            HookConfig::parse_as(yaml, ConfigFormat::Yaml).unwrap(),
+            expected
+        );
+        assert_eq!(
+            HookConfig::parse_as(json, ConfigFormat::Json).unwrap(),
+            expected
+        );
+
+        // The `[[hooks]]` array form works too
+        let yaml_array = "hooks:\n  - name: lint\n    command: cargo clippy\n";
+        let config = HookConfig::parse_as(yaml_array, ConfigFormat::Yaml).unwrap();
+        assert!(config.hooks.unwrap().contains_key("lint"));
+
+        let err = HookConfig::parse_as("hooks: [", ConfigFormat::Yaml).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Failed to parse YAML configuration")
+        );
+    }
+
+    #[test]
+    fn test_config_format_from_path() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("hooks.yaml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("lib/common.yml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("hooks.json")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("hooks.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("hooks.lib")),
+            ConfigFormat::Toml
+        );
+    }
+
+    #[test]
+    fn test_imports_mix_formats() {
         use std::fs;
         use tempfile::TempDir;
         let td = TempDir::new().unwrap();
         let dir = td.path();
-        // Simulate git repo root
-        std::fs::create_dir_all(dir.join(".git")).unwrap();
-        let lib = dir.join("hooks.lib.toml");
-        let base = dir.join("hooks.toml");
+        git2::Repository::init(dir).unwrap();
 
         fs::write(
-            &lib,
-            r#"
-[hooks.lint]
-command = "echo lib-lint"
-
-[groups.common]
-includes = ["lint"]
-"#,
+            dir.join("lint.yaml"),
+            "hooks:\n  lint:\n    command: cargo clippy\n",
         )
         .unwrap();
-
+        fs::write(
+            dir.join("test.json"),
+            r#"{"hooks": {"test": {"command": "cargo test"}}}"#,
+        )
+        .unwrap();
+        let base = dir.join("hooks.yml");
         fs::write(
             &base,
-            r#"
-imports = ["hooks.lib.toml"]
+            "imports: [lint.yaml, test.json]\ngroups:\n  pre-commit:\n    includes: [lint, test]\n",
+        )
+        .unwrap();
+
+        let config = HookConfig::from_file(&base).unwrap();
+        let hooks = config.hooks.unwrap();
+        assert_eq!(
+            hooks["lint"].command,
+            HookCommand::Shell("cargo clippy".to_string())
+        );
+        assert_eq!(
+            hooks["test"].command,
+            HookCommand::Shell("cargo test".to_string())
+        );
+        assert!(config.groups.unwrap().contains_key("pre-commit"));
+    }
+
+    #[test]
+    fn test_imports_merge_and_override() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        let lib = dir.join("hooks.lib.toml");
+        let base = dir.join("hooks.toml");
+
+        fs::write(
+            &lib,
+            r#"
+[hooks.lint]
+command = "echo lib-lint"
+
+[groups.common]
+includes = ["lint"]
+"#,
+        )
+        .unwrap();
+
+        fs::write(
+            &base,
+            r#"
+imports = ["hooks.lib.toml"]
 
 [hooks.lint]
 command = "echo local-lint"  # override
@@ -698,8 +2203,393 @@ includes = ["common", "lint", "test"]
         // local override should win
         match &hooks["lint"].command {
             HookCommand::Shell(s) => assert_eq!(s, "echo local-lint"),
-            HookCommand::Args(_) => panic!("expected shell"),
+            _ => panic!("expected shell"),
+        }
+    }
+
+    #[test]
+    fn test_git_imports_parse() {
+        let cfg: HookConfig = toml::from_str(
+            r#"
+imports = [
+    { git = "https://github.com/org/hooks", ref = "v1" },
+    { git = "git@github.com:org/hooks", ref = "main", path = "rust.toml", when = "exists:Cargo.toml" },
+]
+"#,
+        )
+        .unwrap();
+        let imports = cfg.imports.unwrap();
+        assert_eq!(
+            imports[0],
+            Import::Git(GitImport {
+                git: "https://github.com/org/hooks".to_string(),
+                git_ref: "v1".to_string(),
+                path: "hooks.toml".to_string(),
+                when: None,
+            })
+        );
+        assert_eq!(
+            imports[0].to_string(),
+            "hooks.toml from https://github.com/org/hooks at v1"
+        );
+        assert_eq!(imports[1].path(), "rust.toml");
+        assert_eq!(imports[1].condition(), Some("exists:Cargo.toml"));
+
+        // A git import needs a ref to pin
+        assert!(
+            toml::from_str::<HookConfig>("imports = [{ git = \"https://github.com/org/hooks\" }]")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_conditional_imports() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = &td.path().join("repo");
+        git2::Repository::init(dir).unwrap();
+        fs::create_dir(dir.join("lib")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "").unwrap();
+        for lang in ["rust", "node", "ci", "local"] {
+            fs::write(
+                dir.join(format!("lib/{lang}.toml")),
+                format!("[hooks.{lang}]\ncommand = \"echo {lang}\"\n"),
+            )
+            .unwrap();
+        }
+        // Conditions are checked against the project even in nested imports
+        fs::write(
+            dir.join("lib/all.toml"),
+            r#"
+imports = [
+    { path = "rust.toml", when = "exists:Cargo.toml" },
+    { path = "node.toml", when = "exists:package.json" },
+]
+"#,
+        )
+        .unwrap();
+        let base = dir.join("hooks.toml");
+        fs::write(
+            &base,
+            r#"
+imports = [
+    "lib/all.toml",
+    { path = "lib/ci.toml", when = "env:PATH" },
+    { path = "lib/local.toml", when = "env:PETER_HOOK_TEST_UNSET_VARIABLE" },
+]
+"#,
+        )
+        .unwrap();
+
+        let (cfg, diag) = HookConfig::from_file_with_trace(&base).unwrap();
+        let hooks = cfg.hooks.unwrap();
+        let names: Vec<&String> = hooks.keys().collect();
+        assert_eq!(names, ["rust", "ci"]);
+        let skipped: Vec<(&str, &str)> = diag
+            .skipped
+            .iter()
+            .map(|s| (s.resolved.rsplit('/').next().unwrap(), s.when.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [
+                ("node.toml", "exists:package.json"),
+                ("local.toml", "env:PETER_HOOK_TEST_UNSET_VARIABLE")
+            ]
+        );
+
+        // The path rules apply even when the condition doesn't hold
+        fs::write(
+            &base,
+            "imports = [{ path = \"../outside.toml\", when = \"exists:nothing\" }]\n",
+        )
+        .unwrap();
+        fs::write(td.path().join("outside.toml"), "").unwrap();
+        let err = HookConfig::from_file(&base).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("outside repository root"),
+            "{err:#}"
+        );
+
+        fs::write(
+            &base,
+            "imports = [{ path = \"lib/ci.toml\", when = \"os:linux\" }]\n",
+        )
+        .unwrap();
+        let err = HookConfig::from_file(&base).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Invalid import condition"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_local_overrides_apply_last() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        fs::write(
+            dir.join("hooks.lib.toml"),
+            r#"
+[hooks.lint]
+command = "echo lib-lint"
+env = { LEVEL = "lib" }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("hooks.toml"),
+            r#"
+imports = ["hooks.lib.toml"]
+
+[hooks.test]
+command = "echo test"
+env = { LEVEL = "shared", CI = "1" }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE_NAME),
+            r#"
+[hooks.lint]
+enabled = false
+
+[hooks.test]
+env = { LEVEL = "local", DEBUG = "1" }
+"#,
+        )
+        .unwrap();
+
+        let (cfg, diag) = HookConfig::from_file_with_trace(dir.join("hooks.toml")).unwrap();
+        let hooks = cfg.hooks.unwrap();
+        assert!(!hooks["lint"].enabled, "imported hook disabled locally");
+        assert!(hooks["test"].enabled);
+        let env = hooks["test"].env.as_ref().unwrap();
+        assert_eq!(env["LEVEL"], "local", "local env wins over the config");
+        assert_eq!(env["CI"], "1");
+        assert_eq!(env["DEBUG"], "1");
+
+        let local = diag.local_override.unwrap();
+        assert_eq!(
+            local.path,
+            dir.join(LOCAL_CONFIG_FILE_NAME).display().to_string()
+        );
+        assert_eq!(local.hooks.len(), 2);
+        assert_eq!(local.hooks[0].enabled, Some(false));
+        assert_eq!(local.hooks[1].env, ["LEVEL", "DEBUG"]);
+
+        // Disabling survives a round trip through the merged config
+        let explained = HookConfig::from_file(dir.join("hooks.toml"))
+            .unwrap()
+            .to_toml()
+            .unwrap();
+        assert!(explained.contains("enabled = false"), "{explained}");
+    }
+
+    #[test]
+    fn test_hook_defaults_inherited_and_overridden() {
+        let config = HookConfig::parse(
+            r#"
+[defaults.hook]
+modifies_repository = true
+execution_type = "in-place"
+env = { CI = "1", LEVEL = "default" }
+tags = ["shared"]
+
+[hooks.inherits]
+command = "echo inherits"
+
+[hooks.overrides]
+command = "echo overrides"
+modifies_repository = false
+execution_type = "other"
+env = { LEVEL = "hook" }
+tags = ["own"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.defaults, None, "defaults are consumed once applied");
+
+        let hooks = config.hooks.unwrap();
+        let inherits = &hooks["inherits"];
+        assert!(inherits.modifies_repository);
+        assert_eq!(inherits.execution_type, ExecutionType::InPlace);
+        assert_eq!(inherits.tags, Some(vec!["shared".to_string()]));
+        assert_eq!(
+            inherits.env.as_ref().unwrap()["LEVEL"],
+            "default".to_string()
+        );
+
+        let overrides = &hooks["overrides"];
+        assert!(
+            !overrides.modifies_repository,
+            "an explicit false beats the default"
+        );
+        assert_eq!(overrides.execution_type, ExecutionType::Other);
+        assert_eq!(overrides.tags, Some(vec!["own".to_string()]));
+        let env = overrides.env.as_ref().unwrap();
+        assert_eq!(env["LEVEL"], "hook");
+        assert_eq!(env["CI"], "1", "env is merged with the defaults");
+
+        let array_form = HookConfig::parse(
+            r#"
+[defaults.hook]
+run_at_root = true
+
+[[hooks]]
+name = "first"
+command = "true"
+
+[[hooks]]
+name = "second"
+command = "true"
+run_at_root = false
+"#,
+        )
+        .unwrap()
+        .hooks
+        .unwrap();
+        assert!(array_form["first"].run_at_root);
+        assert!(!array_form["second"].run_at_root);
+
+        let err = HookConfig::parse(
+            "[defaults.hook]
+command = \"true\"\n",
+        )
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown field `command`"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_hook_defaults_apply_after_imports() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        fs::write(
+            dir.join("hooks.lib.toml"),
+            r#"
+[defaults.hook]
+fail_on = "^WARNING:"
+priority = 1
+
+[hooks.lint]
+command = "echo lint"
+priority = 5
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("hooks.toml"),
+            r#"
+imports = ["hooks.lib.toml"]
+
+[defaults.hook]
+priority = 2
+env = { CI = "1" }
+
+[hooks.test]
+command = "echo test"
+"#,
+        )
+        .unwrap();
+
+        let hooks = HookConfig::from_file(dir.join("hooks.toml"))
+            .unwrap()
+            .hooks
+            .unwrap();
+        for name in ["lint", "test"] {
+            let hook = &hooks[name];
+            assert_eq!(hook.fail_on.as_deref(), Some("^WARNING:"), "{name}");
+            assert_eq!(hook.env.as_ref().unwrap()["CI"], "1", "{name}");
         }
+        assert_eq!(hooks["lint"].priority, 5, "the hook's own value wins");
+        assert_eq!(
+            hooks["test"].priority, 2,
+            "the importing file's defaults win over imported ones"
+        );
+    }
+
+    #[test]
+    fn test_local_overrides_are_not_imported() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        let sub = dir.join("sub");
+        fs::create_dir(&sub).unwrap();
+        fs::write(
+            sub.join("lib.toml"),
+            "[hooks.lint]\ncommand = \"echo lint\"\n",
+        )
+        .unwrap();
+        fs::write(
+            sub.join(LOCAL_CONFIG_FILE_NAME),
+            "[hooks.lint]\nenabled = false\n",
+        )
+        .unwrap();
+
+        // The local file next to an imported config doesn't travel with it
+        fs::write(dir.join("hooks.toml"), "imports = [\"sub/lib.toml\"]\n").unwrap();
+        let cfg = HookConfig::from_file(dir.join("hooks.toml")).unwrap();
+        assert!(cfg.hooks.unwrap()["lint"].enabled);
+
+        // ...and can't be imported directly
+        fs::write(
+            dir.join("hooks.toml"),
+            "imports = [\"sub/hooks.local.toml\"]\n",
+        )
+        .unwrap();
+        let err = HookConfig::from_file(dir.join("hooks.toml")).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("hooks.local.toml holds local overrides"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_local_overrides_reject_unknown_hooks_and_fields() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        fs::write(
+            dir.join("hooks.toml"),
+            "[hooks.lint]\ncommand = \"echo lint\"\n",
+        )
+        .unwrap();
+
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE_NAME),
+            "[hooks.fmt]\nenabled = false\n",
+        )
+        .unwrap();
+        let err = HookConfig::from_file(dir.join("hooks.toml")).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("refers to unknown hook 'fmt'"),
+            "{err:#}"
+        );
+
+        // Local files change hooks, they don't redefine them
+        fs::write(
+            dir.join(LOCAL_CONFIG_FILE_NAME),
+            "[hooks.lint]\ncommand = \"true\"\n",
+        )
+        .unwrap();
+        let err = HookConfig::from_file(dir.join("hooks.toml")).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Failed to parse local override file"),
+            "{err:#}"
+        );
     }
 
     #[test]
@@ -708,7 +2598,7 @@ includes = ["common", "lint", "test"]
         use tempfile::TempDir;
         let td = TempDir::new().unwrap();
         let dir = td.path();
-        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        git2::Repository::init(dir).unwrap();
         let a = dir.join("a.toml");
         let b = dir.join("b.toml");
         fs::write(
@@ -728,42 +2618,206 @@ includes = ["common", "lint", "test"]
         )
         .unwrap();
 
-        let cfg = HookConfig::from_file(&a).unwrap();
-        let names = cfg.get_hook_names();
-        assert!(names.contains(&"a".to_string()));
-        assert!(names.contains(&"b".to_string()));
+        let cfg = HookConfig::from_file(&a).unwrap();
+        let names = cfg.get_hook_names();
+        assert!(names.contains(&"a".to_string()));
+        assert!(names.contains(&"b".to_string()));
+    }
+
+    #[test]
+    fn test_imports_reject_absolute_outside_home() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        let base = dir.join("hooks.toml");
+        fs::write(&base, "imports = [\"/etc/passwd\"]\n").unwrap();
+        let err = HookConfig::from_file(&base).unwrap_err();
+        // Now we expect it to be rejected because it's not in home directory/allowlist
+        assert!(format!("{err:#}").contains("Absolute import path not allowed"));
+    }
+
+    #[test]
+    fn test_imports_reject_outside_repo_root() {
+        use std::fs;
+        use tempfile::TempDir;
+        let outer = TempDir::new().unwrap();
+        let outer_dir = outer.path();
+        // repo root
+        git2::Repository::init(outer_dir.join("repo")).unwrap();
+        // file outside repo
+        let outside = outer_dir.join("evil.toml");
+        fs::write(&outside, "[hooks.bad]\ncommand=\"echo bad\"\n").unwrap();
+        // hooks.toml at repo root trying to import ../evil.toml
+        let base = outer_dir.join("repo/hooks.toml");
+        fs::write(&base, "imports = [\"../evil.toml\"]\n").unwrap();
+        let err = HookConfig::from_file(&base).unwrap_err();
+        assert!(format!("{err:#}").contains("outside repository root"));
+    }
+
+    #[test]
+    fn test_imports_from_trusted_dir_outside_repo() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let meta_dir = td.path().canonicalize().unwrap();
+        let shared = meta_dir.join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(
+            shared.join("common.toml"),
+            "[hooks.common]\ncommand = \"echo\"\n",
+        )
+        .unwrap();
+        let repo_root = meta_dir.join("service");
+        git2::Repository::init(&repo_root).unwrap();
+
+        let mut global_config = GlobalConfig::default();
+        let relative = "../shared/common.toml";
+        let err = resolve_import(relative, &repo_root, &repo_root, &global_config).unwrap_err();
+        assert!(format!("{err:#}").contains("outside repository root"));
+        let absolute = shared.join("common.toml").display().to_string();
+        let err = resolve_import(&absolute, &repo_root, &repo_root, &global_config).unwrap_err();
+        assert!(format!("{err:#}").contains("Absolute import path not allowed"));
+
+        global_config.security.trusted_import_dirs = vec![shared.clone()];
+        let (resolved, is_absolute) =
+            resolve_import(relative, &repo_root, &repo_root, &global_config).unwrap();
+        assert_eq!(resolved, shared.join("common.toml"));
+        assert!(!is_absolute);
+        let (resolved, is_absolute) =
+            resolve_import(&absolute, &repo_root, &repo_root, &global_config).unwrap();
+        assert_eq!(resolved, shared.join("common.toml"));
+        assert!(is_absolute);
+
+        // Trusting one directory does not open up its siblings
+        fs::write(meta_dir.join("other.toml"), "").unwrap();
+        let err =
+            resolve_import("../other.toml", &repo_root, &repo_root, &global_config).unwrap_err();
+        assert!(format!("{err:#}").contains("outside repository root"));
+    }
+
+    #[test]
+    fn test_imports_contained_to_submodule_root() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let outer_dir = td.path();
+        git2::Repository::init(outer_dir).unwrap();
+
+        // Submodule layout: the checkout has a `.git` file pointing into the
+        // superproject's `.git/modules`
+        let sub_dir = outer_dir.join("vendor/sub");
+        fs::create_dir_all(&sub_dir).unwrap();
+        git2::Repository::init_opts(
+            outer_dir.join(".git/modules/sub"),
+            git2::RepositoryInitOptions::new().workdir_path(&sub_dir),
+        )
+        .unwrap();
+        assert!(sub_dir.join(".git").is_file());
+
+        fs::write(
+            sub_dir.join("lib.toml"),
+            "[hooks.lib]\ncommand = \"echo lib\"\n",
+        )
+        .unwrap();
+        fs::write(
+            outer_dir.join("outer.toml"),
+            "[hooks.outer]\ncommand = \"echo\"\n",
+        )
+        .unwrap();
+
+        let inside = sub_dir.join("hooks.toml");
+        fs::write(&inside, "imports = [\"lib.toml\"]\n").unwrap();
+        let cfg = HookConfig::from_file(&inside).unwrap();
+        assert!(cfg.hooks.unwrap().contains_key("lib"));
+
+        let escaping = sub_dir.join("escape.toml");
+        fs::write(&escaping, "imports = [\"../../outer.toml\"]\n").unwrap();
+        let err = HookConfig::from_file(&escaping).unwrap_err();
+        assert!(format!("{err:#}").contains("outside repository root"));
+    }
+
+    #[test]
+    fn test_imports_contained_to_worktree_root() {
+        use std::fs;
+        use tempfile::TempDir;
+        let td = TempDir::new().unwrap();
+        let main_dir = td.path().join("main");
+        let repo = git2::Repository::init(&main_dir).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+
+        let wt_dir = td.path().join("wt");
+        repo.worktree("wt", &wt_dir, None).unwrap();
+        assert!(wt_dir.join(".git").is_file());
+
+        fs::create_dir_all(wt_dir.join("lib")).unwrap();
+        fs::write(
+            wt_dir.join("lib/common.toml"),
+            "[hooks.common]\ncommand = \"echo\"\n",
+        )
+        .unwrap();
+        fs::write(
+            main_dir.join("main.toml"),
+            "[hooks.main]\ncommand = \"echo\"\n",
+        )
+        .unwrap();
+
+        let inside = wt_dir.join("hooks.toml");
+        fs::write(&inside, "imports = [\"lib/common.toml\"]\n").unwrap();
+        let cfg = HookConfig::from_file(&inside).unwrap();
+        assert!(cfg.hooks.unwrap().contains_key("common"));
+
+        // The main checkout is a different working tree
+        let escaping = wt_dir.join("escape.toml");
+        fs::write(&escaping, "imports = [\"../main/main.toml\"]\n").unwrap();
+        let err = HookConfig::from_file(&escaping).unwrap_err();
+        assert!(format!("{err:#}").contains("outside repository root"));
     }
 
     #[test]
-    fn test_imports_reject_absolute_outside_home() {
+    fn test_imports_in_bare_repository() {
         use std::fs;
         use tempfile::TempDir;
         let td = TempDir::new().unwrap();
-        let dir = td.path();
-        std::fs::create_dir_all(dir.join(".git")).unwrap();
-        let base = dir.join("hooks.toml");
-        fs::write(&base, "imports = [\"/etc/passwd\"]\n").unwrap();
-        let err = HookConfig::from_file(&base).unwrap_err();
-        // Now we expect it to be rejected because it's not in home directory/allowlist
-        assert!(format!("{err:#}").contains("Absolute import path not allowed"));
+        let bare_dir = td.path().join("repo.git");
+        git2::Repository::init_bare(&bare_dir).unwrap();
+
+        fs::write(
+            bare_dir.join("lib.toml"),
+            "[hooks.lib]\ncommand = \"echo\"\n",
+        )
+        .unwrap();
+        let base = bare_dir.join("hooks.toml");
+        fs::write(&base, "imports = [\"lib.toml\"]\n").unwrap();
+
+        let cfg = HookConfig::from_file(&base).unwrap();
+        assert!(cfg.hooks.unwrap().contains_key("lib"));
     }
 
     #[test]
-    fn test_imports_reject_outside_repo_root() {
+    fn test_check_imports_reports_each_import() {
         use std::fs;
         use tempfile::TempDir;
-        let outer = TempDir::new().unwrap();
-        let outer_dir = outer.path();
-        // repo root
-        std::fs::create_dir_all(outer_dir.join("repo/.git")).unwrap();
-        // file outside repo
-        let outside = outer_dir.join("evil.toml");
-        fs::write(&outside, "[hooks.bad]\ncommand=\"echo bad\"\n").unwrap();
-        // hooks.toml at repo root trying to import ../evil.toml
-        let base = outer_dir.join("repo/hooks.toml");
-        fs::write(&base, "imports = [\"../evil.toml\"]\n").unwrap();
-        let err = HookConfig::from_file(&base).unwrap_err();
-        assert!(format!("{err:#}").contains("outside repository root"));
+        let td = TempDir::new().unwrap();
+        let dir = td.path();
+        git2::Repository::init(dir).unwrap();
+        fs::write(dir.join("ok.toml"), "[hooks.ok]\ncommand=\"echo ok\"\n").unwrap();
+        let base = dir.join("hooks.toml");
+        fs::write(&base, "imports = [\"ok.toml\", \"missing.toml\"]\n").unwrap();
+
+        let results = HookConfig::check_imports(&base).unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, "ok.toml");
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, "missing.toml");
+        let err = results[1].1.as_ref().unwrap_err();
+        assert!(format!("{err:#}").contains("Failed to resolve import path"));
     }
 
     #[test]
@@ -790,21 +2844,61 @@ parallel = true
         let groups = config.groups.unwrap();
 
         assert_eq!(
-            groups["sequential"].get_execution_strategy(),
+            groups["sequential"].get_execution_strategy(ExecutionStrategy::default()),
             ExecutionStrategy::Sequential
         );
         assert_eq!(
-            groups["parallel"].get_execution_strategy(),
+            groups["parallel"].get_execution_strategy(ExecutionStrategy::default()),
             ExecutionStrategy::Parallel
         );
         assert_eq!(
-            groups["force-parallel"].get_execution_strategy(),
+            groups["force-parallel"].get_execution_strategy(ExecutionStrategy::default()),
             ExecutionStrategy::ForceParallel
         );
         assert_eq!(
-            groups["backward-compat"].get_execution_strategy(),
+            groups["backward-compat"].get_execution_strategy(ExecutionStrategy::default()),
+            ExecutionStrategy::Parallel
+        );
+    }
+
+    #[test]
+    fn test_execution_strategy_global_default_precedence() {
+        let toml = r#"
+[hooks.test1]
+command = "echo test1"
+
+[groups.unset]
+includes = ["test1"]
+
+[groups.explicit]
+includes = ["test1"]
+execution = "sequential"
+
+[groups.legacy]
+includes = ["test1"]
+parallel = false
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let groups = config.groups.unwrap();
+
+        assert_eq!(groups["unset"].execution, None);
+        assert_eq!(
+            groups["unset"].get_execution_strategy(ExecutionStrategy::Parallel),
             ExecutionStrategy::Parallel
         );
+        assert_eq!(
+            groups["unset"].get_execution_strategy(ExecutionStrategy::default()),
+            ExecutionStrategy::Sequential
+        );
+        assert_eq!(
+            groups["explicit"].get_execution_strategy(ExecutionStrategy::Parallel),
+            ExecutionStrategy::Sequential
+        );
+        assert_eq!(
+            groups["legacy"].get_execution_strategy(ExecutionStrategy::Parallel),
+            ExecutionStrategy::Sequential
+        );
     }
 
     #[test]
@@ -928,6 +3022,64 @@ run_always = true
         assert!(err.to_string().contains("bad-hook"));
     }
 
+    #[test]
+    fn test_validation_run_always_if_no_changes() {
+        let config = HookConfig::parse(
+            "[hooks.tests-touched]\ncommand = \"check\"\nfiles = [\"tests/**\"]\n\
+             run_always_if_no_changes = true\n",
+        )
+        .unwrap();
+        assert!(config.hooks.unwrap()["tests-touched"].run_always_if_no_changes);
+
+        let err = HookConfig::parse(
+            "[hooks.both]\ncommand = \"check\"\nrun_always = true\n\
+             run_always_if_no_changes = true\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("'both'"), "{err}");
+    }
+
+    #[test]
+    fn test_validation_workdir_glob() {
+        let err = HookConfig::parse(
+            r#"
+[hooks.bad-hook]
+command = "npm test"
+workdir = "web"
+workdir_glob = "packages/*"
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("cannot combine 'workdir_glob'"));
+
+        let err = HookConfig::parse(
+            r#"
+[hooks.bad-hook]
+command = "npm test"
+workdir_glob = "packages/[*"
+"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid workdir_glob pattern"));
+
+        // `[defaults.hook]` locations don't reach a `workdir_glob` hook
+        let config = HookConfig::parse(
+            r#"
+[defaults.hook]
+workdir = "//"
+run_at_root = true
+
+[hooks.npm-test]
+command = "npm test"
+workdir_glob = "//packages/*/package.json"
+"#,
+        )
+        .unwrap();
+        let hook = &config.hooks.unwrap()["npm-test"];
+        assert_eq!(hook.workdir, None);
+        assert!(!hook.run_at_root);
+    }
+
     #[test]
     fn test_validation_allows_files_without_run_always() {
         let toml = r#"
@@ -1083,6 +3235,299 @@ command = "echo 'default behavior'"
         assert!(!default_hook.run_at_root); // Default should be false
     }
 
+    #[test]
+    fn test_parse_hooks_array_preserves_order() {
+        let toml = r#"
+[[hooks]]
+name = "format"
+command = "cargo fmt"
+modifies_repository = true
+
+[[hooks]]
+name = "lint"
+command = ["cargo", "clippy"]
+execution_type = "in-place"
+
+[[hooks]]
+name = "audit"
+command = "cargo audit"
+run_always = true
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let hooks = config.hooks.unwrap();
+
+        let names: Vec<&str> = hooks.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["format", "lint", "audit"]);
+        assert!(hooks["format"].modifies_repository);
+        assert_eq!(hooks["lint"].execution_type, ExecutionType::InPlace);
+        assert!(hooks["audit"].run_always);
+    }
+
+    #[test]
+    fn test_parse_hooks_table_preserves_declaration_order() {
+        let toml = r#"
+[hooks.zeta]
+command = "echo zeta"
+
+[hooks.alpha]
+command = "echo alpha"
+
+[hooks.mid]
+command = "echo mid"
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let names: Vec<String> = config.hooks.unwrap().keys().cloned().collect();
+        assert_eq!(names, vec!["zeta", "alpha", "mid"]);
+    }
+
+    #[test]
+    fn test_parse_hooks_array_rejects_duplicate_names() {
+        let toml = r#"
+[[hooks]]
+name = "lint"
+command = "echo one"
+
+[[hooks]]
+name = "lint"
+command = "echo two"
+"#;
+
+        let err = HookConfig::parse(toml).unwrap_err();
+        assert!(format!("{err:#}").contains("duplicate hook name 'lint'"));
+    }
+
+    #[test]
+    fn test_parse_hooks_array_requires_name() {
+        let toml = r#"
+[[hooks]]
+command = "echo nameless"
+"#;
+
+        let err = HookConfig::parse(toml).unwrap_err();
+        assert!(format!("{err:#}").contains("name"));
+    }
+
+    #[test]
+    fn test_imports_keep_order_with_local_overrides() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _ = git2::Repository::init(temp.path()).unwrap();
+        let base = temp.path().join("base.toml");
+        std::fs::write(
+            &base,
+            r#"
+[[hooks]]
+name = "format"
+command = "echo base-format"
+
+[[hooks]]
+name = "lint"
+command = "echo base-lint"
+"#,
+        )
+        .unwrap();
+
+        let local = temp.path().join("hooks.toml");
+        std::fs::write(
+            &local,
+            r#"
+imports = ["base.toml"]
+
+[[hooks]]
+name = "test"
+command = "echo test"
+
+[[hooks]]
+name = "format"
+command = "echo local-format"
+"#,
+        )
+        .unwrap();
+
+        let config = HookConfig::from_file(&local).unwrap();
+        let hooks = config.hooks.unwrap();
+
+        // Imported hooks come first; a local override keeps the imported position
+        let names: Vec<&str> = hooks.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["format", "lint", "test"]);
+        assert_eq!(
+            hooks["format"].command,
+            HookCommand::Shell("echo local-format".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_steps_command() {
+        let config = HookConfig::parse(
+            r#"
+[hooks.check]
+command = { steps = ["cargo fmt --check", ["cargo", "clippy", "--", "-D", "warnings"]] }
+execution_type = "in-place"
+"#,
+        )
+        .unwrap();
+
+        let hook = &config.hooks.unwrap()["check"];
+        assert_eq!(
+            hook.command,
+            HookCommand::Steps {
+                steps: vec![
+                    HookCommand::Shell("cargo fmt --check".to_string()),
+                    HookCommand::Args(
+                        ["cargo", "clippy", "--", "-D", "warnings"]
+                            .map(String::from)
+                            .to_vec()
+                    ),
+                ]
+            }
+        );
+        assert_eq!(
+            hook.command.to_string(),
+            "cargo fmt --check && cargo clippy -- -D warnings"
+        );
+
+        for (toml, expected) in [
+            (
+                "[hooks.check]\ncommand = { steps = [] }\n",
+                "empty steps list",
+            ),
+            (
+                "[hooks.check]\ncommand = { steps = [{ steps = [\"true\"] }] }\n",
+                "itself a list of steps",
+            ),
+            (
+                "[hooks.check]\ncommand = \"true\"\nskip_if = { steps = [\"true\"] }\n",
+                "cannot use steps in skip_if",
+            ),
+            (
+                "[hooks.check]\ncommand = { steps = [\"echo {CHANGED_FILES}\"] }\n",
+                "{CHANGED_FILES}",
+            ),
+        ] {
+            let err = HookConfig::parse(toml).unwrap_err();
+            assert!(format!("{err:#}").contains(expected), "{toml}: {err:#}");
+        }
+    }
+
+    #[test]
+    fn test_override_records_execution_changes() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let _ = git2::Repository::init(temp.path()).unwrap();
+        std::fs::write(
+            temp.path().join("base.toml"),
+            r#"
+[hooks.lint]
+command = "cargo clippy"
+files = ["**/*.rs"]
+
+[hooks.format]
+command = "cargo fmt --check"
+files = ["**/*.rs"]
+"#,
+        )
+        .unwrap();
+
+        let local = temp.path().join("hooks.toml");
+        std::fs::write(
+            &local,
+            r#"
+imports = ["base.toml"]
+
+[hooks.lint]
+command = "cargo clippy --all-targets"
+run_always = true
+execution_type = "other"
+
+[hooks.format]
+command = "cargo fmt --all --check"
+files = ["**/*.rs"]
+"#,
+        )
+        .unwrap();
+
+        let (_, diag) = HookConfig::from_file_with_trace(&local).unwrap();
+        let changes: IndexMap<&str, &[String]> = diag
+            .overrides
+            .iter()
+            .map(|o| (o.name.as_str(), o.changes.as_slice()))
+            .collect();
+        assert_eq!(
+            changes["lint"],
+            [
+                r#"files: ["**/*.rs"] -> none"#,
+                "run_always: false -> true",
+                "execution_type: per-file -> other",
+            ]
+        );
+        assert!(
+            changes["format"].is_empty(),
+            "only the command changed: {changes:?}"
+        );
+    }
+
+    #[test]
+    fn test_on_failure_command() {
+        let toml = r#"
+[hooks.shell-hint]
+command = "cargo fmt --check"
+on_failure = "echo 'Run cargo fmt to fix formatting'"
+
+[hooks.args-cleanup]
+command = "make test"
+on_failure = ["make", "clean"]
+
+[hooks.plain]
+command = "echo ok"
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let hooks = config.hooks.unwrap();
+
+        assert_eq!(
+            hooks["shell-hint"].on_failure,
+            Some(HookCommand::Shell(
+                "echo 'Run cargo fmt to fix formatting'".to_string()
+            ))
+        );
+        assert_eq!(
+            hooks["args-cleanup"].on_failure,
+            Some(HookCommand::Args(vec![
+                "make".to_string(),
+                "clean".to_string()
+            ]))
+        );
+        assert_eq!(hooks["plain"].on_failure, None);
+    }
+
+    #[test]
+    fn test_fail_on_patterns() {
+        let toml = r#"
+[hooks.lint]
+command = "npm run lint"
+fail_on = "^WARNING:"
+fail_on_allow = "deprecated"
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let lint = &config.hooks.unwrap()["lint"];
+        assert_eq!(lint.fail_on.as_deref(), Some("^WARNING:"));
+        assert_eq!(lint.fail_on_allow.as_deref(), Some("deprecated"));
+
+        let err = HookConfig::parse(
+            r#"
+[hooks.lint]
+command = "npm run lint"
+fail_on = "WARNING: ("
+"#,
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains("Hook 'lint' has an invalid fail_on pattern"));
+
+        // Patterns that would compile to an enormous automaton are rejected
+        assert!(compile_output_pattern(r"(\w{1000}){1000}").is_err());
+    }
+
     #[test]
     fn test_absolute_imports_not_in_allowlist() {
         use std::fs;
@@ -1091,7 +3536,7 @@ command = "echo 'default behavior'"
         let temp_dir = TempDir::new().unwrap();
         let repo_root = temp_dir.path().join("repo");
         fs::create_dir_all(&repo_root).unwrap();
-        fs::create_dir_all(repo_root.join(".git")).unwrap();
+        git2::Repository::init(&repo_root).unwrap();
 
         let hooks_file = repo_root.join("hooks.toml");
 
@@ -1127,7 +3572,7 @@ command = "echo test"
         let temp_dir = TempDir::new().unwrap();
         let repo_root = temp_dir.path().join("repo");
         fs::create_dir_all(&repo_root).unwrap();
-        fs::create_dir_all(repo_root.join(".git")).unwrap();
+        git2::Repository::init(&repo_root).unwrap();
 
         let hooks_file = repo_root.join("hooks.toml");
 
@@ -1198,7 +3643,7 @@ command = "rm -rf /"
                 // Create repository
                 let repo_root = home_dir.join("project");
                 fs::create_dir_all(&repo_root).unwrap();
-                fs::create_dir_all(repo_root.join(".git")).unwrap();
+                git2::Repository::init(&repo_root).unwrap();
 
                 let hooks_file = repo_root.join("hooks.toml");
                 let toml_content = format!(
@@ -1235,7 +3680,7 @@ command = "echo test"
 
         let temp_dir = TempDir::new().unwrap();
         let repo_root = temp_dir.path();
-        fs::create_dir_all(repo_root.join(".git")).unwrap();
+        git2::Repository::init(repo_root).unwrap();
 
         // Create imported file
         let imported_file = repo_root.join("shared.toml");
@@ -1294,6 +3739,30 @@ description = "Real hooks at root"
         assert!(!groups["pre-push"].includes.is_empty());
     }
 
+    #[test]
+    fn test_group_isolation_parsing() {
+        let toml = r#"
+[groups.pre-commit]
+includes = ["lint"]
+isolation = "worktree"
+
+[groups.pre-push]
+includes = ["test"]
+"#;
+
+        let config = HookConfig::parse(toml).unwrap();
+        let groups = config.groups.unwrap();
+        assert_eq!(groups["pre-commit"].isolation, Some(Isolation::Worktree));
+        assert_eq!(groups["pre-push"].isolation, None);
+
+        let invalid = r#"
+[groups.pre-commit]
+includes = ["lint"]
+isolation = "container"
+"#;
+        assert!(HookConfig::parse(invalid).is_err());
+    }
+
     #[test]
     fn test_placeholder_default_false() {
         let toml = r#"
@@ -1314,7 +3783,7 @@ includes = ["lint"]
         use tempfile::TempDir;
         let td = TempDir::new().unwrap();
         let dir = td.path();
-        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        git2::Repository::init(dir).unwrap();
         let config_file = dir.join("hooks.toml");
 
         fs::write(
@@ -1354,6 +3823,26 @@ impl std::fmt::Display for HookCommand {
         match self {
             Self::Shell(cmd) => write!(f, "{cmd}"),
             Self::Args(args) => write!(f, "{}", args.join(" ")),
+            Self::Script { script, shell } => {
+                let mut lines = script
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty());
+                write!(
+                    f,
+                    "{} script: {}",
+                    shell.as_deref().unwrap_or(DEFAULT_SCRIPT_SHELL),
+                    lines.next().unwrap_or_default()
+                )?;
+                if lines.next().is_some() {
+                    f.write_str(" ...")?;
+                }
+                Ok(())
+            }
+            Self::Steps { steps } => {
+                let steps: Vec<String> = steps.iter().map(ToString::to_string).collect();
+                write!(f, "{}", steps.join(" && "))
+            }
         }
     }
 }
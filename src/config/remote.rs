@@ -0,0 +1,379 @@
+//! Imports fetched from git repositories
+//!
+//! An `imports` entry like `{ git = "https://github.com/org/hooks", ref =
+//! "v1", path = "rust.toml" }` is cloned once, at the commit `ref` names, into
+//! ~/.cache/peter-hook/imports and loaded from that checkout afterwards.
+//! Later runs never touch the network, so they work offline. Only hosts listed
+//! in `security.allowed_import_hosts` may be fetched from.
+
+use crate::config::{GitImport, GlobalConfig, LOCAL_CONFIG_FILE_NAME};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Checkouts of git imports, one per repository URL and ref
+#[derive(Debug, Clone)]
+pub struct GitImportCache {
+    dir: PathBuf,
+}
+
+/// What a checkout in the cache holds, recorded next to it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct CacheEntry {
+    /// Repository URL
+    git: String,
+    /// The ref as written in the import
+    #[serde(rename = "ref")]
+    git_ref: String,
+    /// Commit the ref named when it was fetched
+    commit: String,
+}
+
+impl GitImportCache {
+    /// A cache kept in `dir`
+    #[must_use]
+    pub const fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The cache in its default location, ~/.cache/peter-hook/imports
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be determined
+    pub fn open_default() -> Result<Self> {
+        let cache_dir = dirs::cache_dir().context("Unable to determine cache directory")?;
+
+        Ok(Self::new(cache_dir.join("peter-hook").join("imports")))
+    }
+
+    /// The local path of the file `import` names, fetching its repository
+    /// first if it isn't cached yet
+    ///
+    /// The checkout must still be at the commit recorded when it was fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository cannot be fetched, the ref doesn't
+    /// name a commit, the checkout was modified, or the file is missing or
+    /// outside the repository
+    pub fn fetch(&self, import: &GitImport) -> Result<PathBuf> {
+        let key = cache_key(import);
+        let checkout = self.dir.join(&key);
+        let entry_path = self.dir.join(format!("{key}.toml"));
+
+        if checkout.is_dir() && entry_path.is_file() {
+            log::debug!(
+                "git import cached url={} ref={} path={}",
+                import.git,
+                import.git_ref,
+                checkout.display()
+            );
+        } else {
+            self.clone_into(import, &checkout, &entry_path)?;
+        }
+
+        let content = std::fs::read_to_string(&entry_path)
+            .with_context(|| format!("Failed to read {}", entry_path.display()))?;
+        let entry: CacheEntry = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", entry_path.display()))?;
+        let head = git(["rev-parse", "HEAD"], Some(&checkout))?;
+        if head != entry.commit {
+            bail!(
+                "Cached import {import} is at commit {head}, not {} as fetched; delete {} to \
+                 fetch it again",
+                entry.commit,
+                checkout.display()
+            );
+        }
+
+        let file = checkout.join(&import.path);
+        let file_real = file
+            .canonicalize()
+            .with_context(|| format!("Import file not found: {import}"))?;
+        let checkout_real = checkout.canonicalize().unwrap_or(checkout);
+        if !file_real.starts_with(&checkout_real)
+            || file_real.starts_with(checkout_real.join(".git"))
+        {
+            bail!("import outside the fetched repository is not allowed: {import}");
+        }
+        if file_real.file_name() == Some(LOCAL_CONFIG_FILE_NAME.as_ref()) {
+            bail!(
+                "{LOCAL_CONFIG_FILE_NAME} holds local overrides and cannot be imported: {import}"
+            );
+        }
+        Ok(file_real)
+    }
+
+    /// Clone the repository of `import` into `checkout`, at the commit its
+    /// ref names, and record that commit in `entry_path`
+    fn clone_into(&self, import: &GitImport, checkout: &Path, entry_path: &Path) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create cache directory: {}", self.dir.display()))?;
+        // Clone next to the checkout and move it into place once complete, so
+        // an interrupted fetch leaves nothing that looks cached
+        let staging = tempfile::Builder::new()
+            .prefix(".fetch-")
+            .tempdir_in(&self.dir)
+            .context("Failed to create a directory to fetch an import into")?;
+        let repo = staging.path().join("repo");
+
+        log::info!(
+            "fetching git import url={} ref={}",
+            import.git,
+            import.git_ref
+        );
+        git(
+            [
+                OsStr::new("clone"),
+                OsStr::new("--quiet"),
+                OsStr::new("--no-checkout"),
+                OsStr::new("--"),
+                OsStr::new(&import.git),
+                repo.as_os_str(),
+            ],
+            None,
+        )
+        .with_context(|| {
+            format!(
+                "Failed to fetch import {import} (it isn't cached yet, so fetching it needs \
+                 network access)"
+            )
+        })?;
+
+        let commit = resolve_ref(&repo, &import.git_ref)
+            .with_context(|| format!("Failed to fetch import {import}"))?;
+        git(["checkout", "--quiet", "--detach", &commit], Some(&repo))?;
+
+        if checkout.exists() {
+            std::fs::remove_dir_all(checkout)
+                .with_context(|| format!("Failed to remove {}", checkout.display()))?;
+        }
+        std::fs::rename(&repo, checkout)
+            .with_context(|| format!("Failed to move fetched import to {}", checkout.display()))?;
+
+        let entry = CacheEntry {
+            git: import.git.clone(),
+            git_ref: import.git_ref.clone(),
+            commit,
+        };
+        let content = toml::to_string_pretty(&entry).context("Failed to serialize cache entry")?;
+        std::fs::write(entry_path, content)
+            .with_context(|| format!("Failed to write {}", entry_path.display()))
+    }
+}
+
+/// Check that the host of a git import URL is in
+/// `security.allowed_import_hosts`
+///
+/// # Errors
+///
+/// Returns an error if the URL has no host or its host isn't allowed
+pub fn check_import_host(url: &str, global_config: &GlobalConfig) -> Result<()> {
+    let Some(host) = git_url_host(url) else {
+        bail!("Git import URL has no host: {url}\nHint: Use an https://, ssh:// or file:// URL");
+    };
+    if !global_config.is_import_host_allowed(&host) {
+        bail!(
+            "Git import host not allowed: {host} ({url})\nHint: Allow it with: peter-hook \
+             config set security.allowed_import_hosts {host}"
+        );
+    }
+    Ok(())
+}
+
+/// The host of a git URL, lowercased: `github.com` for
+/// `https://github.com/org/hooks` and `git@github.com:org/hooks`, and `file`
+/// for `file://` URLs. Local paths have no host.
+#[must_use]
+pub fn git_url_host(url: &str) -> Option<String> {
+    let authority = if let Some((scheme, rest)) = url.split_once("://") {
+        if scheme.eq_ignore_ascii_case("file") {
+            return Some("file".to_string());
+        }
+        let authority = rest.split('/').next().unwrap_or_default();
+        let authority = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        match authority.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+            _ => authority,
+        }
+    } else {
+        // scp-like syntax: [user@]host:path, with no slash before the colon
+        let (authority, _) = url.split_once(':')?;
+        if authority.contains('/') {
+            return None;
+        }
+        authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host)
+    };
+    (!authority.is_empty()).then(|| authority.to_ascii_lowercase())
+}
+
+/// The commit `git_ref` names in the freshly cloned `repo`: a tag, a branch
+/// or a full commit hash
+fn resolve_ref(repo: &Path, git_ref: &str) -> Result<String> {
+    // A full hash pins the commit itself, never a branch or tag named like it
+    let candidates = if git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit()) {
+        vec![git_ref.to_string()]
+    } else {
+        vec![
+            format!("refs/tags/{git_ref}"),
+            format!("refs/remotes/origin/{git_ref}"),
+        ]
+    };
+    candidates
+        .iter()
+        .find_map(|candidate| {
+            git(
+                [
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{candidate}^{{commit}}"),
+                ],
+                Some(repo),
+            )
+            .ok()
+        })
+        .with_context(|| format!("ref '{git_ref}' does not name a commit in the repository"))
+}
+
+/// Cache directory name for an import's repository and ref
+fn cache_key(import: &GitImport) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(import.git.as_bytes());
+    hasher.update([0]);
+    hasher.update(import.git_ref.as_bytes());
+    hex::encode(&hasher.finalize()[..12])
+}
+
+/// Run git without prompting for credentials, returning its trimmed stdout
+fn git<I, S>(args: I, dir: Option<&Path>) -> Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut command = Command::new("git");
+    command.args(args).env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(dir) = dir {
+        command.current_dir(dir);
+    }
+    let output = command.output().context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// A bare repository whose `v1` tag has `hooks.toml` with a `lint` hook,
+    /// and whose `main` branch later changes it
+    fn bare_repo_fixture(root: &Path) -> (String, String) {
+        let work = root.join("work");
+        let bare = root.join("hooks.git");
+        fs::create_dir_all(&work).unwrap();
+        let run = |args: &[&str]| git(args.iter().copied(), Some(&work)).unwrap();
+        run(&["init", "--quiet", "--initial-branch=main"]);
+        run(&["config", "user.name", "Test"]);
+        run(&["config", "user.email", "test@test.com"]);
+        fs::write(
+            work.join("hooks.toml"),
+            "[hooks.lint]\ncommand = \"echo v1\"\n",
+        )
+        .unwrap();
+        run(&["add", "hooks.toml"]);
+        run(&["commit", "--quiet", "-m", "v1"]);
+        run(&["tag", "v1"]);
+        let v1 = run(&["rev-parse", "HEAD"]);
+        fs::write(
+            work.join("hooks.toml"),
+            "[hooks.lint]\ncommand = \"echo v2\"\n",
+        )
+        .unwrap();
+        run(&["commit", "--quiet", "-am", "v2"]);
+        run(&["clone", "--quiet", "--bare", ".", bare.to_str().unwrap()]);
+        (format!("file://{}", bare.display()), v1)
+    }
+
+    fn import(git: &str, git_ref: &str) -> GitImport {
+        GitImport {
+            git: git.to_string(),
+            git_ref: git_ref.to_string(),
+            path: "hooks.toml".to_string(),
+            when: None,
+        }
+    }
+
+    #[test]
+    fn test_fetch_pins_ref_and_reuses_cache_offline() {
+        let temp_dir = TempDir::new().unwrap();
+        let (url, v1) = bare_repo_fixture(temp_dir.path());
+        let cache = GitImportCache::new(temp_dir.path().join("cache"));
+
+        for (git_ref, expected) in [("v1", "echo v1"), ("main", "echo v2"), (&v1[..], "echo v1")] {
+            let file = cache.fetch(&import(&url, git_ref)).unwrap();
+            let content = fs::read_to_string(&file).unwrap();
+            assert!(content.contains(expected), "{git_ref}: {content}");
+        }
+
+        // Once cached, the repository is no longer needed
+        fs::remove_dir_all(temp_dir.path().join("hooks.git")).unwrap();
+        let file = cache.fetch(&import(&url, "v1")).unwrap();
+        assert!(fs::read_to_string(&file).unwrap().contains("echo v1"));
+
+        // Uncached refs can't be fetched offline
+        let err = cache.fetch(&import(&url, "v2")).unwrap_err();
+        assert!(format!("{err:#}").contains("isn't cached yet"), "{err:#}");
+    }
+
+    #[test]
+    fn test_fetch_rejects_unknown_refs_and_escaping_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let (url, _) = bare_repo_fixture(temp_dir.path());
+        let cache = GitImportCache::new(temp_dir.path().join("cache"));
+
+        let err = cache.fetch(&import(&url, "v9")).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("ref 'v9' does not name a commit"),
+            "{err:#}"
+        );
+
+        let mut escaping = import(&url, "v1");
+        escaping.path = "../../hooks.git/HEAD".to_string();
+        assert!(cache.fetch(&escaping).is_err());
+    }
+
+    #[test]
+    fn test_git_url_host() {
+        for (url, host) in [
+            ("https://github.com/org/hooks", Some("github.com")),
+            (
+                "https://user@GitHub.com:8443/org/hooks.git",
+                Some("github.com"),
+            ),
+            (
+                "ssh://git@git.example.com/org/hooks",
+                Some("git.example.com"),
+            ),
+            ("git@github.com:org/hooks.git", Some("github.com")),
+            ("file:///srv/git/hooks.git", Some("file")),
+            ("/srv/git/hooks.git", None),
+            ("./hooks", None),
+        ] {
+            assert_eq!(git_url_host(url).as_deref(), host, "{url}");
+        }
+    }
+}
@@ -9,7 +9,6 @@ use crate::hooks::resolver::WorktreeContext;
 use anyhow::{Context, Result};
 use std::{
     collections::HashMap,
-    io::IsTerminal,
     path::{Path, PathBuf},
 };
 
@@ -21,6 +20,8 @@ use std::{
 pub struct TemplateResolver {
     /// Available template variables (whitelist only)
     variables: HashMap<String, String>,
+    /// `CHANGED_FILES` with each path shell-quoted, used in shell commands
+    changed_files_quoted: String,
 }
 
 impl TemplateResolver {
@@ -75,7 +76,10 @@ impl TemplateResolver {
         variables.insert("CHANGED_FILES_LIST".to_string(), String::new());
         variables.insert("CHANGED_FILES_FILE".to_string(), String::new());
 
-        Self { variables }
+        Self {
+            variables,
+            changed_files_quoted: String::new(),
+        }
     }
 
     /// Create a new template resolver with worktree-aware variables
@@ -103,14 +107,20 @@ impl TemplateResolver {
             worktree_context.common_dir.display().to_string(),
         );
 
-        // Worktree-specific variables
+        // Worktree-specific variables (WORKTREE_NAME is empty in the main
+        // repository so hooks can reference it unconditionally)
         variables.insert(
             "IS_WORKTREE".to_string(),
             worktree_context.is_worktree.to_string(),
         );
-        if let Some(ref worktree_name) = worktree_context.worktree_name {
-            variables.insert("WORKTREE_NAME".to_string(), worktree_name.clone());
-        }
+        variables.insert(
+            "WORKTREE_NAME".to_string(),
+            worktree_context.worktree_name.clone().unwrap_or_default(),
+        );
+        variables.insert(
+            "WORKTREE_ROOT".to_string(),
+            worktree_context.repo_root.display().to_string(),
+        );
 
         // Relative paths
         if let Ok(relative_config) = config_dir.strip_prefix(&worktree_context.repo_root) {
@@ -141,13 +151,21 @@ impl TemplateResolver {
             variables.insert("PATH".to_string(), path);
         }
 
+        // Run-wide variables from `run --env`
+        for (key, value) in &worktree_context.run_env {
+            variables.insert(format!("env:{key}"), value.clone());
+        }
+
         // Initialize CHANGED_FILES variables as empty (will be set when files are
         // provided)
         variables.insert("CHANGED_FILES".to_string(), String::new());
         variables.insert("CHANGED_FILES_LIST".to_string(), String::new());
         variables.insert("CHANGED_FILES_FILE".to_string(), String::new());
 
-        Self { variables }
+        Self {
+            variables,
+            changed_files_quoted: String::new(),
+        }
     }
 
     /// Resolve templates in a string using `{VARIABLE_NAME}` syntax
@@ -156,10 +174,33 @@ impl TemplateResolver {
     ///
     /// Returns an error if template resolution fails
     pub fn resolve_string(&self, input: &str) -> Result<String> {
+        self.resolve(input, false)
+    }
+
+    /// Resolve templates in a command or script run by a shell
+    ///
+    /// Like [`Self::resolve_string`], except each path in `{CHANGED_FILES}`
+    /// is quoted so the shell splits the list back into the original paths,
+    /// and `{FAILED_STDOUT}` / `{FAILED_STDERR}` are quoted as single words
+    /// so hook output is never run as shell code.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if template resolution fails
+    pub fn resolve_shell(&self, input: &str) -> Result<String> {
+        self.resolve(input, true)
+    }
+
+    /// Resolve templates, quoting `{CHANGED_FILES}` for a shell if `shell`
+    fn resolve(&self, input: &str, shell: bool) -> Result<String> {
         let mut result = input.to_string();
+        let mut search_from = 0;
 
-        // Find all {VAR} patterns and replace them
-        while let Some(start) = result.find('{') {
+        // Find all {VAR} patterns and replace them. Scanning resumes after each
+        // replacement so substituted values (e.g. hook output) are never
+        // expanded again.
+        while let Some(offset) = result[search_from..].find('{') {
+            let start = search_from + offset;
             let end = result[start..].find('}').ok_or_else(|| {
                 anyhow::anyhow!("Unclosed template variable: {}", &result[start..])
             })?;
@@ -167,17 +208,35 @@ impl TemplateResolver {
 
             let var_name = &result[start + 1..end];
             let replacement = self
-                .resolve_variable(var_name)
+                .resolve_variable(var_name, shell)
                 .with_context(|| format!("Failed to resolve template variable: {var_name}"))?;
 
             result.replace_range(start..=end, &replacement);
+            search_from = start + replacement.len();
         }
 
         Ok(result)
     }
 
     /// Resolve a single template variable
-    fn resolve_variable(&self, var_name: &str) -> Result<String> {
+    fn resolve_variable(&self, var_name: &str, shell: bool) -> Result<String> {
+        if shell {
+            match var_name {
+                "CHANGED_FILES" => return Ok(self.changed_files_quoted.clone()),
+                // Captured output is arbitrary text, never shell code
+                "FAILED_STDOUT" | "FAILED_STDERR" => {
+                    if let Some(output) = self.variables.get(var_name) {
+                        return Ok(shell_quote(output));
+                    }
+                }
+                _ => {}
+            }
+        }
+        if var_name.starts_with("glob:") {
+            anyhow::bail!(
+                "{{{var_name}}} is only expanded as a whole argument of an array command"
+            );
+        }
         // Only allow predefined template variables from our whitelist
         self.variables.get(var_name).cloned().ok_or_else(|| {
             anyhow::anyhow!(
@@ -209,11 +268,62 @@ impl TemplateResolver {
 
     /// Resolve templates in command arguments
     ///
+    /// An argument that is exactly `{glob:<pattern>}` expands to one argument
+    /// per matching path (see [`Self::expand_glob`]), since array commands
+    /// don't go through a shell that would expand it.
+    ///
     /// # Errors
     ///
-    /// Returns an error if template resolution fails
+    /// Returns an error if template resolution fails or a glob matches no
+    /// files
     pub fn resolve_command_args(&self, args: &[String]) -> Result<Vec<String>> {
-        args.iter().map(|arg| self.resolve_string(arg)).collect()
+        let mut resolved = Vec::with_capacity(args.len());
+        for arg in args {
+            match glob_argument(arg) {
+                Some(pattern) => resolved.extend(self.expand_glob(pattern)?),
+                None => resolved.push(self.resolve_string(arg)?),
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Expand a `{glob:<pattern>}` argument into the paths it matches
+    ///
+    /// Relative patterns are matched from the repository root (the working
+    /// directory outside a repository) and expand to sorted absolute paths,
+    /// so they work wherever the hook runs. As in a shell, wildcards don't
+    /// match names starting with `.`. A pattern that matches nothing is an
+    /// error rather than an empty expansion, which many tools would take as
+    /// "check everything".
+    fn expand_glob(&self, pattern: &str) -> Result<Vec<String>> {
+        let full_pattern = if Path::new(pattern).is_absolute() {
+            pattern.to_string()
+        } else {
+            let root = self
+                .variables
+                .get("REPO_ROOT")
+                .or_else(|| self.variables.get("WORKING_DIR"))
+                .context("No directory to expand globs from")?;
+            format!("{}/{pattern}", glob::Pattern::escape(root))
+        };
+
+        let options = glob::MatchOptions {
+            require_literal_leading_dot: true,
+            ..glob::MatchOptions::new()
+        };
+        let mut paths = glob::glob_with(&full_pattern, options)
+            .with_context(|| format!("Invalid glob pattern: {pattern}"))?
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Failed to expand {{glob:{pattern}}}"))?;
+        if paths.is_empty() {
+            anyhow::bail!("{{glob:{pattern}}} matched no files");
+        }
+        paths.sort();
+
+        Ok(paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect())
     }
 
     /// Set `CHANGED_FILES` template variables
@@ -229,6 +339,14 @@ impl TemplateResolver {
             .collect::<Vec<_>>()
             .join(" ");
 
+        // The same list quoted so a shell splits it back into the original
+        // paths
+        self.changed_files_quoted = changed_files
+            .iter()
+            .map(|p| shell_quote(&p.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
         // Newline-delimited list
         let changed_list = changed_files
             .iter()
@@ -236,8 +354,10 @@ impl TemplateResolver {
             .collect::<Vec<_>>()
             .join("\n");
 
+        log::debug!("CHANGED_FILES='{changed_space}'");
+
         if crate::debug::is_enabled() {
-            if std::io::stderr().is_terminal() {
+            if crate::output::stderr_styled() {
                 eprintln!(
                     "\x1b[95m🔍 \x1b[1m\x1b[38;5;213mCHANGED_FILES\x1b[0m \x1b[95mtemplate \
                      variables:\x1b[0m"
@@ -279,6 +399,20 @@ impl TemplateResolver {
         );
     }
 
+    /// Set `FAILED_*` template variables describing a failed hook
+    ///
+    /// Used when resolving a hook's `on_failure` command.
+    pub fn set_failed_hook(&mut self, name: &str, exit_code: i32, stdout: &str, stderr: &str) {
+        self.variables
+            .insert("FAILED_HOOK".to_string(), name.to_string());
+        self.variables
+            .insert("FAILED_EXIT_CODE".to_string(), exit_code.to_string());
+        self.variables
+            .insert("FAILED_STDOUT".to_string(), stdout.to_string());
+        self.variables
+            .insert("FAILED_STDERR".to_string(), stderr.to_string());
+    }
+
     /// Get all available template variables
     #[must_use]
     pub const fn get_available_variables(&self) -> &HashMap<String, String> {
@@ -294,6 +428,27 @@ impl TemplateResolver {
     }
 }
 
+/// Quote `value` for use as a single word in a POSIX shell command
+///
+/// Values made only of characters the shell never treats specially are
+/// returned unchanged; anything else is wrapped in single quotes.
+#[must_use]
+pub fn shell_quote(value: &str) -> String {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "-_./+,:=@%".contains(c);
+    if !value.is_empty() && value.chars().all(is_plain) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// The pattern of an argument that is exactly `{glob:<pattern>}`
+fn glob_argument(arg: &str) -> Option<&str> {
+    arg.strip_prefix("{glob:")?
+        .strip_suffix('}')
+        .filter(|pattern| !pattern.is_empty())
+}
+
 /// Find git repository root by walking up directories
 fn find_git_root(start_dir: &Path) -> Result<PathBuf> {
     let mut current = start_dir;
@@ -313,6 +468,7 @@ fn find_git_root(start_dir: &Path) -> Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::TempDir;
 
     #[test]
@@ -333,6 +489,36 @@ mod tests {
         assert!(result.contains("project project")); // PROJECT_NAME should be "project"
     }
 
+    #[test]
+    fn test_failed_hook_templating_is_not_reexpanded() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let mut template_resolver = TemplateResolver::new(temp_dir.path(), temp_dir.path());
+        template_resolver.set_failed_hook("lint", 3, "{FAILED_STDOUT} {UNKNOWN}", "");
+
+        let result = template_resolver
+            .resolve_string("{FAILED_HOOK} exited {FAILED_EXIT_CODE}: {FAILED_STDOUT}")
+            .expect("resolve_string");
+
+        assert_eq!(result, "lint exited 3: {FAILED_STDOUT} {UNKNOWN}");
+    }
+
+    #[test]
+    fn test_failed_hook_output_is_shell_quoted() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let mut template_resolver = TemplateResolver::new(temp_dir.path(), temp_dir.path());
+        template_resolver.set_failed_hook("lint", 1, "x $(touch pwned) y", "it's `id`; rm");
+
+        let result = template_resolver
+            .resolve_shell("echo {FAILED_STDOUT} {FAILED_STDERR}")
+            .expect("resolve_shell");
+        assert_eq!(result, r"echo 'x $(touch pwned) y' 'it'\''s `id`; rm'");
+
+        let result = template_resolver
+            .resolve_string("{FAILED_STDOUT}")
+            .expect("resolve_string");
+        assert_eq!(result, "x $(touch pwned) y");
+    }
+
     #[test]
     fn test_changed_files_templating() {
         let temp_dir = TempDir::new().expect("failed to create temp dir");
@@ -354,6 +540,47 @@ mod tests {
         assert!(result.contains("changed.txt"));
     }
 
+    #[test]
+    fn test_changed_files_are_shell_quoted_in_shell_commands() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let mut template_resolver = TemplateResolver::new(temp_dir.path(), temp_dir.path());
+        let changed_files = vec![
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("docs/my file.md"),
+            PathBuf::from("it's.txt"),
+        ];
+        template_resolver.set_changed_files(&changed_files, None);
+
+        let result = template_resolver
+            .resolve_shell("{CHANGED_FILES}")
+            .expect("resolve_shell");
+        assert_eq!(result, r"src/main.rs 'docs/my file.md' 'it'\''s.txt'");
+
+        let result = template_resolver
+            .resolve_string("{CHANGED_FILES}")
+            .expect("resolve_string");
+        assert_eq!(result, "src/main.rs docs/my file.md it's.txt");
+
+        let result = template_resolver
+            .resolve_command_args(&["{CHANGED_FILES}".to_string()])
+            .expect("resolve_command_args");
+        assert_eq!(result, vec!["src/main.rs docs/my file.md it's.txt"]);
+
+        let result = template_resolver
+            .resolve_string("{CHANGED_FILES_LIST}")
+            .expect("resolve_string");
+        assert_eq!(result, "src/main.rs\ndocs/my file.md\nit's.txt");
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("src/a-b_c.rs"), "src/a-b_c.rs");
+        assert_eq!(shell_quote(""), "''");
+        assert_eq!(shell_quote("a b"), "'a b'");
+        assert_eq!(shell_quote("$(rm -rf /)"), "'$(rm -rf /)'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
     #[test]
     fn test_command_args_templating() {
         let temp_dir = TempDir::new().expect("failed to create temp dir");
@@ -377,6 +604,64 @@ mod tests {
         assert!(resolved_args[3].contains(temp_dir.path().to_str().unwrap()));
     }
 
+    #[test]
+    fn test_command_args_glob_expansion() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::create_dir_all(root.join("src/.hidden")).unwrap();
+        for file in [
+            "src/b.ts",
+            "src/a.ts",
+            "src/nested/c.ts",
+            "src/.hidden/d.ts",
+            "src/e.js",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+        let template_resolver = TemplateResolver::new(root, root);
+
+        let args = vec![
+            "eslint".to_string(),
+            "{glob:src/**/*.ts}".to_string(),
+            "--fix".to_string(),
+        ];
+        let resolved = template_resolver.resolve_command_args(&args).unwrap();
+        let expected: Vec<String> = ["src/a.ts", "src/b.ts", "src/nested/c.ts"]
+            .iter()
+            .map(|file| root.join(file).display().to_string())
+            .collect();
+        assert_eq!(resolved[0], "eslint");
+        assert_eq!(resolved[1..4], expected);
+        assert_eq!(resolved[4], "--fix");
+        assert_eq!(resolved.len(), 5);
+
+        // Absolute patterns are used as given
+        let absolute = format!("{{glob:{}/src/*.js}}", root.display());
+        assert_eq!(
+            template_resolver.resolve_command_args(&[absolute]).unwrap(),
+            [root.join("src/e.js").display().to_string()]
+        );
+    }
+
+    #[test]
+    fn test_command_args_glob_without_matches() {
+        let temp_dir = TempDir::new().expect("failed to create temp dir");
+        let resolver = TemplateResolver::new(temp_dir.path(), temp_dir.path());
+
+        let error = resolver
+            .resolve_command_args(&["{glob:src/**/*.ts}".to_string()])
+            .unwrap_err();
+        assert!(error.to_string().contains("matched no files"), "{error:#}");
+
+        // Only a whole argument is expanded
+        let error = resolver
+            .resolve_command_args(&["--files={glob:*.ts}".to_string()])
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("whole argument"), "{error:#}");
+        assert!(resolver.resolve_string("eslint {glob:*.ts}").is_err());
+    }
+
     #[test]
     fn test_env_map_templating() {
         let temp_dir = TempDir::new().expect("failed to create temp dir");
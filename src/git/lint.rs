@@ -1,5 +1,6 @@
 //! Lint mode file discovery with .gitignore support
 
+use super::HookIgnore;
 use anyhow::{Context, Result};
 use std::{
     collections::HashSet,
@@ -45,7 +46,10 @@ impl LintFileDiscovery {
     }
 
     /// Use git ls-files to efficiently discover non-ignored files
-    fn discover_with_git(&self, _repo_root: &Path) -> Result<Vec<PathBuf>> {
+    ///
+    /// Files excluded by the repository's `.peterhookignore` are dropped on
+    /// top of the `.gitignore` rules.
+    fn discover_with_git(&self, repo_root: &Path) -> Result<Vec<PathBuf>> {
         // Run git ls-files from the start directory
         // This respects .gitignore rules hierarchically up to repo root
         let output = Command::new("git")
@@ -78,7 +82,10 @@ impl LintFileDiscovery {
             }
         }
 
-        Ok(files)
+        Ok(match HookIgnore::load(repo_root)? {
+            Some(ignore) => ignore.filter(files),
+            None => files,
+        })
     }
 
     /// Manual file discovery (fallback for non-git directories)
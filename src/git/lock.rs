@@ -0,0 +1,102 @@
+//! Repository lock serializing operations that rewrite the hooks directory
+//!
+//! `install` and `uninstall` hold an advisory lock on `peter-hook.lock` in the
+//! common git directory, so two of them (in any worktree of the repository)
+//! never write hook scripts and backups at the same time. The operating system
+//! releases the lock when the file is closed, including when the process
+//! panics or is killed.
+
+use anyhow::{Context, Result};
+use fs4::fs_std::FileExt;
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+/// Name of the lock file in the common git directory
+pub const LOCK_FILE_NAME: &str = "peter-hook.lock";
+
+/// Held lock on a repository; released when dropped
+#[derive(Debug)]
+pub struct RepoLock {
+    /// Open lock file; closing it releases the lock
+    file: File,
+    /// Location of the lock file
+    pub path: PathBuf,
+}
+
+impl RepoLock {
+    /// Lock the repository whose common git directory is `common_dir`
+    ///
+    /// If another peter-hook operation holds the lock, prints a notice and
+    /// waits for it to finish.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock file cannot be opened or locked
+    pub fn acquire(common_dir: &Path) -> Result<Self> {
+        let path = common_dir.join(LOCK_FILE_NAME);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let locked = file
+            .try_lock_exclusive()
+            .with_context(|| format!("Failed to lock {}", path.display()))?;
+        if !locked {
+            eprintln!(
+                "⏳ Another peter-hook operation is in progress (lock: {}); waiting for it to \
+                 finish...",
+                path.display()
+            );
+            file.lock_exclusive()
+                .with_context(|| format!("Failed to lock {}", path.display()))?;
+        }
+
+        log::info!("repo lock acquired path={}", path.display());
+        Ok(Self { file, path })
+    }
+}
+
+impl Drop for RepoLock {
+    fn drop(&mut self) {
+        // Closing the file releases the lock too; qualified because std's
+        // own `File::unlock` is still unstable
+        if let Err(e) = FileExt::unlock(&self.file) {
+            log::warn!("failed to unlock {}: {e}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::mpsc, thread, time::Duration};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repo_lock_waits_for_holder() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_path_buf();
+
+        let held = RepoLock::acquire(&dir).unwrap();
+        assert_eq!(held.path, dir.join(LOCK_FILE_NAME));
+
+        let (sender, acquired) = mpsc::channel();
+        let waiter = thread::spawn(move || {
+            let _lock = RepoLock::acquire(&dir).unwrap();
+            sender.send(()).unwrap();
+        });
+
+        assert!(
+            acquired.recv_timeout(Duration::from_millis(200)).is_err(),
+            "second lock must wait while the first is held"
+        );
+        drop(held);
+        acquired.recv_timeout(Duration::from_secs(10)).unwrap();
+        waiter.join().unwrap();
+    }
+}
@@ -1,13 +1,23 @@
 //! Git repository integration
 
 pub mod changes;
+pub mod hookignore;
 pub mod installer;
 pub mod lint;
+pub mod lock;
+pub mod push;
 pub mod repository;
+pub mod rewrite;
+pub mod state;
 pub mod worktree;
 
 pub use changes::*;
+pub use hookignore::*;
 pub use installer::*;
 pub use lint::*;
+pub use lock::*;
+pub use push::*;
 pub use repository::*;
+pub use rewrite::*;
+pub use state::*;
 pub use worktree::*;
@@ -0,0 +1,133 @@
+//! Ref updates git passes to `pre-push` hooks
+//!
+//! Git writes one line per ref being pushed to the hook's stdin:
+//! `<local ref> <local sha> <remote ref> <remote sha>`. Hooks with `push_refs`
+//! patterns only run when one of the remote refs matches.
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+
+/// One ref being pushed, as reported to `pre-push`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushRefUpdate {
+    /// Local ref being pushed (`(delete)` when deleting the remote ref)
+    pub local_ref: String,
+    /// Object the local ref points to
+    pub local_sha: String,
+    /// Remote ref being updated, e.g. `refs/heads/main`
+    pub remote_ref: String,
+    /// Object the remote ref currently points to (all zeros if it is new)
+    pub remote_sha: String,
+}
+
+/// Parse the `pre-push` stdin lines; blank lines are ignored
+///
+/// # Errors
+///
+/// Returns an error if a line doesn't have exactly four fields
+pub fn parse_push_refs(input: &str) -> Result<Vec<PushRefUpdate>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [local_ref, local_sha, remote_ref, remote_sha] = fields[..] else {
+                anyhow::bail!(
+                    "Malformed pre-push ref line (expected '<local ref> <local sha> <remote ref> \
+                     <remote sha>'): {line}"
+                );
+            };
+            Ok(PushRefUpdate {
+                local_ref: local_ref.to_string(),
+                local_sha: local_sha.to_string(),
+                remote_ref: remote_ref.to_string(),
+                remote_sha: remote_sha.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Compile a `push_refs` pattern
+///
+/// # Errors
+///
+/// Returns an error if the pattern is not a valid glob
+pub fn compile_push_ref_pattern(pattern: &str) -> Result<Pattern> {
+    Pattern::new(pattern).with_context(|| format!("Invalid push_refs pattern: {pattern}"))
+}
+
+/// Whether any pushed remote ref matches one of `patterns`
+///
+/// `*` stays within one ref component (`refs/heads/release/*` doesn't match
+/// `refs/heads/release/1.x/hotfix`); `**` crosses components.
+///
+/// # Errors
+///
+/// Returns an error if a pattern is not a valid glob
+pub fn push_refs_match(patterns: &[String], updates: &[PushRefUpdate]) -> Result<bool> {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    let patterns = patterns
+        .iter()
+        .map(|pattern| compile_push_ref_pattern(pattern))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(updates.iter().any(|update| {
+        patterns
+            .iter()
+            .any(|pattern| pattern.matches_with(&update.remote_ref, options))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZERO: &str = "0000000000000000000000000000000000000000";
+
+    fn push_to(remote_ref: &str) -> Vec<PushRefUpdate> {
+        parse_push_refs(&format!(
+            "refs/heads/work 1111111111111111111111111111111111111111 {remote_ref} {ZERO}\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_push_refs() {
+        let updates = parse_push_refs(&format!(
+            "refs/heads/main 1111111111111111111111111111111111111111 refs/heads/main \
+             2222222222222222222222222222222222222222\n\n(delete) {ZERO} refs/heads/old \
+             3333333333333333333333333333333333333333\n"
+        ))
+        .unwrap();
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].local_ref, "refs/heads/main");
+        assert_eq!(
+            updates[0].remote_sha,
+            "2222222222222222222222222222222222222222"
+        );
+        assert_eq!(updates[1].local_ref, "(delete)");
+        assert_eq!(updates[1].remote_ref, "refs/heads/old");
+
+        assert!(parse_push_refs("refs/heads/main 1111 refs/heads/main\n").is_err());
+    }
+
+    #[test]
+    fn test_push_refs_match_protected_branches() {
+        let patterns = vec![
+            "refs/heads/main".to_string(),
+            "refs/heads/release/*".to_string(),
+        ];
+
+        assert!(push_refs_match(&patterns, &push_to("refs/heads/main")).unwrap());
+        assert!(push_refs_match(&patterns, &push_to("refs/heads/release/2.0")).unwrap());
+        assert!(!push_refs_match(&patterns, &push_to("refs/heads/feature/login")).unwrap());
+        assert!(!push_refs_match(&patterns, &push_to("refs/heads/release/2.0/hotfix")).unwrap());
+        assert!(!push_refs_match(&patterns, &[]).unwrap());
+
+        let deep = vec!["refs/heads/release/**".to_string()];
+        assert!(push_refs_match(&deep, &push_to("refs/heads/release/2.0/hotfix")).unwrap());
+    }
+}
@@ -233,6 +233,94 @@ impl GitRepository {
         }
     }
 
+    /// Point git at `hooks_dir` for this worktree only
+    ///
+    /// Enables `extensions.worktreeConfig` in the shared config and sets
+    /// `core.hooksPath` in the worktree-scoped config, so other worktrees keep
+    /// using the shared hooks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this is not a linked worktree or git config fails
+    pub fn set_worktree_hooks_path(&self, hooks_dir: &Path) -> Result<()> {
+        if !self.is_worktree {
+            anyhow::bail!("Worktree hooks path can only be set in a linked worktree");
+        }
+
+        self.git_config(&["extensions.worktreeConfig", "true"])?;
+        self.git_config(&[
+            "--worktree",
+            "core.hooksPath",
+            &hooks_dir.display().to_string(),
+        ])?;
+        Ok(())
+    }
+
+    /// Get the worktree-scoped `core.hooksPath`, if one is configured
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git config cannot be read
+    pub fn get_worktree_hooks_path(&self) -> Result<Option<PathBuf>> {
+        if !self.is_worktree || !self.worktree_config_enabled()? {
+            return Ok(None);
+        }
+
+        let output = self.git_config_output(&["--worktree", "--get", "core.hooksPath"])?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((!value.is_empty()).then(|| PathBuf::from(value)))
+    }
+
+    /// Remove the worktree-scoped `core.hooksPath`
+    ///
+    /// Returns `true` if a value was removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git config cannot be updated
+    pub fn unset_worktree_hooks_path(&self) -> Result<bool> {
+        if self.get_worktree_hooks_path()?.is_none() {
+            return Ok(false);
+        }
+
+        self.git_config(&["--worktree", "--unset", "core.hooksPath"])?;
+        Ok(true)
+    }
+
+    /// Whether `extensions.worktreeConfig` is enabled for this repository
+    fn worktree_config_enabled(&self) -> Result<bool> {
+        let output = self.git_config_output(&["--bool", "--get", "extensions.worktreeConfig"])?;
+        Ok(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "true")
+    }
+
+    /// Run `git config` in this repository, failing on a non-zero exit
+    fn git_config(&self, args: &[&str]) -> Result<()> {
+        let output = self.git_config_output(args)?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git config {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Run `git config` in this repository and return its output
+    fn git_config_output(&self, args: &[&str]) -> Result<std::process::Output> {
+        std::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.root)
+            .arg("config")
+            .args(args)
+            .output()
+            .context("Failed to run git config")
+    }
+
     /// List all worktrees in this repository
     ///
     /// # Errors
@@ -0,0 +1,108 @@
+//! Rewritten commits git passes to `post-rewrite` hooks
+//!
+//! After `git commit --amend` or `git rebase`, git writes one line per
+//! rewritten commit to the hook's stdin: `<old sha> <new sha> [<extra info>]`.
+//! Hooks receive the pairs in `PETER_HOOK_REWRITES`.
+
+use super::ChangeDetectionMode;
+use anyhow::Result;
+use std::path::Path;
+
+/// Environment variable holding the rewritten commits, one
+/// `<old sha> <new sha>` pair per line
+pub const REWRITES_ENV: &str = "PETER_HOOK_REWRITES";
+
+/// One commit rewritten by an amend or rebase, as reported to `post-rewrite`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewrittenCommit {
+    /// Commit before the rewrite
+    pub old_sha: String,
+    /// Commit it was rewritten to
+    pub new_sha: String,
+}
+
+/// Parse the `post-rewrite` stdin lines; blank lines and extra info are
+/// ignored
+///
+/// # Errors
+///
+/// Returns an error if a line has fewer than two fields
+pub fn parse_rewrites(input: &str) -> Result<Vec<RewrittenCommit>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let (Some(old_sha), Some(new_sha)) = (fields.next(), fields.next()) else {
+                anyhow::bail!(
+                    "Malformed post-rewrite line (expected '<old sha> <new sha> [<extra info>]'): \
+                     {line}"
+                );
+            };
+            Ok(RewrittenCommit {
+                old_sha: old_sha.to_string(),
+                new_sha: new_sha.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The value of [`REWRITES_ENV`] for `rewrites`
+#[must_use]
+pub fn rewrites_env_value(rewrites: &[RewrittenCommit]) -> String {
+    rewrites
+        .iter()
+        .map(|rewrite| format!("{} {}", rewrite.old_sha, rewrite.new_sha))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Changes made by every rewritten commit: from the parent of the first new
+/// commit (git reports them oldest first) to `HEAD`
+///
+/// `None` if there are no rewrites or that parent doesn't exist in the
+/// repository at `repo_root`, e.g. when the root commit was rewritten.
+#[must_use]
+pub fn rewritten_commits_mode(
+    rewrites: &[RewrittenCommit],
+    repo_root: &Path,
+) -> Option<ChangeDetectionMode> {
+    let from = format!("{}^", rewrites.first()?.new_sha);
+    let repo = git2::Repository::open(repo_root).ok()?;
+    repo.revparse_single(&from).ok()?;
+    Some(ChangeDetectionMode::CommitRange {
+        from,
+        to: "HEAD".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rewrites() {
+        let rewrites = parse_rewrites(
+            "1111111111111111111111111111111111111111 2222222222222222222222222222222222222222\n\n\
+             3333333333333333333333333333333333333333 4444444444444444444444444444444444444444 \
+             extra\n",
+        )
+        .unwrap();
+
+        assert_eq!(rewrites.len(), 2);
+        assert_eq!(
+            rewrites[1],
+            RewrittenCommit {
+                old_sha: "3333333333333333333333333333333333333333".to_string(),
+                new_sha: "4444444444444444444444444444444444444444".to_string(),
+            }
+        );
+        assert_eq!(
+            rewrites_env_value(&rewrites),
+            "1111111111111111111111111111111111111111 2222222222222222222222222222222222222222\n\
+             3333333333333333333333333333333333333333 4444444444444444444444444444444444444444"
+        );
+
+        assert!(parse_rewrites("1111111111111111111111111111111111111111\n").is_err());
+    }
+}
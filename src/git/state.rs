@@ -0,0 +1,299 @@
+//! State kept between peter-hook runs
+//!
+//! Stored under `peter-hook/` in the worktree's own git directory, so it is
+//! never committed and each linked worktree keeps its own: the marker
+//! `run --since-last-run` diffs against, and the hook timing history `doctor`
+//! reports from when `execution.timing_history` is set.
+
+use super::ChangeDetectionMode;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Directory in the git directory that holds peter-hook's state
+pub const STATE_DIR_NAME: &str = "peter-hook";
+
+/// Where the last successful run of an event left off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastRunMarker {
+    /// Commit `HEAD` was at when the run finished
+    pub commit: String,
+    /// When the run finished, in seconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+impl LastRunMarker {
+    /// Path of the marker for `event` in `git_dir`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `event` can't be used as a file name
+    pub fn path(git_dir: &Path, event: &str) -> Result<PathBuf> {
+        if event.is_empty() || event.starts_with('.') || event.contains(['/', '\\']) {
+            anyhow::bail!("Invalid event name for a run marker: '{event}'");
+        }
+        Ok(git_dir
+            .join(STATE_DIR_NAME)
+            .join("last-run")
+            .join(format!("{event}.json")))
+    }
+
+    /// Load the marker for `event`, if a successful run has left one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the marker exists but cannot be read or parsed
+    pub fn load(git_dir: &Path, event: &str) -> Result<Option<Self>> {
+        let path = Self::path(git_dir, event)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read run marker: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse run marker: {}", path.display()))
+            .map(Some)
+    }
+
+    /// Record that `event` just ran successfully at the current `HEAD`
+    ///
+    /// Returns `None` without writing anything when `HEAD` has no commit yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `HEAD` cannot be read or the marker cannot be
+    /// written
+    pub fn record(git_dir: &Path, repo_root: &Path, event: &str) -> Result<Option<Self>> {
+        let path = Self::path(git_dir, event)?;
+        let Some(commit) = rev_parse(repo_root, "HEAD")? else {
+            return Ok(None);
+        };
+        let marker = Self {
+            commit,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        };
+
+        let dir = path.parent().context("Run marker path has no parent")?;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create state directory: {}", dir.display()))?;
+        // Write to a temporary file and rename, so a reader never sees a
+        // partial marker
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(&marker)?)
+            .with_context(|| format!("Failed to write run marker: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write run marker: {}", path.display()))?;
+
+        log::info!("run marker recorded event={event} commit={}", marker.commit);
+        Ok(Some(marker))
+    }
+
+    /// Change detection against this marker's commit, or `None` if the commit
+    /// no longer exists (e.g. garbage collected after a rebase)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git cannot be run
+    pub fn change_mode(&self, repo_root: &Path) -> Result<Option<ChangeDetectionMode>> {
+        let spec = format!("{}^{{commit}}", self.commit);
+        Ok(rev_parse(repo_root, &spec)?.map(|commit| ChangeDetectionMode::SinceLastRun { commit }))
+    }
+}
+
+/// Recent durations of each hook, kept when `execution.timing_history` is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimingHistory {
+    /// Durations in milliseconds by hook name, oldest first
+    #[serde(default)]
+    pub hooks: BTreeMap<String, Vec<u64>>,
+}
+
+/// A hook's recorded durations, summarized
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookTiming {
+    /// Hook name
+    pub name: String,
+    /// Median duration in milliseconds
+    pub median_ms: u64,
+    /// Number of runs recorded
+    pub runs: usize,
+}
+
+impl TimingHistory {
+    /// Path of the history in `git_dir`
+    #[must_use]
+    pub fn path(git_dir: &Path) -> PathBuf {
+        git_dir.join(STATE_DIR_NAME).join("timings.json")
+    }
+
+    /// Load the history; empty if none was recorded
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history exists but cannot be read or parsed
+    pub fn load(git_dir: &Path) -> Result<Self> {
+        let path = Self::path(git_dir);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read timing history: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse timing history: {}", path.display()))
+    }
+
+    /// Save the history
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history cannot be written
+    pub fn save(&self, git_dir: &Path) -> Result<()> {
+        let path = Self::path(git_dir);
+        let dir = path.parent().context("Timing history path has no parent")?;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create state directory: {}", dir.display()))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write timing history: {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to write timing history: {}", path.display()))
+    }
+
+    /// Add a run of `hook` that took `duration_ms`, keeping its last `keep`
+    /// runs
+    pub fn record(&mut self, hook: &str, duration_ms: u64, keep: usize) {
+        let runs = self.hooks.entry(hook.to_string()).or_default();
+        runs.push(duration_ms);
+        let excess = runs.len().saturating_sub(keep);
+        runs.drain(..excess);
+    }
+
+    /// Up to `limit` hooks, slowest median first (ties by name)
+    #[must_use]
+    pub fn slowest(&self, limit: usize) -> Vec<HookTiming> {
+        let mut timings: Vec<HookTiming> = self
+            .hooks
+            .iter()
+            .filter(|(_, runs)| !runs.is_empty())
+            .map(|(name, runs)| {
+                let mut sorted = runs.clone();
+                sorted.sort_unstable();
+                let mid = sorted.len() / 2;
+                let median_ms = if sorted.len() % 2 == 0 {
+                    sorted[mid - 1].midpoint(sorted[mid])
+                } else {
+                    sorted[mid]
+                };
+                HookTiming {
+                    name: name.clone(),
+                    median_ms,
+                    runs: runs.len(),
+                }
+            })
+            .collect();
+        timings.sort_by(|a, b| {
+            b.median_ms
+                .cmp(&a.median_ms)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        timings.truncate(limit);
+        timings
+    }
+}
+
+/// Resolve `spec` to a full object name, or `None` if it doesn't resolve
+fn rev_parse(repo_root: &Path, spec: &str) -> Result<Option<String>> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", spec])
+        .current_dir(repo_root)
+        .output()
+        .with_context(|| format!("Failed to run git command: git rev-parse {spec}"))?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok((output.status.success() && !name.is_empty()).then_some(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(repo_dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn test_last_run_marker_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        let git_dir = repo.join(".git");
+        git(repo, &["init", "-q"]);
+        git(repo, &["config", "user.name", "Test User"]);
+        git(repo, &["config", "user.email", "test@example.com"]);
+
+        assert_eq!(LastRunMarker::load(&git_dir, "pre-commit").unwrap(), None);
+        assert_eq!(
+            LastRunMarker::record(&git_dir, repo, "pre-commit").unwrap(),
+            None,
+            "no marker before the first commit"
+        );
+
+        git(repo, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+        let recorded = LastRunMarker::record(&git_dir, repo, "pre-commit")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            LastRunMarker::load(&git_dir, "pre-commit").unwrap(),
+            Some(recorded.clone())
+        );
+        assert_eq!(LastRunMarker::load(&git_dir, "pre-push").unwrap(), None);
+        assert!(matches!(
+            recorded.change_mode(repo).unwrap(),
+            Some(ChangeDetectionMode::SinceLastRun { commit }) if commit == recorded.commit
+        ));
+
+        let gone = LastRunMarker {
+            commit: "0".repeat(40),
+            timestamp: 0,
+        };
+        assert!(gone.change_mode(repo).unwrap().is_none());
+        assert!(LastRunMarker::path(&git_dir, "../escape").is_err());
+    }
+
+    #[test]
+    fn test_timing_history_keeps_last_runs_and_ranks_by_median() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+
+        let mut history = TimingHistory::load(&git_dir).unwrap();
+        assert!(history.slowest(5).is_empty());
+        for ms in [100, 5000, 300, 200] {
+            history.record("lint", ms, 3);
+        }
+        for ms in [900, 1000] {
+            history.record("test", ms, 3);
+        }
+        history.record("fmt", 50, 3);
+        assert_eq!(history.hooks["lint"], [5000, 300, 200]);
+        history.save(&git_dir).unwrap();
+
+        let history = TimingHistory::load(&git_dir).unwrap();
+        let slowest = history.slowest(2);
+        let ranked: Vec<(&str, u64, usize)> = slowest
+            .iter()
+            .map(|t| (t.name.as_str(), t.median_ms, t.runs))
+            .collect();
+        assert_eq!(ranked, [("test", 950, 2), ("lint", 300, 3)]);
+    }
+}
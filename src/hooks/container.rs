@@ -0,0 +1,216 @@
+//! Running hooks inside a container
+//!
+//! A hook with `container = { image = "..." }` runs its commands through
+//! `docker run` or `podman run` instead of on the host. The repository is
+//! mounted at its host path, so absolute paths, templates and the hook's
+//! working directory mean the same thing inside the container. peter-hook's
+//! temporary files (inline scripts, the `{CHANGED_FILES_FILE}` list) are
+//! mounted read-only at their host paths as well.
+
+use super::resolver::WorktreeContext;
+use crate::config::ContainerSpec;
+use anyhow::{Context, Result, bail};
+use std::{path::Path, process::Command};
+
+/// Runtimes tried, in order, for containers that don't name one
+pub const CONTAINER_RUNTIMES: &[&str] = &["docker", "podman"];
+
+/// Find the runtime to run `spec` with: its own `runtime`, or the first of
+/// [`CONTAINER_RUNTIMES`] on `PATH`
+///
+/// # Errors
+///
+/// Returns an error if the requested runtime, or any runtime at all, is not
+/// installed
+pub fn find_runtime(spec: &ContainerSpec) -> Result<String> {
+    if let Some(runtime) = &spec.runtime {
+        if crate::doctor::binary_exists(runtime) {
+            return Ok(runtime.clone());
+        }
+        bail!(
+            "Container runtime '{runtime}' for image '{}' was not found on PATH",
+            spec.image
+        );
+    }
+
+    CONTAINER_RUNTIMES
+        .iter()
+        .find(|runtime| crate::doctor::binary_exists(runtime))
+        .map(ToString::to_string)
+        .with_context(|| {
+            format!(
+                "No container runtime for image '{}': neither {} was found on PATH",
+                spec.image,
+                CONTAINER_RUNTIMES.join(" nor ")
+            )
+        })
+}
+
+/// Build the `<runtime> run` invocation that runs `command` in the container
+///
+/// The program, arguments, working directory and explicitly set environment
+/// variables of `command` carry over; the host environment is not inherited.
+#[must_use]
+pub fn containerize(
+    command: &Command,
+    spec: &ContainerSpec,
+    runtime: &str,
+    worktree_context: &WorktreeContext,
+    temp_files: &[&Path],
+) -> Command {
+    let mut container = Command::new(runtime);
+    container.args(["run", "--rm"]);
+
+    let repo_root = &worktree_context.repo_root;
+    container.args(["-v", &bind_mount(repo_root, "")]);
+    // A linked worktree's git data lives in the main repository
+    if !worktree_context.common_dir.starts_with(repo_root) {
+        container.args(["-v", &bind_mount(&worktree_context.common_dir, "")]);
+    }
+    for file in temp_files {
+        container.args(["-v", &bind_mount(file, ":ro")]);
+    }
+    for mount in &spec.mounts {
+        container.args(["-v", mount]);
+    }
+
+    if let Some(dir) = command.get_current_dir() {
+        container.arg("-w").arg(dir);
+        container.current_dir(dir);
+    }
+    for (key, value) in command.get_envs() {
+        if let Some(value) = value {
+            let mut assignment = key.to_os_string();
+            assignment.push("=");
+            assignment.push(value);
+            container.arg("-e").arg(assignment);
+        }
+    }
+
+    container
+        .arg(&spec.image)
+        .arg(command.get_program())
+        .args(command.get_args());
+    container
+}
+
+/// `-v` value mounting a host path at the same path in the container
+fn bind_mount(path: &Path, options: &str) -> String {
+    format!("{0}:{0}{options}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use std::path::PathBuf;
+
+    fn args(command: &Command) -> Vec<String> {
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn context(repo_root: &str, common_dir: &str) -> WorktreeContext {
+        WorktreeContext {
+            is_worktree: false,
+            worktree_name: None,
+            repo_root: PathBuf::from(repo_root),
+            common_dir: PathBuf::from(common_dir),
+            working_dir: PathBuf::from(repo_root),
+            run_env: IndexMap::new(),
+            push_refs: None,
+        }
+    }
+
+    #[test]
+    fn test_containerize_wraps_command() {
+        let mut command = Command::new("sh");
+        command
+            .args(["-c", "cargo fmt --check \"$@\"", "sh", "src/main.rs"])
+            .current_dir("/work/repo/crates/app")
+            .env("PETER_HOOK_CHANGED_COUNT", "1");
+        let spec = ContainerSpec {
+            image: "rust:1.86".to_string(),
+            mounts: vec!["/home/me/.cargo:/usr/local/cargo:ro".to_string()],
+            runtime: None,
+        };
+
+        let wrapped = containerize(
+            &command,
+            &spec,
+            "podman",
+            &context("/work/repo", "/work/repo/.git"),
+            &[Path::new("/tmp/peter-hook-script-1.sh")],
+        );
+
+        assert_eq!(
+            args(&wrapped),
+            [
+                "podman",
+                "run",
+                "--rm",
+                "-v",
+                "/work/repo:/work/repo",
+                "-v",
+                "/tmp/peter-hook-script-1.sh:/tmp/peter-hook-script-1.sh:ro",
+                "-v",
+                "/home/me/.cargo:/usr/local/cargo:ro",
+                "-w",
+                "/work/repo/crates/app",
+                "-e",
+                "PETER_HOOK_CHANGED_COUNT=1",
+                "rust:1.86",
+                "sh",
+                "-c",
+                "cargo fmt --check \"$@\"",
+                "sh",
+                "src/main.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_containerize_mounts_linked_worktree_git_dir() {
+        let mut command = Command::new("true");
+        command.current_dir("/work/feature");
+        let spec = ContainerSpec {
+            image: "alpine".to_string(),
+            mounts: Vec::new(),
+            runtime: None,
+        };
+
+        let wrapped = containerize(
+            &command,
+            &spec,
+            "docker",
+            &context("/work/feature", "/work/repo/.git"),
+            &[],
+        );
+
+        let wrapped = args(&wrapped);
+        assert!(
+            wrapped
+                .windows(2)
+                .any(|pair| pair == ["-v", "/work/repo/.git:/work/repo/.git"]),
+            "{wrapped:?}"
+        );
+    }
+
+    #[test]
+    fn test_find_runtime_reports_missing_runtime() {
+        let spec = ContainerSpec {
+            image: "alpine".to_string(),
+            mounts: Vec::new(),
+            runtime: Some("peter-hook-no-such-runtime".to_string()),
+        };
+
+        let err = find_runtime(&spec).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'peter-hook-no-such-runtime' for image 'alpine' was not found"),
+            "{err:#}"
+        );
+    }
+}
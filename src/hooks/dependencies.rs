@@ -134,9 +134,10 @@ impl DependencyResolver {
         let mut queue = VecDeque::new();
         let mut result = Vec::new();
 
-        // Start with hooks that have no dependencies
-        for (hook, &degree) in &in_degree {
-            if degree == 0 {
+        // Start with hooks that have no dependencies, keeping the input order so
+        // the resulting sequence is deterministic
+        for hook in hook_names {
+            if in_degree[hook] == 0 {
                 queue.push_back(hook.clone());
             }
         }
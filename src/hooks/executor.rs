@@ -1,21 +1,45 @@
 //! Hook execution engine
 
 use crate::{
-    config::{ExecutionStrategy, ExecutionType, HookCommand, TemplateResolver},
+    config::{
+        ContainerSpec, DEFAULT_SCRIPT_SHELL, ExecutionStrategy, ExecutionType, HookCommand,
+        HookDefinition, Isolation, TemplateResolver, compile_output_pattern,
+    },
     git::FilePatternMatcher,
-    hooks::{DependencyResolver, ResolvedHook, ResolvedHooks},
-    output::formatter,
+    hooks::{
+        DependencyResolver, ExecutionPhase, ExecutionPlan, ResolvedHook, ResolvedHooks, container,
+        drop_deleted_files,
+        interrupt::{self, OutputStream},
+        isolation::IsolatedWorktree,
+        resolver::resolve_workdir,
+    },
+    output::{
+        self, ReportFormat, formatter, github, hook_label, output_limits, report_format, show_files,
+    },
 };
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
 use std::{
-    collections::HashMap,
-    io::IsTerminal,
+    fmt::Write as _,
+    io::Write as _,
     path::{Path, PathBuf},
-    process::{Command, Stdio},
-    sync::{Arc, Mutex},
+    process::{Command, Output, Stdio},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
+use tempfile::TempPath;
+
+/// Upper bound, in bytes, for the argument list of a single per-file
+/// invocation. Well below the usual `ARG_MAX` so the environment still fits.
+#[cfg(windows)]
+const MAX_COMMAND_LINE_BYTES: usize = 30_000;
+#[cfg(not(windows))]
+const MAX_COMMAND_LINE_BYTES: usize = 128 * 1024;
 
 /// Executes resolved hooks
 pub struct HookExecutor {
@@ -24,24 +48,241 @@ pub struct HookExecutor {
     parallel: bool,
 }
 
-/// Result of hook execution
+/// Files a hook would be invoked with, computed without running it
+#[derive(Debug, Clone, Serialize)]
+pub struct HookFilePlan {
+    /// Hook name
+    pub hook: String,
+    /// How the hook receives files
+    pub execution_type: ExecutionType,
+    /// Directory the hook runs in (file paths are relative to it)
+    pub execution_dir: PathBuf,
+    /// Whether the hook would run
+    pub will_run: bool,
+    /// Files after applying the hook's `files` patterns
+    pub files: Vec<PathBuf>,
+}
+
+/// Environment a hook would run with, computed without running it
 #[derive(Debug, Clone)]
+pub struct HookEnvPlan {
+    /// Hook name
+    pub hook: String,
+    /// Whether the hook would run
+    pub will_run: bool,
+    /// Variables set on top of the inherited environment, sorted by name
+    pub env: IndexMap<String, String>,
+}
+
+/// Shown instead of the value of a sensitive variable
+pub const REDACTED_VALUE: &str = "<redacted>";
+
+/// Parts of variable names whose values are redacted by `run --dump-env`
+const SENSITIVE_ENV_KEY_PARTS: &[&str] = &["TOKEN", "SECRET", "PASSWORD"];
+
+impl HookEnvPlan {
+    /// Replace the values of variables whose names contain `TOKEN`, `SECRET`
+    /// or `PASSWORD` (in any case) with [`REDACTED_VALUE`]
+    pub fn redact(&mut self) {
+        for (key, value) in &mut self.env {
+            if is_sensitive_env_key(key) {
+                *value = REDACTED_VALUE.to_string();
+            }
+        }
+    }
+}
+
+/// Whether a variable name looks like it holds a credential
+#[must_use]
+pub fn is_sensitive_env_key(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    SENSITIVE_ENV_KEY_PARTS
+        .iter()
+        .any(|part| key.contains(part))
+}
+
+/// File that lives for the duration of a hook run (an inline script or the
+/// `{CHANGED_FILES_FILE}` list)
+///
+/// The name is unique and unpredictable, and the file is removed when dropped
+/// on every exit path, or by the interrupt handler.
+struct HookTempFile {
+    path: TempPath,
+    _tracked: interrupt::TempFileGuard,
+}
+
+impl HookTempFile {
+    fn create(prefix: &str, suffix: &str, contents: &str) -> Result<Self> {
+        let mut file = tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .tempfile()
+            .context("Failed to create temporary file")?;
+        file.write_all(contents.as_bytes())
+            .context("Failed to write temporary file")?;
+        let path = file.into_temp_path();
+        Ok(Self {
+            _tracked: interrupt::track_temp_file(&path),
+            path,
+        })
+    }
+}
+
+/// Result of hook execution
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecutionResult {
     /// Exit code of the hook
     pub exit_code: i32,
-    /// Standard output
+    /// Standard output (in the `--output-dir` logs rather than the report)
+    #[serde(skip)]
     pub stdout: String,
-    /// Standard error
+    /// Standard error (in the `--output-dir` logs rather than the report)
+    #[serde(skip)]
     pub stderr: String,
     /// Whether the hook succeeded (exit code 0)
     pub success: bool,
+    /// Why the hook was skipped without running, if it was
+    pub skipped: Option<SkipReason>,
+    /// Step of a `steps` command that failed, if one did
+    pub failed_step: Option<FailedStep>,
+    /// The hook's `description`, shown next to its name in the summary
+    pub description: Option<String>,
+    /// Changed files the hook ran for, after its `files` patterns
+    /// (repository-relative); empty if it got none
+    pub files: Vec<PathBuf>,
+    /// Whether `stdout` holds stdout and stderr interleaved in write order
+    /// (`combined_output`)
+    #[serde(skip)]
+    pub combined_output: bool,
+    /// How long the hook took, in milliseconds; `None` if it didn't run
+    pub duration_ms: Option<u64>,
+    /// Whether a failure of the hook is advisory (`allow_failure`) and
+    /// doesn't fail the run
+    pub allow_failure: bool,
+}
+
+impl ExecutionResult {
+    /// Whether the hook failed in a way that fails the run: it failed and
+    /// isn't allowed to
+    #[must_use]
+    pub const fn is_blocking_failure(&self) -> bool {
+        !self.success && !self.allow_failure
+    }
+
+    /// Whether the hook failed but is allowed to (`allow_failure`)
+    #[must_use]
+    pub const fn is_advisory_failure(&self) -> bool {
+        !self.success && self.allow_failure
+    }
+
+    /// Result for a command that exited with `exit_code`; it succeeded if
+    /// that is 0
+    #[must_use]
+    pub const fn new(exit_code: i32, stdout: String, stderr: String) -> Self {
+        Self {
+            exit_code,
+            stdout,
+            stderr,
+            success: exit_code == 0,
+            skipped: None,
+            failed_step: None,
+            description: None,
+            files: Vec::new(),
+            combined_output: false,
+            duration_ms: None,
+            allow_failure: false,
+        }
+    }
+
+    /// Result for a hook that could not be executed at all
+    fn errored(error: &anyhow::Error, description: Option<String>) -> Self {
+        Self {
+            description,
+            ..Self::new(-1, String::new(), format!("Execution error: {error:#}"))
+        }
+    }
+}
+
+/// Step of a `steps` command that failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FailedStep {
+    /// Position of the step, starting at 1
+    pub index: usize,
+    /// Number of steps in the command
+    pub count: usize,
+    /// The step's command
+    pub command: String,
+}
+
+impl std::fmt::Display for FailedStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed at step {}/{}: {}",
+            self.index, self.count, self.command
+        )
+    }
+}
+
+/// Why a hook was skipped without running
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Its `skip_if` condition exited 0
+    SkipIf,
+    /// It sets `enabled = false`
+    Disabled,
+    /// None of the refs being pushed matches its `push_refs`
+    PushRefs,
+}
+
+impl SkipReason {
+    /// Short explanation shown in the run summary
+    #[must_use]
+    pub const fn describe(self) -> &'static str {
+        match self {
+            Self::SkipIf => "skip_if condition met",
+            Self::Disabled => "disabled",
+            Self::PushRefs => "no pushed ref matches push_refs",
+        }
+    }
+}
+
+/// Failed hooks counted against a run's `bail_after`, shared by every hook
+/// of the run
+#[derive(Debug)]
+struct FailureBudget {
+    limit: Option<usize>,
+    failures: AtomicUsize,
+}
+
+impl FailureBudget {
+    const fn new(limit: Option<usize>) -> Self {
+        Self {
+            limit,
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count a finished hook
+    fn record(&self, success: bool) {
+        if !success {
+            self.failures.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Whether enough hooks failed that no more should start
+    fn exhausted(&self) -> bool {
+        self.limit
+            .is_some_and(|limit| self.failures.load(Ordering::SeqCst) >= limit)
+    }
 }
 
 /// Results from executing multiple hooks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecutionResults {
-    /// Results for each hook by name
-    pub results: HashMap<String, ExecutionResult>,
+    /// Results for each hook by name, in the order they completed
+    pub results: IndexMap<String, ExecutionResult>,
     /// Overall success (all hooks succeeded)
     pub success: bool,
 }
@@ -68,16 +309,50 @@ impl HookExecutor {
     ///
     /// Returns an error if any hook fails to execute due to system issues
     pub fn execute_multiple(groups: &[crate::hooks::ConfigGroup]) -> Result<ExecutionResults> {
-        let mut all_results = HashMap::new();
+        Self::execute_multiple_from(groups, None)
+    }
+
+    /// Execute multiple configuration groups, resuming at a named hook
+    ///
+    /// Groups before the one containing `continue_from` are skipped, and within
+    /// that group execution starts at the named hook (see [`Self::execute_from`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `continue_from` is not part of any group, or if any
+    /// hook fails to execute due to system issues
+    pub fn execute_multiple_from(
+        groups: &[crate::hooks::ConfigGroup],
+        continue_from: Option<&str>,
+    ) -> Result<ExecutionResults> {
+        let start_group = match continue_from {
+            Some(name) => groups
+                .iter()
+                .position(|group| group.resolved_hooks.hooks.contains_key(name))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Hook '{name}' is not part of this run; cannot continue from it"
+                    )
+                })?,
+            None => 0,
+        };
+
+        let mut all_results = IndexMap::new();
         let mut overall_success = true;
 
-        for group in groups {
-            let results = Self::execute(&group.resolved_hooks).with_context(|| {
-                format!(
-                    "Failed to execute hooks from config: {}",
-                    group.config_path.display()
-                )
-            })?;
+        for (index, group) in groups.iter().enumerate().skip(start_group) {
+            let resume_at = if index == start_group {
+                continue_from
+            } else {
+                None
+            };
+            let results =
+                Self::execute_from(&group.resolved_hooks, resume_at).with_context(|| {
+                    format!(
+                        "Failed to execute hooks from config: {}",
+                        group.config_path.display()
+                    )
+                })?;
 
             if !results.success {
                 overall_success = false;
@@ -113,6 +388,202 @@ impl HookExecutor {
     /// Returns an error if any hook fails to execute due to system issues
     /// (not hook failure - that's reported in the results)
     pub fn execute(resolved_hooks: &ResolvedHooks) -> Result<ExecutionResults> {
+        Self::execute_from(resolved_hooks, None)
+    }
+
+    /// Execute resolved hooks, optionally resuming at a named hook
+    ///
+    /// Hooks that come before `continue_from` in the resolved order are
+    /// skipped. With dependencies, every phase before the one containing the
+    /// named hook is skipped instead, with a warning if any of its
+    /// dependencies did not run.
+    ///
+    /// Group `before` commands run first; if one fails, no hooks run. Group
+    /// `after` commands always run last. Both are reported as
+    /// `<group> (before)` / `<group> (after)` entries in the results.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `continue_from` is not one of the resolved hooks, or
+    /// if any hook fails to execute due to system issues
+    pub fn execute_from(
+        resolved_hooks: &ResolvedHooks,
+        continue_from: Option<&str>,
+    ) -> Result<ExecutionResults> {
+        if let Some(name) = continue_from {
+            if !resolved_hooks.hooks.contains_key(name) {
+                return Err(anyhow::anyhow!(
+                    "Hook '{name}' is not part of this run; cannot continue from it"
+                ));
+            }
+        }
+
+        let mut results = IndexMap::new();
+        let mut success = true;
+
+        for lifecycle in &resolved_hooks.before {
+            let label = format!("{} (before)", lifecycle.group);
+            let result = Self::run_lifecycle_command(&label, &lifecycle.command, resolved_hooks);
+            success &= result.success;
+            results.insert(label, result);
+            if !success {
+                break;
+            }
+        }
+
+        // Setup failures skip the hooks, but teardown always runs
+        let hook_results = if success {
+            Some(Self::execute_hooks_from(resolved_hooks, continue_from))
+        } else {
+            None
+        };
+
+        for lifecycle in &resolved_hooks.after {
+            let label = format!("{} (after)", lifecycle.group);
+            let result = Self::run_lifecycle_command(&label, &lifecycle.command, resolved_hooks);
+            success &= result.success;
+            results.insert(label, result);
+        }
+
+        if let Some(hook_results) = hook_results {
+            let hook_results = hook_results?;
+            success &= hook_results.success;
+            // Keep before -> hooks -> after ordering in the report
+            let after_results = results.split_off(resolved_hooks.before.len());
+            results.extend(hook_results.results);
+            results.extend(after_results);
+        }
+
+        Ok(ExecutionResults { results, success })
+    }
+
+    /// Run a group `before`/`after` command in the config directory
+    fn run_lifecycle_command(
+        label: &str,
+        command: &HookCommand,
+        resolved_hooks: &ResolvedHooks,
+    ) -> ExecutionResult {
+        let started = Instant::now();
+        let outcome = resolved_hooks
+            .config_path
+            .parent()
+            .context("Config file has no parent directory")
+            .and_then(|config_dir| {
+                let template_resolver = TemplateResolver::with_worktree_context(
+                    config_dir,
+                    config_dir,
+                    &resolved_hooks.worktree_context,
+                );
+                let (command_parts, _script_file) =
+                    Self::resolve_command_parts(command, &template_resolver)?;
+                log::info!(
+                    "exec {label} cwd={} command={command_parts:?}",
+                    config_dir.display()
+                );
+
+                let output = interrupt::output(
+                    Command::new(&command_parts[0])
+                        .args(&command_parts[1..])
+                        .envs(&resolved_hooks.worktree_context.run_env)
+                        .current_dir(config_dir),
+                )
+                .with_context(|| format!("Failed to execute {label} command"))?;
+
+                Ok(ExecutionResult::new(
+                    output.status.code().unwrap_or(-1),
+                    String::from_utf8_lossy(&output.stdout).to_string(),
+                    String::from_utf8_lossy(&output.stderr).to_string(),
+                ))
+            });
+
+        let result = outcome.unwrap_or_else(|e| ExecutionResult::errored(&e, None));
+        log::info!(
+            "{label} finished success={} exit_code={} duration_ms={}",
+            result.success,
+            result.exit_code,
+            started.elapsed().as_millis()
+        );
+        result
+    }
+
+    /// Resolve templates in a hook-style command into argv form
+    ///
+    /// For inline scripts the returned guard owns the script file; keep it
+    /// alive until the command has run.
+    fn resolve_command_parts(
+        command: &HookCommand,
+        template_resolver: &TemplateResolver,
+    ) -> Result<(Vec<String>, Option<HookTempFile>)> {
+        let (parts, script_file) = match command {
+            HookCommand::Shell(cmd) => (
+                vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    template_resolver
+                        .resolve_shell(cmd)
+                        .context("Failed to resolve command template")?,
+                ],
+                None,
+            ),
+            HookCommand::Args(args) => (
+                template_resolver
+                    .resolve_command_args(args)
+                    .context("Failed to resolve command arguments")?,
+                None,
+            ),
+            HookCommand::Script { script, shell } => {
+                let (parts, script_file) =
+                    Self::prepare_script(script, shell.as_deref(), template_resolver)?;
+                (parts, Some(script_file))
+            }
+            HookCommand::Steps { .. } => {
+                return Err(anyhow::anyhow!(
+                    "Steps are only supported in a hook's command"
+                ));
+            }
+        };
+
+        if parts.is_empty() {
+            return Err(anyhow::anyhow!("Empty command"));
+        }
+        Ok((parts, script_file))
+    }
+
+    /// Resolve templates in an inline script and write it to a temporary
+    /// file
+    ///
+    /// Returns the argv that runs the file with `shell` (default `sh`) and
+    /// the file itself, which is deleted when dropped.
+    fn prepare_script(
+        script: &str,
+        shell: Option<&str>,
+        template_resolver: &TemplateResolver,
+    ) -> Result<(Vec<String>, HookTempFile)> {
+        let body = template_resolver
+            .resolve_shell(script)
+            .context("Failed to resolve script template")?;
+
+        let script_file = HookTempFile::create("peter-hook-script-", ".sh", &body)
+            .context("Failed to write script file")?;
+
+        let mut parts: Vec<String> = shell
+            .unwrap_or(DEFAULT_SCRIPT_SHELL)
+            .split_whitespace()
+            .map(ToString::to_string)
+            .collect();
+        if parts.is_empty() {
+            return Err(anyhow::anyhow!("Empty shell for script"));
+        }
+        parts.push(script_file.path.to_string_lossy().into_owned());
+
+        Ok((parts, script_file))
+    }
+
+    /// Execute the resolved hooks themselves (without group lifecycle commands)
+    fn execute_hooks_from(
+        resolved_hooks: &ResolvedHooks,
+        continue_from: Option<&str>,
+    ) -> Result<ExecutionResults> {
         // Check if we need dependency resolution
         let needs_dependencies = resolved_hooks
             .hooks
@@ -120,14 +591,101 @@ impl HookExecutor {
             .any(|hook| hook.definition.depends_on.is_some());
 
         if needs_dependencies {
-            Self::execute_with_dependencies(resolved_hooks)
-        } else {
-            Self::execute_with_strategy(resolved_hooks, resolved_hooks.execution_strategy)
+            return Self::execute_with_dependencies(resolved_hooks, continue_from);
+        }
+
+        let Some(name) = continue_from else {
+            return Self::execute_with_strategy(resolved_hooks, resolved_hooks.execution_strategy);
+        };
+
+        let start = resolved_hooks.hooks.get_index_of(name).unwrap_or(0);
+        let skipped: Vec<&str> = resolved_hooks
+            .hooks
+            .keys()
+            .take(start)
+            .map(String::as_str)
+            .collect();
+        if !skipped.is_empty() {
+            eprintln!(
+                "⏭️  Continuing from '{name}', skipping: {}",
+                skipped.join(", ")
+            );
+        }
+
+        let remaining = ResolvedHooks {
+            hooks: resolved_hooks
+                .hooks
+                .iter()
+                .skip(start)
+                .map(|(name, hook)| (name.clone(), hook.clone()))
+                .collect(),
+            ..resolved_hooks.clone()
+        };
+        Self::execute_with_strategy(&remaining, remaining.execution_strategy)
+    }
+
+    /// The phases the resolved hooks run in, as [`execute`](Self::execute)
+    /// runs them
+    ///
+    /// With `depends_on` anywhere this is the dependency plan. Otherwise it
+    /// follows the execution strategy: one sequential phase, or a parallel
+    /// phase followed by a sequential one for `modifies_repository` hooks.
+    /// Group `before`/`after` commands are not part of the plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the dependencies are circular
+    pub fn execution_plan(resolved_hooks: &ResolvedHooks) -> Result<ExecutionPlan> {
+        if resolved_hooks
+            .hooks
+            .values()
+            .any(|hook| hook.definition.depends_on.is_some())
+        {
+            return Self::dependency_plan(resolved_hooks);
+        }
+
+        let all: Vec<String> = resolved_hooks.hooks.keys().cloned().collect();
+        let (modifying, safe): (Vec<String>, Vec<String>) = all
+            .iter()
+            .cloned()
+            .partition(|name| resolved_hooks.hooks[name].definition.modifies_repository);
+        let phases = match resolved_hooks.execution_strategy {
+            ExecutionStrategy::Sequential => vec![(all, false)],
+            ExecutionStrategy::Parallel => vec![(safe, true), (modifying, false)],
+            ExecutionStrategy::ForceParallel => vec![(all, true)],
+        };
+
+        Ok(ExecutionPlan {
+            phases: phases
+                .into_iter()
+                .filter(|(hooks, _)| !hooks.is_empty())
+                .map(|(hooks, parallel)| ExecutionPhase { hooks, parallel })
+                .collect(),
+        })
+    }
+
+    /// Phases that respect every hook's `depends_on`
+    fn dependency_plan(resolved_hooks: &ResolvedHooks) -> Result<ExecutionPlan> {
+        let mut resolver = DependencyResolver::new();
+        let hook_names: Vec<String> = resolved_hooks.hooks.keys().cloned().collect();
+
+        // Build dependency graph
+        for (name, hook) in &resolved_hooks.hooks {
+            let dependencies = hook.definition.depends_on.clone().unwrap_or_default();
+            resolver.add_hook(name.clone(), dependencies);
         }
+
+        resolver
+            .resolve(&hook_names)
+            .context("Failed to resolve hook dependencies")
     }
 
     /// Execute hooks with a specific execution strategy
     ///
+    /// Once `bail_after` hooks have failed no further hook starts; hooks
+    /// already running in a parallel phase finish, and the hooks that never
+    /// ran are reported on stderr.
+    ///
     /// # Errors
     ///
     /// Returns an error if any hook fails to execute due to system issues
@@ -135,28 +693,56 @@ impl HookExecutor {
         resolved_hooks: &ResolvedHooks,
         strategy: ExecutionStrategy,
     ) -> Result<ExecutionResults> {
-        match strategy {
-            ExecutionStrategy::Sequential => Self::execute_sequential(resolved_hooks),
-            ExecutionStrategy::Parallel => Self::execute_parallel_safe(resolved_hooks),
-            ExecutionStrategy::ForceParallel => Ok(Self::execute_parallel_unsafe(resolved_hooks)),
+        let budget = Arc::new(FailureBudget::new(resolved_hooks.bail_after));
+        let results = match strategy {
+            ExecutionStrategy::Sequential => Self::execute_sequential(resolved_hooks, &budget)?,
+            ExecutionStrategy::Parallel => Self::execute_parallel_safe(resolved_hooks, &budget)?,
+            ExecutionStrategy::ForceParallel => {
+                Self::execute_parallel_unsafe(resolved_hooks, &budget)
+            }
+        };
+
+        let not_run: Vec<&str> = resolved_hooks
+            .hooks
+            .keys()
+            .filter(|name| !results.results.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if budget.exhausted() && !not_run.is_empty() {
+            eprintln!(
+                "⛔ Stopped after {} failed hooks (--bail-after {}), not run: {}",
+                budget.failures.load(Ordering::SeqCst),
+                budget.limit.unwrap_or_default(),
+                not_run.join(", ")
+            );
         }
+
+        Ok(results)
     }
 
     /// Execute hooks sequentially (original behavior)
-    fn execute_sequential(resolved_hooks: &ResolvedHooks) -> Result<ExecutionResults> {
-        let mut results = HashMap::new();
+    fn execute_sequential(
+        resolved_hooks: &ResolvedHooks,
+        budget: &FailureBudget,
+    ) -> Result<ExecutionResults> {
+        let mut results = IndexMap::new();
         let mut overall_success = true;
 
         for (name, hook) in &resolved_hooks.hooks {
-            let result = Self::execute_single_hook(
+            if budget.exhausted() {
+                break;
+            }
+            let result = Self::execute_hook(
                 name,
                 hook,
                 &resolved_hooks.worktree_context,
                 resolved_hooks.changed_files.as_deref(),
+                resolved_hooks.isolation,
             )
             .with_context(|| format!("Failed to execute hook: {name}"))?;
 
-            if !result.success {
+            budget.record(!result.is_blocking_failure());
+            if result.is_blocking_failure() {
                 overall_success = false;
             }
 
@@ -170,7 +756,10 @@ impl HookExecutor {
     }
 
     /// Execute hooks in parallel, respecting repository modification safety
-    fn execute_parallel_safe(resolved_hooks: &ResolvedHooks) -> Result<ExecutionResults> {
+    fn execute_parallel_safe(
+        resolved_hooks: &ResolvedHooks,
+        budget: &Arc<FailureBudget>,
+    ) -> Result<ExecutionResults> {
         // Separate hooks into safe-to-parallelize and repository-modifying
         let mut safe_hooks = Vec::new();
         let mut modifying_hooks = Vec::new();
@@ -179,34 +768,41 @@ impl HookExecutor {
             if hook.definition.modifies_repository {
                 modifying_hooks.push((name.clone(), hook));
             } else {
-                safe_hooks.push((name.clone(), hook));
+                safe_hooks.push(name);
             }
         }
 
-        let results = Arc::new(Mutex::new(HashMap::new()));
+        let results = Arc::new(Mutex::new(IndexMap::new()));
         let overall_success = Arc::new(Mutex::new(true));
 
         // First, run all safe hooks in parallel
         if !safe_hooks.is_empty() {
             let mut handles = Vec::new();
 
-            for (name, hook) in safe_hooks {
+            for (name, hook) in Self::in_priority_order(resolved_hooks, safe_hooks) {
                 let name = name.clone();
                 let hook = hook.clone();
                 let results = Arc::clone(&results);
                 let overall_success = Arc::clone(&overall_success);
+                let budget = Arc::clone(budget);
 
                 let worktree_context = resolved_hooks.worktree_context.clone();
                 let changed_files = resolved_hooks.changed_files.clone();
+                let isolation = resolved_hooks.isolation;
                 let handle = thread::spawn(move || {
-                    match Self::execute_single_hook(
+                    if budget.exhausted() {
+                        return;
+                    }
+                    match Self::execute_hook(
                         &name,
                         &hook,
                         &worktree_context,
                         changed_files.as_deref(),
+                        isolation,
                     ) {
                         Ok(result) => {
-                            let success = result.success;
+                            let success = !result.is_blocking_failure();
+                            budget.record(success);
                             results.lock().unwrap().insert(name, result);
                             if !success {
                                 *overall_success.lock().unwrap() = false;
@@ -214,12 +810,9 @@ impl HookExecutor {
                         }
                         Err(e) => {
                             // Create a failed result for execution errors
-                            let result = ExecutionResult {
-                                exit_code: -1,
-                                stdout: String::new(),
-                                stderr: format!("Execution error: {e:#}"),
-                                success: false,
-                            };
+                            let result =
+                                ExecutionResult::errored(&e, hook.definition.description.clone());
+                            budget.record(false);
                             results.lock().unwrap().insert(name, result);
                             *overall_success.lock().unwrap() = false;
                         }
@@ -238,15 +831,20 @@ impl HookExecutor {
 
         // Then, run repository-modifying hooks sequentially
         for (name, hook) in modifying_hooks {
-            let result = Self::execute_single_hook(
+            if budget.exhausted() {
+                break;
+            }
+            let result = Self::execute_hook(
                 &name,
                 hook,
                 &resolved_hooks.worktree_context,
                 resolved_hooks.changed_files.as_deref(),
+                resolved_hooks.isolation,
             )
             .with_context(|| format!("Failed to execute hook: {name}"))?;
 
-            if !result.success {
+            budget.record(!result.is_blocking_failure());
+            if result.is_blocking_failure() {
                 *overall_success.lock().unwrap() = false;
             }
 
@@ -265,41 +863,62 @@ impl HookExecutor {
         })
     }
 
+    /// Order hooks for spawning in parallel: highest `priority` first, so
+    /// long-running hooks start early; ties keep their configured order
+    fn in_priority_order<'a>(
+        resolved_hooks: &'a ResolvedHooks,
+        names: impl IntoIterator<Item = &'a String>,
+    ) -> Vec<(&'a String, &'a ResolvedHook)> {
+        let mut hooks: Vec<_> = names
+            .into_iter()
+            .map(|name| (name, &resolved_hooks.hooks[name]))
+            .collect();
+        hooks.sort_by_key(|(_, hook)| std::cmp::Reverse(hook.definition.priority));
+        hooks
+    }
+
     /// Execute all hooks in parallel (unsafe - ignores repository modification)
-    fn execute_parallel_unsafe(resolved_hooks: &ResolvedHooks) -> ExecutionResults {
-        let results = Arc::new(Mutex::new(HashMap::new()));
+    fn execute_parallel_unsafe(
+        resolved_hooks: &ResolvedHooks,
+        budget: &Arc<FailureBudget>,
+    ) -> ExecutionResults {
+        let results = Arc::new(Mutex::new(IndexMap::new()));
         let overall_success = Arc::new(Mutex::new(true));
         let mut handles = Vec::new();
 
-        for (name, hook) in &resolved_hooks.hooks {
+        for (name, hook) in Self::in_priority_order(resolved_hooks, resolved_hooks.hooks.keys()) {
             let name = name.clone();
             let hook = hook.clone();
             let results = Arc::clone(&results);
             let overall_success = Arc::clone(&overall_success);
+            let budget = Arc::clone(budget);
 
             let worktree_context = resolved_hooks.worktree_context.clone();
             let changed_files = resolved_hooks.changed_files.clone();
+            let isolation = resolved_hooks.isolation;
             let handle = thread::spawn(move || {
-                match Self::execute_single_hook(
+                if budget.exhausted() {
+                    return;
+                }
+                match Self::execute_hook(
                     &name,
                     &hook,
                     &worktree_context,
                     changed_files.as_deref(),
+                    isolation,
                 ) {
                     Ok(result) => {
-                        let success = result.success;
+                        let success = !result.is_blocking_failure();
+                        budget.record(success);
                         results.lock().unwrap().insert(name, result);
                         if !success {
                             *overall_success.lock().unwrap() = false;
                         }
                     }
                     Err(e) => {
-                        let result = ExecutionResult {
-                            exit_code: -1,
-                            stdout: String::new(),
-                            stderr: format!("Execution error: {e:#}"),
-                            success: false,
-                        };
+                        let result =
+                            ExecutionResult::errored(&e, hook.definition.description.clone());
+                        budget.record(false);
                         results.lock().unwrap().insert(name, result);
                         *overall_success.lock().unwrap() = false;
                     }
@@ -328,36 +947,31 @@ impl HookExecutor {
     }
 
     /// Execute hooks respecting dependencies
-    fn execute_with_dependencies(resolved_hooks: &ResolvedHooks) -> Result<ExecutionResults> {
-        let mut resolver = DependencyResolver::new();
-        let hook_names: Vec<String> = resolved_hooks.hooks.keys().cloned().collect();
-
-        // Build dependency graph
-        for (name, hook) in &resolved_hooks.hooks {
-            let dependencies = hook.definition.depends_on.clone().unwrap_or_default();
-            resolver.add_hook(name.clone(), dependencies);
-        }
+    fn execute_with_dependencies(
+        resolved_hooks: &ResolvedHooks,
+        continue_from: Option<&str>,
+    ) -> Result<ExecutionResults> {
+        let plan = Self::dependency_plan(resolved_hooks)?;
 
-        // Resolve execution plan
-        let plan = resolver
-            .resolve(&hook_names)
-            .context("Failed to resolve hook dependencies")?;
+        // When resuming, skip every phase before the one containing the hook
+        let start_phase = continue_from.map_or(0, |name| {
+            Self::report_skipped_phases(resolved_hooks, &plan.phases, name)
+        });
 
-        let mut all_results = HashMap::new();
+        let mut all_results = IndexMap::new();
         let mut overall_success = true;
 
         // Execute hooks phase by phase
-        for phase in &plan.phases {
-            let mut phase_results = HashMap::new();
+        for phase in plan.phases.iter().skip(start_phase) {
+            let mut phase_results = IndexMap::new();
 
             if phase.parallel && phase.hooks.len() > 1 {
                 // Execute phase hooks in parallel
-                let results = Arc::new(Mutex::new(HashMap::new()));
+                let results = Arc::new(Mutex::new(IndexMap::new()));
                 let phase_success = Arc::new(Mutex::new(true));
                 let mut handles = Vec::new();
 
-                for hook_name in &phase.hooks {
-                    let hook = &resolved_hooks.hooks[hook_name];
+                for (hook_name, hook) in Self::in_priority_order(resolved_hooks, &phase.hooks) {
                     let name = hook_name.clone();
                     let hook = hook.clone();
                     let results = Arc::clone(&results);
@@ -365,27 +979,27 @@ impl HookExecutor {
 
                     let worktree_context = resolved_hooks.worktree_context.clone();
                     let changed_files = resolved_hooks.changed_files.clone();
+                    let isolation = resolved_hooks.isolation;
                     let handle = thread::spawn(move || {
-                        match Self::execute_single_hook(
+                        match Self::execute_hook(
                             &name,
                             &hook,
                             &worktree_context,
                             changed_files.as_deref(),
+                            isolation,
                         ) {
                             Ok(result) => {
-                                let success = result.success;
+                                let success = !result.is_blocking_failure();
                                 results.lock().unwrap().insert(name, result);
                                 if !success {
                                     *phase_success.lock().unwrap() = false;
                                 }
                             }
                             Err(e) => {
-                                let result = ExecutionResult {
-                                    exit_code: -1,
-                                    stdout: String::new(),
-                                    stderr: format!("Execution error: {e:#}"),
-                                    success: false,
-                                };
+                                let result = ExecutionResult::errored(
+                                    &e,
+                                    hook.definition.description.clone(),
+                                );
                                 results.lock().unwrap().insert(name, result);
                                 *phase_success.lock().unwrap() = false;
                             }
@@ -417,15 +1031,16 @@ impl HookExecutor {
                 // Execute phase hooks sequentially
                 for hook_name in &phase.hooks {
                     let hook = &resolved_hooks.hooks[hook_name];
-                    let result = Self::execute_single_hook(
+                    let result = Self::execute_hook(
                         hook_name,
                         hook,
                         &resolved_hooks.worktree_context,
                         resolved_hooks.changed_files.as_deref(),
+                        resolved_hooks.isolation,
                     )
                     .with_context(|| format!("Failed to execute hook: {hook_name}"))?;
 
-                    let success = result.success;
+                    let success = !result.is_blocking_failure();
                     phase_results.insert(hook_name.clone(), result);
 
                     if !success {
@@ -448,49 +1063,384 @@ impl HookExecutor {
         })
     }
 
-    /// Execute a single hook
-    #[allow(clippy::too_many_lines, clippy::option_if_let_else)]
-    fn execute_single_hook(
+    /// Find the phase containing `name` and report what resuming there skips
+    ///
+    /// Returns the index of the phase to start from.
+    fn report_skipped_phases(
+        resolved_hooks: &ResolvedHooks,
+        phases: &[crate::hooks::ExecutionPhase],
+        name: &str,
+    ) -> usize {
+        let start = phases
+            .iter()
+            .position(|phase| phase.hooks.iter().any(|hook| hook == name))
+            .unwrap_or(0);
+
+        let skipped: Vec<&str> = phases[..start]
+            .iter()
+            .flat_map(|phase| phase.hooks.iter().map(String::as_str))
+            .collect();
+        if !skipped.is_empty() {
+            eprintln!(
+                "⏭️  Continuing from '{name}', skipping: {}",
+                skipped.join(", ")
+            );
+        }
+
+        let skipped_dependencies: Vec<&str> = resolved_hooks.hooks[name]
+            .definition
+            .depends_on
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|dep| skipped.contains(dep))
+            .collect();
+        if !skipped_dependencies.is_empty() {
+            eprintln!(
+                "⚠️  Dependencies of '{name}' were skipped and assumed to have passed: {}",
+                skipped_dependencies.join(", ")
+            );
+        }
+
+        start
+    }
+
+    /// Execute a single hook, in its own worktree when the group asks for
+    /// isolation
+    fn execute_hook(
         name: &str,
         hook: &ResolvedHook,
         worktree_context: &crate::hooks::resolver::WorktreeContext,
         changed_files: Option<&[PathBuf]>,
+        isolation: Option<Isolation>,
     ) -> Result<ExecutionResult> {
-        match hook.definition.execution_type {
-            ExecutionType::PerFile => {
-                Self::execute_per_file_hook(name, hook, worktree_context, changed_files)
-            }
-            ExecutionType::InPlace => {
-                Self::execute_in_place_hook(name, hook, worktree_context, changed_files)
-            }
-            ExecutionType::Other => {
-                Self::execute_other_hook(name, hook, worktree_context, changed_files)
+        match isolation {
+            Some(Isolation::Worktree) => {
+                let worktree = IsolatedWorktree::create(name, worktree_context, changed_files)?;
+                Self::execute_single_hook(
+                    name,
+                    &worktree.hook(hook),
+                    &worktree.context(worktree_context),
+                    changed_files,
+                )
             }
+            None => Self::execute_single_hook(name, hook, worktree_context, changed_files),
         }
     }
 
-    /// Execute hook with files passed as individual arguments (per-file mode)
-    fn execute_per_file_hook(
+    /// Execute a single hook
+    #[allow(clippy::too_many_lines, clippy::option_if_let_else)]
+    fn execute_single_hook(
         name: &str,
         hook: &ResolvedHook,
         worktree_context: &crate::hooks::resolver::WorktreeContext,
         changed_files: Option<&[PathBuf]>,
     ) -> Result<ExecutionResult> {
-        // Get relevant changed files based on hook's file patterns
-        let relevant_changed = Self::filter_relevant_files(hook, changed_files);
-
-        // Skip execution if no files match (whether pattern specified or not)
-        if relevant_changed.is_empty() && !hook.definition.run_always {
+        log::info!(
+            "hook started name={name} execution_type={:?} workdir={}",
+            hook.definition.execution_type,
+            hook.working_directory.display()
+        );
+        let started = Instant::now();
+
+        let skipped = if !hook.definition.enabled {
+            Some(SkipReason::Disabled)
+        } else if !Self::push_refs_match(hook, worktree_context)? {
+            Some(SkipReason::PushRefs)
+        } else if let Some(skip_if) = &hook.definition.skip_if {
+            Self::skip_condition_met(name, hook, worktree_context, skip_if)?
+                .then_some(SkipReason::SkipIf)
+        } else {
+            None
+        };
+        if let Some(reason) = skipped {
+            log::info!("hook skipped name={name} reason={reason:?}");
             return Ok(ExecutionResult {
-                exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                success: true,
+                skipped,
+                description: hook.definition.description.clone(),
+                ..ExecutionResult::new(0, String::new(), String::new())
             });
         }
 
-        // Determine execution directory (same logic as execute_command_parts)
-        let execution_dir = if hook.definition.run_at_root {
+        let result = match &hook.definition.command {
+            HookCommand::Steps { steps } => {
+                Self::execute_steps(name, hook, worktree_context, changed_files, steps)
+            }
+            _ => Self::execute_command(name, hook, worktree_context, changed_files),
+        }
+        .inspect_err(|e| log::error!("hook errored name={name} error={e:#}"))?;
+
+        let mut result = Self::apply_fail_on(name, &hook.definition, result)?;
+        result.description.clone_from(&hook.definition.description);
+        result.combined_output = hook.definition.combined_output;
+        result.allow_failure = hook.definition.allow_failure;
+        result.files =
+            Self::filter_relevant_files(hook, changed_files, &worktree_context.repo_root);
+
+        let mut result = match &hook.definition.on_failure {
+            Some(on_failure) if !result.success => {
+                Self::run_on_failure(name, hook, worktree_context, on_failure, result)
+            }
+            _ => result,
+        };
+        result.duration_ms = Some(u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX));
+        log::info!(
+            "hook finished name={name} success={} exit_code={} duration_ms={}",
+            result.success,
+            result.exit_code,
+            result.duration_ms.unwrap_or_default()
+        );
+        Ok(result)
+    }
+
+    /// Run a hook's (single) command the way its `execution_type` asks for
+    fn execute_command(
+        name: &str,
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        changed_files: Option<&[PathBuf]>,
+    ) -> Result<ExecutionResult> {
+        match hook.definition.execution_type {
+            ExecutionType::PerFile => {
+                Self::execute_per_file_hook(name, hook, worktree_context, changed_files)
+            }
+            ExecutionType::InPlace => {
+                Self::execute_in_place_hook(name, hook, worktree_context, changed_files)
+            }
+            ExecutionType::Other => {
+                Self::execute_other_hook(name, hook, worktree_context, changed_files)
+            }
+        }
+    }
+
+    /// Run the steps of a `steps` command in order, each like a hook of its
+    /// own with the same settings, until one fails
+    ///
+    /// The output of each step that ran is kept under a `[step i/n] <command>`
+    /// line; the result carries the exit code of the last step that ran.
+    fn execute_steps(
+        name: &str,
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        changed_files: Option<&[PathBuf]>,
+        steps: &[HookCommand],
+    ) -> Result<ExecutionResult> {
+        let mut combined = ExecutionResult::new(0, String::new(), String::new());
+
+        for (index, step) in steps.iter().enumerate() {
+            let header = format!("[step {}/{}] {step}", index + 1, steps.len());
+            log::info!("hook step name={name} {header}");
+
+            let mut step_hook = hook.clone();
+            step_hook.definition.command = step.clone();
+            let result =
+                Self::execute_command(name, &step_hook, worktree_context, changed_files)
+                    .with_context(|| format!("Hook '{name}' failed to run step {}", index + 1))?;
+
+            for (output, text) in [
+                (&mut combined.stdout, &result.stdout),
+                (&mut combined.stderr, &result.stderr),
+            ] {
+                if !text.is_empty() {
+                    output.push_str(&header);
+                    output.push('\n');
+                    output.push_str(text);
+                    if !text.ends_with('\n') {
+                        output.push('\n');
+                    }
+                }
+            }
+            combined.exit_code = result.exit_code;
+            if !result.success {
+                combined.success = false;
+                combined.failed_step = Some(FailedStep {
+                    index: index + 1,
+                    count: steps.len(),
+                    command: step.to_string(),
+                });
+                break;
+            }
+        }
+
+        Ok(combined)
+    }
+
+    /// Fail a hook whose output has a line matching its `fail_on` pattern
+    /// (and not its `fail_on_allow` pattern), even if it exited 0
+    fn apply_fail_on(
+        name: &str,
+        definition: &HookDefinition,
+        mut result: ExecutionResult,
+    ) -> Result<ExecutionResult> {
+        let Some(fail_on) = &definition.fail_on else {
+            return Ok(result);
+        };
+        let fail_on = compile_output_pattern(fail_on)
+            .with_context(|| format!("Hook '{name}' has an invalid fail_on pattern"))?;
+        let allow = definition
+            .fail_on_allow
+            .as_deref()
+            .map(compile_output_pattern)
+            .transpose()
+            .with_context(|| format!("Hook '{name}' has an invalid fail_on_allow pattern"))?;
+
+        let matched = result
+            .stdout
+            .lines()
+            .chain(result.stderr.lines())
+            .find(|line| {
+                fail_on.is_match(line) && !allow.as_ref().is_some_and(|allow| allow.is_match(line))
+            })
+            .map(str::to_string);
+
+        if let Some(line) = matched {
+            log::info!("hook output matched fail_on name={name}");
+            if !result.stderr.is_empty() && !result.stderr.ends_with('\n') {
+                result.stderr.push('\n');
+            }
+            let _ = writeln!(
+                result.stderr,
+                "peter-hook: output matched fail_on pattern: {line}"
+            );
+            result.success = false;
+            if result.exit_code == 0 {
+                result.exit_code = 1;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Whether a hook with `push_refs` should run for the refs being pushed;
+    /// hooks without it, and runs where the refs are unknown, always pass
+    fn push_refs_match(
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+    ) -> Result<bool> {
+        match (&hook.definition.push_refs, &worktree_context.push_refs) {
+            (Some(patterns), Some(updates)) => crate::git::push_refs_match(patterns, updates),
+            _ => Ok(true),
+        }
+    }
+
+    /// Run a hook's `skip_if` condition; exit code 0 means skip the hook
+    fn skip_condition_met(
+        name: &str,
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        skip_if: &HookCommand,
+    ) -> Result<bool> {
+        let config_dir = hook
+            .source_file
+            .parent()
+            .context("Hook source file has no parent directory")?;
+        let template_resolver = TemplateResolver::with_worktree_context(
+            config_dir,
+            &hook.working_directory,
+            worktree_context,
+        );
+
+        let (command_parts, script_file) =
+            Self::resolve_command_parts(skip_if, &template_resolver)?;
+        let condition = Self::execute_command_parts(
+            &format!("{name} (skip_if)"),
+            hook,
+            worktree_context,
+            skip_if,
+            &command_parts,
+            script_file.as_ref().map(|file| &*file.path).as_slice(),
+        )
+        .with_context(|| format!("Failed to run skip_if condition for hook: {name}"))?;
+
+        Ok(condition.success)
+    }
+
+    /// Run a failed hook's `on_failure` command and fold its output into the
+    /// hook's result
+    ///
+    /// The original exit code and failure are always preserved; if the
+    /// `on_failure` command itself fails, that is appended to stderr.
+    fn run_on_failure(
+        name: &str,
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        on_failure: &HookCommand,
+        mut result: ExecutionResult,
+    ) -> ExecutionResult {
+        let outcome = hook
+            .source_file
+            .parent()
+            .context("Hook source file has no parent directory")
+            .and_then(|config_dir| {
+                let mut template_resolver = TemplateResolver::with_worktree_context(
+                    config_dir,
+                    &hook.working_directory,
+                    worktree_context,
+                );
+                template_resolver.set_failed_hook(
+                    name,
+                    result.exit_code,
+                    &result.stdout,
+                    &result.stderr,
+                );
+
+                let (command_parts, script_file) =
+                    Self::resolve_command_parts(on_failure, &template_resolver)?;
+
+                Self::execute_command_parts(
+                    &format!("{name} (on_failure)"),
+                    hook,
+                    worktree_context,
+                    on_failure,
+                    &command_parts,
+                    script_file.as_ref().map(|file| &*file.path).as_slice(),
+                )
+            });
+
+        match outcome {
+            Ok(on_failure_result) => {
+                log::info!(
+                    "on_failure finished hook={name} exit_code={}",
+                    on_failure_result.exit_code
+                );
+                result.stdout.push_str(&on_failure_result.stdout);
+                result.stderr.push_str(&on_failure_result.stderr);
+                if !on_failure_result.success {
+                    let _ = writeln!(
+                        result.stderr,
+                        "\non_failure command for '{name}' failed with exit code {}",
+                        on_failure_result.exit_code
+                    );
+                }
+            }
+            Err(e) => {
+                let _ = writeln!(
+                    result.stderr,
+                    "\non_failure command for '{name}' could not run: {e:#}"
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Execute hook with files passed as individual arguments (per-file mode)
+    fn execute_per_file_hook(
+        name: &str,
+        hook: &ResolvedHook,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        changed_files: Option<&[PathBuf]>,
+    ) -> Result<ExecutionResult> {
+        // Get relevant changed files based on hook's file patterns
+        let relevant_changed =
+            Self::filter_relevant_files(hook, changed_files, &worktree_context.repo_root);
+
+        // Skip execution if no files match (whether pattern specified or not)
+        if relevant_changed.is_empty() && !Self::runs_without_files(hook, changed_files) {
+            return Ok(ExecutionResult::new(0, String::new(), String::new()));
+        }
+
+        // Determine execution directory (same logic as execute_command_parts)
+        let execution_dir = if hook.definition.run_at_root {
             &worktree_context.repo_root
         } else {
             &hook.working_directory
@@ -515,30 +1465,98 @@ impl HookExecutor {
             worktree_context,
         );
 
-        let mut base_command_parts = match &hook.definition.command {
+        // Shell commands and inline scripts get the files as positional
+        // arguments ("$@"), so paths are never re-split or re-parsed by the
+        // shell
+        let (base_command_parts, script_file) = match &hook.definition.command {
             HookCommand::Shell(cmd) => {
                 let resolved_cmd = template_resolver
-                    .resolve_string(cmd)
+                    .resolve_shell(cmd)
                     .context("Failed to resolve command template")?;
-                vec!["sh".to_string(), "-c".to_string(), resolved_cmd]
+                (
+                    vec![
+                        "sh".to_string(),
+                        "-c".to_string(),
+                        format!("{resolved_cmd} \"$@\""),
+                        "sh".to_string(),
+                    ],
+                    None,
+                )
             }
             HookCommand::Args(args) => {
                 if args.is_empty() {
                     return Err(anyhow::anyhow!("Empty command for hook: {name}"));
                 }
-                template_resolver
+                let parts = template_resolver
                     .resolve_command_args(args)
-                    .context("Failed to resolve command arguments")?
+                    .context("Failed to resolve command arguments")?;
+                (parts, None)
+            }
+            HookCommand::Script { script, shell } => {
+                let (parts, file) =
+                    Self::prepare_script(script, shell.as_deref(), &template_resolver)?;
+                (parts, Some(file))
             }
+            HookCommand::Steps { .. } => return Err(nested_steps_error(name)),
         };
 
-        // Add transformed files as individual arguments
-        for file in &transformed_files {
-            base_command_parts.push(file.to_string_lossy().to_string());
+        let file_args: Vec<String> = transformed_files
+            .iter()
+            .map(|file| file.to_string_lossy().to_string())
+            .collect();
+        let chunks = chunk_file_args(&base_command_parts, &file_args, MAX_COMMAND_LINE_BYTES);
+
+        if chunks.len() > 1 {
+            log::info!(
+                "hook chunked name={name} files={} invocations={}",
+                file_args.len(),
+                chunks.len()
+            );
+            if crate::debug::is_enabled() {
+                eprintln!(
+                    "[DEBUG] Splitting {} files for hook '{name}' into {} invocations",
+                    file_args.len(),
+                    chunks.len()
+                );
+            }
+        }
+
+        // Chunks always run one after another so repository-modifying hooks
+        // never race with themselves
+        Self::run_chunks(&chunks, |chunk| {
+            let mut command_parts = base_command_parts.clone();
+            command_parts.extend_from_slice(chunk);
+            Self::execute_command_parts(
+                name,
+                hook,
+                worktree_context,
+                &hook.definition.command,
+                &command_parts,
+                script_file.as_ref().map(|file| &*file.path).as_slice(),
+            )
+        })
+    }
+
+    /// Run a per-file command once per chunk of file arguments and fold the
+    /// results into one. Every chunk runs even after a failure so the output
+    /// covers all files; the first non-zero exit code is reported.
+    fn run_chunks<F>(chunks: &[&[String]], mut run: F) -> Result<ExecutionResult>
+    where
+        F: FnMut(&[String]) -> Result<ExecutionResult>,
+    {
+        let mut aggregate = ExecutionResult::new(0, String::new(), String::new());
+
+        for chunk in chunks {
+            let result = run(chunk)?;
+            aggregate.stdout.push_str(&result.stdout);
+            aggregate.stderr.push_str(&result.stderr);
+            if !result.success && aggregate.success {
+                aggregate.success = false;
+                aggregate.exit_code = result.exit_code;
+            }
         }
 
-        // Execute the command with file arguments
-        Self::execute_command_parts(name, hook, worktree_context, &base_command_parts)
+        Ok(aggregate)
     }
 
     /// Execute hook once in config directory without file arguments (in-place mode)
@@ -549,16 +1567,12 @@ impl HookExecutor {
         changed_files: Option<&[PathBuf]>,
     ) -> Result<ExecutionResult> {
         // Get relevant changed files for filtering check
-        let relevant_changed = Self::filter_relevant_files(hook, changed_files);
+        let relevant_changed =
+            Self::filter_relevant_files(hook, changed_files, &worktree_context.repo_root);
 
         // Skip execution if no files match (whether pattern specified or not)
-        if relevant_changed.is_empty() && !hook.definition.run_always {
-            return Ok(ExecutionResult {
-                exit_code: 0,
-                stdout: String::new(),
-                stderr: String::new(),
-                success: true,
-            });
+        if relevant_changed.is_empty() && !Self::runs_without_files(hook, changed_files) {
+            return Ok(ExecutionResult::new(0, String::new(), String::new()));
         }
 
         // Build command without file arguments for in-place execution
@@ -572,25 +1586,39 @@ impl HookExecutor {
             worktree_context,
         );
 
-        let command_parts = match &hook.definition.command {
+        let (command_parts, script_file) = match &hook.definition.command {
             HookCommand::Shell(cmd) => {
                 let resolved_cmd = template_resolver
-                    .resolve_string(cmd)
+                    .resolve_shell(cmd)
                     .context("Failed to resolve command template")?;
-                vec!["sh".to_string(), "-c".to_string(), resolved_cmd]
+                (vec!["sh".to_string(), "-c".to_string(), resolved_cmd], None)
             }
             HookCommand::Args(args) => {
                 if args.is_empty() {
                     return Err(anyhow::anyhow!("Empty command for hook: {name}"));
                 }
-                template_resolver
+                let parts = template_resolver
                     .resolve_command_args(args)
-                    .context("Failed to resolve command arguments")?
+                    .context("Failed to resolve command arguments")?;
+                (parts, None)
             }
+            HookCommand::Script { script, shell } => {
+                let (parts, file) =
+                    Self::prepare_script(script, shell.as_deref(), &template_resolver)?;
+                (parts, Some(file))
+            }
+            HookCommand::Steps { .. } => return Err(nested_steps_error(name)),
         };
 
         // Execute once in the config directory (or custom workdir)
-        Self::execute_command_parts(name, hook, worktree_context, &command_parts)
+        Self::execute_command_parts(
+            name,
+            hook,
+            worktree_context,
+            &hook.definition.command,
+            &command_parts,
+            script_file.as_ref().map(|file| &*file.path).as_slice(),
+        )
     }
 
     /// Execute hook using template variables (other/manual mode) - original
@@ -605,24 +1633,170 @@ impl HookExecutor {
         Self::execute_original_hook(name, hook, worktree_context, changed_files)
     }
 
+    /// Compute the files each resolved hook would receive, without executing
+    ///
+    /// Applies the same pattern filtering, path transformation, and skip rules
+    /// as execution, so it explains why a hook would or would not run.
+    #[must_use]
+    pub fn plan_files(resolved_hooks: &ResolvedHooks) -> Vec<HookFilePlan> {
+        let worktree_context = &resolved_hooks.worktree_context;
+
+        resolved_hooks
+            .hooks
+            .iter()
+            .map(|(name, hook)| {
+                let relevant = Self::filter_relevant_files(
+                    hook,
+                    resolved_hooks.changed_files.as_deref(),
+                    &worktree_context.repo_root,
+                );
+
+                // Per-file and in-place hooks are skipped without matching files
+                // Patterns were validated when the config was loaded
+                let will_run = hook.definition.enabled
+                    && Self::push_refs_match(hook, worktree_context).unwrap_or(true)
+                    && match hook.definition.execution_type {
+                        ExecutionType::PerFile | ExecutionType::InPlace => {
+                            !relevant.is_empty()
+                                || Self::runs_without_files(
+                                    hook,
+                                    resolved_hooks.changed_files.as_deref(),
+                                )
+                        }
+                        ExecutionType::Other => true,
+                    };
+
+                let execution_dir = if hook.definition.run_at_root {
+                    worktree_context.repo_root.clone()
+                } else {
+                    hook.working_directory.clone()
+                };
+                let files = Self::transform_file_paths(
+                    &relevant,
+                    &worktree_context.repo_root,
+                    &execution_dir,
+                );
+
+                HookFilePlan {
+                    hook: name.clone(),
+                    execution_type: hook.definition.execution_type,
+                    execution_dir,
+                    will_run,
+                    files,
+                }
+            })
+            .collect()
+    }
+
+    /// Environment each hook would run with on top of the inherited one,
+    /// computed without running it
+    ///
+    /// Includes `PETER_HOOK_CHANGED_FILES` and `PETER_HOOK_CHANGED_COUNT` for
+    /// hooks that receive them (`execution_type = "other"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hook's `env` templates cannot be resolved
+    pub fn plan_env(resolved_hooks: &ResolvedHooks) -> Result<Vec<HookEnvPlan>> {
+        let worktree_context = &resolved_hooks.worktree_context;
+
+        resolved_hooks
+            .hooks
+            .iter()
+            .zip(Self::plan_files(resolved_hooks))
+            .map(|((name, hook), files)| {
+                let config_dir = hook
+                    .source_file
+                    .parent()
+                    .context("Hook source file has no parent directory")?;
+                let mut template_resolver = TemplateResolver::with_worktree_context(
+                    config_dir,
+                    &hook.working_directory,
+                    worktree_context,
+                );
+                let other = hook.definition.execution_type == ExecutionType::Other;
+                if other {
+                    template_resolver.set_changed_files(&files.files, None);
+                }
+
+                let mut env = Self::env_overlay(hook, &template_resolver, worktree_context)
+                    .with_context(|| format!("Failed to resolve env of hook '{name}'"))?;
+                if other {
+                    let changed_list = files
+                        .files
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    env.insert("PETER_HOOK_CHANGED_FILES".to_string(), changed_list);
+                    env.insert(
+                        "PETER_HOOK_CHANGED_COUNT".to_string(),
+                        files.files.len().to_string(),
+                    );
+                }
+                env.sort_keys();
+
+                Ok(HookEnvPlan {
+                    hook: name.clone(),
+                    will_run: files.will_run,
+                    env,
+                })
+            })
+            .collect()
+    }
+
+    /// Variables set on a hook's command: run-wide `--env` values, then the
+    /// hook's own `env` with templates resolved, which wins on conflict
+    fn env_overlay(
+        hook: &ResolvedHook,
+        template_resolver: &TemplateResolver,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+    ) -> Result<IndexMap<String, String>> {
+        let mut overlay = worktree_context.run_env.clone();
+        if let Some(env) = &hook.definition.env {
+            let resolved_env = template_resolver
+                .resolve_env(env)
+                .context("Failed to resolve environment variable templates")?;
+            overlay.extend(resolved_env);
+        }
+        Ok(overlay)
+    }
+
     /// Filter files based on hook's file patterns
+    ///
+    /// Deleted files are dropped unless the hook takes them (see
+    /// [`drop_deleted_files`]).
     fn filter_relevant_files(
         hook: &ResolvedHook,
         changed_files: Option<&[PathBuf]>,
+        repo_root: &Path,
     ) -> Vec<PathBuf> {
         let Some(cf) = changed_files else {
             return Vec::new();
         };
 
-        hook.definition.files.as_ref().map_or_else(
+        let matched = hook.definition.files.as_ref().map_or_else(
             || cf.to_vec(),
             |patterns| {
                 FilePatternMatcher::new(patterns).map_or_else(
                     |_| cf.to_vec(),
-                    |matcher| cf.iter().filter(|p| matcher.matches(p)).cloned().collect(),
+                    |matcher| {
+                        let matcher = matcher.relative_to(repo_root);
+                        cf.iter().filter(|p| matcher.matches(p)).cloned().collect()
+                    },
                 )
             },
-        )
+        );
+
+        drop_deleted_files(&hook.definition, repo_root, matched)
+    }
+
+    /// Whether a `per-file` or `in-place` hook runs when no changed file is
+    /// left for it: with `run_always`, or with `run_always_if_no_changes`
+    /// while file detection is on
+    const fn runs_without_files(hook: &ResolvedHook, changed_files: Option<&[PathBuf]>) -> bool {
+        hook.definition.run_always
+            || (hook.definition.run_always_if_no_changes && changed_files.is_some())
     }
 
     /// Transform file paths from repo-relative to execution-directory-relative
@@ -650,18 +1824,23 @@ impl HookExecutor {
                 // If execution_dir is not a prefix of abs_file, keep the original path
                 abs_file
                     .strip_prefix(execution_dir)
-                    .map(|p| p.to_path_buf())
-                    .unwrap_or_else(|_| file.clone())
+                    .map_or_else(|_| file.clone(), Path::to_path_buf)
             })
             .collect()
     }
 
     /// Execute command parts with proper setup
+    ///
+    /// `command` is the hook command the parts were resolved from.
+    /// `temp_files` are peter-hook's temporary files the command refers to,
+    /// mounted into the container when the hook runs in one.
     fn execute_command_parts(
         name: &str,
         hook: &ResolvedHook,
         worktree_context: &crate::hooks::resolver::WorktreeContext,
+        command: &HookCommand,
         command_parts: &[String],
+        temp_files: &[&Path],
     ) -> Result<ExecutionResult> {
         if command_parts.is_empty() {
             return Err(anyhow::anyhow!("Empty command for hook: {name}"));
@@ -677,6 +1856,9 @@ impl HookExecutor {
             worktree_context,
         );
 
+        let command_parts =
+            with_activate(hook, command, command_parts.to_vec(), &template_resolver)?;
+
         // Build command
         let mut command = Command::new(&command_parts[0]);
         if command_parts.len() > 1 {
@@ -688,7 +1870,7 @@ impl HookExecutor {
             let resolved_workdir = template_resolver
                 .resolve_string(workdir_template)
                 .context("Failed to resolve workdir template")?;
-            PathBuf::from(resolved_workdir)
+            resolve_workdir(&resolved_workdir, config_dir, &worktree_context.repo_root)
         } else if hook.definition.run_at_root {
             // If run_at_root is true, use the repository root
             worktree_context.repo_root.clone()
@@ -696,15 +1878,19 @@ impl HookExecutor {
             hook.working_directory.clone()
         };
         command.current_dir(&working_dir);
+        log::info!(
+            "exec hook={name} cwd={} command={command_parts:?}",
+            working_dir.display()
+        );
 
         // Set environment variables
-        if let Some(env) = &hook.definition.env {
-            let resolved_env = template_resolver
-                .resolve_env(env)
-                .context("Failed to resolve environment variable templates")?;
-            for (key, value) in resolved_env {
-                command.env(key, value);
-            }
+        command.envs(Self::env_overlay(
+            hook,
+            &template_resolver,
+            worktree_context,
+        )?);
+        if let Some(container) = &hook.definition.container {
+            command = Self::containerize(name, &command, container, worktree_context, temp_files)?;
         }
 
         // Configure stdio
@@ -713,7 +1899,7 @@ impl HookExecutor {
 
         // Debug output
         if crate::debug::is_enabled() {
-            if std::io::stderr().is_terminal() {
+            if crate::output::stderr_styled() {
                 eprintln!(
                     "\x1b[38;5;220m⚡ \x1b[1m\x1b[38;5;196mEXECUTING:\x1b[0m \
                      \x1b[38;5;226m{name}\x1b[0m"
@@ -726,8 +1912,7 @@ impl HookExecutor {
         }
 
         // Execute command
-        let output = command
-            .output()
+        let output = Self::capture_output(name, hook, &mut command)
             .with_context(|| format!("Failed to execute hook command: {name}"))?;
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -736,7 +1921,7 @@ impl HookExecutor {
         let success = output.status.success();
 
         // Debug output for result
-        if crate::debug::is_enabled() && std::io::stderr().is_terminal() {
+        if crate::debug::is_enabled() && crate::output::stderr_styled() {
             if success {
                 eprintln!(
                     "\x1b[38;5;46m🎉 \x1b[1m\x1b[38;5;82mSUCCESS:\x1b[0m \
@@ -750,46 +1935,58 @@ impl HookExecutor {
             }
         }
 
-        Ok(ExecutionResult {
-            exit_code,
-            stdout,
-            stderr,
-            success,
-        })
+        Ok(ExecutionResult::new(exit_code, stdout, stderr))
+    }
+
+    /// Run a hook's command to completion, with its stderr merged into
+    /// stdout if it sets `combined_output`
+    ///
+    /// With `--stream`, each output line is also printed as soon as it is
+    /// written, tagged with `name`.
+    fn capture_output(
+        name: &str,
+        hook: &ResolvedHook,
+        command: &mut Command,
+    ) -> std::io::Result<Output> {
+        if output::stream() {
+            let stdout_prefix = output::stream_prefix(name, false);
+            let stderr_prefix = output::stream_prefix(name, true);
+            interrupt::streamed_output(command, hook.definition.combined_output, |stream, line| {
+                match stream {
+                    OutputStream::Stdout => {
+                        write_tagged_line(&mut std::io::stdout().lock(), &stdout_prefix, line);
+                    }
+                    OutputStream::Stderr => {
+                        write_tagged_line(&mut std::io::stderr().lock(), &stderr_prefix, line);
+                    }
+                }
+            })
+        } else if hook.definition.combined_output {
+            interrupt::combined_output(command)
+        } else {
+            interrupt::output(command)
+        }
     }
 
     /// Create temporary file for changed files list
-    fn create_changed_files_temp_file(relevant_changed: &[PathBuf]) -> Option<PathBuf> {
+    fn create_changed_files_temp_file(relevant_changed: &[PathBuf]) -> Option<HookTempFile> {
         if relevant_changed.is_empty() {
             None
         } else {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_nanos();
-            let tmp_path = std::env::temp_dir().join(format!(
-                "peter-hook-changed-{}-{}.lst",
-                std::process::id(),
-                now
-            ));
             let changed_list = relevant_changed
                 .iter()
                 .map(|p| p.to_string_lossy())
                 .collect::<Vec<_>>()
                 .join("\n");
 
-            if std::fs::write(&tmp_path, &changed_list).is_ok() {
-                Some(tmp_path)
-            } else {
-                None
-            }
+            HookTempFile::create("peter-hook-changed-", ".lst", &changed_list).ok()
         }
     }
 
     /// Print debug output for changed files
     fn print_changed_files_debug(name: &str, relevant_changed: &[PathBuf]) {
         if crate::debug::is_enabled() {
-            if std::io::stderr().is_terminal() {
+            if crate::output::stderr_styled() {
                 eprintln!(
                     "\x1b[38;5;200m🎯 \x1b[1m\x1b[38;5;51mExecuting hook:\x1b[0m \
                      \x1b[38;5;226m{name}\x1b[0m"
@@ -824,20 +2021,28 @@ impl HookExecutor {
     }
 
     /// Build command from hook definition with template resolution
+    ///
+    /// For inline scripts the returned guard owns the script file; keep it
+    /// alive until the command has run.
+    #[allow(clippy::too_many_lines)]
     fn build_command_from_hook(
         hook: &ResolvedHook,
         template_resolver: &TemplateResolver,
         name: &str,
         worktree_context: &crate::hooks::resolver::WorktreeContext,
-    ) -> Result<Command> {
+    ) -> Result<(Command, Option<HookTempFile>)> {
+        let mut script_file = None;
         let mut command = match &hook.definition.command {
             HookCommand::Shell(cmd) => {
                 let resolved_cmd = template_resolver
-                    .resolve_string(cmd)
+                    .resolve_shell(cmd)
                     .context("Failed to resolve command template")?;
+                log::debug!(
+                    "template resolved hook={name} original={cmd:?} resolved={resolved_cmd:?}"
+                );
 
                 if crate::debug::is_enabled() {
-                    if std::io::stderr().is_terminal() {
+                    if crate::output::stderr_styled() {
                         eprintln!(
                             "\x1b[38;5;208m🧙‍♂️ \x1b[1m\x1b[38;5;198mShell command resolved:\x1b[0m"
                         );
@@ -852,8 +2057,14 @@ impl HookExecutor {
                     }
                 }
 
-                let mut command = Command::new("sh");
-                command.args(["-c", &resolved_cmd]);
+                let parts = with_activate(
+                    hook,
+                    &hook.definition.command,
+                    vec!["sh".to_string(), "-c".to_string(), resolved_cmd],
+                    template_resolver,
+                )?;
+                let mut command = Command::new(&parts[0]);
+                command.args(&parts[1..]);
                 command
             }
             HookCommand::Args(args) => {
@@ -863,9 +2074,12 @@ impl HookExecutor {
                 let resolved_args = template_resolver
                     .resolve_command_args(args)
                     .context("Failed to resolve command arguments")?;
+                log::debug!(
+                    "template resolved hook={name} original={args:?} resolved={resolved_args:?}"
+                );
 
                 if crate::debug::is_enabled() {
-                    if std::io::stderr().is_terminal() {
+                    if crate::output::stderr_styled() {
                         eprintln!(
                             "\x1b[38;5;165m🚀 \x1b[1m\x1b[38;5;51mArgs command resolved:\x1b[0m"
                         );
@@ -889,20 +2103,50 @@ impl HookExecutor {
                     }
                 }
 
+                let resolved_args = with_activate(
+                    hook,
+                    &hook.definition.command,
+                    resolved_args,
+                    template_resolver,
+                )?;
                 let mut command = Command::new(&resolved_args[0]);
                 if resolved_args.len() > 1 {
                     command.args(&resolved_args[1..]);
                 }
                 command
             }
+            HookCommand::Script { script, shell } => {
+                let (parts, file) =
+                    Self::prepare_script(script, shell.as_deref(), template_resolver)?;
+                log::debug!("script written hook={name} path={}", file.path.display());
+
+                if crate::debug::is_enabled() {
+                    eprintln!(
+                        "[DEBUG] Script for hook '{name}' written to {}",
+                        file.path.display()
+                    );
+                }
+
+                script_file = Some(file);
+                let parts =
+                    with_activate(hook, &hook.definition.command, parts, template_resolver)?;
+                let mut command = Command::new(&parts[0]);
+                command.args(&parts[1..]);
+                command
+            }
+            HookCommand::Steps { .. } => return Err(nested_steps_error(name)),
         };
 
         // Set working directory (resolve template if needed)
         let working_dir = if let Some(workdir_template) = &hook.definition.workdir {
+            let config_dir = hook
+                .source_file
+                .parent()
+                .context("Hook source file has no parent directory")?;
             let resolved_workdir = template_resolver
                 .resolve_string(workdir_template)
                 .context("Failed to resolve workdir template")?;
-            PathBuf::from(resolved_workdir)
+            resolve_workdir(&resolved_workdir, config_dir, &worktree_context.repo_root)
         } else if hook.definition.run_at_root {
             // If run_at_root is true, use the repository root
             worktree_context.repo_root.clone()
@@ -910,22 +2154,26 @@ impl HookExecutor {
             hook.working_directory.clone()
         };
         command.current_dir(&working_dir);
+        log::info!(
+            "exec hook={name} cwd={} command={:?}",
+            working_dir.display(),
+            std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .collect::<Vec<_>>()
+        );
 
-        // Set environment variables with template resolution
-        if let Some(env) = &hook.definition.env {
-            let resolved_env = template_resolver
-                .resolve_env(env)
-                .context("Failed to resolve environment variable templates")?;
-            for (key, value) in resolved_env {
-                command.env(key, value);
-            }
-        }
+        // Set environment variables
+        command.envs(Self::env_overlay(
+            hook,
+            template_resolver,
+            worktree_context,
+        )?);
 
         // Configure stdio
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
 
-        Ok(command)
+        Ok((command, script_file))
     }
 
     /// Print debug output for execution results
@@ -937,7 +2185,7 @@ impl HookExecutor {
         stderr: &str,
     ) {
         if crate::debug::is_enabled() {
-            if std::io::stderr().is_terminal() {
+            if crate::output::stderr_styled() {
                 if success {
                     eprintln!(
                         "\x1b[38;5;46m🎉 \x1b[1m\x1b[38;5;82mHook SUCCESS:\x1b[0m \
@@ -978,6 +2226,29 @@ impl HookExecutor {
         }
     }
 
+    /// Wrap a fully configured hook command so it runs in the hook's container
+    fn containerize(
+        name: &str,
+        command: &Command,
+        container: &ContainerSpec,
+        worktree_context: &crate::hooks::resolver::WorktreeContext,
+        temp_files: &[&Path],
+    ) -> Result<Command> {
+        let runtime = container::find_runtime(container)
+            .with_context(|| format!("Cannot run hook '{name}' in a container"))?;
+        log::info!(
+            "container hook={name} runtime={runtime} image={}",
+            container.image
+        );
+        Ok(container::containerize(
+            command,
+            container,
+            &runtime,
+            worktree_context,
+            temp_files,
+        ))
+    }
+
     /// Original hook execution logic (for Other execution type)
     fn execute_original_hook(
         name: &str,
@@ -1003,7 +2274,10 @@ impl HookExecutor {
                 |patterns| {
                     FilePatternMatcher::new(patterns).map_or_else(
                         |_| cf.to_vec(),
-                        |matcher| cf.iter().filter(|p| matcher.matches(p)).cloned().collect(),
+                        |matcher| {
+                            let matcher = matcher.relative_to(&worktree_context.repo_root);
+                            cf.iter().filter(|p| matcher.matches(p)).cloned().collect()
+                        },
                     )
                 },
             )
@@ -1030,15 +2304,39 @@ impl HookExecutor {
         Self::print_changed_files_debug(name, &transformed_files);
 
         // Set changed files in template resolver (using transformed paths)
-        template_resolver.set_changed_files(&transformed_files, changed_files_file.as_deref());
+        template_resolver.set_changed_files(
+            &transformed_files,
+            changed_files_file.as_ref().map(|file| &*file.path),
+        );
 
         // Build command with template resolution
-        let mut command =
+        let (mut command, script_file) =
             Self::build_command_from_hook(hook, &template_resolver, name, worktree_context)?;
 
+        // Expose the same file set to scripts that prefer the environment.
+        // Environment values can't hold NUL bytes, so entries are separated
+        // by newlines
+        let changed_list = transformed_files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        command.env("PETER_HOOK_CHANGED_FILES", changed_list).env(
+            "PETER_HOOK_CHANGED_COUNT",
+            transformed_files.len().to_string(),
+        );
+        if let Some(container) = &hook.definition.container {
+            let temp_files: Vec<&Path> = [&script_file, &changed_files_file]
+                .into_iter()
+                .flatten()
+                .map(|file| &*file.path)
+                .collect();
+            command = Self::containerize(name, &command, container, worktree_context, &temp_files)?;
+        }
+
         // Debug output right before execution
         if crate::debug::is_enabled() {
-            if std::io::stderr().is_terminal() {
+            if crate::output::stderr_styled() {
                 eprintln!(
                     "\x1b[38;5;220m⚡ \x1b[1m\x1b[38;5;196mABOUT TO EXECUTE:\x1b[0m \
                      \x1b[38;5;226m{name}\x1b[0m"
@@ -1051,15 +2349,9 @@ impl HookExecutor {
         }
 
         // Execute the command
-        let output = command
-            .output()
+        let output = Self::capture_output(name, hook, &mut command)
             .with_context(|| format!("Failed to execute hook command: {name}"))?;
 
-        // Cleanup temp file, if any
-        if let Some(p) = changed_files_file {
-            let _ = std::fs::remove_file(p);
-        }
-
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         let exit_code = output.status.code().unwrap_or(-1);
@@ -1068,13 +2360,86 @@ impl HookExecutor {
         // Debug output for results
         Self::print_execution_debug_output(name, success, exit_code, &stdout, &stderr);
 
-        Ok(ExecutionResult {
-            exit_code,
-            stdout,
-            stderr,
-            success,
-        })
+        Ok(ExecutionResult::new(exit_code, stdout, stderr))
+    }
+}
+
+/// Write one live output line of a hook behind its `[name] ` tag, in a
+/// single write so lines of concurrent hooks don't mix
+fn write_tagged_line(out: &mut impl std::io::Write, prefix: &str, line: &[u8]) {
+    let mut tagged = Vec::with_capacity(prefix.len() + line.len() + 1);
+    tagged.extend_from_slice(prefix.as_bytes());
+    tagged.extend_from_slice(line);
+    if !line.ends_with(b"\n") {
+        tagged.push(b'\n');
+    }
+    let _ = out.write_all(&tagged);
+    let _ = out.flush();
+}
+
+/// Error for a `steps` command reaching code that runs a single command;
+/// `execute_steps` runs each step on its own and validation
+/// rejects nested steps
+fn nested_steps_error(name: &str) -> anyhow::Error {
+    anyhow::anyhow!("Hook '{name}' has a step that is itself a list of steps")
+}
+
+/// Run a resolved command of a hook (its `command`, `skip_if` or
+/// `on_failure`, given as `command`) after the hook's `activate` snippet
+///
+/// Shell commands get the snippet in front of their own script, so anything
+/// it defines (functions, aliases) is still available; other commands are
+/// `exec`ed from a `sh` that ran the snippet and inherit its environment.
+/// Either way, the command doesn't run if the snippet fails.
+fn with_activate(
+    hook: &ResolvedHook,
+    command: &HookCommand,
+    mut parts: Vec<String>,
+    template_resolver: &TemplateResolver,
+) -> Result<Vec<String>> {
+    let Some(activate) = &hook.definition.activate else {
+        return Ok(parts);
+    };
+    let activate = template_resolver
+        .resolve_shell(activate)
+        .context("Failed to resolve activate template")?;
+    let prelude = format!("{{\n{activate}\n}} || exit\n");
+
+    if matches!(command, HookCommand::Shell(_)) {
+        // Shell commands are always `sh -c <script> ...`
+        parts[2] = format!("{prelude}{}", parts[2]);
+        return Ok(parts);
     }
+    Ok(["sh", "-c", &format!("{prelude}exec \"$@\""), "sh"]
+        .into_iter()
+        .map(String::from)
+        .chain(parts)
+        .collect())
+}
+
+/// Split file arguments into chunks so that the base command plus each chunk
+/// stays under `limit` bytes. Each argument is counted with its terminating
+/// NUL and pointer slot, as the kernel does. A single file that exceeds the
+/// limit on its own still gets a chunk of its own. An empty file list yields
+/// one empty chunk so the command runs once.
+fn chunk_file_args<'a>(base: &[String], files: &'a [String], limit: usize) -> Vec<&'a [String]> {
+    let arg_cost = |arg: &String| arg.len() + 1 + std::mem::size_of::<usize>();
+    let base_cost: usize = base.iter().map(arg_cost).sum();
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut size = base_cost;
+    for (index, file) in files.iter().enumerate() {
+        let cost = arg_cost(file);
+        if index > start && size + cost > limit {
+            chunks.push(&files[start..index]);
+            start = index;
+            size = base_cost;
+        }
+        size += cost;
+    }
+    chunks.push(&files[start..]);
+    chunks
 }
 
 impl Default for HookExecutor {
@@ -1091,21 +2456,63 @@ impl ExecutionResults {
         println!("{}", fmt.section_header("Hook Execution Summary"));
 
         for (name, result) in &self.results {
-            println!(
-                "{}",
-                fmt.hook_result(name, result.success, result.exit_code)
-            );
+            let label = hook_label(name, result.description.as_deref());
+            if let Some(reason) = result.skipped {
+                println!("{}", fmt.hook_skipped(&label, reason.describe()));
+                continue;
+            }
 
+            if result.is_advisory_failure() {
+                println!("{}", fmt.hook_allowed_failure(&label, result.exit_code));
+            } else {
+                println!(
+                    "{}",
+                    fmt.hook_result(&label, result.success, result.exit_code)
+                );
+            }
+            if let Some(step) = &result.failed_step {
+                println!("  {step}");
+            }
+            if show_files() && !result.files.is_empty() {
+                let files: Vec<String> = result
+                    .files
+                    .iter()
+                    .map(|file| file.display().to_string())
+                    .collect();
+                println!("  files: {}", files.join(" "));
+            }
+
+            // Streamed output was already printed as the hooks ran
+            if output::stream() {
+                continue;
+            }
+            let limit = output_limits().for_outcome(result.success);
             if !result.stdout.is_empty() {
-                println!("  stdout: {}", result.stdout.trim());
+                let stream = if result.combined_output {
+                    "output"
+                } else {
+                    "stdout"
+                };
+                println!("{}", fmt.hook_output(stream, &result.stdout, limit));
             }
 
             if !result.stderr.is_empty() {
-                println!("  stderr: {}", result.stderr.trim());
+                println!("{}", fmt.hook_output("stderr", &result.stderr, limit));
             }
         }
 
         println!("{}", fmt.overall_result(self.success));
+        let advisory = self.get_advisory_failures();
+        if !advisory.is_empty() {
+            println!("{}", fmt.allowed_failures(&advisory));
+        }
+
+        if report_format() == ReportFormat::Github {
+            let workspace = std::env::var_os("GITHUB_WORKSPACE").map(PathBuf::from);
+            for command in github::annotations(self, workspace.as_deref()) {
+                println!("{command}");
+            }
+        }
     }
 
     /// Print execution with progress bar (TTY only)
@@ -1135,41 +2542,135 @@ impl ExecutionResults {
         }
     }
 
-    /// Get failed hooks
+    /// Get failed hooks, except those allowed to fail (see
+    /// [`Self::get_advisory_failures`])
     #[must_use]
     pub fn get_failed_hooks(&self) -> Vec<&str> {
         self.results
             .iter()
             .filter_map(|(name, result)| {
-                if result.success {
-                    None
-                } else {
+                if result.is_blocking_failure() {
                     Some(name.as_str())
+                } else {
+                    None
                 }
             })
             .collect()
     }
+
+    /// Get failed hooks that are allowed to fail (`allow_failure`), which
+    /// don't fail the run
+    #[must_use]
+    pub fn get_advisory_failures(&self) -> Vec<&str> {
+        self.results
+            .iter()
+            .filter(|(_, result)| result.is_advisory_failure())
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Write each hook's output to `<dir>/<hook>.stdout.log` and
+    /// `<dir>/<hook>.stderr.log`, and the results without output to
+    /// `<dir>/report.json`, creating `dir` if needed
+    ///
+    /// Hook names are sanitized for the filesystem; names that collide after
+    /// sanitizing get a numeric suffix.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory, a log file or the report cannot be
+    /// written
+    pub fn write_logs(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create output directory: {}", dir.display()))?;
+
+        let mut used = std::collections::HashSet::new();
+        for (name, result) in &self.results {
+            let base = sanitize_file_stem(name);
+            let mut stem = base.clone();
+            let mut suffix = 2;
+            while !used.insert(stem.clone()) {
+                stem = format!("{base}-{suffix}");
+                suffix += 1;
+            }
+
+            for (stream, contents) in [("stdout", &result.stdout), ("stderr", &result.stderr)] {
+                let path = dir.join(format!("{stem}.{stream}.log"));
+                std::fs::write(&path, contents)
+                    .with_context(|| format!("Failed to write hook log: {}", path.display()))?;
+            }
+            log::debug!("hook logs written name={name} stem={stem}");
+        }
+
+        let path = dir.join("report.json");
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write run report: {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Make a hook name safe to use as a file name
+///
+/// Keeps ASCII letters, digits, `.`, `-` and `_`; everything else (path
+/// separators from multi-config prefixes, spaces, `:`) becomes `_`. Leading
+/// dots are replaced so logs are never hidden files.
+fn sanitize_file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let trimmed = stem.trim_start_matches('.');
+    let stem = format!("{}{trimmed}", "_".repeat(stem.len() - trimmed.len()));
+    if stem.is_empty() {
+        "hook".to_string()
+    } else {
+        stem
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{HookCommand, HookDefinition};
-    use std::{collections::HashMap, path::PathBuf};
+    use indexmap::IndexMap;
+    use std::path::{Path, PathBuf};
 
     fn create_test_hook(command: HookCommand, workdir: Option<String>) -> ResolvedHook {
         ResolvedHook {
             definition: HookDefinition {
                 command,
                 workdir,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: None,
                 run_always: true, // Always run in tests since we pass None for changed_files
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::PerFile,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             working_directory: std::env::temp_dir(),
             source_file: PathBuf::from("test.toml"),
@@ -1183,6 +2684,8 @@ mod tests {
             repo_root: std::env::temp_dir(),
             common_dir: std::env::temp_dir().join(".git"),
             working_dir: std::env::temp_dir(),
+            run_env: IndexMap::new(),
+            push_refs: None,
         }
     }
 
@@ -1200,6 +2703,257 @@ mod tests {
         assert!(result.stderr.is_empty());
     }
 
+    #[test]
+    fn test_run_env_layered_under_hook_env() {
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.run_env = IndexMap::from([
+            ("RUN_ONLY".to_string(), "from-run".to_string()),
+            ("SHARED".to_string(), "from-run".to_string()),
+        ]);
+
+        for execution_type in [ExecutionType::PerFile, ExecutionType::Other] {
+            let mut hook = create_test_hook(
+                HookCommand::Shell("echo \"$RUN_ONLY $SHARED {env:SHARED}\"".to_string()),
+                None,
+            );
+            hook.definition.execution_type = execution_type;
+            hook.definition.env = Some(std::collections::HashMap::from([(
+                "SHARED".to_string(),
+                "from-hook".to_string(),
+            )]));
+
+            let result =
+                HookExecutor::execute_single_hook("test", &hook, &worktree_context, None).unwrap();
+
+            assert!(result.success, "{execution_type:?}: {}", result.stderr);
+            assert_eq!(
+                result.stdout.trim(),
+                "from-run from-hook from-run",
+                "{execution_type:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan_env_resolves_and_redacts() {
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.run_env = IndexMap::from([
+            ("SHARED".to_string(), "from-run".to_string()),
+            ("GITHUB_TOKEN".to_string(), "ghp_run".to_string()),
+        ]);
+
+        let mut lint = create_test_hook(HookCommand::Shell("true".to_string()), None);
+        lint.definition.env = Some(std::collections::HashMap::from([
+            ("SHARED".to_string(), "from-hook".to_string()),
+            ("TARGET_DIR".to_string(), "{REPO_ROOT}/target".to_string()),
+            ("db_password".to_string(), "hunter2".to_string()),
+        ]));
+        let mut notify = create_test_hook(HookCommand::Shell("true".to_string()), None);
+        notify.definition.execution_type = ExecutionType::Other;
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks: IndexMap::from([("lint".to_string(), lint), ("notify".to_string(), notify)]),
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: Some(vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")]),
+            worktree_context,
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let mut plans = HookExecutor::plan_env(&resolved_hooks).unwrap();
+        let repo_root = std::env::temp_dir().display().to_string();
+        assert_eq!(
+            plans[0].env,
+            IndexMap::from([
+                ("GITHUB_TOKEN".to_string(), "ghp_run".to_string()),
+                ("SHARED".to_string(), "from-hook".to_string()),
+                ("TARGET_DIR".to_string(), format!("{repo_root}/target")),
+                ("db_password".to_string(), "hunter2".to_string()),
+            ])
+        );
+        assert_eq!(plans[1].env["PETER_HOOK_CHANGED_COUNT"], "2");
+        assert!(!plans[0].env.contains_key("PETER_HOOK_CHANGED_COUNT"));
+
+        plans[0].redact();
+        assert_eq!(plans[0].env["GITHUB_TOKEN"], REDACTED_VALUE);
+        assert_eq!(plans[0].env["db_password"], REDACTED_VALUE);
+        assert_eq!(plans[0].env["SHARED"], "from-hook");
+        assert!(is_sensitive_env_key("aws_secret_access_key"));
+        assert!(!is_sensitive_env_key("TARGET_DIR"));
+    }
+
+    #[test]
+    fn test_execute_steps_in_order() {
+        let mut hook = create_test_hook(
+            HookCommand::Steps {
+                steps: vec![
+                    HookCommand::Shell("echo first; export LEAK=1".to_string()),
+                    HookCommand::Args(vec![
+                        "sh".to_string(),
+                        "-c".to_string(),
+                        "echo \"second$LEAK\"".to_string(),
+                    ]),
+                ],
+            },
+            None,
+        );
+        hook.definition.execution_type = ExecutionType::InPlace;
+
+        let result = HookExecutor::execute_single_hook(
+            "steps",
+            &hook,
+            &create_test_worktree_context(),
+            None,
+        )
+        .unwrap();
+
+        assert!(result.success, "{}", result.stderr);
+        assert_eq!(result.failed_step, None);
+        assert_eq!(
+            result.stdout,
+            "[step 1/2] echo first; export LEAK=1\nfirst\n[step 2/2] sh -c echo \"second$LEAK\"\nsecond\n",
+            "steps run in order as separate processes"
+        );
+    }
+
+    #[test]
+    fn test_activate_runs_before_command() {
+        let activate = "TOOLCHAIN=venv; export TOOLCHAIN; greet() ( echo \"hi from $1\" )";
+        for execution_type in [ExecutionType::Other, ExecutionType::InPlace] {
+            let mut shell =
+                create_test_hook(HookCommand::Shell("greet \"$TOOLCHAIN\"".to_string()), None);
+            shell.definition.activate = Some(activate.to_string());
+            shell.definition.execution_type = execution_type;
+            let result = HookExecutor::execute_single_hook(
+                "shell",
+                &shell,
+                &create_test_worktree_context(),
+                None,
+            )
+            .unwrap();
+            assert!(result.success, "{}", result.stderr);
+            assert_eq!(result.stdout, "hi from venv\n", "{execution_type:?}");
+
+            let mut args = create_test_hook(
+                HookCommand::Args(vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "echo \"args see $TOOLCHAIN\"".to_string(),
+                ]),
+                None,
+            );
+            args.definition.activate = Some(activate.to_string());
+            args.definition.execution_type = execution_type;
+            let result = HookExecutor::execute_single_hook(
+                "args",
+                &args,
+                &create_test_worktree_context(),
+                None,
+            )
+            .unwrap();
+            assert!(result.success, "{}", result.stderr);
+            assert_eq!(result.stdout, "args see venv\n", "{execution_type:?}");
+        }
+
+        let mut failing = create_test_hook(HookCommand::Shell("echo ran".to_string()), None);
+        failing.definition.activate = Some("echo 'no venv' >&2; false".to_string());
+        failing.definition.execution_type = ExecutionType::Other;
+        let result = HookExecutor::execute_single_hook(
+            "failing",
+            &failing,
+            &create_test_worktree_context(),
+            None,
+        )
+        .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.stdout, "",
+            "command must not run after a failed activate"
+        );
+        assert!(result.stderr.contains("no venv"), "{}", result.stderr);
+    }
+
+    #[test]
+    fn test_activate_with_args_skip_if_and_on_failure() {
+        let mut hook = create_test_hook(HookCommand::Shell("exit 3".to_string()), None);
+        hook.definition.activate = Some("TOOLCHAIN=venv; export TOOLCHAIN".to_string());
+        hook.definition.execution_type = ExecutionType::Other;
+        hook.definition.skip_if = Some(HookCommand::Args(vec!["false".to_string()]));
+        hook.definition.on_failure = Some(HookCommand::Args(vec![
+            "printf".to_string(),
+            "%s|%s|%s\n".to_string(),
+            "{FAILED_HOOK}".to_string(),
+            "one".to_string(),
+            "two".to_string(),
+        ]));
+
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+        assert!(!result.success, "skip_if exited 1, so the hook ran");
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(result.stdout, "lint|one|two\n");
+
+        hook.definition.skip_if = Some(HookCommand::Args(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "test \"$TOOLCHAIN\" = venv".to_string(),
+        ]));
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+        assert!(result.success, "skip_if sees the activated environment");
+        assert_eq!(result.stdout, "");
+    }
+
+    #[test]
+    fn test_execute_steps_stops_at_first_failure() {
+        let marker =
+            std::env::temp_dir().join(format!("peter-hook-steps-marker-{}", std::process::id()));
+        let _ = std::fs::remove_file(&marker);
+        let hook = create_test_hook(
+            HookCommand::Steps {
+                steps: vec![
+                    HookCommand::Shell("echo formatted".to_string()),
+                    HookCommand::Shell("echo 'lint error' >&2; exit 3".to_string()),
+                    HookCommand::Shell(format!("touch {}", marker.display())),
+                ],
+            },
+            None,
+        );
+
+        let result = HookExecutor::execute_single_hook(
+            "steps",
+            &hook,
+            &create_test_worktree_context(),
+            None,
+        )
+        .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(
+            result.failed_step,
+            Some(FailedStep {
+                index: 2,
+                count: 3,
+                command: "echo 'lint error' >&2; exit 3".to_string(),
+            })
+        );
+        assert!(result.stdout.contains("formatted"));
+        assert!(
+            result
+                .stderr
+                .starts_with("[step 2/3] echo 'lint error' >&2; exit 3\nlint error"),
+            "{}",
+            result.stderr
+        );
+        assert!(!marker.exists(), "steps after the failure must not run");
+    }
+
     #[test]
     fn test_execute_shell_command_failure() {
         let hook = create_test_hook(HookCommand::Shell("exit 1".to_string()), None);
@@ -1232,42 +2986,507 @@ mod tests {
     }
 
     #[test]
-    fn test_execute_multiple_hooks() {
-        let mut hooks = HashMap::new();
+    fn test_execute_multiple_hooks() {
+        let mut hooks = IndexMap::new();
+
+        hooks.insert(
+            "success".to_string(),
+            create_test_hook(HookCommand::Shell("exit 0".to_string()), None),
+        );
+
+        hooks.insert(
+            "failure".to_string(),
+            create_test_hook(HookCommand::Shell("exit 1".to_string()), None),
+        );
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+
+        assert!(!results.success); // Overall failure due to one failed hook
+        assert_eq!(results.results.len(), 2);
+
+        assert!(results.results["success"].success);
+        assert!(!results.results["failure"].success);
+
+        let failed = results.get_failed_hooks();
+        assert_eq!(failed, vec!["failure"]);
+    }
+
+    #[test]
+    fn test_allow_failure_is_advisory() {
+        let mut advisory = create_test_hook(HookCommand::Shell("exit 3".to_string()), None);
+        advisory.definition.allow_failure = true;
+        let mut hooks = IndexMap::new();
+        hooks.insert("spell".to_string(), advisory);
+        hooks.insert(
+            "lint".to_string(),
+            create_test_hook(HookCommand::Shell("exit 0".to_string()), None),
+        );
+
+        for execution_strategy in [ExecutionStrategy::Sequential, ExecutionStrategy::Parallel] {
+            let resolved_hooks = ResolvedHooks {
+                config_path: PathBuf::from("test.toml"),
+                hooks: hooks.clone(),
+                execution_strategy,
+                bail_after: Some(1),
+                isolation: None,
+                changed_files: None,
+                worktree_context: create_test_worktree_context(),
+                before: Vec::new(),
+                after: Vec::new(),
+            };
+
+            let results = HookExecutor::execute(&resolved_hooks).unwrap();
+            // The failure is reported, but neither fails the run nor uses
+            // up the failure budget
+            assert!(results.success, "{execution_strategy:?}");
+            assert!(!results.results["spell"].success);
+            assert_eq!(results.results["spell"].exit_code, 3);
+            assert!(results.results["lint"].success);
+            assert!(results.get_failed_hooks().is_empty());
+            assert_eq!(results.get_advisory_failures(), ["spell"]);
+        }
+
+        // A hard failure still fails the run alongside an advisory one
+        hooks.insert(
+            "test".to_string(),
+            create_test_hook(HookCommand::Shell("exit 1".to_string()), None),
+        );
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+        assert!(!results.success);
+        assert_eq!(results.get_failed_hooks(), ["test"]);
+        assert_eq!(results.get_advisory_failures(), ["spell"]);
+    }
+
+    #[test]
+    fn test_execute_from_skips_earlier_hooks_in_order() {
+        let mut hooks = IndexMap::new();
+        for name in ["format", "lint", "test", "audit"] {
+            hooks.insert(
+                name.to_string(),
+                create_test_hook(HookCommand::Shell(format!("echo {name}")), None),
+            );
+        }
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let results = HookExecutor::execute_from(&resolved_hooks, Some("test")).unwrap();
+
+        assert!(results.success);
+        let ran: Vec<&str> = results.results.keys().map(String::as_str).collect();
+        assert_eq!(ran, vec!["test", "audit"]);
+    }
+
+    #[test]
+    fn test_execute_from_unknown_hook_fails() {
+        let mut hooks = IndexMap::new();
+        hooks.insert(
+            "lint".to_string(),
+            create_test_hook(HookCommand::Shell("echo lint".to_string()), None),
+        );
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let err = HookExecutor::execute_from(&resolved_hooks, Some("missing")).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'missing' is not part of this run")
+        );
+    }
+
+    #[test]
+    fn test_execute_from_skips_dependency_phases() {
+        let with_deps = |name: &str, deps: &[&str]| {
+            let mut hook = create_test_hook(HookCommand::Shell(format!("echo {name}")), None);
+            if !deps.is_empty() {
+                hook.definition.depends_on = Some(deps.iter().map(ToString::to_string).collect());
+            }
+            hook
+        };
+
+        let mut hooks = IndexMap::new();
+        hooks.insert("format".to_string(), with_deps("format", &[]));
+        hooks.insert("lint".to_string(), with_deps("lint", &["format"]));
+        hooks.insert("test".to_string(), with_deps("test", &["lint"]));
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let results = HookExecutor::execute_from(&resolved_hooks, Some("lint")).unwrap();
+
+        assert!(results.success);
+        let ran: Vec<&str> = results.results.keys().map(String::as_str).collect();
+        assert_eq!(ran, vec!["lint", "test"]);
+    }
+
+    #[test]
+    fn test_skip_if_condition_skips_hook() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("ran");
+        let mut hook = create_test_hook(
+            HookCommand::Shell(format!("touch {}", marker.display())),
+            None,
+        );
+        hook.definition.skip_if = Some(HookCommand::Shell(
+            "test -f \"{REPO_ROOT}/skip.flag\"".to_string(),
+        ));
+        std::fs::write(temp_dir.path().join("skip.flag"), "").unwrap();
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = temp_dir.path().to_path_buf();
+
+        let result =
+            HookExecutor::execute_single_hook("gen", &hook, &worktree_context, None).unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.skipped, Some(SkipReason::SkipIf));
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn test_skip_if_condition_failing_runs_hook() {
+        let mut hook = create_test_hook(HookCommand::Shell("echo ran".to_string()), None);
+        hook.definition.skip_if = Some(HookCommand::Args(vec!["false".to_string()]));
+
+        let result =
+            HookExecutor::execute_single_hook("gen", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.skipped, None);
+        assert_eq!(result.stdout.trim(), "ran");
+    }
+
+    #[test]
+    fn test_fail_on_matching_output_fails_hook() {
+        let mut hook = create_test_hook(
+            HookCommand::Shell("echo ok; echo 'WARNING: deprecated' >&2".to_string()),
+            None,
+        );
+        hook.definition.fail_on = Some("^WARNING:".to_string());
+
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 1);
+        assert!(
+            result
+                .stderr
+                .contains("output matched fail_on pattern: WARNING: deprecated")
+        );
+    }
+
+    #[test]
+    fn test_combined_output_interleaves_streams() {
+        let command =
+            "echo context; echo 'error: one' >&2; echo more; echo 'error: two' >&2; exit 1";
+        let mut hook = create_test_hook(HookCommand::Shell(command.to_string()), None);
+
+        let separate = HookExecutor::execute_single_hook(
+            "check",
+            &hook,
+            &create_test_worktree_context(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(separate.stdout, "context\nmore\n");
+        assert_eq!(separate.stderr, "error: one\nerror: two\n");
+        assert!(!separate.combined_output);
+
+        hook.definition.combined_output = true;
+        let combined = HookExecutor::execute_single_hook(
+            "check",
+            &hook,
+            &create_test_worktree_context(),
+            None,
+        )
+        .unwrap();
+        assert!(!combined.success);
+        assert_eq!(combined.exit_code, 1);
+        assert_eq!(combined.stdout, "context\nerror: one\nmore\nerror: two\n");
+        assert!(combined.stderr.is_empty());
+        assert!(combined.combined_output);
+    }
+
+    #[test]
+    fn test_fail_on_without_match_passes() {
+        let mut hook = create_test_hook(HookCommand::Shell("echo 'all good'".to_string()), None);
+        hook.definition.fail_on = Some("(?i)warning|error".to_string());
+
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.exit_code, 0);
+    }
+
+    #[test]
+    fn test_fail_on_allow_suppresses_known_noise() {
+        let mut hook = create_test_hook(
+            HookCommand::Shell("echo 'WARNING: deprecated flag --foo'".to_string()),
+            None,
+        );
+        hook.definition.fail_on = Some("^WARNING:".to_string());
+        hook.definition.fail_on_allow = Some("deprecated flag".to_string());
+
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+        assert!(result.success);
+
+        // Lines that aren't allowed still fail the hook
+        hook.definition.command = HookCommand::Shell(
+            "echo 'WARNING: deprecated flag --foo'; echo 'WARNING: disk full'".to_string(),
+        );
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+        assert!(!result.success);
+        assert!(result.stderr.contains("WARNING: disk full"));
+    }
+
+    #[test]
+    fn test_fail_on_keeps_nonzero_exit_code() {
+        let mut hook = create_test_hook(HookCommand::Shell("echo FATAL; exit 4".to_string()), None);
+        hook.definition.fail_on = Some("FATAL".to_string());
+
+        let result =
+            HookExecutor::execute_single_hook("lint", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 4);
+    }
+
+    #[test]
+    fn test_on_failure_runs_with_failed_hook_output() {
+        let mut hook = create_test_hook(
+            HookCommand::Shell("echo 'bad format'; exit 3".to_string()),
+            None,
+        );
+        hook.definition.execution_type = crate::config::parser::ExecutionType::Other;
+        hook.definition.on_failure = Some(HookCommand::Shell(
+            "echo 'hint: {FAILED_HOOK} exited {FAILED_EXIT_CODE}'".to_string(),
+        ));
+
+        let result =
+            HookExecutor::execute_single_hook("fmt", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 3);
+        assert!(result.stdout.contains("bad format"));
+        assert!(result.stdout.contains("hint: fmt exited 3"));
+    }
+
+    #[test]
+    fn test_on_failure_does_not_execute_hook_output() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let marker = temp_dir.path().join("pwned");
+        let mut hook = create_test_hook(
+            HookCommand::Shell(format!(
+                "echo \"x \\$(touch {}) it's\" >&2; exit 1",
+                marker.display()
+            )),
+            None,
+        );
+        hook.definition.execution_type = crate::config::parser::ExecutionType::Other;
+
+        for on_failure in [
+            HookCommand::Shell("echo hint: {FAILED_STDERR}".to_string()),
+            HookCommand::Script {
+                script: "echo hint: {FAILED_STDERR}".to_string(),
+                shell: None,
+            },
+        ] {
+            hook.definition.on_failure = Some(on_failure);
+            let result = HookExecutor::execute_single_hook(
+                "lint",
+                &hook,
+                &create_test_worktree_context(),
+                None,
+            )
+            .unwrap();
+
+            assert!(!result.success);
+            assert!(!marker.exists(), "hook output was run as shell code");
+            assert!(
+                result
+                    .stdout
+                    .contains(&format!("hint: x $(touch {}) it's", marker.display())),
+                "{}",
+                result.stdout
+            );
+        }
+    }
+
+    #[test]
+    fn test_on_failure_not_run_on_success() {
+        let mut hook = create_test_hook(HookCommand::Shell("echo ok".to_string()), None);
+        hook.definition.on_failure = Some(HookCommand::Shell("echo cleanup".to_string()));
+
+        let result =
+            HookExecutor::execute_single_hook("ok", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(result.success);
+        assert!(!result.stdout.contains("cleanup"));
+    }
+
+    #[test]
+    fn test_on_failure_failure_does_not_mask_original() {
+        let mut hook = create_test_hook(HookCommand::Shell("exit 2".to_string()), None);
+        hook.definition.on_failure = Some(HookCommand::Args(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "exit 7".to_string(),
+        ]));
 
-        hooks.insert(
-            "success".to_string(),
-            create_test_hook(HookCommand::Shell("exit 0".to_string()), None),
+        let result =
+            HookExecutor::execute_single_hook("test", &hook, &create_test_worktree_context(), None)
+                .unwrap();
+
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 2);
+        assert!(
+            result
+                .stderr
+                .contains("on_failure command for 'test' failed with exit code 7")
         );
+    }
+
+    fn resolved_with_lifecycle(
+        hook_command: &str,
+        before: Option<&str>,
+        after: &str,
+    ) -> (tempfile::TempDir, ResolvedHooks) {
+        use crate::hooks::resolver::LifecycleCommand;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let lifecycle = |command: &str| LifecycleCommand {
+            group: "pre-commit".to_string(),
+            command: HookCommand::Shell(command.to_string()),
+        };
 
+        let mut hooks = IndexMap::new();
         hooks.insert(
-            "failure".to_string(),
-            create_test_hook(HookCommand::Shell("exit 1".to_string()), None),
+            "check".to_string(),
+            create_test_hook(HookCommand::Shell(hook_command.to_string()), None),
         );
 
         let resolved_hooks = ResolvedHooks {
-            config_path: PathBuf::from("test.toml"),
+            config_path: temp_dir.path().join("hooks.toml"),
             hooks,
             execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
             changed_files: None,
             worktree_context: create_test_worktree_context(),
+            before: before.map(lifecycle).into_iter().collect(),
+            after: vec![lifecycle(after)],
         };
+        (temp_dir, resolved_hooks)
+    }
+
+    #[test]
+    fn test_group_after_runs_on_success() {
+        let (temp_dir, resolved_hooks) =
+            resolved_with_lifecycle("exit 0", Some("touch started"), "touch stopped");
 
         let results = HookExecutor::execute(&resolved_hooks).unwrap();
 
-        assert!(!results.success); // Overall failure due to one failed hook
-        assert_eq!(results.results.len(), 2);
+        assert!(results.success);
+        let order: Vec<&str> = results.results.keys().map(String::as_str).collect();
+        assert_eq!(
+            order,
+            vec!["pre-commit (before)", "check", "pre-commit (after)"]
+        );
+        assert!(temp_dir.path().join("started").exists());
+        assert!(temp_dir.path().join("stopped").exists());
+    }
 
-        assert!(results.results["success"].success);
-        assert!(!results.results["failure"].success);
+    #[test]
+    fn test_group_after_runs_on_hook_failure() {
+        let (temp_dir, resolved_hooks) =
+            resolved_with_lifecycle("exit 1", Some("touch started"), "touch stopped");
 
-        let failed = results.get_failed_hooks();
-        assert_eq!(failed, vec!["failure"]);
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+
+        assert!(!results.success);
+        assert!(!results.results["check"].success);
+        assert!(results.results["pre-commit (after)"].success);
+        assert!(temp_dir.path().join("stopped").exists());
+    }
+
+    #[test]
+    fn test_group_before_failure_skips_hooks() {
+        let (temp_dir, resolved_hooks) =
+            resolved_with_lifecycle("touch ran", Some("exit 4"), "touch stopped");
+
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+
+        assert!(!results.success);
+        assert_eq!(results.results["pre-commit (before)"].exit_code, 4);
+        assert!(!results.results.contains_key("check"));
+        assert!(temp_dir.path().join("stopped").exists());
     }
 
     #[test]
     fn test_parallel_safe_execution() {
-        let mut hooks = HashMap::new();
+        let mut hooks = IndexMap::new();
 
         // Safe hooks that can run in parallel
         hooks.insert(
@@ -1290,8 +3509,12 @@ mod tests {
             config_path: PathBuf::from("test.toml"),
             hooks,
             execution_strategy: ExecutionStrategy::Parallel,
+            bail_after: None,
+            isolation: None,
             changed_files: None,
             worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
         };
 
         let results = HookExecutor::execute(&resolved_hooks).unwrap();
@@ -1305,7 +3528,7 @@ mod tests {
 
     #[test]
     fn test_sequential_execution() {
-        let mut hooks = HashMap::new();
+        let mut hooks = IndexMap::new();
 
         hooks.insert(
             "hook1".to_string(),
@@ -1321,8 +3544,12 @@ mod tests {
             config_path: PathBuf::from("test.toml"),
             hooks,
             execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
             changed_files: None,
             worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
         };
 
         let results = HookExecutor::execute(&resolved_hooks).unwrap();
@@ -1331,9 +3558,93 @@ mod tests {
         assert_eq!(results.results.len(), 2);
     }
 
+    #[test]
+    fn test_bail_after_stops_sequential_run() {
+        let hooks: IndexMap<_, _> = [
+            ("fail1", "exit 1"),
+            ("pass", "true"),
+            ("fail2", "exit 1"),
+            ("fail3", "exit 1"),
+            ("late", "true"),
+        ]
+        .into_iter()
+        .map(|(name, command)| {
+            (
+                name.to_string(),
+                create_test_hook(HookCommand::Shell(command.to_string()), None),
+            )
+        })
+        .collect();
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: Some(2),
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+
+        assert!(!results.success);
+        assert_eq!(
+            results.results.keys().collect::<Vec<_>>(),
+            ["fail1", "pass", "fail2"]
+        );
+
+        let resolved_hooks = ResolvedHooks {
+            bail_after: None,
+            ..resolved_hooks
+        };
+        assert_eq!(
+            HookExecutor::execute(&resolved_hooks)
+                .unwrap()
+                .get_failed_hooks()
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_bail_after_skips_sequential_phase_after_parallel_failures() {
+        let mut hooks = IndexMap::new();
+        for name in ["fail1", "fail2"] {
+            hooks.insert(
+                name.to_string(),
+                create_test_hook(HookCommand::Shell("exit 1".to_string()), None),
+            );
+        }
+        let mut format = create_test_hook(HookCommand::Shell("true".to_string()), None);
+        format.definition.modifies_repository = true;
+        hooks.insert("format".to_string(), format);
+
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("test.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Parallel,
+            bail_after: Some(1),
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        let results = HookExecutor::execute(&resolved_hooks).unwrap();
+
+        // Hooks already dispatched in the parallel phase may still finish
+        assert!(!results.success);
+        assert!(!results.results.is_empty());
+        assert!(!results.results.contains_key("format"));
+    }
+
     #[test]
     fn test_force_parallel_execution() {
-        let mut hooks = HashMap::new();
+        let mut hooks = IndexMap::new();
 
         // Even repository-modifying hooks run in parallel (unsafe mode)
         hooks.insert(
@@ -1356,8 +3667,12 @@ mod tests {
             config_path: PathBuf::from("test.toml"),
             hooks,
             execution_strategy: ExecutionStrategy::ForceParallel,
+            bail_after: None,
+            isolation: None,
             changed_files: None,
             worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
         };
 
         let results = HookExecutor::execute(&resolved_hooks).unwrap();
@@ -1374,14 +3689,31 @@ mod tests {
             definition: HookDefinition {
                 command,
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository,
                 files: None,
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::PerFile,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             working_directory: std::env::temp_dir(),
             source_file: PathBuf::from("test.toml"),
@@ -1399,14 +3731,31 @@ mod tests {
                         .to_string(),
                 ),
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: Some(vec!["**/*.rs".to_string()]),
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::Other,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             working_directory: std::env::temp_dir(),
             source_file: PathBuf::from("test.toml"),
@@ -1422,20 +3771,103 @@ mod tests {
         assert!(!out.contains("README.md"));
     }
 
+    #[test]
+    fn test_changed_files_quoted_only_for_shell_commands() {
+        let worktree_context = create_test_worktree_context();
+        let changes = vec![
+            PathBuf::from("src/a.rs"),
+            PathBuf::from("src/with space.rs"),
+        ];
+
+        let mut hook = create_test_hook(
+            HookCommand::Shell("printf '%s|' {CHANGED_FILES}".to_string()),
+            None,
+        );
+        hook.definition.execution_type = crate::config::parser::ExecutionType::Other;
+        let result =
+            HookExecutor::execute_single_hook("shell", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "src/a.rs|src/with space.rs|");
+
+        let mut hook = create_test_hook(
+            HookCommand::Args(vec![
+                "printf".to_string(),
+                "%s|".to_string(),
+                "{CHANGED_FILES}".to_string(),
+            ]),
+            None,
+        );
+        hook.definition.execution_type = crate::config::parser::ExecutionType::Other;
+        let changes = vec![PathBuf::from("src/with space.rs")];
+        let result =
+            HookExecutor::execute_single_hook("args", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "src/with space.rs|");
+    }
+
+    #[test]
+    fn test_changed_files_exported_to_environment() {
+        let mut hook = create_test_hook(
+            HookCommand::Shell(
+                "printf '%s|' \"$PETER_HOOK_CHANGED_COUNT\" \"$PETER_HOOK_CHANGED_FILES\""
+                    .to_string(),
+            ),
+            None,
+        );
+        hook.definition.files = Some(vec!["**/*.rs".to_string()]);
+        hook.definition.execution_type = crate::config::parser::ExecutionType::Other;
+        let worktree_context = create_test_worktree_context();
+        let changes = vec![
+            PathBuf::from("src/a.rs"),
+            PathBuf::from("README.md"),
+            PathBuf::from("src/with space.rs"),
+        ];
+
+        let result =
+            HookExecutor::execute_single_hook("env", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "2|src/a.rs\nsrc/with space.rs|");
+
+        let result =
+            HookExecutor::execute_single_hook("env", &hook, &worktree_context, None).unwrap();
+        assert!(result.success);
+        assert_eq!(result.stdout, "0||");
+    }
+
     #[test]
     fn test_env_vars_all_changed_files_no_filter() {
         let hook = ResolvedHook {
             definition: HookDefinition {
                 command: HookCommand::Shell("printf '%s\n' '{CHANGED_FILES}'".to_string()),
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: None,
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::Other,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             working_directory: std::env::temp_dir(),
             source_file: PathBuf::from("test.toml"),
@@ -1451,6 +3883,48 @@ mod tests {
         assert!(out.contains("b/c"));
     }
 
+    #[test]
+    fn test_special_filenames_reach_hooks_intact() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let files = vec![PathBuf::from("my file.txt"), PathBuf::from("it's.txt")];
+        for file in &files {
+            std::fs::write(temp_dir.path().join(file), "").unwrap();
+        }
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = temp_dir.path().to_path_buf();
+        let expected = "[my file.txt]\n[it's.txt]\n";
+
+        for (command, execution_type) in [
+            (
+                HookCommand::Args(vec![
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    "printf '[%s]\\n' \"$@\"".to_string(),
+                    "sh".to_string(),
+                ]),
+                crate::config::parser::ExecutionType::PerFile,
+            ),
+            (
+                HookCommand::Shell("printf '[%s]\\n'".to_string()),
+                crate::config::parser::ExecutionType::PerFile,
+            ),
+            (
+                HookCommand::Shell("printf '[%s]\\n' {CHANGED_FILES}".to_string()),
+                crate::config::parser::ExecutionType::Other,
+            ),
+        ] {
+            let mut hook = create_test_hook(command, None);
+            hook.definition.execution_type = execution_type;
+            hook.working_directory = temp_dir.path().to_path_buf();
+
+            let result =
+                HookExecutor::execute_single_hook("quoted", &hook, &worktree_context, Some(&files))
+                    .unwrap();
+            assert!(result.success, "{}", result.stderr);
+            assert_eq!(result.stdout, expected);
+        }
+    }
+
     #[test]
     fn test_env_vars_empty_when_no_changes() {
         let hook = ResolvedHook {
@@ -1461,14 +3935,31 @@ mod tests {
                         .to_string(),
                 ),
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: None,
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::Other,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             working_directory: std::env::temp_dir(),
             source_file: PathBuf::from("test.toml"),
@@ -1495,6 +3986,8 @@ mod tests {
             working_dir: config_dir.clone(),
             is_worktree: false,
             worktree_name: None,
+            run_env: IndexMap::new(),
+            push_refs: None,
         };
 
         // Hook with run_at_root = true should run at repo root
@@ -1502,14 +3995,31 @@ mod tests {
             definition: HookDefinition {
                 command: HookCommand::Shell("pwd".to_string()),
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: None,
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::Other,
                 run_at_root: true,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             source_file: config_dir.join("hooks.toml"),
             working_directory: config_dir.clone(),
@@ -1520,14 +4030,31 @@ mod tests {
             definition: HookDefinition {
                 command: HookCommand::Shell("pwd".to_string()),
                 workdir: None,
+                workdir_glob: None,
                 env: None,
                 description: None,
                 modifies_repository: false,
                 files: None,
                 run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
                 depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
                 execution_type: crate::config::parser::ExecutionType::Other,
                 run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
             },
             source_file: config_dir.join("hooks.toml"),
             working_directory: config_dir.clone(),
@@ -1559,4 +4086,477 @@ mod tests {
             .expect("canonicalize config pwd");
         assert_eq!(canonical_config_pwd, canonical_config);
     }
+
+    #[test]
+    fn test_workdir_root_relative_and_config_relative() {
+        use std::fs;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().expect("create temp dir");
+        let config_dir = temp_dir.path().join("subdir");
+        fs::create_dir_all(config_dir.join("scripts")).expect("create scripts dir");
+        fs::create_dir_all(temp_dir.path().join("tools")).expect("create tools dir");
+
+        let worktree_context = crate::hooks::resolver::WorktreeContext {
+            repo_root: temp_dir.path().to_path_buf(),
+            common_dir: temp_dir.path().to_path_buf(),
+            working_dir: config_dir.clone(),
+            is_worktree: false,
+            worktree_name: None,
+            run_env: IndexMap::new(),
+            push_refs: None,
+        };
+
+        let hook_with_workdir = |workdir: &str| ResolvedHook {
+            definition: HookDefinition {
+                command: HookCommand::Shell("pwd".to_string()),
+                workdir: Some(workdir.to_string()),
+                workdir_glob: None,
+                env: None,
+                description: None,
+                modifies_repository: false,
+                files: None,
+                run_always: false,
+                run_always_if_no_changes: false,
+                include_deleted: false,
+                depends_on: None,
+                only_on_branch_change: false,
+                push_refs: None,
+                execution_type: crate::config::parser::ExecutionType::Other,
+                run_at_root: false,
+                on_failure: None,
+                fix_command: None,
+                activate: None,
+                skip_if: None,
+                enabled: true,
+                fail_on: None,
+                fail_on_allow: None,
+                tags: None,
+                container: None,
+                priority: 0,
+                combined_output: false,
+                allow_failure: false,
+            },
+            source_file: config_dir.join("hooks.toml"),
+            working_directory: config_dir.clone(),
+        };
+
+        let run_pwd = |hook: &ResolvedHook| {
+            let result =
+                HookExecutor::execute_single_hook("pwd", hook, &worktree_context, None).unwrap();
+            assert!(result.success);
+            PathBuf::from(result.stdout.trim())
+                .canonicalize()
+                .expect("canonicalize pwd")
+        };
+
+        // Leading `//` is relative to the repository root
+        assert_eq!(
+            run_pwd(&hook_with_workdir("//tools")),
+            temp_dir.path().join("tools").canonicalize().unwrap()
+        );
+
+        // Plain relative paths stay relative to the config directory
+        assert_eq!(
+            run_pwd(&hook_with_workdir("scripts")),
+            config_dir.join("scripts").canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_per_file_hook_skips_deleted_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("kept.rs"), "").unwrap();
+
+        let mut hook = create_test_hook(
+            HookCommand::Args(vec!["printf".to_string(), "%s\n".to_string()]),
+            None,
+        );
+        hook.definition.run_always = false;
+        hook.working_directory = temp_dir.path().to_path_buf();
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = temp_dir.path().to_path_buf();
+        let changes = vec![PathBuf::from("kept.rs"), PathBuf::from("gone.rs")];
+
+        let result =
+            HookExecutor::execute_single_hook("fmt", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert!(result.success, "{}", result.stderr);
+        assert_eq!(result.stdout.trim(), "kept.rs");
+
+        // Only deleted files: the hook is skipped rather than handed them
+        let result = HookExecutor::execute_single_hook(
+            "fmt",
+            &hook,
+            &worktree_context,
+            Some(&[PathBuf::from("gone.rs")]),
+        )
+        .unwrap();
+        assert!(result.success);
+        assert!(result.stdout.is_empty());
+
+        hook.definition.include_deleted = true;
+        let result =
+            HookExecutor::execute_single_hook("fmt", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert_eq!(result.stdout.trim(), "kept.rs\ngone.rs");
+    }
+
+    #[test]
+    fn test_execute_script_command() {
+        let hook = create_test_hook(
+            HookCommand::Script {
+                script: "set -e\necho first\necho \"second: $0\"\n".to_string(),
+                shell: None,
+            },
+            None,
+        );
+
+        let worktree_context = create_test_worktree_context();
+        let result =
+            HookExecutor::execute_single_hook("test", &hook, &worktree_context, None).unwrap();
+
+        assert!(result.success, "{}", result.stderr);
+        let mut lines = result.stdout.lines();
+        assert_eq!(lines.next(), Some("first"));
+        let script_path = lines.next().unwrap().strip_prefix("second: ").unwrap();
+        // The script file only lives for the duration of the hook
+        assert!(!Path::new(script_path).exists());
+    }
+
+    #[test]
+    fn test_per_file_script_receives_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "").unwrap();
+        std::fs::write(temp_dir.path().join("b.rs"), "").unwrap();
+
+        let mut hook = create_test_hook(
+            HookCommand::Script {
+                script: "for f in \"$@\"; do\n  echo \"checked $f\"\ndone\n".to_string(),
+                shell: Some("sh -e".to_string()),
+            },
+            None,
+        );
+        hook.definition.run_always = false;
+        hook.working_directory = temp_dir.path().to_path_buf();
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = temp_dir.path().to_path_buf();
+        let changes = vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")];
+
+        let result =
+            HookExecutor::execute_single_hook("check", &hook, &worktree_context, Some(&changes))
+                .unwrap();
+        assert!(result.success, "{}", result.stderr);
+        assert_eq!(result.stdout.trim(), "checked a.rs\nchecked b.rs");
+    }
+
+    #[test]
+    fn test_other_script_resolves_templates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "").unwrap();
+
+        let mut hook = create_test_hook(
+            HookCommand::Script {
+                script: "echo start\necho {CHANGED_FILES}\n".to_string(),
+                shell: None,
+            },
+            None,
+        );
+        hook.definition.execution_type = ExecutionType::Other;
+        hook.definition.run_always = false;
+        hook.working_directory = temp_dir.path().to_path_buf();
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = temp_dir.path().to_path_buf();
+
+        let result = HookExecutor::execute_single_hook(
+            "check",
+            &hook,
+            &worktree_context,
+            Some(&[PathBuf::from("a.rs")]),
+        )
+        .unwrap();
+        assert!(result.success, "{}", result.stderr);
+        assert_eq!(result.stdout.trim(), "start\na.rs");
+    }
+
+    #[test]
+    fn test_concurrent_hooks_get_distinct_changed_files_lists() {
+        let mut hook = create_test_hook(
+            HookCommand::Shell("cat {CHANGED_FILES_FILE}; echo; echo {CHANGED_FILES_FILE}".into()),
+            None,
+        );
+        hook.definition.execution_type = ExecutionType::Other;
+        hook.definition.run_always = false;
+        let worktree_context = create_test_worktree_context();
+
+        let outputs: Vec<(String, String)> = std::thread::scope(|scope| {
+            // Spawn every hook before joining any, so they overlap
+            #[allow(clippy::needless_collect)]
+            let handles: Vec<_> = (0..32)
+                .map(|i| {
+                    let (hook, worktree_context) = (&hook, &worktree_context);
+                    scope.spawn(move || {
+                        let file = format!("file-{i}.rs");
+                        let result = HookExecutor::execute_single_hook(
+                            "list",
+                            hook,
+                            worktree_context,
+                            Some(&[PathBuf::from(&file)]),
+                        )
+                        .unwrap();
+                        assert!(result.success, "{}", result.stderr);
+                        (file, result.stdout)
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut paths = std::collections::HashSet::new();
+        for (file, stdout) in outputs {
+            let mut lines = stdout.lines();
+            assert_eq!(lines.next(), Some(file.as_str()));
+            let path = lines.next().unwrap().to_string();
+            // Each hook saw its own list, which is gone once it finished
+            assert!(!Path::new(&path).exists());
+            assert!(paths.insert(path));
+        }
+    }
+
+    #[test]
+    fn test_results_record_filtered_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = temp_dir.path();
+        std::fs::create_dir(repo.join("src")).unwrap();
+        for file in ["a.rs", "src/b.rs", "notes.md"] {
+            std::fs::write(repo.join(file), "").unwrap();
+        }
+        // `gone.rs` was deleted
+        let changed: Vec<PathBuf> = ["a.rs", "src/b.rs", "gone.rs", "notes.md"]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        let mut worktree_context = create_test_worktree_context();
+        worktree_context.repo_root = repo.to_path_buf();
+
+        let hook = |execution_type, files: &[&str]| {
+            let mut hook = create_test_hook(HookCommand::Shell("true".to_string()), None);
+            hook.definition.run_always = false;
+            hook.definition.execution_type = execution_type;
+            hook.definition.files = Some(files.iter().map(ToString::to_string).collect());
+            hook.working_directory = repo.to_path_buf();
+            hook
+        };
+        let mut disabled = hook(ExecutionType::PerFile, &["*.rs"]);
+        disabled.definition.enabled = false;
+
+        let mut results = IndexMap::new();
+        for (name, hook) in [
+            ("rust", hook(ExecutionType::PerFile, &["*.rs"])),
+            ("rust-other", hook(ExecutionType::Other, &["*.rs"])),
+            ("docs", hook(ExecutionType::InPlace, &["*.md"])),
+            ("disabled", disabled),
+        ] {
+            let result =
+                HookExecutor::execute_single_hook(name, &hook, &worktree_context, Some(&changed))
+                    .unwrap();
+            assert!(result.success, "{name}: {}", result.stderr);
+            results.insert(name.to_string(), result);
+        }
+
+        let files = |name: &str| results[name].files.clone();
+        assert_eq!(
+            files("rust"),
+            [PathBuf::from("a.rs"), PathBuf::from("src/b.rs")],
+            "deleted files are dropped for per-file hooks"
+        );
+        assert_eq!(
+            files("rust-other"),
+            [
+                PathBuf::from("a.rs"),
+                PathBuf::from("src/b.rs"),
+                PathBuf::from("gone.rs")
+            ]
+        );
+        assert_eq!(files("docs"), [PathBuf::from("notes.md")]);
+        assert!(files("disabled").is_empty());
+
+        let results = ExecutionResults {
+            results,
+            success: true,
+        };
+        let dir = repo.join("logs");
+        results.write_logs(&dir).unwrap();
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("report.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            report["results"]["rust"]["files"],
+            serde_json::json!(["a.rs", "src/b.rs"])
+        );
+        assert_eq!(report["results"]["disabled"]["skipped"], "disabled");
+        assert_eq!(report["success"], true);
+        assert!(report["results"]["rust"].get("stdout").is_none());
+    }
+
+    #[test]
+    fn test_write_logs_sanitizes_names() {
+        let output = |stdout: &str| ExecutionResult::new(0, stdout.to_string(), String::new());
+        let mut results = IndexMap::new();
+        results.insert("lint".to_string(), output("lint out"));
+        results.insert("/repo/api/hooks.toml:test".to_string(), output("api"));
+        results.insert("/repo/api/hooks.toml_test".to_string(), output("collides"));
+        results.insert("setup (before)".to_string(), output("setup"));
+        let results = ExecutionResults {
+            results,
+            success: true,
+        };
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("logs");
+        results.write_logs(&dir).unwrap();
+
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).unwrap();
+        assert_eq!(read("lint.stdout.log"), "lint out");
+        assert_eq!(read("lint.stderr.log"), "");
+        assert_eq!(read("_repo_api_hooks.toml_test.stdout.log"), "api");
+        assert_eq!(read("_repo_api_hooks.toml_test-2.stdout.log"), "collides");
+        assert_eq!(read("setup__before_.stdout.log"), "setup");
+        assert_eq!(sanitize_file_stem("../.hidden"), "___.hidden");
+        assert_eq!(sanitize_file_stem(""), "hook");
+    }
+
+    #[test]
+    fn test_chunk_file_args_respects_limit() {
+        let base = vec!["lint".to_string()];
+        let files: Vec<String> = (0..10_000).map(|i| format!("src/file_{i:05}.rs")).collect();
+        let limit = 4096;
+
+        let chunks = chunk_file_args(&base, &files, limit);
+        assert!(chunks.len() > 1);
+
+        let cost = |arg: &String| arg.len() + 1 + std::mem::size_of::<usize>();
+        let base_cost: usize = base.iter().map(cost).sum();
+        for chunk in &chunks {
+            assert!(!chunk.is_empty());
+            assert!(base_cost + chunk.iter().map(cost).sum::<usize>() <= limit);
+        }
+
+        // Chunks are contiguous and cover every file exactly once, in order
+        let rejoined: Vec<&String> = chunks.iter().flat_map(|chunk| chunk.iter()).collect();
+        assert_eq!(rejoined, files.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_chunk_file_args_small_and_oversized() {
+        let base = vec!["lint".to_string()];
+        let files = vec!["a.rs".to_string(), "b.rs".to_string()];
+        assert_eq!(
+            chunk_file_args(&base, &files, MAX_COMMAND_LINE_BYTES).len(),
+            1
+        );
+        assert_eq!(chunk_file_args(&base, &[], MAX_COMMAND_LINE_BYTES).len(), 1);
+
+        let huge = vec!["x".repeat(100), "b.rs".to_string()];
+        let chunks = chunk_file_args(&base, &huge, 50);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], &huge[..1]);
+        assert_eq!(chunks[1], &huge[1..]);
+    }
+
+    #[test]
+    fn test_run_chunks_aggregates_results_sequentially() {
+        let base = vec!["lint".to_string()];
+        let files: Vec<String> = (0..1_000).map(|i| format!("f{i}")).collect();
+        let chunks = chunk_file_args(&base, &files, 512);
+        assert!(chunks.len() > 2);
+
+        let mut seen = Vec::new();
+        let result = HookExecutor::run_chunks(&chunks, |chunk| {
+            seen.push(chunk.to_vec());
+            let fail = seen.len() == 2;
+            Ok(ExecutionResult::new(
+                if fail { 3 } else { 0 },
+                format!("{}\n", chunk.len()),
+                String::new(),
+            ))
+        })
+        .unwrap();
+
+        // Every chunk runs, in order, even after the second one fails
+        assert_eq!(seen.len(), chunks.len());
+        assert_eq!(seen.concat(), files);
+        assert!(!result.success);
+        assert_eq!(result.exit_code, 3);
+        assert_eq!(result.stdout.lines().count(), chunks.len());
+
+        let ok = HookExecutor::run_chunks(&chunks, |_| {
+            Ok(ExecutionResult::new(0, String::new(), String::new()))
+        })
+        .unwrap();
+        assert!(ok.success);
+        assert_eq!(ok.exit_code, 0);
+    }
+
+    #[test]
+    fn test_parallel_dispatch_follows_priority() {
+        let mut hooks = IndexMap::new();
+        for (name, priority) in [("quick", 0), ("slow", 5), ("last", -1), ("slower", 5)] {
+            let mut hook = create_test_hook(HookCommand::Shell("true".to_string()), None);
+            hook.definition.priority = priority;
+            hooks.insert(name.to_string(), hook);
+        }
+        let resolved_hooks = ResolvedHooks {
+            config_path: PathBuf::from("hooks.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Parallel,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: create_test_worktree_context(),
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        // Highest priority first; equal priorities keep their configured order
+        let order: Vec<&str> =
+            HookExecutor::in_priority_order(&resolved_hooks, resolved_hooks.hooks.keys())
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+        assert_eq!(order, ["slow", "slower", "quick", "last"]);
+
+        // A subset, such as one dependency phase, is ordered the same way
+        let phase = vec![
+            "last".to_string(),
+            "quick".to_string(),
+            "slower".to_string(),
+        ];
+        let order: Vec<&str> = HookExecutor::in_priority_order(&resolved_hooks, &phase)
+            .into_iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(order, ["slower", "quick", "last"]);
+    }
+
+    #[test]
+    fn test_filter_relevant_files_matches_absolute_and_relative_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+
+        let mut hook = create_test_hook(HookCommand::Shell("true".to_string()), None);
+        hook.definition.files = Some(vec!["src/*.rs".to_string()]);
+
+        let changed = vec![
+            root.join("src/lib.rs"),
+            PathBuf::from("./src/lib.rs"),
+            PathBuf::from("src/lib.rs"),
+            root.join("README.md"),
+        ];
+        let relevant = HookExecutor::filter_relevant_files(&hook, Some(&changed), root);
+        assert_eq!(relevant, changed[..3]);
+    }
 }
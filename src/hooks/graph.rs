@@ -0,0 +1,130 @@
+//! Execution graph of a resolved group, for `peter-hook graph`
+
+use crate::hooks::{ExecutionPhase, HookExecutor, ResolvedHooks};
+use anyhow::Result;
+use std::fmt::Write as _;
+
+/// The phases a group runs in and the `depends_on` edges between its hooks
+#[derive(Debug, Clone)]
+pub struct HookGraph {
+    /// Group, event or hook the graph was built for
+    pub name: String,
+    /// Phases in execution order, from [`HookExecutor::execution_plan`]
+    pub phases: Vec<ExecutionPhase>,
+    /// `(dependency, dependent)` pairs, both part of the graph
+    pub edges: Vec<(String, String)>,
+}
+
+impl HookGraph {
+    /// Build the graph of the resolved hooks
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hooks' dependencies are circular
+    pub fn new(name: &str, resolved_hooks: &ResolvedHooks) -> Result<Self> {
+        let phases = HookExecutor::execution_plan(resolved_hooks)?.phases;
+        let edges = phases
+            .iter()
+            .flat_map(|phase| &phase.hooks)
+            .flat_map(|hook| {
+                resolved_hooks.hooks[hook]
+                    .definition
+                    .depends_on
+                    .iter()
+                    .flatten()
+                    .filter(|dep| resolved_hooks.hooks.contains_key(*dep))
+                    .map(move |dep| (dep.clone(), hook.clone()))
+            })
+            .collect();
+
+        Ok(Self {
+            name: name.to_string(),
+            phases,
+            edges,
+        })
+    }
+
+    /// Render as an indented tree of phases and their hooks
+    #[must_use]
+    pub fn to_tree(&self) -> String {
+        let mut out = format!("{}\n", self.name);
+        for (index, phase) in self.phases.iter().enumerate() {
+            let last_phase = index + 1 == self.phases.len();
+            let (branch, indent) = if last_phase {
+                ("└──", "    ")
+            } else {
+                ("├──", "│   ")
+            };
+            let _ = writeln!(out, "{branch} {}", phase_label(index, phase));
+
+            for (position, hook) in phase.hooks.iter().enumerate() {
+                let branch = if position + 1 == phase.hooks.len() {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                let deps = self.dependencies_of(hook);
+                if deps.is_empty() {
+                    let _ = writeln!(out, "{indent}{branch} {hook}");
+                } else {
+                    let _ = writeln!(out, "{indent}{branch} {hook} (after {})", deps.join(", "));
+                }
+            }
+        }
+        out
+    }
+
+    /// Render as a Graphviz DOT digraph: one cluster per phase, solid edges
+    /// for `depends_on` and dashed edges between hooks of a sequential phase
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph {} {{\n    rankdir=LR;\n", quote(&self.name));
+        for (index, phase) in self.phases.iter().enumerate() {
+            let _ = writeln!(out, "    subgraph \"cluster_{}\" {{", index + 1);
+            let _ = writeln!(out, "        label={};", quote(&phase_label(index, phase)));
+            for hook in &phase.hooks {
+                let _ = writeln!(out, "        {};", quote(hook));
+            }
+            out.push_str("    }\n");
+        }
+        for (dep, hook) in &self.edges {
+            let _ = writeln!(out, "    {} -> {};", quote(dep), quote(hook));
+        }
+        for phase in self.phases.iter().filter(|phase| !phase.parallel) {
+            for pair in phase.hooks.windows(2) {
+                let _ = writeln!(
+                    out,
+                    "    {} -> {} [style=dashed];",
+                    quote(&pair[0]),
+                    quote(&pair[1])
+                );
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Hooks `hook` waits for through `depends_on`
+    fn dependencies_of(&self, hook: &str) -> Vec<&str> {
+        self.edges
+            .iter()
+            .filter(|(_, dependent)| dependent == hook)
+            .map(|(dep, _)| dep.as_str())
+            .collect()
+    }
+}
+
+/// `phase 2 (parallel)`; a single hook needs no qualifier
+fn phase_label(index: usize, phase: &ExecutionPhase) -> String {
+    let number = index + 1;
+    match (phase.hooks.len(), phase.parallel) {
+        (0 | 1, _) => format!("phase {number}"),
+        (_, true) => format!("phase {number} (parallel)"),
+        (_, false) => format!("phase {number} (sequential)"),
+    }
+}
+
+/// A DOT string literal
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -5,13 +5,15 @@
 //! monorepo-style setups where different subdirectories have different quality gates.
 
 use crate::{
-    config::HookConfig,
-    git::ChangeDetectionMode,
-    hooks::{HookResolver, ResolvedHooks, WorktreeContext},
+    config::{CONFIG_FILE_NAMES, HookConfig, config_file_in},
+    git::{ChangeDetectionMode, FilePatternMatcher, LintFileDiscovery},
+    hooks::{HookResolver, ResolvedHooks, RunDecision, WorktreeContext, run_decision},
 };
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     path::{Path, PathBuf},
 };
 
@@ -26,6 +28,89 @@ pub struct ConfigGroup {
     pub resolved_hooks: ResolvedHooks,
 }
 
+impl ConfigGroup {
+    /// Files in this group that at least one hook's `files` patterns match
+    ///
+    /// Hooks without `files` patterns match every file. `run_always` hooks
+    /// don't count as matching anything on their own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hook's glob patterns are invalid
+    pub fn matched_files(&self) -> Result<Vec<PathBuf>> {
+        let mut matchers = Vec::new();
+        for hook in self.resolved_hooks.hooks.values() {
+            if hook.definition.run_always {
+                continue;
+            }
+            let Some(patterns) = &hook.definition.files else {
+                return Ok(self.files.clone());
+            };
+            matchers.push(
+                FilePatternMatcher::new(patterns)
+                    .context("Failed to compile file patterns")?
+                    .relative_to(&self.resolved_hooks.worktree_context.repo_root),
+            );
+        }
+
+        Ok(self
+            .files
+            .iter()
+            .filter(|file| matchers.iter().any(|matcher| matcher.matches(file)))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Drop config groups none of whose hooks match a changed file
+///
+/// This is `run --affected-only`: subprojects whose files didn't change are
+/// skipped entirely, including their `run_always` hooks.
+///
+/// # Errors
+///
+/// Returns an error if a hook's glob patterns are invalid
+pub fn retain_affected(groups: &mut Vec<ConfigGroup>) -> Result<()> {
+    let mut affected = Vec::with_capacity(groups.len());
+    for group in groups.drain(..) {
+        let matched = group.matched_files()?;
+        log::info!(
+            "affected-only config={} matched_files={}",
+            group.config_path.display(),
+            matched.len()
+        );
+        if !matched.is_empty() {
+            affected.push(group);
+        }
+    }
+
+    *groups = affected;
+    Ok(())
+}
+
+/// Why one hook will or will not run
+#[derive(Debug, Clone, Serialize)]
+pub struct HookExplanation {
+    /// Hook name
+    pub hook: String,
+    /// Whether the hook will run
+    pub will_run: bool,
+    /// The reason behind the decision
+    #[serde(flatten)]
+    pub decision: RunDecision,
+}
+
+/// Run decisions for every hook a configuration defines for an event
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigExplanation {
+    /// The configuration file path
+    pub config_path: PathBuf,
+    /// Changed files that use this configuration
+    pub files: Vec<PathBuf>,
+    /// One entry per hook, in run order
+    pub hooks: Vec<HookExplanation>,
+}
+
 /// Find the nearest hooks.toml file for a given file path
 ///
 /// Walks up from the file's directory to find the first hooks.toml.
@@ -51,8 +136,7 @@ fn find_config_for_file(file_path: &Path, repo_root: &Path) -> Option<PathBuf> {
     let repo_root_canonical = repo_root.canonicalize().ok()?;
 
     loop {
-        let config_path = current.join("hooks.toml");
-        if config_path.exists() {
+        if let Some(config_path) = config_file_in(current) {
             return Some(config_path);
         }
 
@@ -70,6 +154,30 @@ fn find_config_for_file(file_path: &Path, repo_root: &Path) -> Option<PathBuf> {
     None
 }
 
+/// Find every config file in the repository, one per directory
+///
+/// Files are discovered like lint mode does, so configs excluded by
+/// `.gitignore` or `.peterhookignore` are left out. Where a directory has
+/// several config files, the one the resolver would pick wins.
+///
+/// # Errors
+///
+/// Returns an error if file discovery fails
+pub fn find_all_config_files(repo_root: &Path) -> Result<Vec<PathBuf>> {
+    let dirs: BTreeSet<PathBuf> = LintFileDiscovery::new(repo_root)
+        .discover_files()?
+        .into_iter()
+        .filter(|file| {
+            file.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| CONFIG_FILE_NAMES.contains(&name))
+        })
+        .filter_map(|file| file.parent().map(Path::to_path_buf))
+        .collect();
+
+    Ok(dirs.iter().filter_map(|dir| config_file_in(dir)).collect())
+}
+
 /// Resolve hooks for a specific event from a configuration file
 ///
 /// This function loads the config and checks if it defines the requested event.
@@ -224,6 +332,168 @@ pub fn group_files_by_config(
     Ok(groups)
 }
 
+/// Nearest config of each directory, looked up once per directory, and
+/// whether each config defines the event, parsed once per config
+struct ConfigLocator<'a> {
+    repo_root: &'a Path,
+    event: &'a str,
+    nearest: HashMap<PathBuf, Option<PathBuf>>,
+    defines_event: HashMap<PathBuf, bool>,
+}
+
+impl<'a> ConfigLocator<'a> {
+    fn new(repo_root: &'a Path, event: &'a str) -> Self {
+        Self {
+            repo_root,
+            event,
+            nearest: HashMap::new(),
+            defines_event: HashMap::new(),
+        }
+    }
+
+    /// The config in `dir` or its nearest ancestor, up to the repository
+    /// root; every directory on the way is cached with the answer
+    fn nearest_config(&mut self, dir: &Path) -> Option<PathBuf> {
+        let mut visited = Vec::new();
+        let mut current = dir;
+        let found = loop {
+            if let Some(cached) = self.nearest.get(current) {
+                break cached.clone();
+            }
+            visited.push(current.to_path_buf());
+            if let Some(config_path) = config_file_in(current) {
+                break Some(config_path);
+            }
+            match current.parent() {
+                Some(parent) if current != self.repo_root && parent.starts_with(self.repo_root) => {
+                    current = parent;
+                }
+                _ => break None,
+            }
+        };
+
+        for dir in visited {
+            self.nearest.insert(dir, found.clone());
+        }
+        found
+    }
+
+    /// The config that resolves the event for files governed by
+    /// `config_path`: itself, or its nearest ancestor config defining the
+    /// event (which may be a placeholder group)
+    fn event_config(&mut self, config_path: PathBuf) -> Result<Option<PathBuf>> {
+        let mut current = config_path;
+        loop {
+            let defines_event = if let Some(&cached) = self.defines_event.get(&current) {
+                cached
+            } else {
+                let config = HookConfig::from_file(&current)
+                    .with_context(|| format!("Failed to load config: {}", current.display()))?;
+                let defines_event = config
+                    .hooks
+                    .as_ref()
+                    .is_some_and(|hooks| hooks.contains_key(self.event))
+                    || config
+                        .groups
+                        .as_ref()
+                        .is_some_and(|groups| groups.contains_key(self.event));
+                self.defines_event.insert(current.clone(), defines_event);
+                defines_event
+            };
+            if defines_event {
+                return Ok(Some(current));
+            }
+
+            let Some(parent_dir) = current
+                .parent()
+                .and_then(Path::parent)
+                .filter(|dir| dir.starts_with(self.repo_root))
+            else {
+                return Ok(None);
+            };
+            match self.nearest_config(parent_dir) {
+                Some(parent_config) => current = parent_config,
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Resolve hooks only from the configs that govern changed files
+///
+/// This is `run --only-modified-configs`. The nearest config is looked up
+/// once per directory containing a changed file, following parents only as
+/// far as the first config, so configs elsewhere in the repository are never
+/// read. A config that doesn't define the event defers to its nearest
+/// ancestor config that does, as in [`group_files_by_config`]; a placeholder
+/// group there runs nothing. Each config that defines the event is resolved
+/// once, with all the changed files it governs. Nothing is resolved when no
+/// files changed.
+///
+/// # Errors
+///
+/// Returns an error if git operations fail, a config on the path of a
+/// changed file can't be parsed, or hook resolution fails
+pub fn resolve_modified_configs(
+    event: &str,
+    change_mode: &ChangeDetectionMode,
+    repo_root: &Path,
+    worktree_context: &WorktreeContext,
+) -> Result<Vec<ConfigGroup>> {
+    let changed_files = crate::git::GitChangeDetector::new(repo_root)
+        .context("Failed to create git change detector")?
+        .get_changed_files(change_mode)
+        .context("Failed to detect changed files")?;
+    log::info!(
+        "change detection mode={change_mode} changed_files={}",
+        changed_files.len()
+    );
+
+    let mut locator = ConfigLocator::new(repo_root, event);
+    let mut config_files: IndexMap<PathBuf, Vec<PathBuf>> = IndexMap::new();
+    for file in &changed_files {
+        let absolute_file = repo_root.join(file);
+        let Some(dir) = absolute_file.parent() else {
+            continue;
+        };
+        let Some(config_path) = locator.nearest_config(dir) else {
+            continue;
+        };
+        if let Some(event_config) = locator.event_config(config_path)? {
+            config_files
+                .entry(event_config)
+                .or_default()
+                .push(file.clone());
+        }
+    }
+    log::info!(
+        "only-modified-configs event={event} directories_checked={} configs_read={}",
+        locator.nearest.len(),
+        locator.defines_event.len()
+    );
+
+    let mut groups = Vec::new();
+    for (config_path, files) in config_files {
+        let config_dir = config_path
+            .parent()
+            .context("Config file has no parent directory")?;
+        let Some(mut resolved_hooks) =
+            HookResolver::new(config_dir).resolve_hooks_with_files(event, None)?
+        else {
+            continue;
+        };
+        resolved_hooks.changed_files = Some(files.clone());
+        resolved_hooks.worktree_context = worktree_context.clone();
+        groups.push(ConfigGroup {
+            config_path,
+            files,
+            resolved_hooks,
+        });
+    }
+
+    Ok(groups)
+}
+
 /// Resolve hooks hierarchically for all changed files
 ///
 /// This is the main public API for hierarchical resolution. It:
@@ -253,34 +523,109 @@ pub fn resolve_hooks_hierarchically(
     current_dir: &Path,
     worktree_context: &WorktreeContext,
 ) -> Result<Vec<ConfigGroup>> {
+    let detection_on = change_mode.is_some();
+
     // Get changed files if we have a detection mode
     let changed_files = if let Some(mode) = change_mode {
         let detector = crate::git::GitChangeDetector::new(repo_root)
             .context("Failed to create git change detector")?;
-        detector
+        let files = detector
             .get_changed_files(&mode)
-            .context("Failed to detect changed files")?
+            .context("Failed to detect changed files")?;
+        log::info!("change detection mode={mode} changed_files={}", files.len());
+        log::debug!("changed files: {files:?}");
+        files
     } else {
+        log::info!("change detection disabled (all files)");
         // If no change mode (--all-files), use current directory to find config
         // and return empty files list to trigger run_always hooks
         Vec::new()
     };
 
-    if changed_files.is_empty() {
+    let groups = if changed_files.is_empty() {
         // No files changed - check if there's a config from current directory
         // This allows --dry-run and --all-files to work from subdirectories
         let current_resolver = HookResolver::new(current_dir);
-        if let Some(resolved) = current_resolver.resolve_hooks(event)? {
-            return Ok(vec![ConfigGroup {
-                config_path: resolved.config_path.clone(),
-                files: Vec::new(),
-                resolved_hooks: resolved,
-            }]);
-        }
-        return Ok(Vec::new());
+        current_resolver
+            .resolve_hooks(event)?
+            .map(|mut resolved| {
+                // Hooks are resolved unfiltered, but with detection on the
+                // executor must see that nothing changed (for
+                // `run_always_if_no_changes`)
+                if detection_on {
+                    resolved.changed_files = Some(Vec::new());
+                }
+                ConfigGroup {
+                    config_path: resolved.config_path.clone(),
+                    files: Vec::new(),
+                    resolved_hooks: resolved,
+                }
+            })
+            .into_iter()
+            .collect()
+    } else {
+        group_files_by_config(&changed_files, repo_root, event, worktree_context)?
+    };
+
+    if groups.is_empty() {
+        log::info!("no config defines event={event}");
     }
+    for group in &groups {
+        log::info!(
+            "resolved event={event} config={} files={} strategy={:?} hooks={:?}",
+            group.config_path.display(),
+            group.files.len(),
+            group.resolved_hooks.execution_strategy,
+            group.resolved_hooks.hooks.keys().collect::<Vec<_>>()
+        );
+    }
+
+    Ok(groups)
+}
 
-    group_files_by_config(&changed_files, repo_root, event, worktree_context)
+/// Explain, per configuration, which hooks will run for an event and why
+///
+/// Resolves exactly like [`resolve_hooks_hierarchically`] and then applies the
+/// same file filtering the executor uses, without running anything.
+///
+/// # Errors
+///
+/// Returns an error if git operations fail, hook resolution fails, or a hook
+/// has invalid file patterns
+pub fn explain_hooks_hierarchically(
+    event: &str,
+    change_mode: Option<ChangeDetectionMode>,
+    repo_root: &Path,
+    current_dir: &Path,
+    worktree_context: &WorktreeContext,
+) -> Result<Vec<ConfigExplanation>> {
+    let groups =
+        resolve_hooks_hierarchically(event, change_mode, repo_root, current_dir, worktree_context)?;
+
+    groups
+        .into_iter()
+        .map(|group| {
+            let changed_files = group.resolved_hooks.changed_files.as_deref();
+            let hooks = group
+                .resolved_hooks
+                .hooks
+                .iter()
+                .map(|(name, hook)| {
+                    let decision = run_decision(&hook.definition, changed_files, repo_root)?;
+                    Ok(HookExplanation {
+                        hook: name.clone(),
+                        will_run: decision.will_run(),
+                        decision,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok(ConfigExplanation {
+                config_path: group.config_path,
+                files: group.files,
+                hooks,
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -354,6 +699,36 @@ command = "echo src"
         assert_eq!(config, repo_root.join("hooks.toml"));
     }
 
+    #[test]
+    fn test_find_all_config_files() {
+        let temp_dir = create_test_repo();
+        let repo_root = temp_dir.path();
+
+        fs::create_dir_all(repo_root.join("api")).unwrap();
+        fs::create_dir_all(repo_root.join("web/app")).unwrap();
+        fs::create_dir_all(repo_root.join("target")).unwrap();
+        fs::write(repo_root.join(".gitignore"), "target/\n").unwrap();
+        for config in [
+            "hooks.toml",
+            "api/hooks.toml",
+            "api/hooks.yaml",
+            "web/app/hooks.json",
+            "target/hooks.toml",
+        ] {
+            fs::write(repo_root.join(config), "").unwrap();
+        }
+
+        let configs = find_all_config_files(repo_root).unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                repo_root.join("hooks.toml"),
+                repo_root.join("api/hooks.toml"),
+                repo_root.join("web/app/hooks.json"),
+            ]
+        );
+    }
+
     #[test]
     fn test_hierarchical_config_selection() {
         let temp_dir = create_test_repo();
@@ -406,4 +781,112 @@ description = "deep pre-push"
         let config = find_config_for_file(&file, repo_root).unwrap();
         assert_eq!(config, repo_root.join("src/deep/hooks.toml"));
     }
+
+    #[test]
+    fn test_resolve_modified_configs_prunes_and_merges() {
+        let temp_dir = create_test_repo();
+        let repo_root = temp_dir.path();
+
+        // web/ and docs/ only define pre-push, so their files fall back to
+        // the root; other/ is never on a changed file's path
+        for dir in ["web", "docs", "other"] {
+            fs::create_dir_all(repo_root.join(dir)).unwrap();
+        }
+        fs::write(
+            repo_root.join("hooks.toml"),
+            r#"
+[hooks.root-check]
+command = "true"
+
+[groups.pre-commit]
+includes = ["root-check"]
+"#,
+        )
+        .unwrap();
+        for dir in ["web", "docs"] {
+            fs::write(
+                repo_root.join(dir).join("hooks.toml"),
+                r#"
+[hooks.push-check]
+command = "true"
+
+[groups.pre-push]
+includes = ["push-check"]
+"#,
+            )
+            .unwrap();
+        }
+        fs::write(repo_root.join("other/hooks.toml"), "not [valid toml").unwrap();
+
+        let worktree_context = WorktreeContext {
+            is_worktree: false,
+            worktree_name: None,
+            repo_root: repo_root.to_path_buf(),
+            common_dir: repo_root.join(".git"),
+            working_dir: repo_root.to_path_buf(),
+            run_env: indexmap::IndexMap::new(),
+            push_refs: None,
+        };
+        let mode = ChangeDetectionMode::FileList {
+            files: vec![
+                PathBuf::from("web/app.js"),
+                PathBuf::from("docs/guide.md"),
+                PathBuf::from("web/lib/util.js"),
+            ],
+        };
+
+        let groups =
+            resolve_modified_configs("pre-commit", &mode, repo_root, &worktree_context).unwrap();
+
+        // The root config is resolved once, with every file it governs
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].config_path, repo_root.join("hooks.toml"));
+        assert_eq!(
+            groups[0].files,
+            vec![
+                PathBuf::from("web/app.js"),
+                PathBuf::from("docs/guide.md"),
+                PathBuf::from("web/lib/util.js"),
+            ]
+        );
+        assert!(groups[0].resolved_hooks.hooks.contains_key("root-check"));
+
+        // A root placeholder group runs nothing for the files it governs
+        fs::write(
+            repo_root.join("hooks.toml"),
+            r"
+[groups.pre-commit]
+includes = []
+placeholder = true
+",
+        )
+        .unwrap();
+        let groups =
+            resolve_modified_configs("pre-commit", &mode, repo_root, &worktree_context).unwrap();
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_config_locator_caches_directories() {
+        let temp_dir = create_test_repo();
+        let repo_root = temp_dir.path();
+
+        fs::create_dir_all(repo_root.join("pkg/src/deep")).unwrap();
+        fs::write(repo_root.join("pkg/hooks.toml"), "").unwrap();
+
+        let mut locator = ConfigLocator::new(repo_root, "pre-commit");
+        assert_eq!(
+            locator.nearest_config(&repo_root.join("pkg/src/deep")),
+            Some(repo_root.join("pkg/hooks.toml"))
+        );
+        assert_eq!(locator.nearest.len(), 3);
+
+        // Directories without a config above them up to the root have none
+        assert_eq!(locator.nearest_config(repo_root), None);
+        assert_eq!(
+            locator.nearest_config(&repo_root.join("pkg/src")),
+            Some(repo_root.join("pkg/hooks.toml"))
+        );
+        assert_eq!(locator.nearest.len(), 4);
+    }
 }
@@ -0,0 +1,338 @@
+//! Cleanup of running hooks when peter-hook is interrupted
+//!
+//! The executor registers every hook process it spawns and every temporary
+//! file or directory it writes. On SIGINT/SIGTERM (Ctrl-C on Windows) the
+//! handler terminates the registered processes, removes the files and
+//! directories, and exits with 130.
+//!
+//! On Unix each hook runs in its own process group, so terminating it also
+//! reaches anything it started (servers, watchers, `sh -c` children). Hooks
+//! never get the terminal's stdin, so leaving the foreground group is safe.
+
+use anyhow::{Context, Result};
+use std::{
+    collections::HashSet,
+    io::{self, BufRead, Read, Seek},
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    sync::{
+        LazyLock, Mutex, MutexGuard,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+/// Exit code used when a run is interrupted (128 + SIGINT)
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Hook processes and temporary files that are currently live
+#[derive(Default)]
+struct Registry {
+    children: HashSet<u32>,
+    temp_files: HashSet<PathBuf>,
+    temp_dirs: HashSet<PathBuf>,
+}
+
+static REGISTRY: LazyLock<Mutex<Registry>> = LazyLock::new(Mutex::default);
+
+/// Set once an interrupt arrives so no further hooks are started
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+fn registry() -> MutexGuard<'static, Registry> {
+    // A panic while holding the lock can't leave the sets inconsistent
+    REGISTRY
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Install the SIGINT/SIGTERM handler that cleans up running hooks
+///
+/// # Errors
+///
+/// Returns an error if a signal handler is already installed
+pub fn install_handler() -> Result<()> {
+    ctrlc::set_handler(|| {
+        cleanup();
+        eprintln!("\nInterrupted, stopped running hooks");
+        std::process::exit(INTERRUPTED_EXIT_CODE);
+    })
+    .context("Failed to install interrupt handler")
+}
+
+/// Terminate registered hook processes and remove registered temporary files
+fn cleanup() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    let mut registry = registry();
+    for pid in registry.children.drain() {
+        log::warn!("interrupt terminating pid={pid}");
+        terminate(pid);
+    }
+    for path in registry.temp_files.drain() {
+        let _ = std::fs::remove_file(path);
+    }
+    for path in registry.temp_dirs.drain() {
+        let _ = std::fs::remove_dir_all(path);
+    }
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) {
+    use nix::{sys::signal, unistd::Pid};
+
+    // The hook leads its own process group, whose id is its pid
+    if let Ok(pid) = i32::try_from(pid) {
+        let _ = signal::killpg(Pid::from_raw(pid), signal::Signal::SIGTERM);
+    }
+}
+
+#[cfg(not(unix))]
+fn terminate(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/T", "/F", "/PID", &pid.to_string()])
+        .output();
+}
+
+/// Registration of a live hook process; unregistered when dropped
+struct ChildGuard(u32);
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        registry().children.remove(&self.0);
+    }
+}
+
+/// Registration of a temporary file for removal on interrupt; unregistered
+/// (not deleted) when dropped
+pub(crate) struct TempFileGuard(PathBuf);
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        registry().temp_files.remove(&self.0);
+    }
+}
+
+/// Register a temporary file to be removed if the run is interrupted
+pub(crate) fn track_temp_file(path: &Path) -> TempFileGuard {
+    registry().temp_files.insert(path.to_path_buf());
+    TempFileGuard(path.to_path_buf())
+}
+
+/// Registration of a temporary directory for removal on interrupt;
+/// unregistered (not deleted) when dropped
+pub(crate) struct TempDirGuard(PathBuf);
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        registry().temp_dirs.remove(&self.0);
+    }
+}
+
+/// Register a temporary directory to be removed, with everything in it, if
+/// the run is interrupted
+pub(crate) fn track_temp_dir(path: &Path) -> TempDirGuard {
+    registry().temp_dirs.insert(path.to_path_buf());
+    TempDirGuard(path.to_path_buf())
+}
+
+/// Run `command` to completion and collect its output, like
+/// [`Command::output`], keeping the child registered while it runs
+pub(crate) fn output(command: &mut Command) -> io::Result<Output> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let (child, _tracked) = spawn(command)?;
+    child.wait_with_output()
+}
+
+/// Run `command` like [`output`], with stderr merged into stdout (`2>&1`):
+/// the returned `stdout` holds both streams in the order they were written
+/// and `stderr` is empty
+///
+/// Both streams share one file description, so the kernel orders the
+/// writes; reading two pipes afterwards couldn't recover that order.
+pub(crate) fn combined_output(command: &mut Command) -> io::Result<Output> {
+    let mut capture = tempfile::tempfile()?;
+    command
+        .stdout(capture.try_clone()?)
+        .stderr(capture.try_clone()?);
+    let (mut child, _tracked) = spawn(command)?;
+    let status = child.wait()?;
+
+    let mut stdout = Vec::new();
+    capture.rewind()?;
+    capture.read_to_end(&mut stdout)?;
+    Ok(Output {
+        status,
+        stdout,
+        stderr: Vec::new(),
+    })
+}
+
+/// One of a hook's output streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutputStream {
+    /// Standard output
+    Stdout,
+    /// Standard error
+    Stderr,
+}
+
+/// Run `command` like [`output`], passing each line of its stdout and
+/// stderr to `on_line` as soon as it is written
+///
+/// Both pipes are read line by line while the command runs. With
+/// `combined`, lines from both are collected into `stdout` in the order they
+/// arrived, and `stderr` is empty.
+pub(crate) fn streamed_output(
+    command: &mut Command,
+    combined: bool,
+    on_line: impl Fn(OutputStream, &[u8]) + Sync,
+) -> io::Result<Output> {
+    command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let (mut child, _tracked) = spawn(command)?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| io::Error::other("stdout was not captured"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| io::Error::other("stderr was not captured"))?;
+
+    let captured = Mutex::new((Vec::new(), Vec::new()));
+    let read_lines = |pipe: &mut dyn Read, stream: OutputStream| -> io::Result<()> {
+        let mut reader = io::BufReader::new(pipe);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            on_line(stream, &line);
+            {
+                let mut captured = captured
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                if combined || stream == OutputStream::Stdout {
+                    captured.0.extend_from_slice(&line);
+                } else {
+                    captured.1.extend_from_slice(&line);
+                }
+            }
+            line.clear();
+        }
+        Ok(())
+    };
+    let read_lines = &read_lines;
+    std::thread::scope(|scope| {
+        let readers = [
+            scope.spawn(move || read_lines(&mut { stdout }, OutputStream::Stdout)),
+            scope.spawn(move || read_lines(&mut { stderr }, OutputStream::Stderr)),
+        ];
+        readers.into_iter().try_for_each(|reader| {
+            reader
+                .join()
+                .unwrap_or_else(|_| Err(io::Error::other("output reader panicked")))
+        })
+    })?;
+    let status = child.wait()?;
+
+    let (stdout, stderr) = captured
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+/// Spawn `command` in its own process group without stdin, registered until
+/// the returned guard is dropped
+fn spawn(command: &mut Command) -> io::Result<(Child, ChildGuard)> {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        return Err(io::Error::new(
+            io::ErrorKind::Interrupted,
+            "run was interrupted",
+        ));
+    }
+
+    command.stdin(Stdio::null());
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(command, 0);
+
+    let child = command.spawn()?;
+    registry().children.insert(child.id());
+    let tracked = ChildGuard(child.id());
+    Ok((child, tracked))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_tracks_child_while_running() {
+        let output = output(Command::new("sh").args(["-c", "echo $$"])).unwrap();
+        assert!(output.status.success());
+        let pid: u32 = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .unwrap();
+        assert!(!registry().children.contains(&pid));
+    }
+
+    #[test]
+    fn test_combined_output_keeps_write_order() {
+        let output = combined_output(
+            Command::new("sh").args(["-c", "echo out1; echo err1 >&2; echo out2; echo err2 >&2"]),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "out1\nerr1\nout2\nerr2\n"
+        );
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_streamed_output_passes_lines_as_they_arrive() {
+        let lines = Mutex::new(Vec::new());
+        let script = "echo out1; sleep 0.2; echo err1 >&2; sleep 0.2; printf out2";
+        let output = streamed_output(
+            Command::new("sh").args(["-c", script]),
+            false,
+            |stream, line| {
+                lines
+                    .lock()
+                    .unwrap()
+                    .push((stream, String::from_utf8_lossy(line).to_string()));
+            },
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out1\nout2");
+        assert_eq!(String::from_utf8_lossy(&output.stderr), "err1\n");
+        assert_eq!(
+            lines.into_inner().unwrap(),
+            [
+                (OutputStream::Stdout, "out1\n".to_string()),
+                (OutputStream::Stderr, "err1\n".to_string()),
+                (OutputStream::Stdout, "out2".to_string()),
+            ]
+        );
+
+        let output =
+            streamed_output(Command::new("sh").args(["-c", script]), true, |_, _| {}).unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "out1\nerr1\nout2");
+        assert!(output.stderr.is_empty());
+    }
+
+    #[test]
+    fn test_temp_file_guard_unregisters_on_drop() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("changed.lst");
+        std::fs::write(&path, "a.rs").unwrap();
+
+        let guard = track_temp_file(&path);
+        assert!(registry().temp_files.contains(&path));
+        drop(guard);
+        assert!(!registry().temp_files.contains(&path));
+        // Unregistering leaves the file to its owner
+        assert!(path.exists());
+    }
+}
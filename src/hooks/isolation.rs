@@ -0,0 +1,239 @@
+//! Running a hook in its own copy of the repository
+//!
+//! A group with `isolation = "worktree"` runs each of its hooks in a
+//! temporary git worktree of `HEAD` (`git worktree add --detach`), with the
+//! changed files copied in from the working directory. Hooks running side by
+//! side then never see each other's modifications, and nothing a hook writes
+//! reaches the working directory. The worktree is removed once the hook
+//! finishes.
+
+use super::{
+    interrupt,
+    resolver::{ResolvedHook, WorktreeContext},
+};
+use crate::git::{ChangeDetectionMode, GitChangeDetector};
+use anyhow::{Context, Result, bail};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+use tempfile::TempDir;
+
+/// A temporary worktree one hook runs in; removed when dropped
+pub struct IsolatedWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    _dir: TempDir,
+    _tracked: interrupt::TempDirGuard,
+}
+
+impl IsolatedWorktree {
+    /// Check out `HEAD` into a new temporary worktree of the repository at
+    /// `worktree_context.repo_root` and copy the changed files into it
+    ///
+    /// Without a list of changed files (`--all-files`), the working
+    /// directory's uncommitted changes are copied instead. Changed files
+    /// that no longer exist are removed from the worktree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the worktree can't be created, e.g. before the
+    /// first commit, or the changed files can't be copied
+    pub fn create(
+        hook_name: &str,
+        worktree_context: &WorktreeContext,
+        changed_files: Option<&[PathBuf]>,
+    ) -> Result<Self> {
+        let repo_root = &worktree_context.repo_root;
+        let dir = tempfile::Builder::new()
+            .prefix("peter-hook-isolated-")
+            .tempdir()
+            .context("Failed to create a directory for an isolated worktree")?;
+        let tracked = interrupt::track_temp_dir(dir.path());
+        // Keep the repository's directory name for hooks that look at it
+        let path = dir
+            .path()
+            .join(repo_root.file_name().unwrap_or_else(|| "repo".as_ref()));
+
+        let output = Command::new("git")
+            .arg("worktree")
+            .arg("add")
+            .args(["--detach", "--quiet"])
+            .arg(&path)
+            .arg("HEAD")
+            .current_dir(repo_root)
+            .output()
+            .context("Failed to run git worktree add")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to create an isolated worktree for hook '{hook_name}' (isolation = \
+                 \"worktree\" checks out HEAD, so the repository needs a commit): {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        log::info!(
+            "isolated worktree created hook={hook_name} path={}",
+            path.display()
+        );
+
+        let worktree = Self {
+            repo_root: repo_root.clone(),
+            path,
+            _dir: dir,
+            _tracked: tracked,
+        };
+
+        let overlay = match changed_files {
+            Some(files) => files.to_vec(),
+            None => GitChangeDetector::new(repo_root)?
+                .get_changed_files(&ChangeDetectionMode::WorkingDirectory)?,
+        };
+        for file in &overlay {
+            worktree.copy_in(file).with_context(|| {
+                format!(
+                    "Failed to copy {} into the isolated worktree",
+                    file.display()
+                )
+            })?;
+        }
+
+        Ok(worktree)
+    }
+
+    /// `hook` with its working directory and config file inside the worktree
+    #[must_use]
+    pub fn hook(&self, hook: &ResolvedHook) -> ResolvedHook {
+        ResolvedHook {
+            definition: hook.definition.clone(),
+            working_directory: self.map(&hook.working_directory),
+            source_file: self.map(&hook.source_file),
+        }
+    }
+
+    /// `worktree_context` with the repository root (and the current
+    /// directory, when inside it) in the worktree
+    #[must_use]
+    pub fn context(&self, worktree_context: &WorktreeContext) -> WorktreeContext {
+        WorktreeContext {
+            repo_root: self.path.clone(),
+            working_dir: self.map(&worktree_context.working_dir),
+            ..worktree_context.clone()
+        }
+    }
+
+    /// The path in the worktree for a path in the repository; paths outside
+    /// the repository (e.g. imported configs) stay as they are
+    fn map(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.repo_root)
+            .map_or_else(|_| path.to_path_buf(), |relative| self.path.join(relative))
+    }
+
+    /// Make `file` (relative to the repository root) in the worktree match
+    /// the working directory
+    fn copy_in(&self, file: &Path) -> Result<()> {
+        let source = self.repo_root.join(file);
+        let target = self.path.join(file);
+        if source.is_file() {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &target)?;
+        } else if !source.exists() && target.is_file() {
+            fs::remove_file(&target)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for IsolatedWorktree {
+    fn drop(&mut self) {
+        let removed = Command::new("git")
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.path)
+            .current_dir(&self.repo_root)
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if !removed {
+            // Let the directory go with the temp dir and drop git's record
+            let _ = fs::remove_dir_all(&self.path);
+            let _ = Command::new("git")
+                .args(["worktree", "prune"])
+                .current_dir(&self.repo_root)
+                .output();
+        }
+        log::info!("isolated worktree removed path={}", self.path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+
+    fn git(repo: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn test_isolated_worktree_copies_changes_and_cleans_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_root = temp_dir.path().join("repo");
+        fs::create_dir(&repo_root).unwrap();
+        git(&repo_root, &["init", "-q"]);
+        fs::create_dir(repo_root.join("src")).unwrap();
+        fs::write(repo_root.join("src/lib.rs"), "committed").unwrap();
+        fs::write(repo_root.join("gone.txt"), "committed").unwrap();
+        git(&repo_root, &["add", "."]);
+        git(&repo_root, &["commit", "-qm", "init"]);
+
+        fs::write(repo_root.join("src/lib.rs"), "changed").unwrap();
+        fs::write(repo_root.join("src/new.rs"), "new").unwrap();
+        fs::remove_file(repo_root.join("gone.txt")).unwrap();
+
+        let context = WorktreeContext {
+            is_worktree: false,
+            worktree_name: None,
+            repo_root: repo_root.clone(),
+            common_dir: repo_root.join(".git"),
+            working_dir: repo_root.join("src"),
+            run_env: IndexMap::new(),
+            push_refs: None,
+        };
+        let changed = [
+            PathBuf::from("src/lib.rs"),
+            PathBuf::from("src/new.rs"),
+            PathBuf::from("gone.txt"),
+        ];
+
+        let worktree = IsolatedWorktree::create("lint", &context, Some(&changed)).unwrap();
+        let path = worktree.path.clone();
+        assert_eq!(path.file_name(), repo_root.file_name());
+        assert_eq!(
+            fs::read_to_string(path.join("src/lib.rs")).unwrap(),
+            "changed"
+        );
+        assert_eq!(fs::read_to_string(path.join("src/new.rs")).unwrap(), "new");
+        assert!(!path.join("gone.txt").exists());
+
+        let isolated = worktree.context(&context);
+        assert_eq!(isolated.repo_root, path);
+        assert_eq!(isolated.working_dir, path.join("src"));
+
+        drop(worktree);
+        assert!(!path.exists());
+        let output = Command::new("git")
+            .args(["worktree", "list", "--porcelain"])
+            .current_dir(&repo_root)
+            .output()
+            .unwrap();
+        let listed = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(listed.matches("worktree ").count(), 1, "{listed}");
+    }
+}
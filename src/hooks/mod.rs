@@ -1,9 +1,18 @@
+pub mod container;
 pub mod dependencies;
 pub mod executor;
+pub mod graph;
 pub mod hierarchical;
+pub mod interrupt;
+pub mod isolation;
+pub mod repeat;
 pub mod resolver;
+pub mod watch;
 
 pub use dependencies::*;
 pub use executor::*;
+pub use graph::*;
 pub use hierarchical::*;
+pub use repeat::*;
 pub use resolver::*;
+pub use watch::*;
@@ -0,0 +1,228 @@
+//! Repeated execution of the same hooks for flakiness detection
+//!
+//! `run --repeat N` and `lint --repeat N` execute the resolved hooks N times
+//! and tally how often each hook passed or failed, so intermittent failures
+//! show up as a percentage instead of a one-off red run.
+
+use super::executor::ExecutionResults;
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::time::{Duration, Instant};
+
+/// Pass/fail counts of one hook across repeated runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HookTally {
+    /// Runs in which the hook succeeded
+    pub passed: u32,
+    /// Runs in which the hook failed
+    pub failed: u32,
+    /// Runs in which the hook was skipped (`skip_if` or `enabled = false`)
+    pub skipped: u32,
+}
+
+/// Outcome of a single run
+#[derive(Debug, Clone)]
+pub struct RepeatRun {
+    /// Whether every hook succeeded
+    pub success: bool,
+    /// Wall-clock time of the run
+    pub duration: Duration,
+    /// Hooks that failed in this run
+    pub failed_hooks: Vec<String>,
+}
+
+/// Results of executing the same hooks several times
+#[derive(Debug, Clone)]
+pub struct RepeatReport {
+    /// Number of runs requested
+    pub requested: u32,
+    /// Runs actually executed, in order
+    pub runs: Vec<RepeatRun>,
+    /// Tally per hook, in the order hooks first completed
+    pub hooks: IndexMap<String, HookTally>,
+    /// Results of the last run, for `--output-dir` logs
+    pub last: Option<ExecutionResults>,
+}
+
+impl RepeatReport {
+    /// Call `execute` up to `times` times, printing one line per run
+    ///
+    /// With `until_fail`, stops after the first run with a failing hook.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from `execute`
+    pub fn run<F>(times: u32, until_fail: bool, mut execute: F) -> Result<Self>
+    where
+        F: FnMut() -> Result<ExecutionResults>,
+    {
+        let mut report = Self {
+            requested: times,
+            runs: Vec::new(),
+            hooks: IndexMap::new(),
+            last: None,
+        };
+
+        for index in 1..=times {
+            let start = Instant::now();
+            let results = execute()?;
+            let duration = start.elapsed();
+
+            let mut failed_hooks = Vec::new();
+            for (name, result) in &results.results {
+                let tally = report.hooks.entry(name.clone()).or_default();
+                if result.skipped.is_some() {
+                    tally.skipped += 1;
+                } else if result.success {
+                    tally.passed += 1;
+                } else {
+                    tally.failed += 1;
+                    failed_hooks.push(name.clone());
+                }
+            }
+
+            let status = if results.success { "PASS" } else { "FAIL" };
+            if failed_hooks.is_empty() {
+                println!(
+                    "Run {index}/{times}: {status} ({:.2}s)",
+                    duration.as_secs_f64()
+                );
+            } else {
+                println!(
+                    "Run {index}/{times}: {status} ({:.2}s) - failed: {}",
+                    duration.as_secs_f64(),
+                    failed_hooks.join(", ")
+                );
+            }
+
+            let success = results.success;
+            report.runs.push(RepeatRun {
+                success,
+                duration,
+                failed_hooks,
+            });
+            report.last = Some(results);
+
+            if until_fail && !success {
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Whether every executed run succeeded
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.runs.iter().all(|run| run.success)
+    }
+
+    /// Number of executed runs that failed
+    #[must_use]
+    pub fn failed_runs(&self) -> u32 {
+        u32::try_from(self.runs.iter().filter(|run| !run.success).count()).unwrap_or(u32::MAX)
+    }
+
+    /// Percentage of executed runs that failed
+    #[must_use]
+    pub fn flakiness(&self) -> f64 {
+        let executed = u32::try_from(self.runs.len()).unwrap_or(u32::MAX);
+        if executed == 0 {
+            return 0.0;
+        }
+        f64::from(self.failed_runs()) * 100.0 / f64::from(executed)
+    }
+
+    /// Print the per-hook tally and overall flakiness
+    pub fn print_summary(&self) {
+        println!();
+        println!("=== Repeat Summary ===");
+        for (name, tally) in &self.hooks {
+            if tally.skipped == 0 {
+                println!("{name}: {} passed, {} failed", tally.passed, tally.failed);
+            } else {
+                println!(
+                    "{name}: {} passed, {} failed, {} skipped",
+                    tally.passed, tally.failed, tally.skipped
+                );
+            }
+        }
+
+        let total: Duration = self.runs.iter().map(|run| run.duration).sum();
+        println!(
+            "Runs: {} of {}, {} failed ({:.1}% flaky) in {:.2}s",
+            self.runs.len(),
+            self.requested,
+            self.failed_runs(),
+            self.flakiness(),
+            total.as_secs_f64()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::ExecutionResult;
+
+    fn results(outcomes: &[(&str, bool)]) -> ExecutionResults {
+        let results: IndexMap<String, ExecutionResult> = outcomes
+            .iter()
+            .map(|&(name, success)| {
+                (
+                    name.to_string(),
+                    ExecutionResult::new(i32::from(!success), String::new(), String::new()),
+                )
+            })
+            .collect();
+        let success = results.values().all(|r| r.success);
+        ExecutionResults { results, success }
+    }
+
+    #[test]
+    fn test_repeat_tallies_every_run() {
+        let mut call = 0;
+        let report = RepeatReport::run(4, false, || {
+            call += 1;
+            Ok(results(&[("stable", true), ("flaky", call % 2 == 1)]))
+        })
+        .unwrap();
+
+        assert_eq!(report.runs.len(), 4);
+        assert!(!report.success());
+        assert_eq!(report.failed_runs(), 2);
+        assert!((report.flakiness() - 50.0).abs() < f64::EPSILON);
+        assert_eq!(
+            report.hooks["stable"],
+            HookTally {
+                passed: 4,
+                failed: 0,
+                skipped: 0
+            }
+        );
+        assert_eq!(
+            report.hooks["flaky"],
+            HookTally {
+                passed: 2,
+                failed: 2,
+                skipped: 0
+            }
+        );
+        assert_eq!(report.runs[1].failed_hooks, vec!["flaky".to_string()]);
+    }
+
+    #[test]
+    fn test_repeat_until_fail_stops_at_first_failure() {
+        let mut call = 0;
+        let report = RepeatReport::run(10, true, || {
+            call += 1;
+            Ok(results(&[("flaky", call < 3)]))
+        })
+        .unwrap();
+
+        assert_eq!(report.runs.len(), 3);
+        assert_eq!(report.requested, 10);
+        assert_eq!(report.failed_runs(), 1);
+        assert!(!report.last.unwrap().success);
+    }
+}
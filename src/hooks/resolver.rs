@@ -1,13 +1,18 @@
 //! Hierarchical hook resolution system
 
 use crate::{
-    config::{ExecutionStrategy, HookConfig, HookDefinition, HookGroup},
+    config::{
+        ExecutionStrategy, ExecutionType, GlobalConfig, HookCommand, HookConfig, HookDefinition,
+        HookGroup, Isolation, config_file_in,
+    },
     git::{
         ChangeDetectionMode, FilePatternMatcher, GitChangeDetector, GitRepository,
-        LintFileDiscovery,
+        LintFileDiscovery, PushRefUpdate,
     },
 };
 use anyhow::{Context, Result};
+use indexmap::IndexMap;
+use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
@@ -24,14 +29,140 @@ pub struct HookResolver {
 pub struct ResolvedHooks {
     /// The configuration file that was used
     pub config_path: PathBuf,
-    /// Individual hooks to execute
-    pub hooks: HashMap<String, ResolvedHook>,
+    /// Individual hooks to execute, in resolution order (group `includes`
+    /// order, with nested groups expanded in place)
+    pub hooks: IndexMap<String, ResolvedHook>,
     /// Execution strategy for this group of hooks
     pub execution_strategy: ExecutionStrategy,
+    /// Stop starting hooks once this many have failed (`--bail-after`)
+    pub bail_after: Option<usize>,
+    /// The group's `isolation`, if its hooks run in separate copies of the
+    /// repository
+    pub isolation: Option<Isolation>,
     /// Changed files (if file filtering is enabled)
     pub changed_files: Option<Vec<PathBuf>>,
     /// Worktree context information
     pub worktree_context: WorktreeContext,
+    /// Group `before` commands, outermost group first
+    pub before: Vec<LifecycleCommand>,
+    /// Group `after` commands, innermost group first
+    pub after: Vec<LifecycleCommand>,
+}
+
+/// Hook selection by `tags`, applied after resolution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    /// Keep hooks carrying at least one of these tags (`--tag`)
+    pub any: Vec<String>,
+    /// Keep only hooks carrying all of these tags (`--require-tag`)
+    pub all: Vec<String>,
+}
+
+impl TagFilter {
+    /// Whether the filter selects every hook
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.any.is_empty() && self.all.is_empty()
+    }
+
+    /// Whether a hook definition passes the filter
+    #[must_use]
+    pub fn matches(&self, hook_def: &HookDefinition) -> bool {
+        let tags = hook_def.tags.as_deref().unwrap_or_default();
+        (self.any.is_empty() || self.any.iter().any(|tag| tags.contains(tag)))
+            && self.all.iter().all(|tag| tags.contains(tag))
+    }
+}
+
+impl ResolvedHooks {
+    /// Drop hooks that don't pass the tag filter
+    pub fn retain_tagged(&mut self, filter: &TagFilter) {
+        if !filter.is_empty() {
+            self.hooks
+                .retain(|_, hook| filter.matches(&hook.definition));
+        }
+    }
+
+    /// Drop `only_on_branch_change` hooks, for a `post-checkout` run that
+    /// checked out files rather than switching branches
+    pub fn skip_branch_change_hooks(&mut self) {
+        self.hooks
+            .retain(|_, hook| !hook.definition.only_on_branch_change);
+    }
+
+    /// Swap every hook's `command` for its `fix_command`, for `lint --fix`
+    ///
+    /// Fix commands rewrite files, so the hooks are treated as modifying the
+    /// repository and never run in parallel with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the hooks that have no `fix_command`; nothing
+    /// is changed then
+    pub fn use_fix_commands(&mut self) -> Result<()> {
+        let missing: Vec<&str> = self
+            .hooks
+            .iter()
+            .filter(|(_, hook)| hook.definition.fix_command.is_none())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "--fix needs a fix_command, but {} none: {}",
+                if missing.len() == 1 {
+                    "this hook has"
+                } else {
+                    "these hooks have"
+                },
+                missing.join(", ")
+            );
+        }
+
+        for hook in self.hooks.values_mut() {
+            if let Some(fix_command) = hook.definition.fix_command.take() {
+                hook.definition.command = fix_command;
+                hook.definition.modifies_repository = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add run-wide `--fail-on` / `--allow` patterns to every hook, alongside
+    /// (not instead of) the hook's own `fail_on` / `fail_on_allow`
+    pub fn add_output_patterns(&mut self, fail_on: Option<&str>, allow: Option<&str>) {
+        fn either(own: &mut Option<String>, extra: Option<&str>) {
+            if let Some(extra) = extra {
+                *own = Some(
+                    own.as_deref()
+                        .map_or_else(|| extra.to_string(), |own| format!("(?:{own})|(?:{extra})")),
+                );
+            }
+        }
+
+        for hook in self.hooks.values_mut() {
+            either(&mut hook.definition.fail_on, fail_on);
+            either(&mut hook.definition.fail_on_allow, allow);
+        }
+    }
+}
+
+/// Whether the arguments git passes to `post-checkout` (previous HEAD, new
+/// HEAD, branch flag) describe a branch switch rather than a file checkout
+///
+/// Without a branch flag, e.g. a manual `peter-hook run post-checkout`, the
+/// checkout counts as a branch switch so no hook is skipped.
+#[must_use]
+pub fn is_branch_checkout(git_args: &[String]) -> bool {
+    git_args.get(2).is_none_or(|flag| flag != "0")
+}
+
+/// A group-level `before` or `after` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleCommand {
+    /// Name of the group that declared the command
+    pub group: String,
+    /// Command to run
+    pub command: HookCommand,
 }
 
 /// A resolved hook ready for execution
@@ -45,6 +176,257 @@ pub struct ResolvedHook {
     pub source_file: PathBuf,
 }
 
+/// Why a hook will or will not run for a set of changed files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum RunDecision {
+    /// `enabled = false`, e.g. from `hooks.local.toml`
+    Disabled,
+    /// `run_always = true` bypasses file filtering
+    RunAlways,
+    /// `execution_type = "other"` hooks run regardless of changed files
+    OtherExecutionType,
+    /// Changed files matched the hook's `files` patterns (or it has none)
+    MatchedFiles {
+        /// Files the hook will receive
+        files: Vec<PathBuf>,
+    },
+    /// No changed files were detected, or file detection is off (`--all-files`)
+    NoChangedFiles,
+    /// None of the changed files matched the hook's `files` patterns
+    NoMatchingFiles {
+        /// The hook's `files` patterns
+        patterns: Vec<String>,
+        /// Changed files that were checked against the patterns
+        considered: Vec<PathBuf>,
+    },
+    /// Every matching file was deleted, and the hook doesn't set
+    /// `include_deleted`
+    OnlyDeletedFiles {
+        /// Matching files that no longer exist
+        files: Vec<PathBuf>,
+    },
+    /// `run_always_if_no_changes = true` runs the hook without files, as no
+    /// changed file is left for it
+    RunIfNoChanges,
+}
+
+impl RunDecision {
+    /// Whether the hook will run
+    #[must_use]
+    pub const fn will_run(&self) -> bool {
+        matches!(
+            self,
+            Self::RunAlways
+                | Self::OtherExecutionType
+                | Self::MatchedFiles { .. }
+                | Self::RunIfNoChanges
+        )
+    }
+}
+
+/// Decide whether a hook runs for the given changed files, and why
+///
+/// Mirrors the execution-time rules: disabled hooks never run, `run_always`
+/// and `other` hooks always run, everything else needs at least one existing changed file (or any,
+/// with `include_deleted`) matching `files`. Relative paths are resolved
+/// against `repo_root`.
+///
+/// With `run_always_if_no_changes`, a hook that would be skipped for lack of
+/// files runs without them instead, as long as file detection is on
+/// (`changed_files` is `Some`, even if empty).
+///
+/// # Errors
+///
+/// Returns an error if the hook's glob patterns are invalid
+pub fn run_decision(
+    hook_def: &HookDefinition,
+    changed_files: Option<&[PathBuf]>,
+    repo_root: &Path,
+) -> Result<RunDecision> {
+    if !hook_def.enabled {
+        return Ok(RunDecision::Disabled);
+    }
+    if hook_def.run_always {
+        return Ok(RunDecision::RunAlways);
+    }
+    if hook_def.execution_type == ExecutionType::Other {
+        return Ok(RunDecision::OtherExecutionType);
+    }
+
+    let decision = file_decision(hook_def, changed_files, repo_root)?;
+    if hook_def.run_always_if_no_changes && changed_files.is_some() && !decision.will_run() {
+        return Ok(RunDecision::RunIfNoChanges);
+    }
+    Ok(decision)
+}
+
+/// The part of [`run_decision`] that depends on the changed files
+fn file_decision(
+    hook_def: &HookDefinition,
+    changed_files: Option<&[PathBuf]>,
+    repo_root: &Path,
+) -> Result<RunDecision> {
+    let files = match changed_files {
+        Some(files) if !files.is_empty() => files,
+        _ => return Ok(RunDecision::NoChangedFiles),
+    };
+
+    let matched = match &hook_def.files {
+        Some(patterns) => {
+            let matched = matching_files(patterns, files, repo_root)?;
+            if matched.is_empty() {
+                return Ok(RunDecision::NoMatchingFiles {
+                    patterns: patterns.clone(),
+                    considered: files.to_vec(),
+                });
+            }
+            matched
+        }
+        None => files.to_vec(),
+    };
+
+    let existing = drop_deleted_files(hook_def, repo_root, matched.clone());
+    if existing.is_empty() {
+        Ok(RunDecision::OnlyDeletedFiles { files: matched })
+    } else {
+        Ok(RunDecision::MatchedFiles { files: existing })
+    }
+}
+
+/// Drop changed files that no longer exist, so `per-file` and `in-place`
+/// hooks are never handed missing paths
+///
+/// `other` hooks and hooks with `include_deleted = true` keep every file and
+/// can handle deletions themselves. Relative paths are resolved against
+/// `repo_root`.
+#[must_use]
+pub fn drop_deleted_files(
+    hook_def: &HookDefinition,
+    repo_root: &Path,
+    files: Vec<PathBuf>,
+) -> Vec<PathBuf> {
+    if hook_def.include_deleted || hook_def.execution_type == ExecutionType::Other {
+        return files;
+    }
+
+    files
+        .into_iter()
+        .filter(|file| repo_root.join(file).symlink_metadata().is_ok())
+        .collect()
+}
+
+/// Expand group `includes` entries into hook and group names
+///
+/// Literal entries are kept as-is. Entries containing `*`, `?`, `[...]` or
+/// `{a,b}` are patterns matched against the config's hook names (not group
+/// names), and expand to every match in declaration order. A pattern that
+/// matches nothing prints a warning.
+///
+/// # Errors
+///
+/// Returns an error if an entry is not a valid glob pattern
+fn expand_includes(includes: &[String], config: &HookConfig) -> Result<Vec<String>> {
+    let mut expanded = Vec::new();
+
+    for include in includes {
+        if !include.contains(['*', '?', '[', '{']) {
+            expanded.push(include.clone());
+            continue;
+        }
+
+        let patterns = expand_braces(include)
+            .iter()
+            .map(|pattern| glob::Pattern::new(pattern))
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Invalid include pattern: {include}"))?;
+
+        let matched: Vec<&String> = config
+            .hooks
+            .iter()
+            .flat_map(IndexMap::keys)
+            .filter(|name| patterns.iter().any(|pattern| pattern.matches(name)))
+            .collect();
+
+        if matched.is_empty() {
+            eprintln!("⚠️  Include pattern '{include}' does not match any hook");
+        }
+        expanded.extend(matched.into_iter().cloned());
+    }
+
+    Ok(expanded)
+}
+
+/// Expand `{a,b}` alternatives into separate patterns (`lint-{rust,js}` becomes
+/// `lint-rust` and `lint-js`). Several and nested brace sets are supported; a
+/// `{` without a matching `}` is kept literally.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+
+    // Find the matching close brace and the top-level commas inside it
+    let mut depth = 0;
+    let mut commas = Vec::new();
+    let mut close = None;
+    for (index, ch) in pattern[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(open + index);
+                    break;
+                }
+            }
+            ',' if depth == 1 => commas.push(open + index),
+            _ => {}
+        }
+    }
+    let Some(close) = close else {
+        return vec![pattern.to_string()];
+    };
+
+    let prefix = &pattern[..open];
+    let suffix = &pattern[close + 1..];
+    let mut bounds = vec![open];
+    bounds.extend(commas);
+    bounds.push(close);
+
+    bounds
+        .windows(2)
+        .flat_map(|window| {
+            let alternative = &pattern[window[0] + 1..window[1]];
+            expand_braces(&format!("{prefix}{alternative}{suffix}"))
+        })
+        .collect()
+}
+
+/// Execution strategy for groups that don't set one, from the global config
+fn default_execution_strategy() -> ExecutionStrategy {
+    GlobalConfig::load()
+        .unwrap_or_default()
+        .execution
+        .default_strategy
+}
+
+/// Files that match any of the given glob patterns, matched by their path
+/// relative to `repo_root`
+fn matching_files(
+    patterns: &[String],
+    files: &[PathBuf],
+    repo_root: &Path,
+) -> Result<Vec<PathBuf>> {
+    let matcher = FilePatternMatcher::new(patterns)
+        .context("Failed to compile file patterns")?
+        .relative_to(repo_root);
+    Ok(files
+        .iter()
+        .filter(|file| matcher.matches(file))
+        .cloned()
+        .collect())
+}
+
 /// Worktree context information for template expansion and hook resolution
 #[derive(Debug, Clone)]
 pub struct WorktreeContext {
@@ -58,6 +440,309 @@ pub struct WorktreeContext {
     pub common_dir: PathBuf,
     /// Path to the working directory
     pub working_dir: PathBuf,
+    /// Run-wide environment variables from `run --env`, applied under each
+    /// hook's own `env` and available as `{env:KEY}` templates
+    pub run_env: IndexMap<String, String>,
+    /// Refs git is pushing, for `pre-push` hooks with `push_refs`; `None`
+    /// when unknown (e.g. a manual run), which runs those hooks
+    pub push_refs: Option<Vec<PushRefUpdate>>,
+}
+
+/// Resolve a `workdir` value to a directory path
+///
+/// A leading `//` makes the path relative to the repository root (e.g.
+/// `//backend`), absolute paths are used as-is, and any other relative path
+/// is relative to the directory containing the config file.
+#[must_use]
+pub fn resolve_workdir(workdir: &str, config_dir: &Path, repo_root: &Path) -> PathBuf {
+    if let Some(root_relative) = workdir.strip_prefix("//") {
+        return repo_root.join(root_relative);
+    }
+
+    let path = Path::new(workdir);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        config_dir.join(path)
+    }
+}
+
+/// Resolve a hook to the hooks it runs as: itself, or with `workdir_glob` one
+/// copy per matching directory
+///
+/// Copies are named `<hook>:<dir>`, with `<dir>` relative to the config
+/// directory. Each runs in its directory and has its `files` patterns (`*`
+/// when the hook has none) limited to files under it. A glob that matches
+/// nothing prints a warning.
+///
+/// # Errors
+///
+/// Returns an error if `workdir_glob` is not a valid glob pattern
+fn resolve_instances(
+    name: &str,
+    hook_def: &HookDefinition,
+    config_path: &Path,
+    repo_root: &Path,
+) -> Result<Vec<(String, ResolvedHook)>> {
+    let config_dir = config_path
+        .parent()
+        .context("Config file has no parent directory")?;
+    let Some(pattern) = &hook_def.workdir_glob else {
+        let resolved = ResolvedHook {
+            definition: hook_def.clone(),
+            working_directory: HookResolver::resolve_working_directory(
+                hook_def, config_dir, repo_root,
+            ),
+            source_file: config_path.to_path_buf(),
+        };
+        return Ok(vec![(name.to_string(), resolved)]);
+    };
+
+    let dirs = workdir_glob_matches(pattern, config_dir, repo_root)?;
+    if dirs.is_empty() {
+        eprintln!("⚠️  workdir_glob '{pattern}' of hook '{name}' does not match any directory");
+    }
+    Ok(dirs
+        .into_iter()
+        .map(|dir| {
+            let label = relative_to(&dir, config_dir).map_or_else(
+                || dir.display().to_string(),
+                |relative| {
+                    if relative.as_os_str().is_empty() {
+                        ".".to_string()
+                    } else {
+                        relative.display().to_string()
+                    }
+                },
+            );
+            let mut definition = hook_def.clone();
+            definition.files = Some(scope_patterns(definition.files.as_deref(), &dir, repo_root));
+            let resolved = ResolvedHook {
+                definition,
+                working_directory: dir,
+                source_file: config_path.to_path_buf(),
+            };
+            (format!("{name}:{label}"), resolved)
+        })
+        .collect())
+}
+
+/// Directories matched by a `workdir_glob` pattern, sorted
+///
+/// The pattern is resolved like `workdir` (`//` for the repository root,
+/// otherwise relative to the config directory). A matched file stands for its
+/// directory, and wildcards don't match hidden entries such as `.git`.
+///
+/// # Errors
+///
+/// Returns an error if the pattern is not a valid glob pattern
+fn workdir_glob_matches(
+    pattern: &str,
+    config_dir: &Path,
+    repo_root: &Path,
+) -> Result<Vec<PathBuf>> {
+    let full_pattern = if Path::new(pattern).is_absolute() && !pattern.starts_with("//") {
+        pattern.to_string()
+    } else {
+        let (base, relative) = pattern
+            .strip_prefix("//")
+            .map_or((config_dir, pattern), |relative| (repo_root, relative));
+        format!(
+            "{}/{relative}",
+            glob::Pattern::escape(&base.to_string_lossy())
+        )
+    };
+    let options = glob::MatchOptions {
+        require_literal_leading_dot: true,
+        ..glob::MatchOptions::new()
+    };
+
+    let mut dirs = Vec::new();
+    for path in glob::glob_with(&full_pattern, options)
+        .with_context(|| format!("Invalid workdir_glob pattern: {pattern}"))?
+        .flatten()
+    {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) => parent.to_path_buf(),
+                None => continue,
+            }
+        };
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Limit `files` patterns to files under `dir`, with no patterns meaning
+/// every file there
+///
+/// Changed files are relative to the repository root, so each pattern gets
+/// `dir`'s path from the root as a prefix (`*` also matches `/`).
+fn scope_patterns(patterns: Option<&[String]>, dir: &Path, repo_root: &Path) -> Vec<String> {
+    let patterns = patterns.map_or_else(|| vec!["*".to_string()], <[String]>::to_vec);
+    let Some(prefix) = relative_to(dir, repo_root).filter(|prefix| !prefix.as_os_str().is_empty())
+    else {
+        return patterns;
+    };
+    let prefix = glob::Pattern::escape(&prefix.to_string_lossy());
+    patterns
+        .into_iter()
+        .map(|pattern| format!("{prefix}/{pattern}"))
+        .collect()
+}
+
+/// `path` relative to `base`, comparing canonical paths if the given ones
+/// don't share a prefix
+fn relative_to(path: &Path, base: &Path) -> Option<PathBuf> {
+    path.strip_prefix(base)
+        .ok()
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            let path = path.canonicalize().ok()?;
+            let base = base.canonicalize().ok()?;
+            path.strip_prefix(base).ok().map(Path::to_path_buf)
+        })
+}
+
+/// Replace `depends_on` entries naming a group with every hook the group runs
+///
+/// A hook that depends on a group therefore waits for all of the group's
+/// hooks, including those of nested groups. Entries that name a hook are kept
+/// as-is, matching how `includes` prefers hooks over groups of the same name,
+/// except that a hook with `workdir_glob` stands for all of its copies.
+///
+/// # Errors
+///
+/// Returns an error if a group's `includes` contains an invalid pattern
+fn expand_group_dependencies(
+    hooks: &mut IndexMap<String, ResolvedHook>,
+    config: &HookConfig,
+) -> Result<()> {
+    fn collect_members(
+        name: &str,
+        config: &HookConfig,
+        visited: &mut HashSet<String>,
+        members: &mut Vec<String>,
+    ) -> Result<()> {
+        if !visited.insert(name.to_string()) {
+            return Ok(());
+        }
+        if config.hooks.as_ref().is_some_and(|h| h.contains_key(name)) {
+            if !members.iter().any(|member| member == name) {
+                members.push(name.to_string());
+            }
+            return Ok(());
+        }
+        if let Some(group) = config.groups.as_ref().and_then(|groups| groups.get(name)) {
+            for include in &expand_includes(&group.includes, config)? {
+                collect_members(include, config, visited, members)?;
+            }
+        }
+        Ok(())
+    }
+
+    let copies: HashMap<String, Vec<String>> = config
+        .hooks
+        .iter()
+        .flatten()
+        .filter(|(_, hook_def)| hook_def.workdir_glob.is_some())
+        .map(|(name, _)| {
+            let prefix = format!("{name}:");
+            let names = hooks
+                .iter()
+                .filter(|(key, hook)| {
+                    key.starts_with(&prefix) && hook.definition.workdir_glob.is_some()
+                })
+                .map(|(key, _)| key.clone())
+                .collect();
+            (name.clone(), names)
+        })
+        .collect();
+
+    for hook in hooks.values_mut() {
+        let Some(depends_on) = &hook.definition.depends_on else {
+            continue;
+        };
+        let mut expanded = Vec::new();
+        for dependency in depends_on {
+            let is_hook = config
+                .hooks
+                .as_ref()
+                .is_some_and(|h| h.contains_key(dependency));
+            let is_group = config
+                .groups
+                .as_ref()
+                .is_some_and(|g| g.contains_key(dependency));
+            if is_group && !is_hook {
+                collect_members(dependency, config, &mut HashSet::new(), &mut expanded)?;
+            } else if !expanded.contains(dependency) {
+                expanded.push(dependency.clone());
+            }
+        }
+        let expanded = expanded
+            .into_iter()
+            .flat_map(|dependency| {
+                copies
+                    .get(&dependency)
+                    .cloned()
+                    .unwrap_or_else(|| vec![dependency])
+            })
+            .collect();
+        hook.definition.depends_on = Some(expanded);
+    }
+
+    Ok(())
+}
+
+/// Collect the `before`/`after` commands of a group and its nested groups
+///
+/// `before` commands are ordered outermost group first and `after` commands
+/// innermost first, so teardown mirrors setup. Returns empty lists when `name`
+/// is not a group.
+fn collect_group_lifecycle(
+    name: &str,
+    config: &HookConfig,
+) -> (Vec<LifecycleCommand>, Vec<LifecycleCommand>) {
+    fn collect(
+        name: &str,
+        config: &HookConfig,
+        visited: &mut HashSet<String>,
+        before: &mut Vec<LifecycleCommand>,
+        after: &mut Vec<LifecycleCommand>,
+    ) {
+        let Some(group) = config.groups.as_ref().and_then(|groups| groups.get(name)) else {
+            return;
+        };
+        if !visited.insert(name.to_string()) {
+            return;
+        }
+
+        if let Some(command) = &group.before {
+            before.push(LifecycleCommand {
+                group: name.to_string(),
+                command: command.clone(),
+            });
+        }
+        for include in &group.includes {
+            collect(include, config, visited, before, after);
+        }
+        if let Some(command) = &group.after {
+            after.push(LifecycleCommand {
+                group: name.to_string(),
+                command: command.clone(),
+            });
+        }
+    }
+
+    let mut before = Vec::new();
+    let mut after = Vec::new();
+    collect(name, config, &mut HashSet::new(), &mut before, &mut after);
+    (before, after)
 }
 
 impl HookResolver {
@@ -68,20 +753,33 @@ impl HookResolver {
         }
     }
 
-    /// Find the nearest hooks.toml file by walking up the directory tree
+    /// Find the nearest hooks config file by walking up the directory tree
+    ///
+    /// In each directory `hooks.toml` wins over `hooks.yaml`, `hooks.yml` and
+    /// `hooks.json`. Inside a git repository the walk stops at the root of
+    /// the current working tree, so a linked worktree (or submodule) checked
+    /// out inside another working tree never picks up that tree's config.
     ///
     /// # Errors
     ///
     /// Returns an error if there are filesystem access issues
     pub fn find_config_file(&self) -> Result<Option<PathBuf>> {
+        let worktree_root = GitRepository::find_from_dir(&self.current_dir)
+            .ok()
+            .and_then(|repo| repo.root.canonicalize().ok());
         let mut current = self.current_dir.as_path();
 
         loop {
-            let config_path = current.join("hooks.toml");
-            if config_path.exists() {
+            if let Some(config_path) = config_file_in(current) {
                 return Ok(Some(config_path));
             }
 
+            if worktree_root.is_some()
+                && current.canonicalize().ok().as_ref() == worktree_root.as_ref()
+            {
+                return Ok(None);
+            }
+
             match current.parent() {
                 Some(parent) => current = parent,
                 None => return Ok(None),
@@ -129,6 +827,8 @@ impl HookResolver {
             repo_root: repo.root.clone(),
             common_dir: repo.common_dir,
             working_dir: self.current_dir.clone(),
+            run_env: IndexMap::new(),
+            push_refs: None,
         };
 
         // Get changed files if file filtering is requested
@@ -145,20 +845,24 @@ impl HookResolver {
         };
 
         // Look for hooks that match the event name
-        let mut resolved_hooks = HashMap::new();
+        let mut resolved_hooks = IndexMap::new();
         let mut execution_strategy = ExecutionStrategy::Sequential;
+        let mut isolation = None;
 
         // First, try to find a hook or group with the exact event name
         if let Some(hooks) = &config.hooks {
             if let Some(hook_def) = hooks.get(event) {
                 // Apply file filtering
-                if Self::should_run_hook(hook_def, changed_files.as_ref())? {
-                    let resolved = ResolvedHook {
-                        definition: hook_def.clone(),
-                        working_directory: Self::resolve_working_directory(hook_def, config_dir),
-                        source_file: config_path.clone(),
-                    };
-                    resolved_hooks.insert(event.to_string(), resolved);
+                for (name, resolved) in
+                    resolve_instances(event, hook_def, &config_path, &worktree_context.repo_root)?
+                {
+                    if Self::should_run_hook(
+                        &resolved.definition,
+                        changed_files.as_ref(),
+                        &worktree_context.repo_root,
+                    )? {
+                        resolved_hooks.insert(name, resolved);
+                    }
                 }
             }
         }
@@ -172,11 +876,13 @@ impl HookResolver {
                     return Ok(None);
                 }
 
-                execution_strategy = group.get_execution_strategy();
+                execution_strategy = group.get_execution_strategy(default_execution_strategy());
+                isolation = group.isolation;
                 self.resolve_group_with_files(
                     group,
                     &config,
                     config_dir,
+                    &worktree_context.repo_root,
                     &config_path,
                     &mut resolved_hooks,
                     changed_files.as_ref(),
@@ -188,12 +894,19 @@ impl HookResolver {
             return Ok(None);
         }
 
+        expand_group_dependencies(&mut resolved_hooks, &config)?;
+        let (before, after) = collect_group_lifecycle(event, &config);
+
         Ok(Some(ResolvedHooks {
             config_path,
             hooks: resolved_hooks,
             execution_strategy,
+            bail_after: None,
+            isolation,
             changed_files,
             worktree_context,
+            before,
+            after,
         }))
     }
 
@@ -230,11 +943,14 @@ impl HookResolver {
             repo_root: lint_repo_root,
             common_dir: self.current_dir.clone(), // No separate common dir in lint mode
             working_dir: self.current_dir.clone(),
+            run_env: IndexMap::new(),
+            push_refs: None,
         };
 
         // Look for the specific hook by name
-        let mut resolved_hooks = HashMap::new();
+        let mut resolved_hooks = IndexMap::new();
         let mut execution_strategy = ExecutionStrategy::Sequential;
+        let mut isolation = None;
 
         // Check if it's a direct hook
         if let Some(hooks) = &config.hooks {
@@ -254,7 +970,8 @@ impl HookResolver {
         // Check if it's a group
         if let Some(groups) = &config.groups {
             if let Some(group) = groups.get(hook_name) {
-                execution_strategy = group.get_execution_strategy();
+                execution_strategy = group.get_execution_strategy(default_execution_strategy());
+                isolation = group.isolation;
                 // In lint mode, we pass Some(&all_files) to enable file filtering
                 self.resolve_group_for_lint(group, &config, &config_path, &mut resolved_hooks)?;
             }
@@ -264,13 +981,20 @@ impl HookResolver {
             return Ok(None);
         }
 
+        expand_group_dependencies(&mut resolved_hooks, &config)?;
+        let (before, after) = collect_group_lifecycle(hook_name, &config);
+
         Ok(Some(ResolvedHooks {
             config_path,
             hooks: resolved_hooks,
             execution_strategy,
+            bail_after: None,
+            isolation,
             changed_files: Some(all_files), /* In lint mode, "changed files" are all discovered
                                              * files */
             worktree_context,
+            before,
+            after,
         }))
     }
 
@@ -304,6 +1028,8 @@ impl HookResolver {
             repo_root: repo.root.clone(),
             common_dir: repo.common_dir,
             working_dir: self.current_dir.clone(),
+            run_env: IndexMap::new(),
+            push_refs: None,
         };
 
         // Get changed files if change mode is specified
@@ -320,20 +1046,27 @@ impl HookResolver {
         };
 
         // Look for the specific hook by name
-        let mut resolved_hooks = HashMap::new();
+        let mut resolved_hooks = IndexMap::new();
         let mut execution_strategy = ExecutionStrategy::Sequential;
+        let mut isolation = None;
 
         // Check if it's a direct hook
         if let Some(hooks) = &config.hooks {
             if let Some(hook_def) = hooks.get(hook_name) {
                 // Apply file filtering
-                if Self::should_run_hook(hook_def, changed_files.as_ref())? {
-                    let resolved = ResolvedHook {
-                        definition: hook_def.clone(),
-                        working_directory: Self::resolve_working_directory(hook_def, config_dir),
-                        source_file: config_path.clone(),
-                    };
-                    resolved_hooks.insert(hook_name.to_string(), resolved);
+                for (name, resolved) in resolve_instances(
+                    hook_name,
+                    hook_def,
+                    &config_path,
+                    &worktree_context.repo_root,
+                )? {
+                    if Self::should_run_hook(
+                        &resolved.definition,
+                        changed_files.as_ref(),
+                        &worktree_context.repo_root,
+                    )? {
+                        resolved_hooks.insert(name, resolved);
+                    }
                 }
             }
         }
@@ -347,11 +1080,13 @@ impl HookResolver {
                     return Ok(None);
                 }
 
-                execution_strategy = group.get_execution_strategy();
+                execution_strategy = group.get_execution_strategy(default_execution_strategy());
+                isolation = group.isolation;
                 self.resolve_group_with_files(
                     group,
                     &config,
                     config_dir,
+                    &worktree_context.repo_root,
                     &config_path,
                     &mut resolved_hooks,
                     changed_files.as_ref(),
@@ -363,41 +1098,53 @@ impl HookResolver {
             return Ok(None);
         }
 
+        expand_group_dependencies(&mut resolved_hooks, &config)?;
+        let (before, after) = collect_group_lifecycle(hook_name, &config);
+
         Ok(Some(ResolvedHooks {
             config_path,
             hooks: resolved_hooks,
             execution_strategy,
+            bail_after: None,
+            isolation,
             changed_files,
             worktree_context,
+            before,
+            after,
         }))
     }
 
     /// Resolve the working directory for a hook
-    fn resolve_working_directory(hook_def: &HookDefinition, config_dir: &Path) -> PathBuf {
+    fn resolve_working_directory(
+        hook_def: &HookDefinition,
+        config_dir: &Path,
+        repo_root: &Path,
+    ) -> PathBuf {
         hook_def.workdir.as_ref().map_or_else(
             || config_dir.to_path_buf(),
-            |workdir| {
-                let path = Path::new(workdir);
-                if path.is_absolute() {
-                    path.to_path_buf()
-                } else {
-                    config_dir.join(path)
-                }
-            },
+            |workdir| resolve_workdir(workdir, config_dir, repo_root),
         )
     }
 
     /// Check if a hook should run based on file patterns and changed files
     ///
+    /// This only drops hooks whose `files` patterns match none of the changed
+    /// files; with file detection off (`changed_files` is `None`) every hook
+    /// is kept. Whether a kept hook gets any files, or runs at all without
+    /// them, is decided when it executes (see [`run_decision`]).
+    ///
     /// # Errors
     ///
     /// Returns an error if glob patterns are invalid
     fn should_run_hook(
         hook_def: &HookDefinition,
         changed_files: Option<&Vec<PathBuf>>,
+        repo_root: &Path,
     ) -> Result<bool> {
-        // If run_always is true, always run
-        if hook_def.run_always {
+        // If run_always is true, always run. Hooks with
+        // run_always_if_no_changes run whether or not their files match, with
+        // the matching files or none; the executor picks which
+        if hook_def.run_always || hook_def.run_always_if_no_changes {
             return Ok(true);
         }
 
@@ -412,10 +1159,7 @@ impl HookResolver {
         };
 
         // Check if any changed files match the patterns
-        let matcher =
-            FilePatternMatcher::new(patterns).context("Failed to compile file patterns")?;
-
-        Ok(matcher.matches_any(files))
+        Ok(!matching_files(patterns, files, repo_root)?.is_empty())
     }
 
     /// Resolve all hooks in a group for lint mode
@@ -428,7 +1172,7 @@ impl HookResolver {
         group: &HookGroup,
         config: &HookConfig,
         config_path: &Path,
-        resolved_hooks: &mut HashMap<String, ResolvedHook>,
+        resolved_hooks: &mut IndexMap<String, ResolvedHook>,
     ) -> Result<()> {
         let mut visited = HashSet::new();
         self.resolve_group_recursive_for_lint(
@@ -450,10 +1194,10 @@ impl HookResolver {
         group: &HookGroup,
         config: &HookConfig,
         config_path: &Path,
-        resolved_hooks: &mut HashMap<String, ResolvedHook>,
+        resolved_hooks: &mut IndexMap<String, ResolvedHook>,
         visited: &mut HashSet<String>,
     ) -> Result<()> {
-        for include in &group.includes {
+        for include in &expand_includes(&group.includes, config)? {
             if visited.contains(include) {
                 continue; // Avoid infinite loops
             }
@@ -495,13 +1239,15 @@ impl HookResolver {
     /// # Errors
     ///
     /// Returns an error if hook resolution fails
+    #[allow(clippy::too_many_arguments)]
     fn resolve_group_with_files(
         &self,
         group: &HookGroup,
         config: &HookConfig,
         config_dir: &Path,
+        repo_root: &Path,
         config_path: &Path,
-        resolved_hooks: &mut HashMap<String, ResolvedHook>,
+        resolved_hooks: &mut IndexMap<String, ResolvedHook>,
         changed_files: Option<&Vec<PathBuf>>,
     ) -> Result<()> {
         let mut visited = HashSet::new();
@@ -509,6 +1255,7 @@ impl HookResolver {
             group,
             config,
             config_dir,
+            repo_root,
             config_path,
             resolved_hooks,
             &mut visited,
@@ -528,12 +1275,13 @@ impl HookResolver {
         group: &HookGroup,
         config: &HookConfig,
         config_dir: &Path,
+        repo_root: &Path,
         config_path: &Path,
-        resolved_hooks: &mut HashMap<String, ResolvedHook>,
+        resolved_hooks: &mut IndexMap<String, ResolvedHook>,
         visited: &mut HashSet<String>,
         changed_files: Option<&Vec<PathBuf>>,
     ) -> Result<()> {
-        for include in &group.includes {
+        for include in &expand_includes(&group.includes, config)? {
             if visited.contains(include) {
                 continue; // Avoid infinite loops
             }
@@ -543,15 +1291,12 @@ impl HookResolver {
             if let Some(hooks) = &config.hooks {
                 if let Some(hook_def) = hooks.get(include) {
                     // Apply file filtering
-                    if Self::should_run_hook(hook_def, changed_files)? {
-                        let resolved = ResolvedHook {
-                            definition: hook_def.clone(),
-                            working_directory: Self::resolve_working_directory(
-                                hook_def, config_dir,
-                            ),
-                            source_file: config_path.to_path_buf(),
-                        };
-                        resolved_hooks.insert(include.clone(), resolved);
+                    for (name, resolved) in
+                        resolve_instances(include, hook_def, config_path, repo_root)?
+                    {
+                        if Self::should_run_hook(&resolved.definition, changed_files, repo_root)? {
+                            resolved_hooks.insert(name, resolved);
+                        }
                     }
                     continue;
                 }
@@ -564,6 +1309,7 @@ impl HookResolver {
                         nested_group,
                         config,
                         config_dir,
+                        repo_root,
                         config_path,
                         resolved_hooks,
                         visited,
@@ -608,6 +1354,24 @@ mod tests {
         assert_eq!(config_path, root.join("projects/hooks.toml"));
     }
 
+    #[test]
+    fn test_find_config_file_other_formats() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let nested = root.join("web");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("hooks.yaml"), "hooks: {}\n").unwrap();
+        std::fs::write(root.join("hooks.json"), "{}").unwrap();
+
+        let config_path = HookResolver::new(&nested).find_config_file().unwrap();
+        assert_eq!(config_path, Some(nested.join("hooks.yaml")));
+
+        // TOML wins when a directory has several config files
+        create_test_config(&nested, "[hooks]");
+        let config_path = HookResolver::new(&nested).find_config_file().unwrap();
+        assert_eq!(config_path, Some(nested.join("hooks.toml")));
+    }
+
     #[test]
     fn test_resolve_simple_hook() {
         let temp_dir = TempDir::new().unwrap();
@@ -637,6 +1401,503 @@ description = "Simple pre-commit hook"
         );
     }
 
+    #[test]
+    fn test_run_decision_reasons() {
+        let hook = |extra: &str| -> HookDefinition {
+            toml::from_str(&format!("command = \"true\"\n{extra}")).unwrap()
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        let run_decision = |hook_def: &HookDefinition, changed: Option<&[PathBuf]>| {
+            run_decision(hook_def, changed, root)
+        };
+        let changed = vec![PathBuf::from("src/lib.rs"), PathBuf::from("README.md")];
+
+        assert_eq!(
+            run_decision(&hook("files = [\"**/*.rs\"]"), Some(&changed)).unwrap(),
+            RunDecision::MatchedFiles {
+                files: vec![PathBuf::from("src/lib.rs")]
+            }
+        );
+        assert_eq!(
+            run_decision(&hook("files = [\"**/*.py\"]"), Some(&changed)).unwrap(),
+            RunDecision::NoMatchingFiles {
+                patterns: vec!["**/*.py".to_string()],
+                considered: changed.clone(),
+            }
+        );
+        assert_eq!(
+            run_decision(&hook(""), Some(&[])).unwrap(),
+            RunDecision::NoChangedFiles
+        );
+        assert_eq!(
+            run_decision(&hook("files = [\"**/*.py\"]\nrun_always = true"), None).unwrap(),
+            RunDecision::RunAlways
+        );
+        assert_eq!(
+            run_decision(&hook("execution_type = \"other\""), None).unwrap(),
+            RunDecision::OtherExecutionType
+        );
+        assert_eq!(
+            run_decision(&hook("run_always = true\nenabled = false"), None).unwrap(),
+            RunDecision::Disabled
+        );
+        assert!(!RunDecision::NoChangedFiles.will_run());
+
+        // Deleted files only reach hooks that opt in
+        let with_deleted = vec![PathBuf::from("src/gone.rs")];
+        assert_eq!(
+            run_decision(&hook("files = [\"**/*.rs\"]"), Some(&with_deleted)).unwrap(),
+            RunDecision::OnlyDeletedFiles {
+                files: with_deleted.clone()
+            }
+        );
+        assert_eq!(
+            run_decision(
+                &hook("files = [\"**/*.rs\"]\ninclude_deleted = true"),
+                Some(&with_deleted)
+            )
+            .unwrap(),
+            RunDecision::MatchedFiles {
+                files: with_deleted.clone()
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_decision_run_always_if_no_changes() {
+        let hook = |extra: &str| -> HookDefinition {
+            toml::from_str(&format!(
+                "command = \"true\"\nfiles = [\"tests/**\"]\n{extra}"
+            ))
+            .unwrap()
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("tests")).unwrap();
+        std::fs::write(root.join("tests/it.rs"), "").unwrap();
+        std::fs::write(root.join("lib.rs"), "").unwrap();
+        let gated = hook("");
+        let forced = hook("run_always_if_no_changes = true");
+        let with_tests = vec![PathBuf::from("tests/it.rs"), PathBuf::from("lib.rs")];
+        let without_tests = vec![PathBuf::from("lib.rs")];
+
+        // Matching files: both run for them
+        for hook_def in [&gated, &forced] {
+            assert_eq!(
+                run_decision(hook_def, Some(&with_tests), root).unwrap(),
+                RunDecision::MatchedFiles {
+                    files: vec![PathBuf::from("tests/it.rs")]
+                }
+            );
+        }
+
+        // Changed files, none matching, and an empty change set
+        assert!(
+            !run_decision(&gated, Some(&without_tests), root)
+                .unwrap()
+                .will_run()
+        );
+        assert!(!run_decision(&gated, Some(&[]), root).unwrap().will_run());
+        assert_eq!(
+            run_decision(&forced, Some(&without_tests), root).unwrap(),
+            RunDecision::RunIfNoChanges
+        );
+        assert_eq!(
+            run_decision(&forced, Some(&[]), root).unwrap(),
+            RunDecision::RunIfNoChanges
+        );
+
+        // File detection off (`--all-files`) and disabled hooks are unaffected
+        assert_eq!(
+            run_decision(&forced, None, root).unwrap(),
+            RunDecision::NoChangedFiles
+        );
+        assert_eq!(
+            run_decision(
+                &hook("run_always_if_no_changes = true\nenabled = false"),
+                Some(&[]),
+                root
+            )
+            .unwrap(),
+            RunDecision::Disabled
+        );
+    }
+
+    #[test]
+    fn test_resolve_workdir_forms() {
+        let config_dir = Path::new("/repo/backend");
+        let repo_root = Path::new("/repo");
+
+        assert_eq!(
+            resolve_workdir("//tools/lint", config_dir, repo_root),
+            PathBuf::from("/repo/tools/lint")
+        );
+        assert_eq!(resolve_workdir("//", config_dir, repo_root), repo_root);
+        assert_eq!(
+            resolve_workdir("scripts", config_dir, repo_root),
+            PathBuf::from("/repo/backend/scripts")
+        );
+        assert_eq!(
+            resolve_workdir("/opt/tools", config_dir, repo_root),
+            PathBuf::from("/opt/tools")
+        );
+    }
+
+    #[test]
+    fn test_resolve_root_relative_workdir_from_nested_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        let nested = root.join("backend");
+        std::fs::create_dir_all(&nested).unwrap();
+        create_test_config(
+            &nested,
+            r#"
+[hooks.root-relative]
+command = "echo root"
+workdir = "//frontend"
+
+[hooks.config-relative]
+command = "echo config"
+workdir = "scripts"
+
+[groups.pre-commit]
+includes = ["root-relative", "config-relative"]
+"#,
+        );
+
+        let resolver = HookResolver::new(&nested);
+        let result = resolver.resolve_hooks("pre-commit").unwrap().unwrap();
+
+        let repo_root = &result.worktree_context.repo_root;
+        assert_eq!(
+            result.hooks["root-relative"].working_directory,
+            repo_root.join("frontend")
+        );
+        assert_eq!(
+            result.hooks["config-relative"].working_directory,
+            nested.join("scripts")
+        );
+    }
+
+    #[test]
+    fn test_resolve_workdir_glob_per_marker_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        for package in ["packages/web", "packages/api", "packages/docs"] {
+            std::fs::create_dir_all(root.join(package)).unwrap();
+        }
+        std::fs::write(root.join("packages/web/package.json"), "{}").unwrap();
+        std::fs::write(root.join("packages/api/package.json"), "{}").unwrap();
+        create_test_config(
+            root,
+            r#"
+[hooks.npm-test]
+command = "npm test"
+workdir_glob = "packages/*/package.json"
+files = ["*.ts"]
+
+[hooks.summary]
+command = "echo done"
+depends_on = ["npm-test"]
+
+[groups.pre-commit]
+includes = ["npm-test", "summary"]
+"#,
+        );
+
+        let result = HookResolver::new(root)
+            .resolve_hooks("pre-commit")
+            .unwrap()
+            .unwrap();
+
+        let names: Vec<&String> = result.hooks.keys().collect();
+        assert_eq!(
+            names,
+            ["npm-test:packages/api", "npm-test:packages/web", "summary"]
+        );
+        let api = &result.hooks["npm-test:packages/api"];
+        assert_eq!(api.working_directory, root.join("packages/api"));
+        assert_eq!(
+            api.definition.files,
+            Some(vec!["packages/api/*.ts".to_string()])
+        );
+        assert!(
+            FilePatternMatcher::new(api.definition.files.as_ref().unwrap())
+                .unwrap()
+                .matches(Path::new("packages/api/src/main.ts"))
+        );
+        assert!(
+            !FilePatternMatcher::new(api.definition.files.as_ref().unwrap())
+                .unwrap()
+                .matches(Path::new("packages/web/src/main.ts"))
+        );
+        assert_eq!(
+            result.hooks["summary"].definition.depends_on,
+            Some(vec![
+                "npm-test:packages/api".to_string(),
+                "npm-test:packages/web".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_group_preserves_include_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        create_test_config(
+            root,
+            r#"
+[[hooks]]
+name = "alpha"
+command = "echo alpha"
+
+[[hooks]]
+name = "bravo"
+command = "echo bravo"
+
+[[hooks]]
+name = "charlie"
+command = "echo charlie"
+
+[[hooks]]
+name = "delta"
+command = "echo delta"
+
+[groups.checks]
+includes = ["delta", "alpha"]
+
+[groups.pre-commit]
+includes = ["charlie", "checks", "bravo"]
+"#,
+        );
+
+        let resolver = HookResolver::new(root);
+        let result = resolver.resolve_hooks("pre-commit").unwrap().unwrap();
+
+        let names: Vec<&str> = result.hooks.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["charlie", "delta", "alpha", "bravo"]);
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("lint-{rust,js}"),
+            vec!["lint-rust", "lint-js"]
+        );
+        assert_eq!(
+            expand_braces("{a,b}-{x,y}"),
+            vec!["a-x", "a-y", "b-x", "b-y"]
+        );
+        assert_eq!(
+            expand_braces("py.{ruff,mypy{,-strict}}"),
+            vec!["py.ruff", "py.mypy", "py.mypy-strict"]
+        );
+        assert_eq!(expand_braces("lint-{rust"), vec!["lint-{rust"]);
+        assert_eq!(expand_braces("python.*"), vec!["python.*"]);
+    }
+
+    #[test]
+    fn test_resolve_group_include_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        create_test_config(
+            root,
+            r#"
+[hooks."python.ruff"]
+command = "ruff check"
+
+[hooks.lint-js]
+command = "eslint"
+
+[hooks."python.mypy"]
+command = "mypy"
+
+[hooks.lint-rust]
+command = "cargo clippy"
+
+[hooks.lint-go]
+command = "golangci-lint run"
+
+[hooks.test]
+command = "cargo test"
+
+[groups.pre-commit]
+includes = ["test", "python.*", "lint-{rust,js}", "docs-*"]
+"#,
+        );
+
+        let resolver = HookResolver::new(root);
+        let result = resolver.resolve_hooks("pre-commit").unwrap().unwrap();
+
+        // Pattern matches follow declaration order; a pattern matching
+        // nothing adds nothing
+        let names: Vec<&str> = result.hooks.keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            vec!["test", "python.ruff", "python.mypy", "lint-js", "lint-rust"]
+        );
+    }
+
+    #[test]
+    fn test_is_branch_checkout() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // git checkout <branch>
+        assert!(is_branch_checkout(&args(&["1a2b3c", "4d5e6f", "1"])));
+        // git checkout -- <file>
+        assert!(!is_branch_checkout(&args(&["4d5e6f", "4d5e6f", "0"])));
+        // Run by hand without git's arguments
+        assert!(is_branch_checkout(&[]));
+    }
+
+    #[test]
+    fn test_skip_branch_change_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        create_test_config(
+            root,
+            r#"
+[hooks.install-deps]
+command = "npm ci"
+only_on_branch_change = true
+
+[hooks.notify]
+command = "echo checked out"
+
+[groups.post-checkout]
+includes = ["install-deps", "notify"]
+"#,
+        );
+
+        let mut hooks = HookResolver::new(root)
+            .resolve_hooks("post-checkout")
+            .unwrap()
+            .unwrap();
+        assert!(hooks.hooks["install-deps"].definition.only_on_branch_change);
+        assert!(!hooks.hooks["notify"].definition.only_on_branch_change);
+
+        hooks.skip_branch_change_hooks();
+        assert_eq!(hooks.hooks.keys().collect::<Vec<_>>(), vec!["notify"]);
+    }
+
+    #[test]
+    fn test_retain_tagged() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        create_test_config(
+            root,
+            r#"
+[hooks.fmt]
+command = "cargo fmt --check"
+tags = ["fast", "rust"]
+
+[hooks.clippy]
+command = "cargo clippy"
+tags = ["rust"]
+
+[hooks.audit]
+command = "cargo audit"
+tags = ["security", "slow"]
+
+[hooks.untagged]
+command = "true"
+
+[groups.pre-commit]
+includes = ["fmt", "clippy", "audit", "untagged"]
+"#,
+        );
+
+        let resolver = HookResolver::new(root);
+        let selected = |filter: &TagFilter| {
+            let mut hooks = resolver.resolve_hooks("pre-commit").unwrap().unwrap();
+            hooks.retain_tagged(filter);
+            hooks.hooks.keys().cloned().collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            selected(&TagFilter::default()),
+            vec!["fmt", "clippy", "audit", "untagged"]
+        );
+        assert_eq!(
+            selected(&TagFilter {
+                any: vec!["fast".to_string(), "security".to_string()],
+                all: vec![],
+            }),
+            vec!["fmt", "audit"]
+        );
+        assert_eq!(
+            selected(&TagFilter {
+                any: vec![],
+                all: vec!["rust".to_string(), "fast".to_string()],
+            }),
+            vec!["fmt"]
+        );
+        assert!(
+            selected(&TagFilter {
+                any: vec!["security".to_string()],
+                all: vec!["rust".to_string()],
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_resolve_group_lifecycle_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        create_test_config(
+            root,
+            r#"
+[hooks.test]
+command = "cargo test"
+
+[groups.with-db]
+includes = ["test"]
+before = "docker compose up -d db"
+after = ["docker", "compose", "down"]
+
+[groups.pre-commit]
+includes = ["with-db"]
+before = "echo setup"
+after = "echo teardown"
+"#,
+        );
+
+        let resolver = HookResolver::new(root);
+        let result = resolver.resolve_hooks("pre-commit").unwrap().unwrap();
+
+        let before: Vec<&str> = result.before.iter().map(|c| c.group.as_str()).collect();
+        let after: Vec<&str> = result.after.iter().map(|c| c.group.as_str()).collect();
+        assert_eq!(before, vec!["pre-commit", "with-db"]);
+        assert_eq!(after, vec!["with-db", "pre-commit"]);
+        assert_eq!(
+            result.after[0].command,
+            HookCommand::Args(vec![
+                "docker".to_string(),
+                "compose".to_string(),
+                "down".to_string()
+            ])
+        );
+    }
+
     #[test]
     fn test_resolve_hook_group() {
         let temp_dir = TempDir::new().unwrap();
@@ -665,6 +1926,146 @@ includes = ["lint", "test"]
         assert!(result.hooks.contains_key("test"));
     }
 
+    #[test]
+    fn test_depends_on_group_runs_after_group_hooks() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        let config_content = r#"
+[hooks.gen-proto]
+command = "echo gen-proto >> order.txt"
+run_always = true
+
+[hooks.gen-sql]
+command = "echo gen-sql >> order.txt"
+run_always = true
+
+[hooks.schema]
+command = "echo schema >> order.txt"
+run_always = true
+
+[hooks.compile]
+command = "echo compile >> order.txt"
+run_always = true
+depends_on = ["codegen"]
+
+[groups.sql]
+includes = ["gen-sql", "schema"]
+
+[groups.codegen]
+includes = ["gen-proto", "sql"]
+
+[groups.build]
+includes = ["compile"]
+
+[groups.pre-commit]
+includes = ["build", "codegen"]
+"#;
+        create_test_config(root, config_content);
+
+        let resolved = HookResolver::new(root)
+            .resolve_hooks("pre-commit")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            resolved.hooks["compile"].definition.depends_on,
+            Some(vec![
+                "gen-proto".to_string(),
+                "gen-sql".to_string(),
+                "schema".to_string()
+            ])
+        );
+
+        let results = crate::hooks::HookExecutor::execute(&resolved).unwrap();
+        assert!(results.success);
+        let order = std::fs::read_to_string(root.join("order.txt")).unwrap();
+        assert_eq!(order.lines().last(), Some("compile"), "{order}");
+        assert_eq!(order.lines().count(), 4, "{order}");
+    }
+
+    #[test]
+    fn test_depends_on_group_detects_cross_group_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let _ = Git2Repository::init(root).unwrap();
+
+        let config_content = r#"
+[hooks.codegen-hook]
+command = "true"
+run_always = true
+depends_on = ["build"]
+
+[hooks.build-hook]
+command = "true"
+run_always = true
+depends_on = ["codegen"]
+
+[groups.codegen]
+includes = ["codegen-hook"]
+
+[groups.build]
+includes = ["build-hook"]
+
+[groups.pre-commit]
+includes = ["codegen", "build"]
+"#;
+        create_test_config(root, config_content);
+
+        let resolved = HookResolver::new(root)
+            .resolve_hooks("pre-commit")
+            .unwrap()
+            .unwrap();
+        let err = crate::hooks::HookExecutor::execute(&resolved).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Circular dependency"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_find_config_file_stops_at_linked_worktree_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let main_dir = temp_dir.path().join("main");
+        let repo = Git2Repository::init(&main_dir).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let tree_id = repo.index().unwrap().write_tree().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        let main_config = create_test_config(&main_dir, "[hooks.main]\ncommand = \"echo\"\n");
+
+        // Checked out inside the main working tree, without a config of its own
+        std::fs::create_dir_all(main_dir.join(".worktrees")).unwrap();
+        let nested = main_dir.join(".worktrees/nested");
+        repo.worktree("nested", &nested, None).unwrap();
+        std::fs::create_dir_all(nested.join("src")).unwrap();
+
+        // Checked out elsewhere, with its own config
+        let feature = temp_dir.path().join("feature");
+        repo.worktree("feature", &feature, None).unwrap();
+        let feature_config = create_test_config(&feature, "[hooks.feature]\ncommand = \"echo\"\n");
+        std::fs::create_dir_all(feature.join("src")).unwrap();
+
+        assert_eq!(HookResolver::new(&nested).find_config_file().unwrap(), None);
+        assert_eq!(
+            HookResolver::new(nested.join("src"))
+                .find_config_file()
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            HookResolver::new(feature.join("src"))
+                .find_config_file()
+                .unwrap(),
+            Some(feature_config)
+        );
+        assert_eq!(
+            HookResolver::new(&main_dir).find_config_file().unwrap(),
+            Some(main_config)
+        );
+    }
+
     #[test]
     fn test_no_config_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,261 @@
+//! Watch mode: re-running lint hooks when files change (`lint --watch`)
+//!
+//! A [`ChangeWatcher`] batches filesystem events until the tree has been
+//! quiet for a short debounce period. Each batch is narrowed to the files lint
+//! discovery still finds, so `.gitignore` and `.peterhookignore` apply, and
+//! only the hooks whose `files` patterns match those files run again.
+
+use super::{ResolvedHooks, run_decision};
+use crate::git::LintFileDiscovery;
+use anyhow::{Context, Result};
+use indexmap::IndexSet;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+/// Quiet period after the last filesystem event before hooks run again
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Recursive filesystem watcher that reports changed paths in batches
+pub struct ChangeWatcher {
+    /// Kept alive so events keep arriving
+    _watcher: RecommendedWatcher,
+    /// Events from the watcher thread
+    events: Receiver<notify::Result<notify::Event>>,
+    /// Quiet period that ends a batch
+    debounce: Duration,
+}
+
+impl ChangeWatcher {
+    /// Watch `root` and everything below it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform watcher cannot be created or `root`
+    /// cannot be watched
+    pub fn new(root: &Path, debounce: Duration) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(sender).context("Failed to create file watcher")?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch {}", root.display()))?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            debounce,
+        })
+    }
+
+    /// Block until files change, then return every changed path once events
+    /// have stopped for the debounce period
+    ///
+    /// Reads and paths inside `.git` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the watcher reports an error or stops
+    pub fn next_batch(&self) -> Result<Vec<PathBuf>> {
+        let mut changed = IndexSet::new();
+        while changed.is_empty() {
+            let event = self.events.recv().context("File watcher stopped")?;
+            collect_paths(event?, &mut changed);
+        }
+
+        // Only changes restart the quiet period; reads (editors, file
+        // indexers) would otherwise hold the batch open forever
+        let mut quiet_until = Instant::now() + self.debounce;
+        loop {
+            let timeout = quiet_until.saturating_duration_since(Instant::now());
+            match self.events.recv_timeout(timeout) {
+                Ok(event) => {
+                    if collect_paths(event?, &mut changed) {
+                        quiet_until = Instant::now() + self.debounce;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => anyhow::bail!("File watcher stopped"),
+            }
+        }
+
+        log::info!("watch batch changed={}", changed.len());
+        Ok(changed.into_iter().collect())
+    }
+
+    /// Drop the events received so far, e.g. for files the hooks themselves
+    /// just rewrote, so a formatter doesn't trigger itself forever
+    pub fn discard_pending(&self) {
+        while self.events.try_recv().is_ok() {}
+    }
+}
+
+/// Add the paths of a create, modify or remove event, skipping git's own
+/// files; returns whether the event changed anything
+fn collect_paths(event: notify::Event, changed: &mut IndexSet<PathBuf>) -> bool {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return false;
+    }
+    let before = changed.len();
+    changed.extend(event.paths.into_iter().filter(|path| {
+        !path
+            .components()
+            .any(|c| c == Component::Normal(".git".as_ref()))
+    }));
+    changed.len() > before
+}
+
+/// The lint hooks to run again after `changed` paths changed
+///
+/// Keeps the changed files lint discovery still finds (so deleted and ignored
+/// files drop out), then the hooks those files make run, plus the hooks they
+/// depend on. Returns `None` when no hook needs to run.
+///
+/// # Errors
+///
+/// Returns an error if file discovery fails or a hook has invalid file
+/// patterns
+pub fn hooks_for_changes(
+    hooks: &ResolvedHooks,
+    changed: &[PathBuf],
+) -> Result<Option<ResolvedHooks>> {
+    let changed: IndexSet<&PathBuf> = changed.iter().collect();
+    let files: Vec<PathBuf> = LintFileDiscovery::new(&hooks.worktree_context.working_dir)
+        .discover_files()
+        .context("Failed to discover files for lint mode")?
+        .into_iter()
+        .filter(|file| changed.contains(file))
+        .collect();
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut keep = IndexSet::new();
+    for (name, hook) in &hooks.hooks {
+        if run_decision(
+            &hook.definition,
+            Some(&files),
+            &hooks.worktree_context.repo_root,
+        )?
+        .will_run()
+        {
+            keep.insert(name.clone());
+        }
+    }
+    if keep.is_empty() {
+        return Ok(None);
+    }
+
+    // Hooks the matching ones depend on run too, so the plan stays complete
+    let mut index = 0;
+    while let Some(name) = keep.get_index(index).cloned() {
+        let depends_on = hooks.hooks[&name].definition.depends_on.iter().flatten();
+        keep.extend(
+            depends_on
+                .filter(|dep| hooks.hooks.contains_key(*dep))
+                .cloned(),
+        );
+        index += 1;
+    }
+
+    let mut rerun = hooks.clone();
+    rerun.hooks.retain(|name, _| keep.contains(name));
+    rerun.changed_files = Some(files);
+    Ok(Some(rerun))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::{ExecutionStrategy, HookConfig},
+        hooks::{ResolvedHook, WorktreeContext},
+    };
+    use indexmap::IndexMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn lint_hooks(dir: &Path) -> ResolvedHooks {
+        let config = HookConfig::parse(
+            r#"
+[hooks.rust]
+command = "cargo fmt --check"
+files = ["**/*.rs"]
+depends_on = ["setup"]
+
+[hooks.docs]
+command = "mdl"
+files = ["**/*.md"]
+
+[hooks.setup]
+command = "true"
+files = ["Cargo.toml"]
+"#,
+        )
+        .unwrap();
+        let hooks: IndexMap<String, ResolvedHook> = config
+            .hooks
+            .unwrap()
+            .into_iter()
+            .map(|(name, definition)| {
+                let hook = ResolvedHook {
+                    definition,
+                    working_directory: dir.to_path_buf(),
+                    source_file: dir.join("hooks.toml"),
+                };
+                (name, hook)
+            })
+            .collect();
+
+        ResolvedHooks {
+            config_path: dir.join("hooks.toml"),
+            hooks,
+            execution_strategy: ExecutionStrategy::Sequential,
+            bail_after: None,
+            isolation: None,
+            changed_files: None,
+            worktree_context: WorktreeContext {
+                is_worktree: false,
+                worktree_name: None,
+                repo_root: dir.to_path_buf(),
+                common_dir: dir.to_path_buf(),
+                working_dir: dir.to_path_buf(),
+                run_env: IndexMap::new(),
+                push_refs: None,
+            },
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_hooks_for_changes_keeps_matching_hooks_and_dependencies() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        fs::write(dir.join("main.rs"), "").unwrap();
+        fs::write(dir.join("README.md"), "").unwrap();
+        let hooks = lint_hooks(dir);
+
+        let rerun = hooks_for_changes(&hooks, &[dir.join("main.rs")])
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            rerun.hooks.keys().collect::<Vec<_>>(),
+            ["rust", "setup"],
+            "setup runs because rust depends on it"
+        );
+        assert_eq!(rerun.changed_files, Some(vec![dir.join("main.rs")]));
+
+        // Deleted files and files no hook matches don't trigger a run
+        assert!(
+            hooks_for_changes(&hooks, &[dir.join("gone.rs"), dir.join("notes.txt")])
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -17,8 +17,11 @@ pub mod doctor;
 pub mod git;
 /// Hook resolution and execution system
 pub mod hooks;
+/// Structured file logging
+pub mod logging;
 /// Output formatting utilities
 pub mod output;
+pub mod selftest;
 /// Self-update functionality
 pub mod update;
 
@@ -0,0 +1,179 @@
+//! Structured file logging for `--log-file`
+//!
+//! Records are written as JSON lines (one object per line) with a UTC
+//! timestamp, level, source module, thread, and message. The log is
+//! independent of the terminal output, so it can be collected from CI runs
+//! where the pretty output is lost.
+
+use anyhow::{Context, Result};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Logger that appends JSON lines to a file
+struct FileLogger {
+    /// Log file, opened in append mode
+    file: Mutex<File>,
+    /// Most verbose level that is recorded
+    level: LevelFilter,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut line = format_record(record, SystemTime::now());
+        line.push('\n');
+
+        // One write per record, under the lock, so records from parallel
+        // hooks never interleave
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Level recorded in the log file: `debug` with `--debug`, `info` otherwise
+#[must_use]
+pub const fn level_for(debug: bool) -> LevelFilter {
+    if debug {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    }
+}
+
+/// Start logging to `path`, appending if the file already exists
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or a logger is already set
+pub fn init(path: &Path, level: LevelFilter) -> Result<()> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+
+    log::set_boxed_logger(Box::new(FileLogger {
+        file: Mutex::new(file),
+        level,
+    }))
+    .context("Logger already initialized")?;
+    log::set_max_level(level);
+
+    Ok(())
+}
+
+/// Format one log record as a JSON object
+fn format_record(record: &Record, time: SystemTime) -> String {
+    let thread = std::thread::current();
+    serde_json::json!({
+        "ts": format_timestamp(time),
+        "level": record.level().as_str(),
+        "target": record.target(),
+        "thread": thread.name().map_or_else(|| format!("{:?}", thread.id()), ToString::to_string),
+        "msg": record.args().to_string(),
+    })
+    .to_string()
+}
+
+/// Format a time as an RFC 3339 UTC timestamp with millisecond precision
+#[must_use]
+pub fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days(secs / 86_400);
+    let secs_of_day = secs % 86_400;
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// Convert days since 1970-01-01 to a (year, month, day) civil date
+///
+/// Howard Hinnant's `civil_from_days` algorithm, restricted to dates after
+/// the epoch.
+const fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let shifted = days + 719_468;
+    let era = shifted / 146_097;
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_millis(951_782_400_250)),
+            "2000-02-29T00:00:00.250Z"
+        );
+        assert_eq!(
+            format_timestamp(UNIX_EPOCH + Duration::from_secs(1_792_240_496)),
+            "2026-10-17T12:34:56.000Z"
+        );
+    }
+
+    #[test]
+    fn test_format_record_is_json_line() {
+        let line = format_record(
+            &Record::builder()
+                .args(format_args!("hook finished name=lint exit_code=0"))
+                .level(log::Level::Info)
+                .target("peter_hook::hooks::executor")
+                .build(),
+            UNIX_EPOCH,
+        );
+
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["ts"], "1970-01-01T00:00:00.000Z");
+        assert_eq!(value["level"], "INFO");
+        assert_eq!(value["target"], "peter_hook::hooks::executor");
+        assert_eq!(value["msg"], "hook finished name=lint exit_code=0");
+    }
+
+    #[test]
+    fn test_level_for_debug_flag() {
+        assert_eq!(level_for(false), LevelFilter::Info);
+        assert_eq!(level_for(true), LevelFilter::Debug);
+    }
+}
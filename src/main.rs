@@ -1,105 +1,301 @@
 //! Peter Hook - A hierarchical git hooks manager for monorepos
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use indexmap::IndexMap;
 use peter_hook::{
-    HookCommand,
-    cli::{Cli, Commands, ConfigCommand},
-    config::GlobalConfig,
+    cli::{
+        BackupsCommand, Cli, Commands, ConfigCommand, EXIT_HOOK_FAILURE, EXIT_NO_HOOKS,
+        EXIT_USAGE_ERROR, OutputArgs, RepeatArgs, exit_code_for,
+    },
+    config::{ExecutionStrategy, GlobalConfig, TrustStatus, TrustStore},
     debug,
-    git::{ChangeDetectionMode, GitHookInstaller, GitRepository, WorktreeHookStrategy},
-    hooks::{HookExecutor, HookResolver},
+    git::{
+        ChangeDetectionMode, FilePatternMatcher, GitChangeDetector, GitHookInstaller,
+        GitRepository, LastRunMarker, RepoLock, TimingHistory, WorktreeHookStrategy,
+    },
+    hooks::{
+        ChangeWatcher, ExecutionResults, HookExecutor, HookResolver, RepeatReport, ResolvedHooks,
+        TagFilter, WATCH_DEBOUNCE, hooks_for_changes, interrupt, is_branch_checkout,
+    },
+    output,
 };
 use std::{
+    collections::BTreeSet,
     env,
     io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
     process,
 };
 
 fn main() {
+    // Answer dynamic completion requests (COMPLETE=<shell>) before parsing
+    CompleteEnv::with_factory(Cli::command).complete();
+
     if let Err(e) = run() {
         eprintln!("Error: {e:#}");
-        process::exit(1);
+        process::exit(exit_code_for(&e));
     }
 }
 
+#[allow(clippy::too_many_lines)] // one arm per subcommand
 fn run() -> Result<()> {
     let cli = Cli::parse();
 
-    // Enable debug mode if requested
-    if cli.debug {
-        debug::enable();
-    }
+    init_output(&cli)?;
+
+    init_diagnostics(cli.debug, cli.log_file.as_deref())?;
+    interrupt::install_handler()?;
 
     match cli.command {
         Commands::Install {
             force,
             worktree_strategy,
-        } => install_hooks(force, &worktree_strategy),
-        Commands::Uninstall { yes } => uninstall_hooks(yes),
+            all_worktrees,
+            use_path,
+        } => install_hooks(force, &worktree_strategy, all_worktrees, use_path),
+        Commands::Uninstall { yes, keep_backups } => uninstall_hooks(yes, keep_backups),
+        Commands::Backups { subcommand } => handle_backups_command(&subcommand),
         Commands::Run {
             event,
             git_args,
             all_files,
+            files_from,
+            from_stdin,
+            since_last_run,
+            changed_files_output,
+            changed_files_only,
+            affected_only,
+            root,
+            only_modified_configs,
+            allow_custom_event,
+            no_trust_check,
             dry_run,
-        } => run_hooks(&event, &git_args, all_files, dry_run),
+            list_files,
+            json,
+            dump_env,
+            no_redact,
+            continue_from,
+            env,
+            tags,
+            strategy,
+            repeat,
+            output: _,
+            bail_after,
+            output_dir,
+            fail_on,
+            allow,
+        } => run_hooks(
+            &event,
+            RunOptions {
+                git_args: &git_args,
+                change_mode: run_change_mode(
+                    &event,
+                    all_files,
+                    files_from,
+                    from_stdin,
+                    since_last_run,
+                )?
+                .as_ref(),
+                changed_files_output: changed_files_output.as_deref(),
+                changed_files_only,
+                mode: RunMode::from_flags(dry_run, list_files, json, dump_env, no_redact),
+                affected_only,
+                from_root: root,
+                only_modified_configs,
+                allow_custom_event,
+                no_trust_check,
+                continue_from: continue_from.as_deref(),
+                run_env: env.into_iter().collect(),
+                tag_filter: &tags.into_filter(),
+                strategy: strategy.into_strategy(),
+                repeat,
+                bail_after,
+                output_dir: output_dir.as_deref(),
+                fail_on: fail_on.as_deref(),
+                allow: allow.as_deref(),
+            },
+        ),
         Commands::Validate {
             trace_imports,
             json,
-        } => validate_config(trace_imports, json),
+            explain,
+            match_paths,
+        } => validate_config(trace_imports, json, explain, &match_paths),
+        Commands::WhySkipped {
+            name,
+            event,
+            all_files,
+            json,
+        } => why_skipped(&name, event.as_deref(), all_files, json),
+        Commands::Graph { name, format } => show_graph(&name, &format),
         Commands::List => list_hooks(),
         Commands::ListWorktrees => list_worktrees(),
+        Commands::ListEvents { json } => list_events(json),
+        Commands::ConfigPath { dir } => print_config_path(dir.as_deref()),
         Commands::Config { subcommand } => handle_config_command(&subcommand),
-        Commands::Lint { hook_name, dry_run } => run_lint_mode(&hook_name, dry_run),
-        Commands::Version => {
-            show_version();
-            Ok(())
-        }
-        Commands::License => {
-            show_license();
-            Ok(())
-        }
-        Commands::Completions { shell } => {
-            peter_hook::completions::generate_completions(shell);
-            Ok(())
-        }
-        Commands::Doctor => {
-            let exit_code = peter_hook::doctor::run_doctor();
-            if exit_code != 0 {
-                process::exit(exit_code);
-            }
-            Ok(())
-        }
+        Commands::Trust => trust_repository(),
+        Commands::Untrust => untrust_repository(),
+        Commands::Lint {
+            hook_name,
+            dry_run,
+            tags,
+            repeat,
+            output: _,
+            bail_after,
+            watch,
+            fix,
+            no_trust_check,
+            list: _,
+            json,
+        } => hook_name.map_or_else(
+            || list_lint_targets(json),
+            |hook_name| {
+                run_lint_mode(
+                    &hook_name,
+                    &LintOptions {
+                        dry_run,
+                        tag_filter: tags.into_filter(),
+                        repeat,
+                        bail_after,
+                        watch,
+                        fix,
+                        no_trust_check,
+                    },
+                )
+            },
+        ),
+        Commands::Version => show_version(),
+        Commands::License => show_license(),
+        Commands::Completions { shell } => print_completions(shell),
+        Commands::Doctor { json } => run_doctor(json),
+        Commands::Selftest => run_selftest(),
         Commands::Update {
             version,
             force,
             install_dir,
-        } => {
-            let exit_code =
-                peter_hook::update::run_update(version.as_deref(), force, install_dir.as_deref());
-            if exit_code != 0 {
-                process::exit(exit_code);
+            skip_verify,
+            channel,
+            check,
+            rollback,
+        } => run_update(
+            version.as_deref(),
+            force,
+            install_dir.as_deref(),
+            skip_verify,
+            &channel,
+            check,
+            rollback,
+        ),
+    }
+}
+
+/// Add the durations of the hooks that ran to the timing history, when
+/// `execution.timing_history` asks for one
+fn record_timings(git_dir: &Path, results: &ExecutionResults) {
+    let keep = GlobalConfig::load()
+        .unwrap_or_default()
+        .execution
+        .timing_history
+        .unwrap_or_default();
+    if keep == 0 {
+        return;
+    }
+
+    let recorded = TimingHistory::load(git_dir).and_then(|mut history| {
+        for (name, result) in &results.results {
+            if let Some(duration_ms) = result.duration_ms {
+                history.record(name, duration_ms, keep);
             }
-            Ok(())
         }
+        history.save(git_dir)
+    });
+    if let Err(e) = recorded {
+        log::warn!("failed to record hook timings: {e:#}");
+    }
+}
+
+/// Hook output limits from the global `[output]` settings and `--max-output`
+fn output_limits(max_output: Option<usize>) -> output::OutputLimits {
+    GlobalConfig::load()
+        .unwrap_or_default()
+        .output
+        .limits(max_output)
+}
+
+/// Apply the output options: colors, and for `run` and `lint` how hook
+/// output is reported
+fn init_output(cli: &Cli) -> Result<()> {
+    output::init_color(color_choice(cli.color.as_deref(), cli.no_color)?);
+    let hook_output = match &cli.command {
+        Commands::Run { output, .. } | Commands::Lint { output, .. } => output.clone(),
+        _ => OutputArgs::default(),
+    };
+    output::init_output_limits(output_limits(hook_output.max_output));
+    output::init_report_format(
+        hook_output
+            .report_format
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{e}"))?,
+    );
+    output::init_show_files(hook_output.show_files);
+    output::init_stream(hook_output.stream);
+    Ok(())
+}
+
+/// Color choice from `--color` / `--no-color`, if either was given
+fn color_choice(color: Option<&str>, no_color: bool) -> Result<Option<output::ColorChoice>> {
+    if no_color {
+        return Ok(Some(output::ColorChoice::Never));
     }
+    color
+        .map(str::parse)
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("{e}"))
 }
 
 /// Install git hooks for the current repository
-fn install_hooks(force: bool, worktree_strategy: &str) -> Result<()> {
+fn install_hooks(
+    force: bool,
+    worktree_strategy: &str,
+    all_worktrees: bool,
+    use_path: bool,
+) -> Result<()> {
     println!("Installing git hooks...");
 
+    let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+    let _lock = RepoLock::acquire(&repo.common_dir)?;
+
     // Parse the worktree strategy
     let strategy: WorktreeHookStrategy = worktree_strategy
         .parse()
         .map_err(|_| anyhow::anyhow!("Invalid worktree strategy: {}", worktree_strategy))?;
 
-    let installer = GitHookInstaller::with_strategy(strategy)
+    let mut installer = GitHookInstaller::with_strategy(strategy)
         .context("Failed to initialize git hook installer")?;
+    if use_path {
+        installer = installer.with_path_lookup();
+    }
+
+    if all_worktrees {
+        let report = installer
+            .install_all_worktrees(force)
+            .context("Failed to install git hooks in worktrees")?;
+
+        report.print_summary();
+
+        if !report.is_success() {
+            process::exit(1);
+        }
+
+        return Ok(());
+    }
 
     if !force {
         // Check if any hooks would be overwritten
-        let repo = GitRepository::find_from_current_dir()?;
         let existing_hooks = repo.list_hooks()?;
         if !existing_hooks.is_empty()
             && existing_hooks
@@ -139,10 +335,14 @@ fn install_hooks(force: bool, worktree_strategy: &str) -> Result<()> {
 }
 
 /// Uninstall peter-hook managed hooks
-fn uninstall_hooks(yes: bool) -> Result<()> {
+fn uninstall_hooks(yes: bool, keep_backups: bool) -> Result<()> {
     if !yes {
         println!("This will remove all peter-hook managed hooks from your repository.");
-        println!("Backed up hooks will be restored if they exist.");
+        if keep_backups {
+            println!("Backed up hooks will be restored if they exist, keeping the backups.");
+        } else {
+            println!("Backed up hooks will be restored if they exist.");
+        }
         print!("Are you sure you want to continue? [y/N]: ");
         io::stdout().flush().unwrap();
 
@@ -158,7 +358,14 @@ fn uninstall_hooks(yes: bool) -> Result<()> {
         }
     }
 
-    let installer = GitHookInstaller::new().context("Failed to initialize git hook installer")?;
+    let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+    let _lock = RepoLock::acquire(&repo.common_dir)?;
+
+    let mut installer =
+        GitHookInstaller::new().context("Failed to initialize git hook installer")?;
+    if keep_backups {
+        installer = installer.with_backups_kept();
+    }
 
     let report = installer.uninstall_all();
 
@@ -171,6 +378,35 @@ fn uninstall_hooks(yes: bool) -> Result<()> {
     Ok(())
 }
 
+/// Handle backup management commands
+fn handle_backups_command(subcommand: &BackupsCommand) -> Result<()> {
+    match subcommand {
+        BackupsCommand::List => list_backups(),
+    }
+}
+
+/// List backups of replaced hooks with their original hook names
+fn list_backups() -> Result<()> {
+    let installer = GitHookInstaller::new().context("Failed to initialize git hook installer")?;
+    let backups = installer.list_backups()?;
+
+    if backups.is_empty() {
+        println!("No hook backups found.");
+        return Ok(());
+    }
+
+    println!("Hook backups:");
+    for backup in &backups {
+        let modified = backup.modified.map_or_else(
+            || "unknown time".to_string(),
+            peter_hook::logging::format_timestamp,
+        );
+        println!("  {} ← {} ({modified})", backup.hook, backup.path.display());
+    }
+
+    Ok(())
+}
+
 /// List all git hooks in the repository
 fn list_hooks() -> Result<()> {
     let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
@@ -208,13 +444,177 @@ fn list_hooks() -> Result<()> {
     Ok(())
 }
 
+/// Print the phases and dependencies of a group or event as a tree or
+/// Graphviz DOT
+fn show_graph(name: &str, format: &str) -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let resolver = HookResolver::new(&current_dir);
+
+    let Some(resolved_hooks) = resolver.resolve_hooks(name)? else {
+        println!("No hooks found for: {name}");
+        process::exit(EXIT_NO_HOOKS);
+    };
+    let graph = peter_hook::hooks::HookGraph::new(name, &resolved_hooks)?;
+
+    if format == "dot" {
+        print!("{}", graph.to_dot());
+    } else {
+        print!("{}", graph.to_tree());
+    }
+    Ok(())
+}
+
+/// List the supported git events that have a hook or group of the same name
+/// in the nearest config, i.e. the events `install` will wire up
+fn list_events(json: bool) -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let resolver = HookResolver::new(&current_dir);
+
+    let Some(config_path) = resolver.find_config_file()? else {
+        println!("No hooks.toml file found in current directory or parent directories");
+        process::exit(EXIT_NO_HOOKS);
+    };
+    let config = peter_hook::HookConfig::from_file(&config_path)
+        .with_context(|| format!("Failed to load {}", config_path.display()))?;
+
+    let events: Vec<_> = peter_hook::git::SUPPORTED_HOOKS
+        .iter()
+        .filter(|event| config.has_hook(event))
+        .map(|&event| {
+            let kind = match config.groups.as_ref().and_then(|groups| groups.get(event)) {
+                Some(group) if group.placeholder == Some(true) => "placeholder",
+                Some(_) => "group",
+                None => "hook",
+            };
+            (event, kind)
+        })
+        .collect();
+
+    if json {
+        let events: Vec<_> = events
+            .iter()
+            .map(|(event, kind)| serde_json::json!({ "event": event, "kind": kind }))
+            .collect();
+        let output = serde_json::json!({
+            "config": config_path.display().to_string(),
+            "events": events,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Config: {}", config_path.display());
+    if events.is_empty() {
+        println!("No git events are configured");
+        return Ok(());
+    }
+    println!("Configured git events:");
+    for (event, kind) in &events {
+        println!("  {event} ({kind})");
+    }
+
+    Ok(())
+}
+
+/// Print the hooks config file that `run` and `lint` would use from `dir`
+fn print_config_path(dir: Option<&Path>) -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let dir = dir.map_or_else(|| current_dir.clone(), |dir| current_dir.join(dir));
+    if !dir.is_dir() {
+        anyhow::bail!("Not a directory: {}", dir.display());
+    }
+
+    let Some(config_path) = HookResolver::new(&dir).find_config_file()? else {
+        eprintln!(
+            "No hooks config file found in {} or its parent directories",
+            dir.display()
+        );
+        process::exit(EXIT_NO_HOOKS);
+    };
+    println!("{}", config_path.display());
+    Ok(())
+}
+
+/// List the hooks and groups `lint` can run from the nearest config
+fn list_lint_targets(json: bool) -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let resolver = HookResolver::new(&current_dir);
+
+    let Some(config_path) = resolver.find_config_file()? else {
+        println!("No hooks.toml file found in current directory or parent directories");
+        process::exit(EXIT_NO_HOOKS);
+    };
+    let config = peter_hook::HookConfig::from_file(&config_path)
+        .with_context(|| format!("Failed to load {}", config_path.display()))?;
+
+    let targets: Vec<_> = config
+        .get_hook_names()
+        .into_iter()
+        .map(|name| {
+            let (kind, description) =
+                match config.groups.as_ref().and_then(|groups| groups.get(&name)) {
+                    Some(group) => ("group", group.description.clone()),
+                    None => (
+                        "hook",
+                        config
+                            .hooks
+                            .as_ref()
+                            .and_then(|hooks| hooks.get(&name))
+                            .and_then(|hook| hook.description.clone()),
+                    ),
+                };
+            (name, kind, description)
+        })
+        .collect();
+
+    if json {
+        let targets: Vec<_> = targets
+            .iter()
+            .map(|(name, kind, description)| {
+                serde_json::json!({ "name": name, "kind": kind, "description": description })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "config": config_path.display().to_string(),
+            "targets": targets,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Config: {}", config_path.display());
+    if targets.is_empty() {
+        println!("No hooks or groups are defined");
+        return Ok(());
+    }
+    println!("Lint targets:");
+    for (name, kind, description) in &targets {
+        match description {
+            Some(description) => println!("  {name} ({kind}) - {description}"),
+            None => println!("  {name} ({kind})"),
+        }
+    }
+
+    Ok(())
+}
+
 /// Show version information
-fn show_version() {
+#[allow(clippy::unnecessary_wraps)] // Same shape as the other command handlers
+fn show_version() -> Result<()> {
     println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    Ok(())
+}
+
+/// Print the shell completion script
+#[allow(clippy::unnecessary_wraps)] // Same shape as the other command handlers
+fn print_completions(shell: clap_complete::Shell) -> Result<()> {
+    peter_hook::completions::generate_completions(shell);
+    Ok(())
 }
 
 /// Show license information
-fn show_license() {
+#[allow(clippy::unnecessary_wraps)] // Same shape as the other command handlers
+fn show_license() -> Result<()> {
     println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
     println!("License: {}", env!("CARGO_PKG_LICENSE"));
     println!("Repository: {}", env!("CARGO_PKG_REPOSITORY"));
@@ -238,16 +638,178 @@ fn show_license() {
     println!("LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,");
     println!("OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE");
     println!("SOFTWARE.");
+    Ok(())
+}
+
+/// Enable debug output and the `--log-file` logger if requested
+fn init_diagnostics(debug: bool, log_file: Option<&std::path::Path>) -> Result<()> {
+    if debug {
+        debug::enable();
+    }
+
+    if let Some(log_file) = log_file {
+        peter_hook::logging::init(log_file, peter_hook::logging::level_for(debug))?;
+        log::info!(
+            "peter-hook {} started args={:?}",
+            env!("CARGO_PKG_VERSION"),
+            env::args().skip(1).collect::<Vec<_>>()
+        );
+    }
+
+    Ok(())
+}
+
+/// What `peter-hook run` should do with the resolved hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunMode {
+    /// Execute the hooks
+    Execute,
+    /// Show what would run
+    DryRun,
+    /// Show the files each hook would receive
+    ListFiles,
+    /// Show the files each hook would receive, as JSON
+    ListFilesJson,
+    /// Show the environment each hook would be given
+    DumpEnv {
+        /// Whether sensitive values are hidden
+        redact: bool,
+    },
+}
+
+impl RunMode {
+    /// Pick the mode from the `run` flags (clap rejects conflicting ones)
+    #[allow(clippy::fn_params_excessive_bools)]
+    const fn from_flags(
+        dry_run: bool,
+        list_files: bool,
+        json: bool,
+        dump_env: bool,
+        no_redact: bool,
+    ) -> Self {
+        match (dry_run, list_files, json, dump_env) {
+            (true, _, _, _) => Self::DryRun,
+            (_, true, true, _) => Self::ListFilesJson,
+            (_, true, false, _) => Self::ListFiles,
+            (_, _, _, true) => Self::DumpEnv { redact: !no_redact },
+            _ => Self::Execute,
+        }
+    }
+}
+
+/// How `peter-hook run` detects changes and runs the hooks it resolves
+#[allow(clippy::struct_excessive_bools)] // one per `run` flag
+struct RunOptions<'a> {
+    /// Arguments passed from git
+    git_args: &'a [String],
+    /// How to detect changed files, if the event uses them
+    change_mode: Option<&'a ChangeDetectionMode>,
+    /// Where to write the detected changed files
+    changed_files_output: Option<&'a Path>,
+    /// Exit after writing `changed_files_output`
+    changed_files_only: bool,
+    /// What to do with the resolved hooks
+    mode: RunMode,
+    /// Skip configs whose hooks match no changed file
+    affected_only: bool,
+    /// Resolve hooks from the repository root
+    from_root: bool,
+    /// Only read the configs that govern changed files
+    only_modified_configs: bool,
+    /// Allow events that aren't git hook names
+    allow_custom_event: bool,
+    /// Skip the `security.require_trust` check
+    no_trust_check: bool,
+    /// Skip the hooks resolved before this one
+    continue_from: Option<&'a str>,
+    /// Environment variables set for every hook
+    run_env: IndexMap<String, String>,
+    /// Only run hooks with these tags
+    tag_filter: &'a TagFilter,
+    /// Strategy overriding every group's `execution`
+    strategy: Option<ExecutionStrategy>,
+    /// Repeated execution for flakiness detection
+    repeat: RepeatArgs,
+    /// Stop starting hooks after this many failures
+    bail_after: Option<usize>,
+    /// Where to write each hook's output logs
+    output_dir: Option<&'a Path>,
+    /// Fail hooks with an output line matching this pattern
+    fail_on: Option<&'a str>,
+    /// Ignore output lines matching this pattern for `fail_on`
+    allow: Option<&'a str>,
 }
 
 /// Run hooks for a specific git event
 #[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
-fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool) -> Result<()> {
+fn run_hooks(event: &str, options: RunOptions<'_>) -> Result<()> {
+    let RunOptions {
+        git_args,
+        change_mode,
+        changed_files_output,
+        changed_files_only,
+        mode,
+        affected_only,
+        from_root,
+        only_modified_configs,
+        allow_custom_event,
+        no_trust_check,
+        continue_from,
+        mut run_env,
+        tag_filter,
+        strategy,
+        repeat,
+        bail_after,
+        output_dir,
+        fail_on,
+        allow,
+    } = options;
+    let dry_run = mode == RunMode::DryRun;
+    if !allow_custom_event && !peter_hook::git::is_git_hook_event(event) {
+        // A typo would otherwise look like an event nobody configured
+        match peter_hook::git::closest_hook_event(event) {
+            Some(hook) => {
+                eprintln!("Error: '{event}' is not a git hook event. Did you mean '{hook}'?");
+            }
+            None => eprintln!("Error: '{event}' is not a git hook event"),
+        }
+        eprintln!("Use --allow-custom-event to run the hooks named '{event}' anyway");
+        process::exit(EXIT_USAGE_ERROR);
+    }
+    for (flag, pattern) in [("--fail-on", fail_on), ("--allow", allow)] {
+        if let Some(pattern) = pattern {
+            if let Err(e) = peter_hook::config::compile_output_pattern(pattern) {
+                eprintln!("Error: Invalid {flag} pattern: {e:#}");
+                process::exit(EXIT_USAGE_ERROR);
+            }
+        }
+    }
     let current_dir = env::current_dir().context("Failed to get current working directory")?;
 
     // Get repository information for hierarchical resolution
     let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
 
+    // `post-rewrite` checks the files of every rewritten commit, not just
+    // `HEAD`'s, unless the files were chosen another way
+    let rewrites = read_rewrites(event, git_args, change_mode)?;
+    let rewritten_mode = rewrites
+        .as_deref()
+        .filter(|_| matches!(change_mode, Some(ChangeDetectionMode::CommitRange { .. })))
+        .and_then(|rewrites| peter_hook::git::rewritten_commits_mode(rewrites, &repo.root));
+    let change_mode = rewritten_mode.as_ref().or(change_mode);
+    if let Some(rewrites) = &rewrites {
+        run_env
+            .entry(peter_hook::git::REWRITES_ENV.to_string())
+            .or_insert_with(|| peter_hook::git::rewrites_env_value(rewrites));
+    }
+
+    if let Some(path) = changed_files_output {
+        write_changed_files(event, change_mode, &repo.root, path)?;
+        if changed_files_only {
+            return Ok(());
+        }
+    }
+
     // Create worktree context
     let worktree_context = peter_hook::hooks::WorktreeContext {
         is_worktree: repo.is_worktree,
@@ -255,43 +817,98 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
         repo_root: repo.root.clone(),
         common_dir: repo.common_dir.clone(),
         working_dir: current_dir.clone(),
+        run_env,
+        push_refs: None,
     };
 
-    // Determine change detection mode based on event type (unless --all-files is
-    // specified)
-    let change_mode = if all_files {
-        None // No file filtering when --all-files is specified
-    } else {
-        match event {
-            "pre-commit" => Some(ChangeDetectionMode::Staged),
-            "pre-push" => Some(ChangeDetectionMode::Push {
-                remote: "origin".to_string(),
-                remote_branch: "main".to_string(), // TODO: detect actual default branch
-            }),
-            "commit-msg" | "prepare-commit-msg" => None, // Message hooks don't filter by files
-            "post-commit" | "post-merge" | "post-checkout" => {
-                Some(ChangeDetectionMode::CommitRange {
-                    from: "HEAD^".to_string(),
-                    to: "HEAD".to_string(),
-                })
-            }
-            _ => Some(ChangeDetectionMode::WorkingDirectory), // Default for other hooks
-        }
-    };
+    log::info!("run event={event} mode={mode:?} continue_from={continue_from:?}");
 
-    // Use hierarchical resolution to find hooks for each changed file
-    let groups = peter_hook::hooks::resolve_hooks_hierarchically(
-        event,
-        change_mode,
-        &repo.root,
-        &current_dir,
-        &worktree_context,
-    )
+    // With `--root` the config is looked up as if run from the repository
+    // root; hooks still see the real current directory as `{WORKING_DIR}`
+    let resolve_dir = if from_root { &repo.root } else { &current_dir };
+
+    // Use hierarchical resolution to find hooks for each changed file; events
+    // without changed files have nothing to prune by
+    let mut groups = match change_mode.filter(|_| only_modified_configs) {
+        Some(mode) => {
+            peter_hook::hooks::resolve_modified_configs(event, mode, &repo.root, &worktree_context)
+        }
+        None => peter_hook::hooks::resolve_hooks_hierarchically(
+            event,
+            change_mode.cloned(),
+            &repo.root,
+            resolve_dir,
+            &worktree_context,
+        ),
+    }
     .context("Failed to resolve hooks hierarchically")?;
 
+    let push_refs = read_push_refs(event, git_args, change_mode, &groups)?;
+
+    // Configs resolved from the current directory build their own context, so
+    // make sure every group carries the run-wide `--env` values
+    for group in &mut groups {
+        let context = &mut group.resolved_hooks.worktree_context;
+        context.run_env.clone_from(&worktree_context.run_env);
+        context.push_refs.clone_from(&push_refs);
+        group.resolved_hooks.add_output_patterns(fail_on, allow);
+        if let Some(strategy) = strategy {
+            group.resolved_hooks.execution_strategy = strategy;
+        }
+        group.resolved_hooks.bail_after = bail_after;
+    }
+
+    if affected_only {
+        peter_hook::hooks::retain_affected(&mut groups)?;
+        if groups.is_empty() {
+            println!("No subprojects affected by the changed files for event: {event}");
+            return Ok(());
+        }
+    }
+
+    if !tag_filter.is_empty() && !groups.is_empty() {
+        for group in &mut groups {
+            group.resolved_hooks.retain_tagged(tag_filter);
+        }
+        groups.retain(|group| !group.resolved_hooks.hooks.is_empty());
+        log::info!("tag filter {tag_filter:?} kept {} config(s)", groups.len());
+
+        if groups.is_empty() {
+            println!("No hooks match the tag filter for event: {event}");
+            return Ok(());
+        }
+    }
+
+    if event == "post-checkout" && !is_branch_checkout(git_args) && !groups.is_empty() {
+        for group in &mut groups {
+            group.resolved_hooks.skip_branch_change_hooks();
+        }
+        groups.retain(|group| !group.resolved_hooks.hooks.is_empty());
+        log::info!("file checkout kept {} config(s)", groups.len());
+
+        if groups.is_empty() {
+            println!("No hooks to run: the checkout was not a branch switch");
+            return Ok(());
+        }
+    }
+
+    if matches!(mode, RunMode::ListFiles | RunMode::ListFilesJson) {
+        return print_file_plan(event, change_mode, &groups, mode == RunMode::ListFilesJson);
+    }
+    if let RunMode::DumpEnv { redact } = mode {
+        return print_env_plan(event, &groups, redact);
+    }
+    if mode == RunMode::Execute && !no_trust_check {
+        let config_paths = groups
+            .iter()
+            .map(|group| group.resolved_hooks.config_path.as_path())
+            .collect();
+        ensure_trusted(&repo.root, &config_paths)?;
+    }
+
     if groups.is_empty() {
         // No config groups found
-        if io::stdout().is_terminal() {
+        if output::stdout_styled() {
             println!("❌ \x1b[33mNo hooks configured for event:\x1b[0m \x1b[1m{event}\x1b[0m");
             println!("💡 \x1b[36mTip:\x1b[0m Check your \x1b[33mhooks.toml\x1b[0m configuration");
         } else {
@@ -303,7 +920,7 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
         // resolved_hooks for display purposes
         let first_resolved = &groups[0].resolved_hooks;
         let resolved_hooks = first_resolved;
-        if debug::is_enabled() && io::stdout().is_terminal() {
+        if debug::is_enabled() && output::stdout_styled() {
             println!(
                 "\x1b[38;5;201m🎪 \x1b[1m\x1b[38;5;51mPETER-HOOK EXECUTION \
                      EXTRAVAGANZA!\x1b[0m"
@@ -395,7 +1012,7 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
             }
 
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
-        } else if io::stdout().is_terminal() {
+        } else if output::stdout_styled() {
             // Fun terminal output when writing to TTY
             println!("\n🎯 \x1b[1m\x1b[36mHook Configuration Found\x1b[0m");
             println!("📂 \x1b[33m{}\x1b[0m", resolved_hooks.config_path.display());
@@ -487,7 +1104,7 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
 
         // Handle dry-run mode
         if dry_run {
-            if io::stdout().is_terminal() {
+            if output::stdout_styled() {
                 println!("🔍 \x1b[1m\x1b[36mDry Run Mode\x1b[0m - showing what would execute:");
                 println!(
                     "📋 \x1b[33m{}\x1b[0m hooks would run:",
@@ -495,11 +1112,11 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
                 );
 
                 for (name, hook) in &resolved_hooks.hooks {
-                    let cmd_str = match &hook.definition.command {
-                        HookCommand::Shell(cmd) => cmd.clone(),
-                        HookCommand::Args(args) => args.join(" "),
-                    };
+                    let cmd_str = hook.definition.command.to_string();
                     println!("   🎯 \x1b[36m{name}\x1b[0m: \x1b[90m{cmd_str}\x1b[0m");
+                    if let Some(ref description) = hook.definition.description {
+                        println!("      📝 {description}");
+                    }
                     println!(
                         "      📂 Working dir: \x1b[90m{}\x1b[0m",
                         hook.working_directory.display()
@@ -539,11 +1156,9 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
                     event
                 );
                 for (name, hook) in &resolved_hooks.hooks {
-                    let cmd_str = match &hook.definition.command {
-                        HookCommand::Shell(cmd) => cmd.clone(),
-                        HookCommand::Args(args) => args.join(" "),
-                    };
-                    println!("  {name} - {cmd_str}");
+                    let cmd_str = hook.definition.command.to_string();
+                    let label = output::hook_label(name, hook.definition.description.as_deref());
+                    println!("  {label} - {cmd_str}");
                 }
                 if let Some(ref changed_files) = resolved_hooks.changed_files {
                     println!("Changed files: {}", changed_files.len());
@@ -554,10 +1169,37 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
             return Ok(());
         }
 
+        if let Some(times) = repeat.repeat {
+            let report = RepeatReport::run(times, repeat.repeat_until_fail, || {
+                HookExecutor::execute_multiple_from(&groups, continue_from)
+            })
+            .context("Failed to execute hooks")?;
+            return finish_repeat(&report, output_dir);
+        }
+
         // Execute all config groups hierarchically
-        let results = HookExecutor::execute_multiple(&groups).context("Failed to execute hooks")?;
+        let results = HookExecutor::execute_multiple_from(&groups, continue_from)
+            .context("Failed to execute hooks")?;
+
+        // Runs limited to some files or hooks don't vouch for everything
+        // since the last marker
+        if results.success
+            && continue_from.is_none()
+            && tag_filter.is_empty()
+            && !matches!(change_mode, Some(ChangeDetectionMode::FileList { .. }))
+        {
+            if let Err(e) = LastRunMarker::record(&repo.git_dir, &repo.root, event) {
+                log::warn!("failed to record run marker for {event}: {e:#}");
+            }
+        }
+        record_timings(&repo.git_dir, &results);
+
+        if let Some(dir) = output_dir {
+            results.write_logs(dir)?;
+            println!("Hook logs written to {}", dir.display());
+        }
 
-        if debug::is_enabled() && io::stdout().is_terminal() {
+        if debug::is_enabled() && output::stdout_styled() {
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
             if results.success {
                 println!(
@@ -582,7 +1224,7 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
             }
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
             results.print_summary();
-        } else if !debug::is_enabled() && io::stdout().is_terminal() {
+        } else if !debug::is_enabled() && output::stdout_styled() {
             // Fun completion message for successful runs (non-debug TTY output)
             if results.success {
                 let success_messages = [
@@ -596,12 +1238,17 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
                 println!("\n{message}");
 
                 // Show quick summary without hook output (happy path)
-                let passed_count = results.results.len();
+                let advisory = results.get_advisory_failures();
+                let passed_count = results.results.len() - advisory.len();
                 println!(
                     "✅ \x1b[32m{}\x1b[0m hook{} completed successfully\n",
                     passed_count,
                     if passed_count == 1 { "" } else { "s" }
                 );
+                // Failures that don't block still need to be seen
+                if !advisory.is_empty() {
+                    results.print_summary();
+                }
             } else {
                 println!("\n💥 \x1b[31mSome hooks failed!\x1b[0m");
                 let failed = results.get_failed_hooks();
@@ -616,124 +1263,340 @@ fn run_hooks(event: &str, _git_args: &[String], all_files: bool, dry_run: bool)
         }
 
         if !results.success {
-            process::exit(1);
+            process::exit(EXIT_HOOK_FAILURE);
         }
     }
 
     Ok(())
 }
 
+/// Print the effective configuration as a standalone `hooks.toml`
+fn explain_config() -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let resolver = HookResolver::new(&current_dir);
+
+    let Some(config_path) = resolver.find_config_file()? else {
+        println!("No hooks.toml file found in current directory or parent directories");
+        process::exit(EXIT_NO_HOOKS);
+    };
+
+    match peter_hook::HookConfig::from_file(&config_path) {
+        Ok(config) => {
+            println!("# Effective configuration for {}", config_path.display());
+            println!("# (imports merged, overrides applied)");
+            println!();
+            print!("{}", config.to_toml()?);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("✗ Configuration is invalid: {e:#}");
+            process::exit(exit_code_for(&e));
+        }
+    }
+}
+
 /// Validate hook configuration
-fn validate_config(trace_imports: bool, json: bool) -> Result<()> {
+fn validate_config(
+    trace_imports: bool,
+    json: bool,
+    explain: bool,
+    match_paths: &[PathBuf],
+) -> Result<()> {
+    if explain {
+        return explain_config();
+    }
     let current_dir = env::current_dir().context("Failed to get current working directory")?;
 
     let resolver = HookResolver::new(&current_dir);
 
-    match resolver.find_config_file()? {
-        Some(config_path) => {
-            println!("Validating config file: {}", config_path.display());
+    let Some(config_path) = resolver.find_config_file()? else {
+        println!("No hooks.toml file found in current directory or parent directories");
+        process::exit(EXIT_NO_HOOKS);
+    };
 
-            // Try to parse the configuration
-            if trace_imports {
-                match peter_hook::HookConfig::from_file_with_trace(&config_path) {
-                    Ok((config, diag)) => {
-                        println!("✓ Configuration is valid");
+    println!("Validating config file: {}", config_path.display());
 
-                        let hook_names = config.get_hook_names();
-                        if hook_names.is_empty() {
-                            println!("  No hooks or groups defined");
-                        } else {
-                            println!("  Found {} hooks/groups:", hook_names.len());
-                            for name in hook_names {
-                                println!("    - {name}");
-                            }
-                        }
+    // Try to parse the configuration
+    if trace_imports {
+        match peter_hook::HookConfig::from_file_with_trace(&config_path) {
+            Ok((config, diag)) => {
+                println!("✓ Configuration is valid");
 
-                        if json {
-                            // Print diagnostics as JSON
-                            match serde_json::to_string_pretty(&diag) {
-                                Ok(s) => println!("{s}"),
-                                Err(e) => eprintln!("Failed to serialize diagnostics: {e:#}"),
-                            }
-                        } else {
-                            // Human-readable diagnostics
-                            if diag.imports.is_empty() {
-                                println!("(no imports)");
-                            } else {
-                                println!("Imports (order):");
-                                for r in &diag.imports {
-                                    println!("  {} -> {}", r.from, r.resolved);
-                                }
-                            }
-                            if !diag.overrides.is_empty() {
-                                println!("Overrides:");
-                                for o in &diag.overrides {
-                                    println!(
-                                        "  {} {}: {} -> {}",
-                                        o.kind, o.name, o.previous, o.new
-                                    );
-                                }
-                            }
-                            if !diag.cycles.is_empty() {
-                                println!("Cycles (skipped):");
-                                for c in &diag.cycles {
-                                    println!("  {c}");
-                                }
-                            }
-                            if !diag.unused.is_empty() {
-                                println!("Unused imports (no contributions):");
-                                for u in &diag.unused {
-                                    println!("  {u}");
-                                }
+                print_hook_names(&config);
+                print_pattern_matches(&config, match_paths);
+
+                if json {
+                    // Print diagnostics as JSON
+                    match serde_json::to_string_pretty(&diag) {
+                        Ok(s) => println!("{s}"),
+                        Err(e) => eprintln!("Failed to serialize diagnostics: {e:#}"),
+                    }
+                } else {
+                    // Human-readable diagnostics
+                    if diag.imports.is_empty() {
+                        println!("(no imports)");
+                    } else {
+                        println!("Imports (order):");
+                        for r in &diag.imports {
+                            println!("  {} -> {}", r.from, r.resolved);
+                        }
+                    }
+                    if !diag.overrides.is_empty() {
+                        println!("Overrides:");
+                        for o in &diag.overrides {
+                            println!("  {} {}: {} -> {}", o.kind, o.name, o.previous, o.new);
+                            for change in &o.changes {
+                                println!("    ⚠️  changes {change}");
                             }
                         }
                     }
-                    Err(e) => {
-                        eprintln!("✗ Configuration is invalid: {e:#}");
-                        process::exit(1);
+                    if !diag.cycles.is_empty() {
+                        println!("Cycles (skipped):");
+                        for c in &diag.cycles {
+                            println!("  {c}");
+                        }
                     }
-                }
-            } else {
-                match peter_hook::HookConfig::from_file(&config_path) {
-                    Ok(config) => {
-                        println!("✓ Configuration is valid");
-
-                        let hook_names = config.get_hook_names();
-                        if hook_names.is_empty() {
-                            println!("  No hooks or groups defined");
-                        } else {
-                            println!("  Found {} hooks/groups:", hook_names.len());
-                            for name in hook_names {
-                                println!("    - {name}");
-                            }
+                    if !diag.skipped.is_empty() {
+                        println!("Skipped imports (condition not met):");
+                        for s in &diag.skipped {
+                            println!("  {} -> {} (when {})", s.from, s.resolved, s.when);
+                        }
+                    }
+                    if !diag.unused.is_empty() {
+                        println!("Unused imports (no contributions):");
+                        for u in &diag.unused {
+                            println!("  {u}");
                         }
                     }
-                    Err(e) => {
-                        eprintln!("✗ Configuration is invalid: {e:#}");
-                        process::exit(1);
+                    if let Some(local) = &diag.local_override {
+                        print_local_override(local);
                     }
                 }
             }
+            Err(e) => {
+                eprintln!("✗ Configuration is invalid: {e:#}");
+                process::exit(exit_code_for(&e));
+            }
         }
-        None => {
-            println!("No hooks.toml file found in current directory or parent directories");
+    } else {
+        match peter_hook::HookConfig::from_file_with_trace(&config_path) {
+            Ok((config, diag)) => {
+                println!("✓ Configuration is valid");
+
+                print_hook_names(&config);
+                print_pattern_matches(&config, match_paths);
+                print_changed_overrides(&diag);
+            }
+            Err(e) => {
+                eprintln!("✗ Configuration is invalid: {e:#}");
+                process::exit(exit_code_for(&e));
+            }
         }
     }
 
     Ok(())
 }
 
-/// Run hooks in lint mode
-#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
-fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
-    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+/// Warn about hooks whose override runs differently from the imported
+/// definition it replaced
+fn print_changed_overrides(diag: &peter_hook::config::ImportDiagnostics) {
+    for o in diag.overrides.iter().filter(|o| !o.changes.is_empty()) {
+        println!(
+            "⚠️  Hook '{}' in {} overrides {} and runs differently: {}",
+            o.name,
+            o.new,
+            o.previous,
+            o.changes.join("; ")
+        );
+    }
+}
 
-    let resolver = HookResolver::new(&current_dir);
+/// Print what a `hooks.local.toml` file changed, for `validate --trace-imports`
+fn print_local_override(local: &peter_hook::config::LocalOverrideRecord) {
+    println!("Local override (not shared): {}", local.path);
+    for hook in &local.hooks {
+        let mut changes = Vec::new();
+        match hook.enabled {
+            Some(true) => changes.push("enabled".to_string()),
+            Some(false) => changes.push("disabled".to_string()),
+            None => {}
+        }
+        if !hook.env.is_empty() {
+            changes.push(format!("env {}", hook.env.join(", ")));
+        }
+        println!("  hook {}: {}", hook.name, changes.join("; "));
+    }
+}
 
-    if let Some(resolved_hooks) = resolver.resolve_hooks_for_lint(hook_name)? {
-        if debug::is_enabled() && io::stdout().is_terminal() {
-            println!("\x1b[38;5;201m🎪 \x1b[1m\x1b[38;5;51mPETER-HOOK LINT MODE!\x1b[0m");
-            println!(
+/// Print the hooks and groups defined in a validated config, with their tags
+fn print_hook_names(config: &peter_hook::HookConfig) {
+    let hook_names = config.get_hook_names();
+    if hook_names.is_empty() {
+        println!("  No hooks or groups defined");
+        return;
+    }
+
+    println!("  Found {} hooks/groups:", hook_names.len());
+    for name in hook_names {
+        let tags = config
+            .hooks
+            .as_ref()
+            .and_then(|hooks| hooks.get(&name))
+            .and_then(|hook| hook.tags.as_ref())
+            .filter(|tags| !tags.is_empty());
+        match tags {
+            Some(tags) => println!("    - {name} [{}]", tags.join(", ")),
+            None => println!("    - {name}"),
+        }
+    }
+}
+
+/// Print a grid of which `validate --match` paths each hook's `files`
+/// patterns match
+fn print_pattern_matches(config: &peter_hook::HookConfig, paths: &[PathBuf]) {
+    if paths.is_empty() {
+        return;
+    }
+    let Some(hooks) = config.hooks.as_ref().filter(|hooks| !hooks.is_empty()) else {
+        println!("No hooks to match against");
+        return;
+    };
+
+    // Paths are matched like changed files: absolute paths by their path
+    // relative to the repository root, and without a leading `./`
+    let repo_root = env::current_dir()
+        .ok()
+        .and_then(|dir| GitRepository::find_from_dir(&dir).ok())
+        .map(|repo| repo.root);
+    let paths: Vec<&Path> = paths
+        .iter()
+        .map(|path| path.strip_prefix(".").unwrap_or(path))
+        .collect();
+    let headers: Vec<String> = paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect();
+    let name_width = hooks
+        .keys()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max("hook".len());
+
+    println!();
+    println!("File pattern matches:");
+    print!("  {:name_width$}", "hook");
+    for header in &headers {
+        print!("  {header}");
+    }
+    println!();
+
+    for (name, hook) in hooks {
+        let mut cells = vec![format!("  {name:name_width$}")];
+        let matcher = hook
+            .files
+            .as_deref()
+            .map(FilePatternMatcher::new)
+            .transpose()
+            .map(|matcher| {
+                matcher.map(|matcher| match &repo_root {
+                    Some(root) => matcher.relative_to(root),
+                    None => matcher,
+                })
+            });
+        match matcher {
+            Ok(matcher) => {
+                for (path, header) in paths.iter().zip(&headers) {
+                    let hit = matcher.as_ref().is_none_or(|m| m.matches(path));
+                    let mark = if hit { "✓" } else { "✗" };
+                    cells.push(format!("{mark:width$}", width = header.chars().count()));
+                }
+                if matcher.is_none() {
+                    cells.push("(no files patterns, matches everything)".to_string());
+                }
+            }
+            Err(e) => cells.push(format!("{e:#}")),
+        }
+        println!("{}", cells.join("  ").trim_end());
+    }
+}
+
+/// Print the tally of a `--repeat` run, write the last run's logs, and exit
+/// with a hook failure if any run failed
+fn finish_repeat(report: &RepeatReport, output_dir: Option<&Path>) -> Result<()> {
+    if let (Some(dir), Some(results)) = (output_dir, report.last.as_ref()) {
+        results.write_logs(dir)?;
+        println!("Hook logs of the last run written to {}", dir.display());
+    }
+    report.print_summary();
+    if !report.success() {
+        process::exit(EXIT_HOOK_FAILURE);
+    }
+    Ok(())
+}
+
+/// How `peter-hook lint` runs the hooks it resolves
+#[allow(clippy::struct_excessive_bools)] // one per `lint` flag
+struct LintOptions {
+    /// Show what would run without executing hooks
+    dry_run: bool,
+    /// Only run hooks with these tags
+    tag_filter: TagFilter,
+    /// Repeated execution for flakiness detection
+    repeat: RepeatArgs,
+    /// Stop starting hooks after this many failures
+    bail_after: Option<usize>,
+    /// Re-run the hooks matching each batch of changed files
+    watch: bool,
+    /// Run each hook's `fix_command` instead of its command
+    fix: bool,
+    /// Skip the `security.require_trust` check
+    no_trust_check: bool,
+}
+
+/// Run hooks in lint mode
+#[allow(clippy::cognitive_complexity, clippy::too_many_lines)]
+fn run_lint_mode(hook_name: &str, options: &LintOptions) -> Result<()> {
+    let LintOptions {
+        dry_run,
+        ref tag_filter,
+        repeat,
+        bail_after,
+        watch,
+        fix,
+        no_trust_check,
+    } = *options;
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+
+    let resolver = HookResolver::new(&current_dir);
+
+    let mut lint_hooks = resolver
+        .resolve_hooks_for_lint(hook_name)?
+        .map(|mut resolved_hooks| {
+            resolved_hooks.retain_tagged(tag_filter);
+            resolved_hooks.bail_after = bail_after;
+            resolved_hooks
+        });
+    if fix {
+        if let Some(resolved_hooks) = lint_hooks.as_mut() {
+            resolved_hooks
+                .use_fix_commands()
+                .with_context(|| format!("Cannot fix with '{hook_name}'"))?;
+        }
+    }
+
+    if lint_hooks
+        .as_ref()
+        .is_some_and(|resolved_hooks| resolved_hooks.hooks.is_empty())
+    {
+        println!("No hooks in '{hook_name}' match the tag filter");
+        return Ok(());
+    }
+
+    if let Some(resolved_hooks) = lint_hooks {
+        if debug::is_enabled() && output::stdout_styled() {
+            println!("\x1b[38;5;201m🎪 \x1b[1m\x1b[38;5;51mPETER-HOOK LINT MODE!\x1b[0m");
+            println!(
                 "\x1b[38;5;198m📋 Config: \x1b[38;5;87m{}\x1b[0m",
                 resolved_hooks.config_path.display()
             );
@@ -764,7 +1627,7 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
             }
 
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
-        } else if io::stdout().is_terminal() {
+        } else if output::stdout_styled() {
             println!("\n🎯 \x1b[1m\x1b[36mLint Mode:\x1b[0m \x1b[1m\x1b[33m{hook_name}\x1b[0m");
             println!("📂 \x1b[33m{}\x1b[0m", resolved_hooks.config_path.display());
 
@@ -791,15 +1654,15 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
 
         // Handle dry-run mode
         if dry_run {
-            if io::stdout().is_terminal() {
+            if output::stdout_styled() {
                 println!("🔍 \x1b[1m\x1b[36mDry Run Mode\x1b[0m - showing what would execute:");
 
                 for (name, hook) in &resolved_hooks.hooks {
-                    let cmd_str = match &hook.definition.command {
-                        HookCommand::Shell(cmd) => cmd.clone(),
-                        HookCommand::Args(args) => args.join(" "),
-                    };
+                    let cmd_str = hook.definition.command.to_string();
                     println!("   🎯 \x1b[36m{name}\x1b[0m: \x1b[90m{cmd_str}\x1b[0m");
+                    if let Some(ref description) = hook.definition.description {
+                        println!("      📝 {description}");
+                    }
                     println!(
                         "      📂 Working dir: \x1b[90m{}\x1b[0m",
                         hook.working_directory.display()
@@ -817,20 +1680,37 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
                     resolved_hooks.hooks.len()
                 );
                 for (name, hook) in &resolved_hooks.hooks {
-                    let cmd_str = match &hook.definition.command {
-                        HookCommand::Shell(cmd) => cmd.clone(),
-                        HookCommand::Args(args) => args.join(" "),
-                    };
-                    println!("  {name} - {cmd_str}");
+                    let cmd_str = hook.definition.command.to_string();
+                    let label = output::hook_label(name, hook.definition.description.as_deref());
+                    println!("  {label} - {cmd_str}");
                 }
             }
             return Ok(());
         }
 
+        if !no_trust_check {
+            ensure_trusted(
+                &resolved_hooks.worktree_context.repo_root,
+                &BTreeSet::from([resolved_hooks.config_path.as_path()]),
+            )?;
+        }
+
+        if let Some(times) = repeat.repeat {
+            let report = RepeatReport::run(times, repeat.repeat_until_fail, || {
+                HookExecutor::execute(&resolved_hooks)
+            })
+            .context("Failed to execute hooks in lint mode")?;
+            return finish_repeat(&report, None);
+        }
+
+        if watch {
+            return watch_lint(&resolved_hooks);
+        }
+
         let results = HookExecutor::execute(&resolved_hooks)
             .context("Failed to execute hooks in lint mode")?;
 
-        if debug::is_enabled() && io::stdout().is_terminal() {
+        if debug::is_enabled() && output::stdout_styled() {
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
             if results.success {
                 println!(
@@ -850,14 +1730,19 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
             }
             println!("\x1b[38;5;198m{}\x1b[0m", "═".repeat(60));
             results.print_summary();
-        } else if !debug::is_enabled() && io::stdout().is_terminal() {
+        } else if !debug::is_enabled() && output::stdout_styled() {
             if results.success {
                 println!("🎉 Lint passed! All checks completed successfully!");
+                let advisory = results.get_advisory_failures();
+                let passed_count = results.results.len() - advisory.len();
                 println!(
                     "✅ \x1b[32m{}\x1b[0m hook{} completed successfully\n",
-                    results.results.len(),
-                    if results.results.len() == 1 { "" } else { "s" }
+                    passed_count,
+                    if passed_count == 1 { "" } else { "s" }
                 );
+                if !advisory.is_empty() {
+                    results.print_summary();
+                }
             } else {
                 println!("💥 \x1b[31mLint failed!\x1b[0m");
                 let failed = results.get_failed_hooks();
@@ -869,10 +1754,10 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
         }
 
         if !results.success {
-            process::exit(1);
+            process::exit(EXIT_HOOK_FAILURE);
         }
     } else {
-        if io::stdout().is_terminal() {
+        if output::stdout_styled() {
             println!("❌ \x1b[31mHook not found:\x1b[0m \x1b[1m{hook_name}\x1b[0m");
             println!(
                 "💡 \x1b[36mTip:\x1b[0m Run \x1b[33mpeter-hook validate\x1b[0m to see available \
@@ -881,12 +1766,38 @@ fn run_lint_mode(hook_name: &str, dry_run: bool) -> Result<()> {
         } else {
             println!("No hook found with name: {hook_name}");
         }
-        process::exit(1);
+        process::exit(EXIT_NO_HOOKS);
     }
 
     Ok(())
 }
 
+/// `lint --watch`: run the hooks once, then again for every batch of changed
+/// files, until interrupted (the interrupt handler stops running hooks)
+fn watch_lint(resolved_hooks: &ResolvedHooks) -> Result<()> {
+    let watcher = ChangeWatcher::new(&resolved_hooks.worktree_context.working_dir, WATCH_DEBOUNCE)?;
+    let mut pending = Some(resolved_hooks.clone());
+
+    loop {
+        if let Some(hooks) = pending.take() {
+            if output::stdout_styled() {
+                // Clear the screen so each run starts at the top
+                print!("\x1b[2J\x1b[H");
+            }
+            let files = hooks.changed_files.as_ref().map_or(0, Vec::len);
+            println!("Running {} hook(s) on {files} file(s)", hooks.hooks.len());
+            HookExecutor::execute(&hooks)
+                .context("Failed to execute hooks in lint mode")?
+                .print_summary();
+            watcher.discard_pending();
+            println!("Watching for changes (Ctrl-C to stop)...");
+        }
+
+        let changed = watcher.next_batch()?;
+        pending = hooks_for_changes(resolved_hooks, &changed)?;
+    }
+}
+
 /// List all worktrees and their hook configuration
 fn list_worktrees() -> Result<()> {
     let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
@@ -916,76 +1827,708 @@ fn list_worktrees() -> Result<()> {
         println!("   Path: {}", worktree.path.display());
 
         // Check for hooks in this worktree
-        let hooks_dir = if worktree.is_main {
-            repo.get_common_hooks_dir().to_path_buf()
-        } else {
-            // For non-main worktrees, check both shared and worktree-specific locations
-            let common_hooks = repo.get_common_hooks_dir();
-            let worktree_hooks = worktree.path.join(".git/hooks");
+        match worktree_hooks_repository(&repo, &worktree) {
+            Some((hooks_repo, "worktree-specific")) => {
+                print_worktree_hooks(&hooks_repo, "worktree-specific");
+            }
+            _ if !worktree.is_main => println!("   Hooks: shared with main worktree"),
+            _ => print_worktree_hooks(&repo_with_common_hooks(&repo), "shared"),
+        }
 
-            if worktree_hooks.exists() {
-                worktree_hooks
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Find the repository view whose hooks directory a worktree actually uses
+///
+/// Returns the worktree's own hooks directory (configured via the worktree
+/// `core.hooksPath` or present on disk) labelled "worktree-specific", or the
+/// shared hooks directory labelled "shared".
+fn worktree_hooks_repository(
+    repo: &GitRepository,
+    worktree: &peter_hook::git::WorktreeInfo,
+) -> Option<(GitRepository, &'static str)> {
+    if worktree.is_main {
+        return Some((repo_with_common_hooks(repo), "shared"));
+    }
+
+    let worktree_repo = GitRepository::find_from_dir(&worktree.path).ok()?;
+    let own_hooks_dir = worktree_repo
+        .get_worktree_hooks_path()
+        .ok()
+        .flatten()
+        .or_else(|| {
+            let dir = worktree_repo.get_worktree_hooks_dir();
+            dir.exists().then_some(dir)
+        });
+
+    Some(match own_hooks_dir {
+        Some(hooks_dir) => (
+            GitRepository {
+                hooks_dir,
+                ..worktree_repo
+            },
+            "worktree-specific",
+        ),
+        None => (repo_with_common_hooks(&worktree_repo), "shared"),
+    })
+}
+
+/// Repository view pointing at the shared hooks directory
+fn repo_with_common_hooks(repo: &GitRepository) -> GitRepository {
+    GitRepository {
+        hooks_dir: repo.common_dir.join("hooks"),
+        ..repo.clone()
+    }
+}
+
+/// Print the hooks of a worktree annotated as managed/custom and executable
+fn print_worktree_hooks(hooks_repo: &GitRepository, hooks_type: &str) {
+    let Ok(hooks) = hooks_repo.list_hooks() else {
+        println!("   Hooks: unable to read hooks directory");
+        return;
+    };
+
+    if hooks.is_empty() {
+        println!("   Hooks: none");
+        return;
+    }
+
+    println!(
+        "   Hooks ({hooks_type}, {}):",
+        hooks_repo.hooks_dir.display()
+    );
+    for hook_name in hooks {
+        if let Ok(Some(info)) = hooks_repo.get_hook_info(&hook_name) {
+            let status = if info.is_managed {
+                "🔧 managed"
             } else {
-                common_hooks.to_path_buf()
-            }
-        };
+                "📄 custom"
+            };
+            let executable = if info.is_executable { "✅" } else { "❌" };
 
-        if hooks_dir.exists() {
-            if let Ok(entries) = std::fs::read_dir(&hooks_dir) {
-                let mut hook_files: Vec<_> = entries
-                    .filter_map(Result::ok)
-                    .filter_map(|entry| {
-                        let path = entry.path();
-                        if path.is_file() {
-                            path.file_name()
-                                .and_then(|name| name.to_str())
-                                .filter(|name| !name.ends_with(".sample") && !name.starts_with('.'))
-                                .map(ToString::to_string)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
+            println!(
+                "     {} {} {} (executable: {})",
+                executable,
+                hook_name,
+                status,
+                if info.is_executable { "yes" } else { "no" }
+            );
+        }
+    }
+}
 
-                hook_files.sort();
+/// Print the files each resolved hook would receive (`run --list-files`)
+fn print_file_plan(
+    event: &str,
+    change_mode: Option<&ChangeDetectionMode>,
+    groups: &[peter_hook::hooks::ConfigGroup],
+    json: bool,
+) -> Result<()> {
+    let change_mode = change_mode.map_or_else(|| "all files".to_string(), ToString::to_string);
+
+    if json {
+        let configs: Vec<_> = groups
+            .iter()
+            .map(|group| {
+                serde_json::json!({
+                    "config": group.config_path,
+                    "hooks": HookExecutor::plan_files(&group.resolved_hooks),
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "event": event,
+            "change_mode": change_mode,
+            "configs": configs,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
-                if hook_files.is_empty() {
-                    println!("   Hooks: none");
-                } else {
-                    let hooks_type = if worktree.is_main || hooks_dir == repo.get_common_hooks_dir()
-                    {
-                        "shared"
-                    } else {
-                        "worktree-specific"
-                    };
-                    println!("   Hooks ({}): {}", hooks_type, hook_files.join(", "));
-                }
+    println!("Event: {event}");
+    println!("Change detection: {change_mode}");
+
+    if groups.is_empty() {
+        println!("No hooks found for event: {event}");
+        return Ok(());
+    }
+
+    for group in groups {
+        println!("\nConfig: {}", group.config_path.display());
+        for plan in HookExecutor::plan_files(&group.resolved_hooks) {
+            let execution_type = serde_json::to_value(plan.execution_type)?;
+            let execution_type = execution_type.as_str().unwrap_or_default();
+            if plan.will_run {
+                println!(
+                    "  {} ({execution_type}) - {} file{} in {}",
+                    plan.hook,
+                    plan.files.len(),
+                    if plan.files.len() == 1 { "" } else { "s" },
+                    plan.execution_dir.display()
+                );
             } else {
-                println!("   Hooks: unable to read hooks directory");
+                println!(
+                    "  {} ({execution_type}) - skipped: no matching files",
+                    plan.hook
+                );
+            }
+            for file in &plan.files {
+                println!("    {}", file.display());
             }
-        } else {
-            println!("   Hooks: none");
         }
+    }
 
-        println!();
+    Ok(())
+}
+
+/// Print the environment variables each hook would be given (`run
+/// --dump-env`), redacting sensitive values unless `redact` is false
+fn print_env_plan(
+    event: &str,
+    groups: &[peter_hook::hooks::ConfigGroup],
+    redact: bool,
+) -> Result<()> {
+    println!("Event: {event}");
+
+    if groups.is_empty() {
+        println!("No hooks found for event: {event}");
+        return Ok(());
+    }
+
+    for group in groups {
+        println!("\nConfig: {}", group.config_path.display());
+        for mut plan in HookExecutor::plan_env(&group.resolved_hooks)? {
+            if redact {
+                plan.redact();
+            }
+            let skipped = if plan.will_run { "" } else { " (skipped)" };
+            if plan.env.is_empty() {
+                println!("  {}{skipped} - no variables set", plan.hook);
+                continue;
+            }
+            println!("  {}{skipped}", plan.hook);
+            for (key, value) in &plan.env {
+                println!("    {key}={value}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle the update command, including `--check` and `--rollback`
+#[allow(clippy::fn_params_excessive_bools)]
+fn run_update(
+    version: Option<&str>,
+    force: bool,
+    install_dir: Option<&std::path::Path>,
+    skip_verify: bool,
+    channel: &str,
+    check: bool,
+    rollback: bool,
+) -> Result<()> {
+    if rollback {
+        let exit_code = peter_hook::update::run_rollback(install_dir);
+        if exit_code != 0 {
+            process::exit(exit_code);
+        }
+        return Ok(());
+    }
+
+    let channel: peter_hook::update::ReleaseChannel = channel
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid release channel: {}", channel))?;
+
+    if check {
+        let exit_code = peter_hook::update::run_check(channel);
+        if exit_code != 0 {
+            process::exit(exit_code);
+        }
+        return Ok(());
+    }
+
+    let options = peter_hook::update::UpdateOptions {
+        skip_verify,
+        channel,
+    };
+    let exit_code =
+        peter_hook::update::run_update_with_options(version, force, install_dir, &options);
+    if exit_code != 0 {
+        process::exit(exit_code);
     }
+    Ok(())
+}
+
+/// Run health checks and exit with their status code on failure
+#[allow(clippy::unnecessary_wraps)] // Same shape as the other command handlers
+fn run_doctor(json: bool) -> Result<()> {
+    let exit_code = if json {
+        peter_hook::doctor::run_doctor_json()
+    } else {
+        peter_hook::doctor::run_doctor()
+    };
+    if exit_code != 0 {
+        process::exit(exit_code);
+    }
+    Ok(())
+}
 
+/// Run the end-to-end self test and exit with its status code on failure
+#[allow(clippy::unnecessary_wraps)] // Same shape as the other command handlers
+fn run_selftest() -> Result<()> {
+    let exit_code = peter_hook::selftest::run_selftest();
+    if exit_code != 0 {
+        process::exit(exit_code);
+    }
     Ok(())
 }
 
+/// Refs being pushed, from the lines git writes to `pre-push`'s stdin
+///
+/// Stdin is only read when git ran the hook (it passes the remote name and
+/// URL), it isn't a terminal or a `--from-stdin` file list, and some hook has
+/// `push_refs`; otherwise hooks that read the refs themselves still get them.
+fn read_push_refs(
+    event: &str,
+    git_args: &[String],
+    change_mode: Option<&ChangeDetectionMode>,
+    groups: &[peter_hook::hooks::ConfigGroup],
+) -> Result<Option<Vec<peter_hook::git::PushRefUpdate>>> {
+    let gated = groups.iter().any(|group| {
+        group
+            .resolved_hooks
+            .hooks
+            .values()
+            .any(|hook| hook.definition.push_refs.is_some())
+    });
+    if event != "pre-push"
+        || git_args.is_empty()
+        || !gated
+        || io::stdin().is_terminal()
+        || matches!(change_mode, Some(ChangeDetectionMode::FileList { .. }))
+    {
+        return Ok(None);
+    }
+
+    let input = io::read_to_string(io::stdin()).context("Failed to read pushed refs from stdin")?;
+    let updates = peter_hook::git::parse_push_refs(&input)?;
+    log::info!(
+        "pre-push refs={:?}",
+        updates.iter().map(|u| &u.remote_ref).collect::<Vec<_>>()
+    );
+    Ok(Some(updates))
+}
+
+/// Commits rewritten by an amend or rebase, from the lines git writes to
+/// `post-rewrite`'s stdin
+///
+/// Stdin is only read when git ran the hook (it passes `amend` or `rebase`)
+/// and it isn't a terminal or a `--from-stdin` file list.
+fn read_rewrites(
+    event: &str,
+    git_args: &[String],
+    change_mode: Option<&ChangeDetectionMode>,
+) -> Result<Option<Vec<peter_hook::git::RewrittenCommit>>> {
+    if event != "post-rewrite"
+        || git_args.is_empty()
+        || io::stdin().is_terminal()
+        || matches!(change_mode, Some(ChangeDetectionMode::FileList { .. }))
+    {
+        return Ok(None);
+    }
+
+    let input =
+        io::read_to_string(io::stdin()).context("Failed to read rewritten commits from stdin")?;
+    let rewrites = peter_hook::git::parse_rewrites(&input)?;
+    log::info!("post-rewrite {} rewrites={}", git_args[0], rewrites.len());
+    Ok(Some(rewrites))
+}
+
+/// Write the changed files `run` detected to `path` (`-` for stdout), one
+/// repository-relative path per line, sorted
+fn write_changed_files(
+    event: &str,
+    change_mode: Option<&ChangeDetectionMode>,
+    repo_root: &Path,
+    path: &Path,
+) -> Result<()> {
+    let Some(change_mode) = change_mode else {
+        anyhow::bail!("{event} doesn't detect changed files, so there is nothing to write");
+    };
+    let mut files = GitChangeDetector::new(repo_root)?
+        .get_changed_files(change_mode)
+        .context("Failed to detect changed files")?;
+    files.sort();
+    let mut list = String::new();
+    for file in &files {
+        list.push_str(&file.to_string_lossy());
+        list.push('\n');
+    }
+
+    if path.as_os_str() == "-" {
+        print!("{list}");
+        io::stdout()
+            .flush()
+            .context("Failed to write changed files")?;
+    } else {
+        std::fs::write(path, list)
+            .with_context(|| format!("Failed to write changed files: {}", path.display()))?;
+    }
+    log::info!(
+        "changed files written count={} mode={change_mode}",
+        files.len()
+    );
+    Ok(())
+}
+
+/// Determine change detection mode based on event type (unless --all-files is
+/// specified)
+fn change_mode_for_event(event: &str, all_files: bool) -> Option<ChangeDetectionMode> {
+    if all_files {
+        return None; // No file filtering when --all-files is specified
+    }
+
+    match event {
+        "pre-commit" => Some(ChangeDetectionMode::Staged),
+        "pre-push" => Some(ChangeDetectionMode::Push {
+            remote: GlobalConfig::load()
+                .unwrap_or_default()
+                .defaults
+                .remote
+                .unwrap_or_else(|| "origin".to_string()),
+            remote_branch: "main".to_string(), // TODO: detect actual default branch
+        }),
+        "commit-msg" | "prepare-commit-msg" => None, // Message hooks don't filter by files
+        // `post-rewrite` widens this to every rewritten commit (see `run_hooks`)
+        "post-commit" | "post-merge" | "post-checkout" | "post-rewrite" => {
+            Some(ChangeDetectionMode::CommitRange {
+                from: "HEAD^".to_string(),
+                to: "HEAD".to_string(),
+            })
+        }
+        _ => Some(ChangeDetectionMode::WorkingDirectory), // Default for other hooks
+    }
+}
+
+/// Change detection for `run`: an explicit file list from `--files-from` /
+/// `--from-stdin`, the changes since the last successful run with
+/// `--since-last-run`, otherwise the mode for the event
+fn run_change_mode(
+    event: &str,
+    all_files: bool,
+    files_from: Option<PathBuf>,
+    from_stdin: bool,
+    since_last_run: bool,
+) -> Result<Option<ChangeDetectionMode>> {
+    if since_last_run {
+        let repo =
+            GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+        let mode = match LastRunMarker::load(&repo.git_dir, event)? {
+            Some(marker) => marker.change_mode(&repo.root)?,
+            None => None,
+        };
+        if mode.is_none() {
+            println!(
+                "No earlier successful run of {event} to compare against; using its usual \
+                 changed files"
+            );
+        }
+        return Ok(mode.or_else(|| change_mode_for_event(event, false)));
+    }
+
+    let source = if from_stdin {
+        Some(PathBuf::from("-"))
+    } else {
+        files_from
+    };
+    let Some(source) = source else {
+        return Ok(change_mode_for_event(event, all_files));
+    };
+
+    let contents = if source.as_os_str() == "-" {
+        io::read_to_string(io::stdin()).context("Failed to read file list from stdin")?
+    } else {
+        std::fs::read_to_string(&source)
+            .with_context(|| format!("Failed to read file list: {}", source.display()))?
+    };
+    let files = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    Ok(Some(ChangeDetectionMode::FileList { files }))
+}
+
+/// Explain why the hooks for an event (or one hook within it) will or will
+/// not run
+fn why_skipped(name: &str, event: Option<&str>, all_files: bool, json: bool) -> Result<()> {
+    let current_dir = env::current_dir().context("Failed to get current working directory")?;
+    let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+    let worktree_context = peter_hook::hooks::WorktreeContext {
+        is_worktree: repo.is_worktree,
+        worktree_name: repo.get_worktree_name().map(ToString::to_string),
+        repo_root: repo.root.clone(),
+        common_dir: repo.common_dir.clone(),
+        working_dir: current_dir.clone(),
+        run_env: IndexMap::new(),
+        push_refs: None,
+    };
+
+    let (event, hook) = event.map_or((name, None), |event| (event, Some(name)));
+    let change_mode = change_mode_for_event(event, all_files);
+    let mut configs = peter_hook::hooks::explain_hooks_hierarchically(
+        event,
+        change_mode.clone(),
+        &repo.root,
+        &current_dir,
+        &worktree_context,
+    )
+    .context("Failed to resolve hooks hierarchically")?;
+
+    if let Some(hook) = hook {
+        for config in &mut configs {
+            config.hooks.retain(|explanation| explanation.hook == hook);
+        }
+        configs.retain(|config| !config.hooks.is_empty());
+    }
+
+    let change_mode = change_mode.map_or_else(|| "all files".to_string(), |m| m.to_string());
+
+    if json {
+        let output = serde_json::json!({
+            "event": event,
+            "hook": hook,
+            "change_mode": change_mode,
+            "configured": !configs.is_empty(),
+            "configs": configs,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!("Event: {event}");
+    println!("Change detection: {change_mode}");
+
+    if configs.is_empty() {
+        match hook {
+            Some(hook) => println!("Hook '{hook}' is not configured for event: {event}"),
+            None => println!("No hooks configured for event: {event}"),
+        }
+        return Ok(());
+    }
+
+    for config in &configs {
+        println!(
+            "\nConfig: {} ({} changed file{})",
+            config.config_path.display(),
+            config.files.len(),
+            if config.files.len() == 1 { "" } else { "s" }
+        );
+        for explanation in &config.hooks {
+            let status = if explanation.will_run {
+                "runs"
+            } else {
+                "skipped"
+            };
+            println!(
+                "  {} {status}: {}",
+                explanation.hook,
+                describe_run_decision(&explanation.decision)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Human-readable reason for a run decision
+fn describe_run_decision(decision: &peter_hook::hooks::RunDecision) -> String {
+    use peter_hook::hooks::RunDecision;
+
+    let join = |files: &[std::path::PathBuf]| {
+        files
+            .iter()
+            .map(|file| file.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match decision {
+        RunDecision::Disabled => "enabled = false".to_string(),
+        RunDecision::RunAlways => "run_always = true".to_string(),
+        RunDecision::OtherExecutionType => {
+            "execution_type = \"other\" runs regardless of changed files".to_string()
+        }
+        RunDecision::MatchedFiles { files } => {
+            format!("{} matching file(s): {}", files.len(), join(files))
+        }
+        RunDecision::NoChangedFiles => "no changed files (and run_always is not set)".to_string(),
+        RunDecision::NoMatchingFiles {
+            patterns,
+            considered,
+        } => format!(
+            "no changed files match {patterns:?} (considered: {})",
+            join(considered)
+        ),
+        RunDecision::RunIfNoChanges => {
+            "run_always_if_no_changes = true and no changed files are left for it".to_string()
+        }
+        RunDecision::OnlyDeletedFiles { files } => format!(
+            "every matching file was deleted ({}); set include_deleted = true to receive them",
+            join(files)
+        ),
+    }
+}
+
 /// Handle global configuration management commands
 fn handle_config_command(subcommand: &ConfigCommand) -> Result<()> {
     match subcommand {
-        ConfigCommand::Show => show_global_config(),
-        ConfigCommand::Init { force, allow_local } => init_global_config(*force, *allow_local),
+        ConfigCommand::Show { format } => show_global_config(format),
+        ConfigCommand::Init {
+            force,
+            allow_local,
+            global_defaults,
+        } => init_global_config(*force, *allow_local, *global_defaults),
         ConfigCommand::Validate => validate_global_config(),
+        ConfigCommand::Get { key } => get_global_setting(key),
+        ConfigCommand::Set { key, value } => set_global_setting(key, value),
+    }
+}
+
+/// Make sure the user trusts every config about to run hooks, when
+/// `security.require_trust` asks for it
+///
+/// Configs that were never trusted, or changed since, are listed; on a
+/// terminal the user is asked to trust them, otherwise the run stops.
+fn ensure_trusted(repo_root: &Path, config_paths: &BTreeSet<&Path>) -> Result<()> {
+    let config = GlobalConfig::load().context("Failed to load global configuration")?;
+    if !config.security.require_trust {
+        return Ok(());
+    }
+
+    let mut store = TrustStore::load().context("Failed to load trust store")?;
+    let mut untrusted = Vec::new();
+    for &config_path in config_paths {
+        let fingerprint = peter_hook::config::config_fingerprint(config_path)?;
+        match store.status(repo_root, config_path, &fingerprint) {
+            TrustStatus::Trusted => {}
+            status => untrusted.push((config_path, fingerprint, status)),
+        }
+    }
+    if untrusted.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!("These hook configs aren't trusted yet:");
+    for (config_path, _, status) in &untrusted {
+        let reason = if *status == TrustStatus::Changed {
+            "changed since it was trusted"
+        } else {
+            "new"
+        };
+        let shown = config_path.strip_prefix(repo_root).unwrap_or(config_path);
+        eprintln!("  {} ({reason})", shown.display());
+    }
+    if !io::stdin().is_terminal() {
+        anyhow::bail!(
+            "Refusing to run untrusted hooks; review the configs, then run 'peter-hook trust' \
+             (or pass --no-trust-check)"
+        );
+    }
+
+    eprint!("Trust them and run their hooks? [y/N]: ");
+    io::stderr().flush().context("Failed to write prompt")?;
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read input")?;
+    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        anyhow::bail!("Hooks not trusted; nothing was run");
+    }
+
+    for (config_path, fingerprint, _) in untrusted {
+        log::info!("trusted config={}", config_path.display());
+        store.trust(repo_root, config_path, fingerprint);
     }
+    store.save()
+}
+
+/// Trust every hook config in the current repository as it is now
+fn trust_repository() -> Result<()> {
+    let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+    let config_paths = peter_hook::hooks::find_all_config_files(&repo.root)?;
+    if config_paths.is_empty() {
+        println!("No hook configs found in {}", repo.root.display());
+        return Ok(());
+    }
+
+    let mut store = TrustStore::load().context("Failed to load trust store")?;
+    // Configs trusted before but since removed are forgotten
+    store.untrust(&repo.root);
+    for config_path in &config_paths {
+        let fingerprint = peter_hook::config::config_fingerprint(config_path)
+            .with_context(|| format!("Failed to load {}", config_path.display()))?;
+        store.trust(&repo.root, config_path, fingerprint);
+    }
+    store.save()?;
+
+    println!("✓ Trusted hook configs in {}:", repo.root.display());
+    for config_path in &config_paths {
+        let shown = config_path.strip_prefix(&repo.root).unwrap_or(config_path);
+        println!("  {}", shown.display());
+    }
+    Ok(())
+}
+
+/// Forget the trusted hook configs of the current repository
+fn untrust_repository() -> Result<()> {
+    let repo = GitRepository::find_from_current_dir().context("Failed to find git repository")?;
+    let mut store = TrustStore::load().context("Failed to load trust store")?;
+    if store.untrust(&repo.root) == 0 {
+        println!("No trusted hook configs in {}", repo.root.display());
+        return Ok(());
+    }
+    store.save()?;
+
+    println!(
+        "✓ Removed trust for hook configs in {}",
+        repo.root.display()
+    );
+    Ok(())
+}
+
+/// Print one global setting
+fn get_global_setting(key: &str) -> Result<()> {
+    let config = GlobalConfig::load().context("Failed to load global configuration")?;
+    println!("{}", config.get(key)?);
+    Ok(())
+}
+
+/// Change one global setting and save the configuration file
+fn set_global_setting(key: &str, value: &str) -> Result<()> {
+    let mut config = GlobalConfig::load().context("Failed to load global configuration")?;
+    config.set(key, value)?;
+    config.save()?;
+
+    println!("✓ Set {key} = {}", config.get(key)?);
+    Ok(())
 }
 
 /// Show current global configuration
-fn show_global_config() -> Result<()> {
+fn show_global_config(format: &str) -> Result<()> {
     let config_path = GlobalConfig::config_path()?;
 
+    if format == "json" {
+        let config = GlobalConfig::load().context("Failed to load global configuration")?;
+        println!("{}", serde_json::to_string_pretty(&config)?);
+        return Ok(());
+    }
+
     if !config_path.exists() {
         println!(
             "No global configuration file found at: {}",
@@ -1005,7 +2548,7 @@ fn show_global_config() -> Result<()> {
 }
 
 /// Initialize default global configuration file
-fn init_global_config(force: bool, allow_local: bool) -> Result<()> {
+fn init_global_config(force: bool, allow_local: bool, global_defaults: bool) -> Result<()> {
     let config_path = GlobalConfig::config_path()?;
 
     if config_path.exists() && !force {
@@ -1019,7 +2562,11 @@ fn init_global_config(force: bool, allow_local: bool) -> Result<()> {
 
     let mut config = GlobalConfig::default();
     config.security.allow_local = allow_local;
-    config.save()?;
+    if global_defaults {
+        config.save_template()?;
+    } else {
+        config.save()?;
+    }
 
     println!("✓ Created global configuration: {}", config_path.display());
     println!();
@@ -1047,6 +2594,7 @@ fn validate_global_config() -> Result<()> {
     if !config_path.exists() {
         println!("✓ No global configuration file (using defaults)");
         println!("  - allow_local: false (absolute imports disabled)");
+        println!("  - default_strategy: sequential");
         return Ok(());
     }
 
@@ -1086,5 +2634,26 @@ fn validate_global_config() -> Result<()> {
         println!("  Use 'peter-hook config init --allow-local' to enable");
     }
 
+    if !config.security.trusted_import_dirs.is_empty() {
+        println!("Trusted import directories:");
+        for dir in &config.security.trusted_import_dirs {
+            let expanded = PathBuf::from(&*shellexpand::tilde(&dir.to_string_lossy()));
+            let status = if !expanded.is_absolute() {
+                "✗ (ignored: not an absolute path)"
+            } else if expanded.is_dir() {
+                "✓"
+            } else {
+                "? (does not exist)"
+            };
+            println!("  {status} {}", dir.display());
+        }
+    }
+
+    let default_strategy = serde_json::to_value(config.execution.default_strategy)?;
+    println!(
+        "Default execution strategy: {}",
+        default_strategy.as_str().unwrap_or_default()
+    );
+
     Ok(())
 }
@@ -0,0 +1,242 @@
+//! GitHub Actions workflow commands for hook results (`--report-format
+//! github`)
+//!
+//! Each failed hook gets an `::error` summary. Lines of its output that name
+//! a location, either `path:line[:col]: message` (gcc, eslint, ruff,
+//! shellcheck and most linters) or rustc's `error: message` followed by
+//! `--> path:line:col`, also become file-scoped annotations so they show up
+//! inline on the pull request.
+
+use crate::hooks::ExecutionResults;
+use regex::Regex;
+use std::{collections::HashSet, path::Path, sync::LazyLock};
+
+/// `path:line[:col]: message`
+static LOCATION_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:\./)?([^\s:]+):(\d+)(?::(\d+))?:\s*(.+)$").expect("valid regex")
+});
+
+/// rustc's `error[E0308]: message` / `warning: message` header
+static DIAGNOSTIC_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(error|warning)(?:\[[^\]]+\])?: (.+)$").expect("valid regex"));
+
+/// rustc's `--> path:line:col` pointer under a header
+static DIAGNOSTIC_POINTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*--> ([^\s:]+):(\d+):(\d+)$").expect("valid regex"));
+
+/// A location found in hook output
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Annotation {
+    /// `error` or `warning`
+    level: &'static str,
+    file: String,
+    line: String,
+    col: Option<String>,
+    message: String,
+}
+
+/// Workflow commands reporting the failed hooks in `results`
+///
+/// Absolute paths under `workspace` (`GITHUB_WORKSPACE`, the checkout) are
+/// made relative to it, since GitHub matches annotations against
+/// repository paths.
+#[must_use]
+pub fn annotations(results: &ExecutionResults, workspace: Option<&Path>) -> Vec<String> {
+    let mut commands = Vec::new();
+    for (name, result) in &results.results {
+        if result.success {
+            continue;
+        }
+
+        let summary = format!("Hook '{name}' failed with exit code {}", result.exit_code);
+        let summary = match &result.failed_step {
+            Some(step) => format!("{summary} ({step})"),
+            None => summary,
+        };
+        // Hooks allowed to fail don't fail the run, so they only warn
+        let level = if result.allow_failure {
+            "warning"
+        } else {
+            "error"
+        };
+        commands.push(format!(
+            "::{level} title={}::{}",
+            escape_property(&format!("peter-hook: {name}")),
+            escape_data(&summary)
+        ));
+
+        let mut seen = HashSet::new();
+        let output = format!("{}\n{}", result.stdout, result.stderr);
+        for annotation in find_locations(&output) {
+            if !seen.insert(annotation.clone()) {
+                continue;
+            }
+            let file = workspace
+                .and_then(|root| Path::new(&annotation.file).strip_prefix(root).ok())
+                .map_or_else(
+                    || annotation.file.clone(),
+                    |path| path.display().to_string(),
+                );
+            let col = annotation
+                .col
+                .as_ref()
+                .map_or_else(String::new, |col| format!(",col={col}"));
+            commands.push(format!(
+                "::{} file={},line={}{col},title={}::{}",
+                if result.allow_failure {
+                    level
+                } else {
+                    annotation.level
+                },
+                escape_property(&file),
+                annotation.line,
+                escape_property(name),
+                escape_data(&annotation.message)
+            ));
+        }
+    }
+    commands
+}
+
+/// Locations named in one hook's output, in order
+fn find_locations(output: &str) -> Vec<Annotation> {
+    let mut found = Vec::new();
+    let mut pending: Option<(&'static str, &str)> = None;
+    for line in output.lines() {
+        let line = line.trim_end();
+        if let Some(header) = DIAGNOSTIC_HEADER.captures(line) {
+            let level = if &header[1] == "warning" {
+                "warning"
+            } else {
+                "error"
+            };
+            pending = Some((level, header.get(2).map_or("", |m| m.as_str())));
+            continue;
+        }
+        if let Some(pointer) = DIAGNOSTIC_POINTER.captures(line) {
+            if let Some((level, message)) = pending.take() {
+                found.push(Annotation {
+                    level,
+                    file: pointer[1].to_string(),
+                    line: pointer[2].to_string(),
+                    col: Some(pointer[3].to_string()),
+                    message: message.to_string(),
+                });
+            }
+            continue;
+        }
+        if let Some(location) = LOCATION_LINE.captures(line) {
+            let message = location[4].to_string();
+            let level = if message.to_lowercase().starts_with("warning") {
+                "warning"
+            } else {
+                "error"
+            };
+            found.push(Annotation {
+                level,
+                file: location[1].to_string(),
+                line: location[2].to_string(),
+                col: location.get(3).map(|col| col.as_str().to_string()),
+                message,
+            });
+        }
+    }
+    found
+}
+
+/// Escape the message of a workflow command
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value (`file=`, `title=`) of a workflow command
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::ExecutionResult;
+    use indexmap::IndexMap;
+
+    fn result(success: bool, stdout: &str, stderr: &str) -> ExecutionResult {
+        ExecutionResult::new(i32::from(!success), stdout.to_string(), stderr.to_string())
+    }
+
+    #[test]
+    fn test_annotations_for_failed_hooks() {
+        let mut results = IndexMap::new();
+        results.insert(
+            "lint".to_string(),
+            result(
+                false,
+                "src/app.py:12:5: E501 line too long, 100%\n./lib/x.sh:3: warning: quote this\n",
+                "",
+            ),
+        );
+        results.insert(
+            "clippy".to_string(),
+            result(
+                false,
+                "",
+                "warning: unused variable: `x`\n  --> /work/repo/src/main.rs:4:9\n   |\n",
+            ),
+        );
+        results.insert("fmt".to_string(), result(true, "src/ok.rs:1:1: fine\n", ""));
+        let results = ExecutionResults {
+            results,
+            success: false,
+        };
+
+        assert_eq!(
+            annotations(&results, Some(Path::new("/work/repo"))),
+            vec![
+                "::error title=peter-hook%3A lint::Hook 'lint' failed with exit code 1",
+                "::error file=src/app.py,line=12,col=5,title=lint::E501 line too long, 100%25",
+                "::warning file=lib/x.sh,line=3,title=lint::warning: quote this",
+                "::error title=peter-hook%3A clippy::Hook 'clippy' failed with exit code 1",
+                "::warning file=src/main.rs,line=4,col=9,title=clippy::unused variable: `x`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_annotations_summary_only_without_locations() {
+        let mut results = IndexMap::new();
+        results.insert(
+            "tests".to_string(),
+            result(false, "2 tests failed\nsee log\n", "error: aborting\n"),
+        );
+        let results = ExecutionResults {
+            results,
+            success: false,
+        };
+        assert_eq!(
+            annotations(&results, None),
+            vec!["::error title=peter-hook%3A tests::Hook 'tests' failed with exit code 1"]
+        );
+    }
+
+    #[test]
+    fn test_annotations_only_warn_for_hooks_allowed_to_fail() {
+        let mut spell = result(false, "README.md:3:1: error: 'teh' is misspelled\n", "");
+        spell.allow_failure = true;
+        let mut results = IndexMap::new();
+        results.insert("spell".to_string(), spell);
+        let results = ExecutionResults {
+            results,
+            success: true,
+        };
+        assert_eq!(
+            annotations(&results, None),
+            vec![
+                "::warning title=peter-hook%3A spell::Hook 'spell' failed with exit code 1",
+                "::warning file=README.md,line=3,col=1,title=spell::error: 'teh' is misspelled",
+            ]
+        );
+    }
+}
@@ -1,12 +1,314 @@
 //! Output formatting utilities
 
+pub mod github;
+
 use console::{Emoji, style};
 use indicatif::{ProgressBar, ProgressStyle};
-use std::io::IsTerminal;
+use std::{
+    ffi::OsStr,
+    io::IsTerminal,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+};
+
+/// When to style output with colors and emoji
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Style output that goes to a terminal
+    #[default]
+    Auto,
+    /// Always style output, e.g. for CI logs that render ANSI escapes
+    Always,
+    /// Never style output
+    Never,
+}
+
+impl std::str::FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            _ => Err(format!("invalid color choice: {s}")),
+        }
+    }
+}
+
+impl ColorChoice {
+    /// The choice made by the `NO_COLOR` and `FORCE_COLOR` environment
+    /// variables: a non-empty `NO_COLOR` (<https://no-color.org>) means
+    /// `Never`, otherwise a non-empty `FORCE_COLOR` other than `0` means
+    /// `Always`
+    fn from_env(
+        no_color: Option<impl AsRef<OsStr>>,
+        force_color: Option<impl AsRef<OsStr>>,
+    ) -> Self {
+        let set = |value: &OsStr| !value.is_empty();
+        if no_color.is_some_and(|value| set(value.as_ref())) {
+            Self::Never
+        } else if force_color.is_some_and(|value| set(value.as_ref()) && value.as_ref() != "0") {
+            Self::Always
+        } else {
+            Self::Auto
+        }
+    }
+
+    /// Whether a stream that is (or isn't) a terminal gets styled output
+    const fn styles(self, is_terminal: bool) -> bool {
+        match self {
+            Self::Auto => is_terminal,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// Choice set by `--color` / `--no-color`; 0 until set, then the
+/// [`ColorChoice`] discriminant plus one
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(0);
+
+/// Apply the color choice for the rest of the process
+///
+/// `choice` comes from `--color` / `--no-color` and, when given, takes
+/// precedence over `NO_COLOR` and `FORCE_COLOR`.
+pub fn init_color(choice: Option<ColorChoice>) {
+    if let Some(choice) = choice {
+        COLOR_CHOICE.store(choice as u8 + 1, Ordering::Relaxed);
+    }
+    let choice = color_choice();
+    console::set_colors_enabled(choice.styles(std::io::stdout().is_terminal()));
+    console::set_colors_enabled_stderr(choice.styles(std::io::stderr().is_terminal()));
+}
+
+/// The effective color choice: `--color` / `--no-color` if given, otherwise
+/// from the `NO_COLOR` and `FORCE_COLOR` environment variables
+#[must_use]
+pub fn color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        1 => ColorChoice::Auto,
+        2 => ColorChoice::Always,
+        3 => ColorChoice::Never,
+        _ => ColorChoice::from_env(
+            std::env::var_os("NO_COLOR"),
+            std::env::var_os("FORCE_COLOR"),
+        ),
+    }
+}
+
+/// Whether output to stdout should be styled
+#[must_use]
+pub fn stdout_styled() -> bool {
+    color_choice().styles(std::io::stdout().is_terminal())
+}
+
+/// Whether output to stderr should be styled
+#[must_use]
+pub fn stderr_styled() -> bool {
+    color_choice().styles(std::io::stderr().is_terminal())
+}
+
+/// How much of one captured output stream a summary prints; unset limits
+/// don't truncate
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputLimit {
+    /// Most lines to print
+    pub max_lines: Option<usize>,
+    /// Most bytes to print
+    pub max_bytes: Option<usize>,
+}
+
+impl OutputLimit {
+    /// Cut `text` to the limit, keeping its beginning
+    ///
+    /// Returns the kept text and, if anything was cut, a marker saying how
+    /// much. A byte limit cuts at the last line break that fits, or inside
+    /// the first line if even that doesn't fit.
+    #[must_use]
+    pub fn truncate<'a>(&self, text: &'a str) -> (&'a str, Option<String>) {
+        let mut kept = text;
+        if let Some(max_lines) = self.max_lines {
+            if max_lines == 0 {
+                kept = "";
+            } else if let Some((end, _)) = kept.match_indices('\n').nth(max_lines - 1) {
+                kept = &kept[..end];
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            if kept.len() > max_bytes {
+                let mut end = max_bytes;
+                while !kept.is_char_boundary(end) {
+                    end -= 1;
+                }
+                if kept.as_bytes()[end] != b'\n' {
+                    end = kept[..end].rfind('\n').unwrap_or(end);
+                }
+                kept = &kept[..end];
+            }
+        }
+
+        if kept.len() == text.len() {
+            return (text, None);
+        }
+        let lines = text.lines().count() - kept.lines().count();
+        let marker = match lines {
+            0 => format!("... ({} bytes truncated)", text.len() - kept.len()),
+            1 => "... (1 line truncated)".to_string(),
+            _ => format!("... ({lines} lines truncated)"),
+        };
+        (kept, Some(marker))
+    }
+}
+
+/// Output limits for passing and failing hooks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputLimits {
+    /// Limit for hooks that succeeded
+    pub success: OutputLimit,
+    /// Limit for hooks that failed
+    pub failure: OutputLimit,
+}
+
+impl OutputLimits {
+    /// The limit for a hook with this outcome
+    #[must_use]
+    pub const fn for_outcome(&self, success: bool) -> OutputLimit {
+        if success { self.success } else { self.failure }
+    }
+}
+
+/// Limits set by [`init_output_limits`]
+static OUTPUT_LIMITS: OnceLock<OutputLimits> = OnceLock::new();
+
+/// Set the hook output limits for the rest of the process
+///
+/// Only the first call has an effect.
+pub fn init_output_limits(limits: OutputLimits) {
+    let _ = OUTPUT_LIMITS.set(limits);
+}
+
+/// The hook output limits; unlimited unless [`init_output_limits`] was called
+#[must_use]
+pub fn output_limits() -> OutputLimits {
+    OUTPUT_LIMITS.get().copied().unwrap_or_default()
+}
+
+/// Extra reporting added to hook run summaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Only the summary
+    #[default]
+    Text,
+    /// Also GitHub Actions workflow commands (`::error` annotations) for
+    /// failed hooks
+    Github,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "github" => Ok(Self::Github),
+            _ => Err(format!("invalid report format: {s}")),
+        }
+    }
+}
+
+impl ReportFormat {
+    /// `Github` when running in GitHub Actions (`GITHUB_ACTIONS=true`),
+    /// otherwise `Text`
+    fn from_env(github_actions: Option<impl AsRef<OsStr>>) -> Self {
+        if github_actions.is_some_and(|value| value.as_ref() == "true") {
+            Self::Github
+        } else {
+            Self::Text
+        }
+    }
+}
+
+/// Format set by [`init_report_format`]
+static REPORT_FORMAT: OnceLock<ReportFormat> = OnceLock::new();
+
+/// Set the report format for the rest of the process
+///
+/// `format` comes from `--report-format`; without it the format is detected
+/// from `GITHUB_ACTIONS`. Only the first call has an effect.
+pub fn init_report_format(format: Option<ReportFormat>) {
+    let _ = REPORT_FORMAT
+        .set(format.unwrap_or_else(|| ReportFormat::from_env(std::env::var_os("GITHUB_ACTIONS"))));
+}
+
+/// The report format; detected from the environment unless
+/// [`init_report_format`] was called
+#[must_use]
+pub fn report_format() -> ReportFormat {
+    REPORT_FORMAT
+        .get()
+        .copied()
+        .unwrap_or_else(|| ReportFormat::from_env(std::env::var_os("GITHUB_ACTIONS")))
+}
 
-/// Output formatter that strips colors and emojis for non-TTY output
+/// Whether summaries list each hook's files, set by [`init_show_files`]
+static SHOW_FILES: AtomicBool = AtomicBool::new(false);
+
+/// List the files each hook ran for in summaries (`--show-files`)
+pub fn init_show_files(show: bool) {
+    SHOW_FILES.store(show, Ordering::Relaxed);
+}
+
+/// Whether summaries list the files each hook ran for
+#[must_use]
+pub fn show_files() -> bool {
+    SHOW_FILES.load(Ordering::Relaxed)
+}
+
+/// Whether hook output is printed live, set by [`init_stream`]
+static STREAM: AtomicBool = AtomicBool::new(false);
+
+/// Print hook output live as it is written, each line tagged with the
+/// hook's name (`--stream`)
+pub fn init_stream(stream: bool) {
+    STREAM.store(stream, Ordering::Relaxed);
+}
+
+/// Whether hook output is printed live instead of in summaries
+#[must_use]
+pub fn stream() -> bool {
+    STREAM.load(Ordering::Relaxed)
+}
+
+/// The `[name] ` tag for a live output line of hook `name`, colored when
+/// the stream it goes to (stderr or stdout) is styled
+///
+/// Each hook keeps the same color, so lines of concurrent hooks are easy to
+/// tell apart.
+#[must_use]
+pub fn stream_prefix(name: &str, for_stderr: bool) -> String {
+    const COLORS: [console::Color; 6] = [
+        console::Color::Cyan,
+        console::Color::Magenta,
+        console::Color::Yellow,
+        console::Color::Blue,
+        console::Color::Green,
+        console::Color::Red,
+    ];
+    let index = name.bytes().fold(0usize, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(usize::from(byte))
+    }) % COLORS.len();
+    let tag = style(format!("[{name}]")).fg(COLORS[index]);
+    let tag = if for_stderr { tag.for_stderr() } else { tag };
+    format!("{tag} ")
+}
+
+/// Output formatter that strips colors and emojis unless output is styled
+/// (see [`ColorChoice`])
 pub struct OutputFormatter {
-    /// Whether output is going to a TTY
+    /// Whether output is styled
     is_tty: bool,
 }
 
@@ -15,7 +317,7 @@ impl OutputFormatter {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            is_tty: std::io::stdout().is_terminal(),
+            is_tty: stdout_styled(),
         }
     }
 
@@ -93,6 +395,47 @@ impl OutputFormatter {
         }
     }
 
+    /// Format a hook that failed but is allowed to (`allow_failure`)
+    #[must_use]
+    pub fn hook_allowed_failure(&self, name: &str, exit_code: i32) -> String {
+        if self.is_tty {
+            format!(
+                "{} {}: exit code {exit_code} (allowed to fail)",
+                style("⚠").yellow().bold(),
+                style(name).yellow()
+            )
+        } else {
+            format!("[WARN] {name}: exit code {exit_code} (allowed to fail)")
+        }
+    }
+
+    /// Format a hook that was skipped without running, and why
+    #[must_use]
+    pub fn hook_skipped(&self, name: &str, reason: &str) -> String {
+        if self.is_tty {
+            format!(
+                "{} {}: skipped ({reason})",
+                style("↷").yellow().bold(),
+                style(name).yellow()
+            )
+        } else {
+            format!("[SKIP] {name}: skipped ({reason})")
+        }
+    }
+
+    /// Format one captured output stream of a hook, trimmed and cut to
+    /// `limit`
+    #[must_use]
+    pub fn hook_output(&self, stream: &str, output: &str, limit: OutputLimit) -> String {
+        match limit.truncate(output.trim()) {
+            (kept, None) => format!("  {stream}: {kept}"),
+            (kept, Some(marker)) if self.is_tty => {
+                format!("  {stream}: {kept}\n  {}", style(marker).dim())
+            }
+            (kept, Some(marker)) => format!("  {stream}: {kept}\n  {marker}"),
+        }
+    }
+
     /// Format section header
     #[must_use]
     pub fn section_header(&self, title: &str) -> String {
@@ -130,6 +473,22 @@ impl OutputFormatter {
         }
     }
 
+    /// Format the count of failed hooks that are allowed to fail, which the
+    /// overall result leaves out
+    #[must_use]
+    pub fn allowed_failures(&self, names: &[&str]) -> String {
+        let message = format!(
+            "{} hook(s) failed but are allowed to: {}",
+            names.len(),
+            names.join(", ")
+        );
+        if self.is_tty {
+            format!("{} {}", Emoji("⚠️ ", "[WARN]"), style(message).yellow())
+        } else {
+            format!("Warnings: {message}")
+        }
+    }
+
     /// Format a managed/custom status
     #[must_use]
     pub const fn managed_status(&self, is_managed: bool) -> &'static str {
@@ -181,6 +540,17 @@ impl Default for OutputFormatter {
     }
 }
 
+/// A hook's name followed by its description, if it has one
+#[must_use]
+pub fn hook_label(name: &str, description: Option<&str>) -> String {
+    match description {
+        Some(description) if !description.trim().is_empty() => {
+            format!("{name} ({})", description.trim())
+        }
+        _ => name.to_string(),
+    }
+}
+
 /// Global output formatter instance
 static OUTPUT_FORMATTER: std::sync::LazyLock<OutputFormatter> =
     std::sync::LazyLock::new(OutputFormatter::new);
@@ -223,6 +593,138 @@ mod tests {
         assert_eq!(formatter.managed_status(false), "📄 custom");
     }
 
+    #[test]
+    fn test_color_choice_from_env() {
+        let none = None::<&str>;
+        assert_eq!(ColorChoice::from_env(none, none), ColorChoice::Auto);
+        assert_eq!(ColorChoice::from_env(Some("1"), none), ColorChoice::Never);
+        assert_eq!(ColorChoice::from_env(Some(""), none), ColorChoice::Auto);
+        assert_eq!(ColorChoice::from_env(none, Some("1")), ColorChoice::Always);
+        assert_eq!(ColorChoice::from_env(none, Some("0")), ColorChoice::Auto);
+        assert_eq!(ColorChoice::from_env(none, Some("")), ColorChoice::Auto);
+        // NO_COLOR wins over FORCE_COLOR
+        assert_eq!(
+            ColorChoice::from_env(Some("1"), Some("1")),
+            ColorChoice::Never
+        );
+    }
+
+    #[test]
+    fn test_color_choice_styles() {
+        assert!(ColorChoice::Auto.styles(true));
+        assert!(!ColorChoice::Auto.styles(false));
+        assert!(ColorChoice::Always.styles(true));
+        assert!(ColorChoice::Always.styles(false));
+        assert!(!ColorChoice::Never.styles(true));
+        assert!(!ColorChoice::Never.styles(false));
+
+        assert_eq!("ALWAYS".parse(), Ok(ColorChoice::Always));
+        assert!("sometimes".parse::<ColorChoice>().is_err());
+    }
+
+    #[test]
+    fn test_unstyled_output_has_no_escapes() {
+        let formatter = OutputFormatter {
+            is_tty: ColorChoice::Never.styles(true),
+        };
+        let output = [
+            formatter.status(true),
+            formatter.hook_start("lint"),
+            formatter.hook_result("lint", false, 1),
+            formatter.hook_skipped("lint", "disabled"),
+            formatter.section_header("Summary"),
+            formatter.overall_result(true),
+        ]
+        .concat();
+        assert!(!output.contains('\x1b'), "{output:?}");
+        assert!(formatter.create_progress_bar(5).is_none());
+    }
+
+    #[test]
+    fn test_hook_label() {
+        assert_eq!(
+            hook_label("lint", Some("Ensures no debug prints")),
+            "lint (Ensures no debug prints)"
+        );
+        assert_eq!(hook_label("lint", None), "lint");
+        assert_eq!(hook_label("lint", Some("  ")), "lint");
+    }
+
+    #[test]
+    fn test_output_limit_lines() {
+        let limit = OutputLimit {
+            max_lines: Some(3),
+            max_bytes: None,
+        };
+        assert_eq!(limit.truncate("a\nb\nc"), ("a\nb\nc", None));
+        assert_eq!(
+            limit.truncate("a\nb\nc\nd"),
+            ("a\nb\nc", Some("... (1 line truncated)".to_string()))
+        );
+        assert_eq!(
+            limit.truncate("a\nb\nc\nd\ne"),
+            ("a\nb\nc", Some("... (2 lines truncated)".to_string()))
+        );
+        assert_eq!(
+            OutputLimit::default().truncate("a\nb\nc\nd"),
+            ("a\nb\nc\nd", None)
+        );
+    }
+
+    #[test]
+    fn test_output_limit_bytes() {
+        let limit = OutputLimit {
+            max_lines: None,
+            max_bytes: Some(5),
+        };
+        // Exactly at the limit
+        assert_eq!(limit.truncate("ab\ncd"), ("ab\ncd", None));
+        // Cuts at the last line break that fits
+        assert_eq!(
+            limit.truncate("ab\ncd\nef"),
+            ("ab\ncd", Some("... (1 line truncated)".to_string()))
+        );
+        // A single long line is cut inside, never in the middle of a character
+        assert_eq!(
+            limit.truncate("abcdé-fgh"),
+            ("abcd", Some("... (6 bytes truncated)".to_string()))
+        );
+
+        // Lines and bytes combine: the tighter one wins
+        let limit = OutputLimit {
+            max_lines: Some(2),
+            max_bytes: Some(100),
+        };
+        assert_eq!(
+            limit.truncate("a\nb\nc"),
+            ("a\nb", Some("... (1 line truncated)".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hook_output_marks_truncation() {
+        let limit = OutputLimit {
+            max_lines: Some(1),
+            max_bytes: None,
+        };
+        let formatter = OutputFormatter { is_tty: false };
+        assert_eq!(
+            formatter.hook_output("stdout", "ok\n", limit),
+            "  stdout: ok"
+        );
+        assert_eq!(
+            formatter.hook_output("stderr", "one\ntwo\nthree\n", limit),
+            "  stderr: one\n  ... (2 lines truncated)"
+        );
+
+        let limits = OutputLimits {
+            success: limit,
+            failure: OutputLimit::default(),
+        };
+        assert_eq!(limits.for_outcome(true), limit);
+        assert_eq!(limits.for_outcome(false), OutputLimit::default());
+    }
+
     #[test]
     fn test_divider_formatting() {
         let formatter_tty = OutputFormatter { is_tty: true };
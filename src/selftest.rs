@@ -0,0 +1,98 @@
+//! End-to-end self test of hook resolution and execution.
+//!
+//! Runs a harmless echo hook from a throwaway repository through the same
+//! resolver, templating and executor as `peter-hook run`, without touching
+//! the user's configuration or `.git/hooks`.
+
+use crate::hooks::{HookExecutor, HookResolver};
+use anyhow::{Context, Result, bail};
+
+/// Directory name of the throwaway repository, checked via `{PROJECT_NAME}`
+const PROJECT_NAME: &str = "peter-hook-selftest";
+
+/// Event the test configuration defines
+const EVENT: &str = "pre-commit";
+
+/// Name of the test hook
+const HOOK_NAME: &str = "selftest";
+
+/// Configuration written to the throwaway repository
+const CONFIG: &str = r#"[hooks.selftest]
+command = "echo selftest {PROJECT_NAME}"
+run_always = true
+description = "Harmless echo hook run by peter-hook selftest"
+
+[groups.pre-commit]
+includes = ["selftest"]
+"#;
+
+/// Run the self test and report each step.
+///
+/// Returns exit code: 0 if every step passed, 1 otherwise.
+#[must_use]
+pub fn run_selftest() -> i32 {
+    println!("🧪 peter-hook self test");
+    println!("=======================");
+    println!();
+
+    match selftest() {
+        Ok(()) => {
+            println!();
+            println!("✨ peter-hook works in this environment");
+            0
+        }
+        Err(e) => {
+            println!("  ❌ {e:#}");
+            println!();
+            println!("❌ Self test failed");
+            1
+        }
+    }
+}
+
+fn selftest() -> Result<()> {
+    let temp_dir = tempfile::TempDir::new().context("Failed to create a temporary directory")?;
+    let repo_dir = temp_dir.path().join(PROJECT_NAME);
+    std::fs::create_dir(&repo_dir).context("Failed to create the test repository directory")?;
+    git2::Repository::init(&repo_dir).context("Failed to initialize the test repository")?;
+    std::fs::write(repo_dir.join("hooks.toml"), CONFIG)
+        .context("Failed to write the test configuration")?;
+    println!(
+        "  ✅ Created a throwaway repository in {}",
+        repo_dir.display()
+    );
+
+    let resolved_hooks = HookResolver::new(&repo_dir)
+        .resolve_hooks(EVENT)
+        .context("Failed to resolve the test configuration")?
+        .context("The test configuration resolved no hooks")?;
+    if !resolved_hooks.hooks.contains_key(HOOK_NAME) {
+        bail!("The test configuration did not resolve hook '{HOOK_NAME}'");
+    }
+    println!("  ✅ Resolved hook '{HOOK_NAME}' for {EVENT}");
+
+    let results = HookExecutor::execute(&resolved_hooks).context("Failed to execute hooks")?;
+    let result = results
+        .results
+        .get(HOOK_NAME)
+        .context("The test hook did not run")?;
+    if !result.success {
+        bail!(
+            "The test hook failed with exit code {}: {}",
+            result.exit_code,
+            result.stderr.trim()
+        );
+    }
+    println!("  ✅ Executed hook '{HOOK_NAME}'");
+
+    let expected = format!("selftest {PROJECT_NAME}");
+    if result.stdout.trim() != expected {
+        bail!(
+            "Expected the test hook to print '{expected}', got '{}'",
+            result.stdout.trim()
+        );
+    }
+    println!("  ✅ Templates resolved in the hook command");
+
+    Ok(())
+}
@@ -1,18 +1,99 @@
 //! Self-update module.
 
 use sha2::{Digest, Sha256};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// GitHub API endpoint listing published releases.
+const RELEASES_API_URL: &str = "https://api.github.com/repos/workhelix/peter-hook/releases";
+
+/// Base URL that release assets are downloaded from.
+const RELEASE_DOWNLOAD_URL: &str = "https://github.com/workhelix/peter-hook/releases/download";
+
+/// Exit code returned by `update --check` when a newer version is available.
+pub const EXIT_UPDATE_AVAILABLE: i32 = 10;
+
+/// A published release as reported by the release API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseInfo {
+    /// Version string without any tag prefix (e.g. "3.1.1")
+    pub version: String,
+    /// URL of the release notes
+    pub changelog_url: String,
+    /// Whether the release is marked as a pre-release
+    pub prerelease: bool,
+}
+
+/// Release track the updater follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReleaseChannel {
+    /// Only published stable releases (default)
+    #[default]
+    Stable,
+    /// Stable releases and release candidates marked as pre-releases
+    Prerelease,
+}
+
+impl std::str::FromStr for ReleaseChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stable" => Ok(Self::Stable),
+            "prerelease" | "pre-release" => Ok(Self::Prerelease),
+            _ => Err(format!("invalid release channel: {s}")),
+        }
+    }
+}
+
+impl ReleaseChannel {
+    /// Get the string representation
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Prerelease => "prerelease",
+        }
+    }
+}
+
+impl std::fmt::Display for ReleaseChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (*self).as_str())
+    }
+}
+
+/// Options that control how an update is downloaded and installed.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    /// Install without verifying the release checksum (for mirrors that do
+    /// not publish one)
+    pub skip_verify: bool,
+    /// Release track used to pick the latest version
+    pub channel: ReleaseChannel,
+}
 
 /// Run update command to install latest or specified version.
 ///
 /// Returns exit code: 0 if successful, 1 on error, 2 if already up-to-date.
+#[must_use]
+pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>) -> i32 {
+    run_update_with_options(version, force, install_dir, &UpdateOptions::default())
+}
+
+/// Run update command with explicit update options.
+///
+/// Returns exit code: 0 if successful, 1 on error, 2 if already up-to-date.
 ///
 /// # Panics
 ///
 /// May panic if stdout flush fails or stdin read fails during user confirmation prompt.
 #[must_use]
-#[allow(clippy::unused_async)]
-pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>) -> i32 {
+pub fn run_update_with_options(
+    version: Option<&str>,
+    force: bool,
+    install_dir: Option<&Path>,
+    options: &UpdateOptions,
+) -> i32 {
     let current_version = env!("CARGO_PKG_VERSION");
 
     println!("🔄 Checking for updates...");
@@ -21,8 +102,8 @@ pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>
     let target_version = if let Some(v) = version {
         v.to_string()
     } else {
-        match get_latest_version() {
-            Ok(v) => v,
+        match fetch_latest_release(RELEASES_API_URL, options.channel) {
+            Ok(release) => release.version,
             Err(e) => {
                 eprintln!("❌ Failed to check for updates: {e}");
                 return 1;
@@ -36,18 +117,34 @@ pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>
         return 2;
     }
 
+    // Never downgrade implicitly; an older release must be requested by
+    // version and confirmed with --force
+    if is_newer_version(current_version, &target_version).unwrap_or(false) {
+        if version.is_none() {
+            println!(
+                "✅ Running v{current_version}, which is newer than the latest {} release \
+                 (v{target_version})",
+                options.channel
+            );
+            return 2;
+        }
+        if !force {
+            eprintln!(
+                "❌ v{target_version} is older than the current version (v{current_version}). \
+                 Use --force to downgrade."
+            );
+            return 1;
+        }
+    }
+
     println!("✨ Update available: v{target_version} (current: v{current_version})");
 
     // Detect current binary location
-    let install_path = if let Some(dir) = install_dir {
-        dir.join("peter-hook")
-    } else {
-        match std::env::current_exe() {
-            Ok(path) => path,
-            Err(e) => {
-                eprintln!("❌ Failed to determine binary location: {e}");
-                return 1;
-            }
+    let install_path = match resolve_install_path(install_dir) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return 1;
         }
     };
 
@@ -70,11 +167,17 @@ pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>
     }
 
     // Perform update
-    match perform_update(&target_version, &install_path) {
+    match install_release(
+        RELEASE_DOWNLOAD_URL,
+        &target_version,
+        &install_path,
+        options.skip_verify,
+    ) {
         Ok(()) => {
             println!("✅ Successfully updated to v{target_version}");
             println!();
             println!("Run 'peter-hook --version' to verify the installation.");
+            println!("Run 'peter-hook update --rollback' to restore the previous version.");
             0
         }
         Err(e) => {
@@ -84,6 +187,214 @@ pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>
     }
 }
 
+/// Restore the binary that was replaced by the last update.
+///
+/// The current binary becomes the new backup, so running rollback twice
+/// returns to the updated version.
+///
+/// Returns exit code: 0 if successful, 1 on error.
+#[must_use]
+pub fn run_rollback(install_dir: Option<&Path>) -> i32 {
+    let install_path = match resolve_install_path(install_dir) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            return 1;
+        }
+    };
+
+    println!("⏪ Rolling back {}...", install_path.display());
+
+    match rollback_binary(&install_path) {
+        Ok(()) => {
+            println!("✅ Restored previous version");
+            println!();
+            println!("Run 'peter-hook --version' to verify the installation.");
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Rollback failed: {e}");
+            1
+        }
+    }
+}
+
+/// Path of the backup kept for the binary at `install_path`.
+#[must_use]
+pub fn backup_path(install_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.backup", install_path.display()))
+}
+
+/// Replace the binary at `install_path` with `new_binary`.
+///
+/// Any existing binary is first saved to [`backup_path`]. The new binary is
+/// staged next to the target and renamed into place, so a running binary is
+/// never left half-written. An existing binary's permissions are preserved;
+/// otherwise the new binary is made executable.
+///
+/// Returns the backup path if a previous binary was saved.
+///
+/// # Errors
+///
+/// Returns an error if the backup, staging copy or final rename fails.
+pub fn replace_binary(new_binary: &Path, install_path: &Path) -> Result<Option<PathBuf>, String> {
+    let backup = if install_path.exists() {
+        let backup = backup_path(install_path);
+        std::fs::copy(install_path, &backup).map_err(|e| {
+            format!(
+                "Failed to back up existing binary to {}: {}",
+                backup.display(),
+                io_error_message(&e)
+            )
+        })?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    let permissions = match std::fs::metadata(install_path) {
+        Ok(metadata) => metadata.permissions(),
+        Err(_) => executable_permissions(new_binary)?,
+    };
+
+    let staged = stage_copy(new_binary, install_path)?;
+    std::fs::set_permissions(staged.path(), permissions).map_err(|e| e.to_string())?;
+    staged
+        .persist(install_path)
+        .map_err(|e| io_error_message(&e.error))?;
+
+    Ok(backup)
+}
+
+/// Swap the binary at `install_path` with its backup.
+///
+/// # Errors
+///
+/// Returns an error if no backup exists or the swap fails.
+pub fn rollback_binary(install_path: &Path) -> Result<(), String> {
+    let backup = backup_path(install_path);
+    if !backup.exists() {
+        return Err(format!(
+            "No previous version found (expected backup at {})",
+            backup.display()
+        ));
+    }
+
+    // Stage both sides before touching the installed binary
+    let restored = stage_copy(&backup, install_path)?;
+    let previous = if install_path.exists() {
+        Some(stage_copy(install_path, install_path)?)
+    } else {
+        None
+    };
+
+    restored
+        .persist(install_path)
+        .map_err(|e| io_error_message(&e.error))?;
+    if let Some(previous) = previous {
+        previous
+            .persist(&backup)
+            .map_err(|e| io_error_message(&e.error))?;
+    }
+
+    Ok(())
+}
+
+/// Copy `source` to a temporary file in the same directory as `target`, so
+/// it can later be renamed over `target` atomically.
+fn stage_copy(source: &Path, target: &Path) -> Result<tempfile::NamedTempFile, String> {
+    let dir = target
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    let staged = tempfile::Builder::new()
+        .prefix(".peter-hook.")
+        .tempfile_in(dir)
+        .map_err(|e| io_error_message(&e))?;
+    std::fs::copy(source, staged.path()).map_err(|e| io_error_message(&e))?;
+    Ok(staged)
+}
+
+/// Permissions for a freshly installed binary (executable on Unix).
+fn executable_permissions(binary: &Path) -> Result<std::fs::Permissions, String> {
+    #[allow(unused_mut)]
+    let mut perms = std::fs::metadata(binary)
+        .map_err(|e| e.to_string())?
+        .permissions();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        perms.set_mode(0o755);
+    }
+    Ok(perms)
+}
+
+/// Determine where the binary is installed.
+fn resolve_install_path(install_dir: Option<&Path>) -> Result<PathBuf, String> {
+    install_dir.map_or_else(
+        || std::env::current_exe().map_err(|e| format!("Failed to determine binary location: {e}")),
+        |dir| Ok(dir.join("peter-hook")),
+    )
+}
+
+/// Describe an I/O error, with a hint when the install location is not writable.
+fn io_error_message(e: &std::io::Error) -> String {
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        format!(
+            "Permission denied. Try running with sudo or use --install-dir to specify a \
+             writable location:\n  {e}"
+        )
+    } else {
+        e.to_string()
+    }
+}
+
+/// Report whether a newer version is available without installing anything.
+///
+/// Returns exit code: 0 if up-to-date, [`EXIT_UPDATE_AVAILABLE`] if a newer
+/// version exists, 1 on error.
+#[must_use]
+pub fn run_check(channel: ReleaseChannel) -> i32 {
+    run_check_against(RELEASES_API_URL, channel)
+}
+
+/// Run the update check against a specific release API endpoint.
+///
+/// Returns exit code: 0 if up-to-date, [`EXIT_UPDATE_AVAILABLE`] if a newer
+/// version exists, 1 on error.
+#[must_use]
+pub fn run_check_against(api_url: &str, channel: ReleaseChannel) -> i32 {
+    let current_version = env!("CARGO_PKG_VERSION");
+
+    let release = match fetch_latest_release(api_url, channel) {
+        Ok(release) => release,
+        Err(e) => {
+            eprintln!("❌ Failed to check for updates: {e}");
+            return 1;
+        }
+    };
+
+    match is_newer_version(&release.version, current_version) {
+        Ok(true) => {
+            println!(
+                "✨ Update available: v{} (current: v{current_version})",
+                release.version
+            );
+            println!("📝 Changelog: {}", release.changelog_url);
+            println!("💡 Run 'peter-hook update' to install it");
+            EXIT_UPDATE_AVAILABLE
+        }
+        Ok(false) => {
+            println!("✅ Already running latest version (v{current_version})");
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Failed to check for updates: {e}");
+            1
+        }
+    }
+}
+
 /// Get the latest version from GitHub releases.
 ///
 /// Returns the version string (without 'v' prefix) or an error if the network request fails.
@@ -93,31 +404,132 @@ pub fn run_update(version: Option<&str>, force: bool, install_dir: Option<&Path>
 /// Returns an error if the HTTP request fails, the response cannot be parsed as JSON,
 /// or the `tag_name` field is missing from the response.
 pub fn get_latest_version() -> Result<String, String> {
+    fetch_latest_release(RELEASES_API_URL, ReleaseChannel::Stable).map(|release| release.version)
+}
+
+/// Query a release API endpoint for the latest release on `channel`.
+///
+/// `api_url` is the releases collection (e.g. `.../repos/<owner>/<repo>/releases`).
+/// The stable channel uses the `latest` endpoint; the pre-release channel lists
+/// all releases and picks the highest version.
+///
+/// # Errors
+///
+/// Returns an error if the HTTP request fails, the response cannot be parsed as JSON,
+/// the `tag_name` field is missing, or no release matches the channel.
+pub fn fetch_latest_release(api_url: &str, channel: ReleaseChannel) -> Result<ReleaseInfo, String> {
     let client = reqwest::blocking::Client::builder()
         .user_agent("peter-hook-updater")
         .timeout(std::time::Duration::from_secs(10))
         .build()
         .map_err(|e| e.to_string())?;
 
-    let url = "https://api.github.com/repos/workhelix/peter-hook/releases/latest";
-    let response: serde_json::Value = client
-        .get(url)
-        .send()
-        .map_err(|e| e.to_string())?
-        .json()
-        .map_err(|e| e.to_string())?;
+    match channel {
+        ReleaseChannel::Stable => {
+            let response: serde_json::Value = client
+                .get(format!("{api_url}/latest"))
+                .send()
+                .map_err(|e| e.to_string())?
+                .json()
+                .map_err(|e| e.to_string())?;
 
-    let tag_name = response["tag_name"]
+            parse_release(&response)
+        }
+        ReleaseChannel::Prerelease => {
+            let response: Vec<serde_json::Value> = client
+                .get(api_url)
+                .send()
+                .map_err(|e| e.to_string())?
+                .json()
+                .map_err(|e| e.to_string())?;
+
+            let releases = response
+                .iter()
+                .filter(|release| !release["draft"].as_bool().unwrap_or(false))
+                .map(parse_release)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            select_latest_release(&releases, channel)
+                .cloned()
+                .ok_or_else(|| format!("No {channel} releases found"))
+        }
+    }
+}
+
+/// Pick the highest-versioned release allowed on `channel`.
+///
+/// Releases whose version is not valid semver are ignored.
+#[must_use]
+pub fn select_latest_release(
+    releases: &[ReleaseInfo],
+    channel: ReleaseChannel,
+) -> Option<&ReleaseInfo> {
+    releases
+        .iter()
+        .filter(|release| channel == ReleaseChannel::Prerelease || !release.prerelease)
+        .filter_map(|release| {
+            semver::Version::parse(&release.version)
+                .ok()
+                .map(|version| (version, release))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
+/// Parse a single release object from the GitHub release API.
+fn parse_release(release: &serde_json::Value) -> Result<ReleaseInfo, String> {
+    let tag_name = release["tag_name"]
         .as_str()
         .ok_or_else(|| "No tag_name in response".to_string())?;
 
     let version = tag_name
         .trim_start_matches("peter-hook-v")
-        .trim_start_matches('v');
-    Ok(version.to_string())
+        .trim_start_matches('v')
+        .to_string();
+    let changelog_url = release["html_url"].as_str().map_or_else(
+        || format!("https://github.com/workhelix/peter-hook/releases/tag/{tag_name}"),
+        ToString::to_string,
+    );
+
+    Ok(ReleaseInfo {
+        version,
+        changelog_url,
+        prerelease: release["prerelease"].as_bool().unwrap_or(false),
+    })
+}
+
+/// Compare two versions using semver precedence.
+///
+/// Returns `true` if `latest` is strictly newer than `current`.
+///
+/// # Errors
+///
+/// Returns an error if either version is not valid semver.
+pub fn is_newer_version(latest: &str, current: &str) -> Result<bool, String> {
+    let latest =
+        semver::Version::parse(latest).map_err(|e| format!("Invalid version '{latest}': {e}"))?;
+    let current =
+        semver::Version::parse(current).map_err(|e| format!("Invalid version '{current}': {e}"))?;
+    Ok(latest > current)
 }
 
-fn perform_update(version: &str, install_path: &Path) -> Result<(), String> {
+/// Download the release archive for `version` from `base_url`, verify it and
+/// install the contained binary at `install_path`.
+///
+/// The archive is checked against the published `.sha256` file before
+/// anything is written; a missing or mismatching checksum aborts the update
+/// unless `skip_verify` is set.
+///
+/// # Errors
+///
+/// Returns an error if the download fails, checksum verification fails, or
+/// the binary cannot be extracted or installed.
+pub fn install_release(
+    base_url: &str,
+    version: &str,
+    install_path: &Path,
+    skip_verify: bool,
+) -> Result<(), String> {
     // Detect platform
     let platform = get_platform_string();
     let archive_ext = if cfg!(target_os = "windows") {
@@ -126,10 +538,10 @@ fn perform_update(version: &str, install_path: &Path) -> Result<(), String> {
         "tar.gz"
     };
 
-    let filename = format!("peter-hook-{platform}.{archive_ext}");
-    let download_url = format!(
-        "https://github.com/workhelix/peter-hook/releases/download/peter-hook-v{version}/{filename}"
-    );
+    let asset_name = format!("peter-hook-{platform}");
+    let filename = format!("{asset_name}.{archive_ext}");
+    let release_url = format!("{base_url}/peter-hook-v{version}");
+    let download_url = format!("{release_url}/{filename}");
 
     println!("📥 Downloading {filename}...");
 
@@ -151,35 +563,29 @@ fn perform_update(version: &str, install_path: &Path) -> Result<(), String> {
 
     let bytes = response.bytes().map_err(|e| e.to_string())?;
 
-    // Download checksum
-    let checksum_url = format!("{download_url}.sha256");
-    let checksum_response = client
-        .get(&checksum_url)
-        .send()
-        .map_err(|e| e.to_string())?;
-
-    if checksum_response.status().is_success() {
-        println!("🔐 Verifying checksum...");
-        let expected_checksum = checksum_response.text().map_err(|e| e.to_string())?;
-        let expected_hash = expected_checksum
-            .split_whitespace()
-            .next()
-            .ok_or_else(|| "Invalid checksum format".to_string())?;
-
-        // Calculate actual checksum
-        let mut hasher = Sha256::new();
-        hasher.update(&bytes);
-        let actual_hash = hex::encode(hasher.finalize());
-
-        if actual_hash != expected_hash {
+    if skip_verify {
+        eprintln!("⚠️  Skipping checksum verification");
+    } else {
+        // Checksum file is named without the archive extension
+        // (e.g., peter-hook-aarch64-apple-darwin.sha256)
+        let checksum_url = format!("{release_url}/{asset_name}.sha256");
+        let checksum_response = client
+            .get(&checksum_url)
+            .send()
+            .map_err(|e| e.to_string())?;
+
+        if !checksum_response.status().is_success() {
             return Err(format!(
-                "Checksum verification failed!\nExpected: {expected_hash}\nActual:   {actual_hash}"
+                "Checksum file not available at {checksum_url} (HTTP {}).\nRefusing to install \
+                 an unverified binary; use --skip-verify to override.",
+                checksum_response.status()
             ));
         }
 
+        println!("🔐 Verifying checksum...");
+        let checksum_file = checksum_response.text().map_err(|e| e.to_string())?;
+        verify_checksum(&bytes, &checksum_file)?;
         println!("✅ Checksum verified");
-    } else {
-        eprintln!("⚠️  Checksum file not available, skipping verification");
     }
 
     // Extract and install
@@ -210,28 +616,38 @@ fn perform_update(version: &str, install_path: &Path) -> Result<(), String> {
         return Err(format!("Binary not found in archive: {binary_name}"));
     }
 
-    // Make executable on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&temp_binary)
-            .map_err(|e| e.to_string())?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&temp_binary, perms).map_err(|e| e.to_string())?;
+    // Replace binary, keeping the previous one for rollback
+    if let Some(backup) = replace_binary(&temp_binary, install_path)? {
+        println!("💾 Previous version saved to {}", backup.display());
     }
 
-    // Replace binary
-    std::fs::copy(&temp_binary, install_path).map_err(|e| {
-        if e.kind() == std::io::ErrorKind::PermissionDenied {
-            format!(
-                "Permission denied. Try running with sudo or use --install-dir to specify a \
-                 writable location:\n  {e}"
-            )
-        } else {
-            e.to_string()
-        }
-    })?;
+    Ok(())
+}
+
+/// Verify `data` against the contents of a `sha256sum`-style checksum file.
+///
+/// Only the first field of the file is used, so both bare digests and
+/// `<digest>  <filename>` lines are accepted.
+///
+/// # Errors
+///
+/// Returns an error if the checksum file is malformed or the digest does not
+/// match.
+pub fn verify_checksum(data: &[u8], checksum_file: &str) -> Result<(), String> {
+    let expected_hash = checksum_file
+        .split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| "Invalid checksum format".to_string())?
+        .to_ascii_lowercase();
+
+    let actual_hash = hex::encode(Sha256::digest(data));
+
+    if actual_hash != expected_hash {
+        return Err(format!(
+            "Checksum verification failed!\nExpected: {expected_hash}\nActual:   {actual_hash}"
+        ));
+    }
 
     Ok(())
 }
@@ -100,6 +100,410 @@ fn test_doctor_command() {
     assert!(stdout.contains("health check") || stdout.contains("peter-hook"));
 }
 
+#[test]
+fn test_selftest_runs_hook_without_touching_cwd() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = Command::new(bin_path())
+        .arg("selftest")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{stdout}");
+    assert!(stdout.contains("Executed hook 'selftest'"), "{stdout}");
+    assert!(stdout.contains("Templates resolved"), "{stdout}");
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_doctor_json_output() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.test]\ncommand = \"echo test\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("doctor --json should emit valid JSON");
+    assert_eq!(report["passed"], true);
+    assert_eq!(output.status.code(), Some(0));
+
+    let checks = report["checks"].as_array().unwrap();
+    let config_valid = checks
+        .iter()
+        .find(|c| c["id"] == "config.valid")
+        .expect("config.valid check missing");
+    assert_eq!(config_valid["status"], "pass");
+    assert_eq!(config_valid["severity"], "info");
+
+    // No hooks installed yet in a fresh repository
+    let hooks = checks.iter().find(|c| c["id"] == "git.hooks").unwrap();
+    assert_eq!(hooks["status"], "warn");
+    assert_eq!(hooks["severity"], "warning");
+}
+
+#[test]
+fn test_doctor_json_fails_on_invalid_config() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(temp_dir.path().join("hooks.toml"), "invalid [[[ toml").unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["passed"], false);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|c| c["id"] == "config.valid"
+                && c["status"] == "fail"
+                && c["severity"] == "error")
+    );
+}
+
+#[test]
+fn test_doctor_json_reports_hook_drift() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.test]\ncommand = \"echo test\"\n\n[groups.pre-commit]\nincludes = [\"test\"]\n",
+    )
+    .unwrap();
+    let install = Command::new(bin_path())
+        .args(["install"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(install.status.success());
+
+    let drift_checks = || {
+        let output = Command::new(bin_path())
+            .args(["doctor", "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .expect("Failed to execute command");
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        report["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|c| c["id"] == "git.hooks_drift")
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let checks = drift_checks();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "pass");
+
+    let hook_path = temp_dir.path().join(".git/hooks/pre-commit");
+    let script = fs::read_to_string(&hook_path).unwrap();
+    fs::write(&hook_path, script.replace("exec", "echo custom\nexec")).unwrap();
+
+    let checks = drift_checks();
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "warn");
+    assert!(
+        checks[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("'pre-commit'")
+    );
+    assert!(
+        checks[0]["hint"]
+            .as_str()
+            .unwrap()
+            .contains("install --force")
+    );
+}
+
+fn doctor_checks(dir: &std::path::Path, id: &str, path_env: &str) -> Vec<serde_json::Value> {
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(dir)
+        .env("PATH", path_env)
+        .output()
+        .expect("Failed to execute command");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["id"] == id)
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_doctor_json_reports_shadowed_hook_names() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::create_dir_all(temp_dir.path().join("api")).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.lint]\ncommand = \"echo root\"\n\n[hooks.fmt]\ncommand = \"echo fmt\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("api/hooks.toml"),
+        "[hooks.lint]\ncommand = \"echo api\"\n",
+    )
+    .unwrap();
+    let path_env = std::env::var("PATH").unwrap_or_default();
+
+    let checks = doctor_checks(temp_dir.path(), "config.shadowed_hooks", &path_env);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "warn");
+    assert!(checks[0]["message"].as_str().unwrap().contains("'lint'"));
+    assert_eq!(
+        checks[0]["paths"],
+        serde_json::json!(["hooks.toml", "api/hooks.toml"])
+    );
+
+    // Checked from a subdirectory too, across the whole repository
+    let checks = doctor_checks(
+        &temp_dir.path().join("api"),
+        "config.shadowed_hooks",
+        &path_env,
+    );
+    assert_eq!(checks.len(), 1);
+
+    fs::write(
+        temp_dir.path().join("api/hooks.toml"),
+        "[hooks.api-lint]\ncommand = \"echo api\"\n",
+    )
+    .unwrap();
+    let checks = doctor_checks(temp_dir.path(), "config.shadowed_hooks", &path_env);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "pass");
+    assert!(checks[0].get("paths").is_none());
+}
+
+#[test]
+fn test_doctor_json_reports_missing_hook_binary() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.test]\ncommand = \"echo test\"\n\n[groups.pre-commit]\nincludes = [\"test\"]\n",
+    )
+    .unwrap();
+    let install = Command::new(bin_path())
+        .args(["install"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(install.status.success());
+    let path_env = std::env::var("PATH").unwrap_or_default();
+
+    let checks = doctor_checks(temp_dir.path(), "git.hooks_binary", &path_env);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "pass");
+
+    // The binary the hook was installed from has moved away
+    let hook_path = temp_dir.path().join(".git/hooks/pre-commit");
+    let script = fs::read_to_string(&hook_path).unwrap();
+    let moved = temp_dir.path().join("gone/peter-hook");
+    fs::write(
+        &hook_path,
+        script.replace(
+            &bin_path().display().to_string(),
+            &moved.display().to_string(),
+        ),
+    )
+    .unwrap();
+
+    let checks = doctor_checks(temp_dir.path(), "git.hooks_binary", &path_env);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0]["status"], "warn");
+    assert!(
+        checks[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains(&moved.display().to_string())
+    );
+    assert!(checks[0]["hint"].as_str().unwrap().contains("--use-path"));
+}
+
+#[test]
+fn test_install_use_path_resolves_binary_through_path() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.test]\ncommand = \"echo test\"\n\n[groups.pre-commit]\nincludes = [\"test\"]\n",
+    )
+    .unwrap();
+    let install = Command::new(bin_path())
+        .args(["install", "--use-path"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(install.status.success());
+
+    let script = fs::read_to_string(temp_dir.path().join(".git/hooks/pre-commit")).unwrap();
+    assert!(
+        script.contains("exec peter-hook run pre-commit"),
+        "{script}"
+    );
+
+    let bin_dir = bin_path().parent().unwrap().display().to_string();
+    let checks = doctor_checks(temp_dir.path(), "git.hooks_binary", &bin_dir);
+    assert_eq!(checks[0]["status"], "pass");
+
+    let empty_dir = TempDir::new().unwrap();
+    let checks = doctor_checks(
+        temp_dir.path(),
+        "git.hooks_binary",
+        &empty_dir.path().display().to_string(),
+    );
+    assert_eq!(checks[0]["status"], "warn");
+}
+
+#[test]
+fn test_doctor_json_reports_unresolvable_import() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "imports = [\"missing.toml\"]\n[hooks.test]\ncommand = \"echo test\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = report["checks"].as_array().unwrap();
+    let import_check = checks
+        .iter()
+        .find(|c| c["id"] == "security.imports")
+        .expect("security.imports check missing");
+    assert_eq!(import_check["status"], "fail");
+    assert!(
+        import_check["message"]
+            .as_str()
+            .unwrap()
+            .contains("missing.toml")
+    );
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_doctor_and_validate_flag_overrides_that_change_execution() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("shared.toml"),
+        "[hooks.lint]\ncommand = \"cargo clippy\"\nfiles = [\"**/*.rs\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "imports = [\"shared.toml\"]\n[hooks.lint]\ncommand = \"cargo clippy\"\nrun_always = true\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = report["checks"].as_array().unwrap();
+    let overrides = checks
+        .iter()
+        .find(|c| c["id"] == "config.overrides")
+        .expect("config.overrides check missing");
+    assert_eq!(overrides["status"], "warn");
+    let message = overrides["message"].as_str().unwrap();
+    assert!(message.contains("Hook 'lint'"), "{message}");
+    assert!(message.contains("shared.toml"), "{message}");
+    assert!(message.contains("run_always: false -> true"), "{message}");
+
+    let output = Command::new(bin_path())
+        .arg("validate")
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Hook 'lint' in") && stdout.contains(r#"files: ["**/*.rs"] -> none"#),
+        "{stdout}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_doctor_json_flags_world_writable_local_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempDir::new().unwrap();
+    let config_dir = home.path().join(".config/peter-hook");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[security]\nallow_local = true\n",
+    )
+    .unwrap();
+    let local_dir = home.path().join(".local/peter-hook");
+    fs::create_dir_all(&local_dir).unwrap();
+    fs::set_permissions(&local_dir, fs::Permissions::from_mode(0o777)).unwrap();
+
+    let repo = TempDir::new().unwrap();
+    Git2Repository::init(repo.path()).unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(repo.path())
+        .env("HOME", home.path())
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let checks = report["checks"].as_array().unwrap();
+    let global = checks
+        .iter()
+        .find(|c| c["id"] == "security.global_config")
+        .unwrap();
+    assert_eq!(global["status"], "pass");
+    let local = checks
+        .iter()
+        .find(|c| c["id"] == "security.local_dir")
+        .unwrap();
+    assert_eq!(local["status"], "fail");
+    assert!(
+        local["message"]
+            .as_str()
+            .unwrap()
+            .contains("world-writable")
+    );
+}
+
 #[test]
 fn test_validate_no_config() {
     let temp_dir = TempDir::new().unwrap();
@@ -113,8 +517,9 @@ fn test_validate_no_config() {
         .output()
         .expect("Failed to execute command");
 
-    // Should succeed but indicate no config
-    assert!(output.status.success() || output.status.code() == Some(1));
+    // No config file: exit code 4
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No hooks.toml file found"));
 }
 
 #[test]
@@ -278,6 +683,47 @@ fn test_uninstall_with_confirmation() {
     assert!(output.status.success() || output.status.code() == Some(1));
 }
 
+#[test]
+fn test_uninstall_keep_backups_and_backups_list() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.pre-commit]\ncommand = \"true\"\nmodifies_repository = false\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join(".git/hooks/pre-commit"),
+        "#!/bin/sh\necho custom\n",
+    )
+    .unwrap();
+
+    let peter_hook = |args: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{args:?}: {output:?}");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    assert!(peter_hook(&["backups", "list"]).contains("No hook backups found"));
+    peter_hook(&["install", "--force"]);
+
+    let listing = peter_hook(&["backups", "list"]);
+    assert!(listing.contains("pre-commit ← "), "{listing}");
+    assert!(listing.contains("pre-commit.backup"), "{listing}");
+
+    let summary = peter_hook(&["uninstall", "--yes", "--keep-backups"]);
+    assert!(summary.contains("Backups kept"), "{summary}");
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".git/hooks/pre-commit")).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+    assert!(peter_hook(&["backups", "list"]).contains("pre-commit.backup"));
+}
+
 #[test]
 fn test_config_list() {
     let output = Command::new(bin_path())
@@ -325,8 +771,8 @@ modifies_repository = false
         .output()
         .expect("Failed to execute command");
 
-    // Lint mode should work without git repo
-    assert!(output.status.success() || output.status.code() == Some(1));
+    // Loading the config needs a git repository: exit code 3
+    assert_eq!(output.status.code(), Some(3));
 }
 
 #[test]
@@ -345,3 +791,49 @@ fn test_update_check_same_version() {
     // Just verify it doesn't panic
     assert!(matches!(output.status.code(), Some(0..=2)));
 }
+
+#[test]
+fn test_doctor_json_ranks_slowest_hooks_from_timing_history() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.test]\ncommand = \"echo test\"\n",
+    )
+    .unwrap();
+    let state_dir = temp_dir.path().join(".git").join("peter-hook");
+    fs::create_dir_all(&state_dir).unwrap();
+    fs::write(
+        state_dir.join("timings.json"),
+        r#"{"hooks": {
+            "fmt": [120, 80, 100],
+            "build": [9000, 12000, 30000, 11000],
+            "lint": [2500, 2000, 100000]
+        }}"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .args(["doctor", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to execute command");
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let slowest: Vec<&str> = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["id"] == "performance.slow_hook")
+        .map(|c| c["message"].as_str().unwrap())
+        .collect();
+    // Ranked by median, so one slow outlier doesn't put lint first
+    assert_eq!(
+        slowest,
+        [
+            "build: median 11.50s over the last 4 run(s)",
+            "lint: median 2.50s over the last 3 run(s)",
+            "fmt: median 0.10s over the last 3 run(s)",
+        ]
+    );
+}
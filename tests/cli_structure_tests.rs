@@ -54,12 +54,43 @@ fn test_cli_has_all_subcommands() {
         subcommands.contains(&"update"),
         "Missing 'update' subcommand"
     );
+    assert!(
+        subcommands.contains(&"why-skipped"),
+        "Missing 'why-skipped' subcommand"
+    );
+
+    assert!(
+        subcommands.contains(&"list-events"),
+        "Missing 'list-events' subcommand"
+    );
+
+    assert!(
+        subcommands.contains(&"selftest"),
+        "Missing 'selftest' subcommand"
+    );
+
+    assert!(
+        subcommands.contains(&"backups"),
+        "Missing 'backups' subcommand"
+    );
+
+    assert!(subcommands.contains(&"graph"), "Missing 'graph' subcommand");
+    assert!(subcommands.contains(&"trust"), "Missing 'trust' subcommand");
+    assert!(
+        subcommands.contains(&"untrust"),
+        "Missing 'untrust' subcommand"
+    );
+
+    assert!(
+        subcommands.contains(&"config-path"),
+        "Missing 'config-path' subcommand"
+    );
 
-    // Should have exactly 13 subcommands
+    // Should have exactly 21 subcommands
     assert_eq!(
         subcommands.len(),
-        13,
-        "Expected 13 subcommands, got {}",
+        21,
+        "Expected 21 subcommands, got {}",
         subcommands.len()
     );
 }
@@ -175,7 +206,10 @@ fn test_config_command_has_subcommands() {
         .find_subcommand("config")
         .expect("config subcommand not found");
 
-    let subcommands: Vec<_> = config_cmd.get_subcommands().map(clap::Command::get_name).collect();
+    let subcommands: Vec<_> = config_cmd
+        .get_subcommands()
+        .map(clap::Command::get_name)
+        .collect();
 
     assert!(
         subcommands.contains(&"show"),
@@ -325,16 +359,16 @@ fn test_list_and_list_worktrees_have_no_args() {
 }
 
 #[test]
-fn test_doctor_command_has_no_args() {
+fn test_doctor_command_has_json_flag() {
     let cmd = Cli::command();
     let doctor_cmd = cmd
         .find_subcommand("doctor")
         .expect("doctor subcommand not found");
-    assert_eq!(
-        doctor_cmd.get_arguments().count(),
-        0,
-        "doctor command should have no arguments"
-    );
+    let args: Vec<_> = doctor_cmd
+        .get_arguments()
+        .map(|a| a.get_id().as_str())
+        .collect();
+    assert_eq!(args, vec!["json"], "doctor command should only have --json");
 }
 
 #[test]
@@ -352,10 +386,14 @@ fn test_cli_parsing_install_with_flags() {
     if let Commands::Install {
         force,
         worktree_strategy,
+        all_worktrees,
+        use_path,
     } = result.unwrap().command
     {
         assert!(force);
         assert_eq!(worktree_strategy, "per-worktree");
+        assert!(!all_worktrees);
+        assert!(!use_path);
     } else {
         panic!("Expected Install command");
     }
@@ -377,43 +415,597 @@ fn test_cli_parsing_run_with_args() {
     if let Commands::Run {
         event,
         all_files,
+        files_from,
+        from_stdin,
+        since_last_run,
+        changed_files_output,
+        changed_files_only,
+        affected_only,
+        root,
+        only_modified_configs,
+        allow_custom_event,
+        no_trust_check,
         dry_run,
+        list_files,
+        json,
+        dump_env,
+        no_redact,
+        continue_from,
+        env,
+        tags,
+        strategy,
+        repeat,
+        output,
+        bail_after,
+        output_dir,
+        fail_on,
+        allow,
         git_args,
     } = result.unwrap().command
     {
         assert_eq!(event, "pre-commit");
         assert!(all_files);
+        assert_eq!(files_from, None);
+        assert!(!from_stdin);
+        assert!(!since_last_run);
+        assert_eq!(changed_files_output, None);
+        assert!(!changed_files_only);
+        assert!(!affected_only);
+        assert!(!root);
+        assert!(!only_modified_configs);
+        assert!(!allow_custom_event);
+        assert!(!no_trust_check);
         assert!(dry_run);
+        assert!(!list_files);
+        assert!(!json);
+        assert!(!dump_env);
+        assert!(!no_redact);
+        assert_eq!(continue_from, None);
+        assert!(env.is_empty());
+        assert!(tags.any.is_empty() && tags.all.is_empty());
+        assert_eq!(strategy.into_strategy(), None);
+        assert_eq!(repeat.repeat, None);
+        assert!(!repeat.repeat_until_fail);
+        assert_eq!(output.max_output, None);
+        assert_eq!(output.report_format, None);
+        assert!(!output.show_files);
+        assert!(!output.stream);
+        assert_eq!(bail_after, None);
+        assert_eq!(output_dir, None);
+        assert_eq!(fail_on, None);
+        assert_eq!(allow, None);
         assert_eq!(git_args, vec!["extra", "args"]);
     } else {
         panic!("Expected Run command");
     }
 }
 
+#[test]
+fn test_cli_parsing_run_dump_env() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--dump-env"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Run {
+            dump_env: true,
+            no_redact: false,
+            ..
+        }
+    ));
+
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--no-redact"]);
+    assert!(result.is_err(), "--no-redact requires --dump-env");
+
+    let result =
+        Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--dump-env", "--dry-run"]);
+    assert!(result.is_err(), "--dump-env conflicts with --dry-run");
+}
+
+#[test]
+fn test_cli_parsing_run_root() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--root"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Run { root: true, .. }
+    ));
+}
+
+#[test]
+fn test_cli_parsing_run_allow_custom_event() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "deploy", "--allow-custom-event"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Run {
+            allow_custom_event: true,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_cli_parsing_trust_commands() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--no-trust-check"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Run {
+            no_trust_check: true,
+            ..
+        }
+    ));
+    assert!(matches!(
+        Cli::try_parse_from(["peter-hook", "trust"])
+            .unwrap()
+            .command,
+        Commands::Trust
+    ));
+    assert!(matches!(
+        Cli::try_parse_from(["peter-hook", "untrust"])
+            .unwrap()
+            .command,
+        Commands::Untrust
+    ));
+}
+
+#[test]
+fn test_cli_parsing_run_affected_only() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--affected-only"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Run {
+            affected_only: true,
+            ..
+        }
+    ));
+
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "run",
+        "pre-commit",
+        "--affected-only",
+        "--all-files",
+    ]);
+    assert!(
+        result.is_err(),
+        "--affected-only conflicts with --all-files"
+    );
+}
+
+#[test]
+fn test_cli_parsing_run_env() {
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "run",
+        "pre-commit",
+        "--env",
+        "CI_TOKEN=abc=123",
+        "--env",
+        "FEATURE_FLAG=",
+    ]);
+    assert!(result.is_ok(), "Failed to parse run --env");
+
+    if let Commands::Run { env, .. } = result.unwrap().command {
+        assert_eq!(
+            env,
+            vec![
+                ("CI_TOKEN".to_string(), "abc=123".to_string()),
+                ("FEATURE_FLAG".to_string(), String::new()),
+            ]
+        );
+    } else {
+        panic!("Expected Run command");
+    }
+
+    for malformed in ["NO_EQUALS", "=value", "1ABC=x", "BAD-KEY=x"] {
+        assert!(
+            Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--env", malformed]).is_err(),
+            "--env {malformed} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_cli_parsing_run_files_from() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--files-from", "-"]);
+    assert!(result.is_ok(), "Failed to parse run --files-from");
+    if let Commands::Run { files_from, .. } = result.unwrap().command {
+        assert_eq!(files_from, Some(std::path::PathBuf::from("-")));
+    } else {
+        panic!("Expected Run command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--from-stdin"]).is_ok());
+    for conflicting in [
+        &["--from-stdin", "--all-files"][..],
+        &["--files-from", "list.txt", "--all-files"],
+        &["--files-from", "list.txt", "--from-stdin"],
+        &["--since-last-run", "--all-files"],
+        &["--since-last-run", "--from-stdin"],
+        &["--changed-files-output", "-", "--all-files"],
+        &["--changed-files-only"],
+    ] {
+        let mut args = vec!["peter-hook", "run", "pre-commit"];
+        args.extend_from_slice(conflicting);
+        assert!(
+            Cli::try_parse_from(args).is_err(),
+            "{conflicting:?} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn test_cli_parsing_run_and_lint_tags() {
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "run",
+        "pre-commit",
+        "--tag",
+        "fast",
+        "--tag",
+        "security",
+        "--require-tag",
+        "rust",
+    ]);
+    assert!(result.is_ok(), "Failed to parse run --tag");
+
+    if let Commands::Run { tags, .. } = result.unwrap().command {
+        assert_eq!(tags.any, vec!["fast", "security"]);
+        assert_eq!(tags.all, vec!["rust"]);
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "all", "--tag", "fast"]);
+    assert!(result.is_ok(), "Failed to parse lint --tag");
+
+    if let Commands::Lint { tags, .. } = result.unwrap().command {
+        assert_eq!(tags.any, vec!["fast"]);
+        assert!(tags.all.is_empty());
+    } else {
+        panic!("Expected Lint command");
+    }
+}
+
+#[test]
+fn test_cli_parsing_run_and_lint_repeat() {
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "run",
+        "pre-commit",
+        "--repeat",
+        "5",
+        "--repeat-until-fail",
+    ]);
+    if let Commands::Run { repeat, .. } = result.unwrap().command {
+        assert_eq!(repeat.repeat, Some(5));
+        assert!(repeat.repeat_until_fail);
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "tests", "--repeat", "3"]);
+    if let Commands::Lint { repeat, .. } = result.unwrap().command {
+        assert_eq!(repeat.repeat, Some(3));
+        assert!(!repeat.repeat_until_fail);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--repeat", "0"]).is_err());
+    assert!(
+        Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--repeat-until-fail"]).is_err()
+    );
+    assert!(
+        Cli::try_parse_from(["peter-hook", "lint", "tests", "--repeat", "2", "--dry-run"]).is_err()
+    );
+}
+
+#[test]
+fn test_cli_parsing_run_and_lint_bail_after() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--bail-after", "3"]);
+    if let Commands::Run { bail_after, .. } = result.unwrap().command {
+        assert_eq!(bail_after, Some(3));
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "tests", "--bail-after", "1"]);
+    if let Commands::Lint { bail_after, .. } = result.unwrap().command {
+        assert_eq!(bail_after, Some(1));
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--bail-after", "0"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_lint_watch() {
+    let result = Cli::try_parse_from(["peter-hook", "lint", "tests", "--watch"]);
+    if let Commands::Lint { watch, .. } = result.unwrap().command {
+        assert!(watch);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "lint", "tests", "--watch", "--dry-run"]).is_err());
+    assert!(
+        Cli::try_parse_from(["peter-hook", "lint", "tests", "--watch", "--repeat", "2"]).is_err()
+    );
+}
+
+#[test]
+fn test_cli_parsing_lint_list() {
+    let result = Cli::try_parse_from(["peter-hook", "lint", "--list", "--json"]);
+    if let Commands::Lint {
+        hook_name,
+        list,
+        json,
+        ..
+    } = result.unwrap().command
+    {
+        assert!(hook_name.is_none());
+        assert!(list);
+        assert!(json);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "lint"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "lint", "tests", "--list"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "lint", "tests", "--json"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_max_output() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--max-output", "5"]);
+    if let Commands::Run { output, .. } = result.unwrap().command {
+        assert_eq!(output.max_output, Some(5));
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "all", "--max-output", "0"]);
+    if let Commands::Lint { output, .. } = result.unwrap().command {
+        assert_eq!(output.max_output, Some(0));
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "--max-output", "5", "run", "pre-commit"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "install", "--max-output", "5"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_report_format() {
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "run",
+        "pre-commit",
+        "--report-format",
+        "github",
+    ]);
+    if let Commands::Run { output, .. } = result.unwrap().command {
+        assert_eq!(output.report_format.as_deref(), Some("github"));
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "all", "--report-format", "text"]);
+    if let Commands::Lint { output, .. } = result.unwrap().command {
+        assert_eq!(output.report_format.as_deref(), Some("text"));
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(
+        Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--report-format", "xml"]).is_err()
+    );
+    assert!(Cli::try_parse_from(["peter-hook", "--report-format", "text", "lint", "all"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "version", "--report-format", "text"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_show_files() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--show-files"]);
+    if let Commands::Run { output, .. } = result.unwrap().command {
+        assert!(output.show_files);
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "all", "--show-files"]);
+    if let Commands::Lint { output, .. } = result.unwrap().command {
+        assert!(output.show_files);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "--show-files", "lint", "all"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "install", "--show-files"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_stream() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--stream"]);
+    if let Commands::Run { output, .. } = result.unwrap().command {
+        assert!(output.stream);
+    } else {
+        panic!("Expected Run command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "lint", "all", "--stream"]);
+    if let Commands::Lint { output, .. } = result.unwrap().command {
+        assert!(output.stream);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "--stream", "lint", "all"]).is_err());
+    assert!(Cli::try_parse_from(["peter-hook", "config", "show", "--stream"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_lint_fix() {
+    let result = Cli::try_parse_from(["peter-hook", "lint", "format", "--fix"]);
+    if let Commands::Lint { fix, .. } = result.unwrap().command {
+        assert!(fix);
+    } else {
+        panic!("Expected Lint command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "lint", "format", "--fix", "--dry-run"]).is_ok());
+    assert!(Cli::try_parse_from(["peter-hook", "lint", "format", "--fix", "--watch"]).is_err());
+    assert!(
+        Cli::try_parse_from(["peter-hook", "lint", "format", "--fix", "--repeat", "2"]).is_err()
+    );
+}
+
+#[test]
+fn test_cli_parsing_run_list_files_json() {
+    let result = Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--list-files", "--json"]);
+    assert!(result.is_ok(), "Failed to parse run --list-files --json");
+
+    // --json only applies to --list-files, which cannot be combined with --dry-run
+    assert!(Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--json"]).is_err());
+    assert!(
+        Cli::try_parse_from([
+            "peter-hook",
+            "run",
+            "pre-commit",
+            "--list-files",
+            "--dry-run"
+        ])
+        .is_err()
+    );
+}
+
+#[test]
+fn test_cli_parsing_why_skipped() {
+    let result = Cli::try_parse_from([
+        "peter-hook",
+        "why-skipped",
+        "lint",
+        "--event",
+        "pre-commit",
+        "--json",
+    ]);
+    assert!(result.is_ok(), "Failed to parse why-skipped");
+
+    if let Commands::WhySkipped {
+        name,
+        event,
+        all_files,
+        json,
+    } = result.unwrap().command
+    {
+        assert_eq!(name, "lint");
+        assert_eq!(event.as_deref(), Some("pre-commit"));
+        assert!(!all_files);
+        assert!(json);
+    } else {
+        panic!("Expected WhySkipped command");
+    }
+}
+
+#[test]
+fn test_cli_parsing_graph() {
+    let result = Cli::try_parse_from(["peter-hook", "graph", "pre-commit"]);
+    if let Commands::Graph { name, format } = result.unwrap().command {
+        assert_eq!(name, "pre-commit");
+        assert_eq!(format, "tree");
+    } else {
+        panic!("Expected Graph command");
+    }
+
+    let result = Cli::try_parse_from(["peter-hook", "graph", "pre-commit", "--format", "dot"]);
+    if let Commands::Graph { format, .. } = result.unwrap().command {
+        assert_eq!(format, "dot");
+    } else {
+        panic!("Expected Graph command");
+    }
+
+    assert!(Cli::try_parse_from(["peter-hook", "graph", "pre-commit", "--format", "svg"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_run_continue_from() {
+    let result =
+        Cli::try_parse_from(["peter-hook", "run", "pre-commit", "--continue-from", "test"]);
+    assert!(result.is_ok(), "Failed to parse run --continue-from");
+
+    if let Commands::Run { continue_from, .. } = result.unwrap().command {
+        assert_eq!(continue_from.as_deref(), Some("test"));
+    } else {
+        panic!("Expected Run command");
+    }
+}
+
 #[test]
 fn test_cli_parsing_config_subcommands() {
     // Test config show
     let result = Cli::try_parse_from(["peter-hook", "config", "show"]);
     assert!(result.is_ok());
     if let Commands::Config { subcommand } = result.unwrap().command {
-        assert!(matches!(subcommand, ConfigCommand::Show));
+        assert!(matches!(subcommand, ConfigCommand::Show { format } if format == "toml"));
     } else {
         panic!("Expected Config command");
     }
 
+    let result = Cli::try_parse_from(["peter-hook", "config", "show", "--format", "json"]);
+    if let Commands::Config {
+        subcommand: ConfigCommand::Show { format },
+    } = result.unwrap().command
+    {
+        assert_eq!(format, "json");
+    } else {
+        panic!("Expected Config Show command");
+    }
+    assert!(Cli::try_parse_from(["peter-hook", "config", "show", "--format", "yaml"]).is_err());
+
     // Test config init
     let result = Cli::try_parse_from(["peter-hook", "config", "init", "--force", "--allow-local"]);
     assert!(result.is_ok());
     if let Commands::Config { subcommand } = result.unwrap().command {
-        if let ConfigCommand::Init { force, allow_local } = subcommand {
+        if let ConfigCommand::Init {
+            force,
+            allow_local,
+            global_defaults,
+        } = subcommand
+        {
             assert!(force);
             assert!(allow_local);
+            assert!(!global_defaults);
         } else {
             panic!("Expected Init subcommand");
         }
     } else {
         panic!("Expected Config command");
     }
+
+    let result = Cli::try_parse_from(["peter-hook", "config", "init", "--global-defaults"]);
+    assert!(matches!(
+        result.unwrap().command,
+        Commands::Config {
+            subcommand: ConfigCommand::Init {
+                global_defaults: true,
+                ..
+            }
+        }
+    ));
+
+    // Test config get/set
+    let result =
+        Cli::try_parse_from(["peter-hook", "config", "set", "defaults.remote", "upstream"]);
+    if let Commands::Config {
+        subcommand: ConfigCommand::Set { key, value },
+    } = result.unwrap().command
+    {
+        assert_eq!(key, "defaults.remote");
+        assert_eq!(value, "upstream");
+    } else {
+        panic!("Expected Config Set command");
+    }
+    assert!(Cli::try_parse_from(["peter-hook", "config", "get", "defaults.remote"]).is_ok());
+    assert!(Cli::try_parse_from(["peter-hook", "config", "set", "defaults.remote"]).is_err());
 }
 
 #[test]
@@ -62,6 +62,76 @@ allow_local = true
     assert!(config.security.allow_local);
 }
 
+#[test]
+fn test_global_config_execution_default_strategy() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    // Sections are independent: [security] may be omitted
+    fs::write(
+        &config_path,
+        r#"
+[execution]
+default_strategy = "parallel"
+"#,
+    )
+    .unwrap();
+
+    let config = GlobalConfig::from_file(&config_path).unwrap();
+    assert_eq!(
+        config.execution.default_strategy,
+        peter_hook::config::ExecutionStrategy::Parallel
+    );
+    assert!(!config.security.allow_local);
+
+    // Without [execution], groups keep the built-in sequential default
+    assert_eq!(
+        GlobalConfig::default().execution.default_strategy,
+        peter_hook::config::ExecutionStrategy::Sequential
+    );
+}
+
+#[test]
+fn test_global_config_output_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+
+    fs::write(
+        &config_path,
+        r#"
+[output]
+max_lines = 20
+max_bytes = 4096
+failure_max_lines = 200
+"#,
+    )
+    .unwrap();
+
+    let output = GlobalConfig::from_file(&config_path).unwrap().output;
+    let limits = output.limits(None);
+    assert_eq!(limits.success.max_lines, Some(20));
+    assert_eq!(limits.success.max_bytes, Some(4096));
+    // Failures fall back to the general byte limit
+    assert_eq!(limits.failure.max_lines, Some(200));
+    assert_eq!(limits.failure.max_bytes, Some(4096));
+
+    // --max-output replaces every line limit; 0 disables truncation
+    let limits = output.limits(Some(5));
+    assert_eq!(limits.success.max_lines, Some(5));
+    assert_eq!(limits.failure.max_lines, Some(5));
+    assert_eq!(limits.failure.max_bytes, Some(4096));
+    assert_eq!(
+        output.limits(Some(0)),
+        peter_hook::output::OutputLimits::default()
+    );
+
+    // Without [output], nothing is truncated
+    assert_eq!(
+        GlobalConfig::default().output.limits(None),
+        peter_hook::output::OutputLimits::default()
+    );
+}
+
 #[test]
 fn test_global_config_from_file_invalid_toml() {
     let temp_dir = TempDir::new().unwrap();
@@ -102,7 +172,13 @@ fn test_global_config_get_local_dir() {
 #[test]
 fn test_global_config_serialization() {
     let config = GlobalConfig {
-        security: peter_hook::config::SecurityConfig { allow_local: true },
+        security: peter_hook::config::SecurityConfig {
+            allow_local: true,
+            ..Default::default()
+        },
+        execution: peter_hook::config::ExecutionConfig::default(),
+        output: peter_hook::config::OutputConfig::default(),
+        defaults: peter_hook::config::DefaultsConfig::default(),
     };
 
     let serialized = toml::to_string(&config);
@@ -160,7 +236,12 @@ fn test_is_absolute_path_allowed_when_enabled() {
 
 #[test]
 fn test_security_config_clone() {
-    let sec1 = peter_hook::config::SecurityConfig { allow_local: true };
+    let sec1 = peter_hook::config::SecurityConfig {
+        allow_local: true,
+        trusted_import_dirs: vec!["/srv/shared-hooks".into()],
+        require_trust: true,
+        allowed_import_hosts: vec!["github.com".into()],
+    };
     let sec2 = sec1.clone();
     assert_eq!(sec1, sec2);
 }
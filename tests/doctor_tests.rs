@@ -175,3 +175,30 @@ fn test_doctor_run_succeeds() {
         "Doctor should return valid exit code"
     );
 }
+
+#[test]
+fn test_doctor_report_pass_fail() {
+    use peter_hook::doctor::{CheckResult, DoctorReport};
+
+    let healthy = DoctorReport::new(vec![
+        CheckResult::pass("a", "Section", "ok"),
+        CheckResult::warn("b", "Section", "careful"),
+    ]);
+    assert!(healthy.passed);
+    assert!(healthy.has_warnings());
+    assert_eq!(healthy.exit_code(), 0);
+
+    let failing = DoctorReport::new(vec![
+        CheckResult::pass("a", "Section", "ok"),
+        CheckResult::fail("c", "Section", "broken").with_hint("fix it"),
+    ]);
+    assert!(!failing.passed);
+    assert_eq!(failing.exit_code(), 1);
+
+    let json = serde_json::to_value(&failing).unwrap();
+    assert_eq!(json["passed"], false);
+    assert_eq!(json["checks"][1]["status"], "fail");
+    assert_eq!(json["checks"][1]["severity"], "error");
+    assert_eq!(json["checks"][1]["hint"], "fix it");
+    assert!(json["checks"][0].get("hint").is_none());
+}
@@ -0,0 +1,119 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery)]
+//! Exit codes that distinguish failure categories
+
+use git2::Repository as Git2Repository;
+use std::{fs, path::Path, process::Command};
+use tempfile::TempDir;
+
+fn bin_path() -> std::path::PathBuf {
+    assert_cmd::cargo::cargo_bin("peter-hook")
+}
+
+fn exit_code(dir: &Path, args: &[&str]) -> Option<i32> {
+    Command::new(bin_path())
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("Failed to execute")
+        .status
+        .code()
+}
+
+fn repo_with_config(config: &str) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(temp_dir.path().join("hooks.toml"), config).unwrap();
+    temp_dir
+}
+
+const INVALID_CONFIG: &str = r#"
+[hooks.lint]
+command = "echo lint"
+files = ["*.rs"]
+run_always = true
+"#;
+
+#[test]
+fn test_hook_failure_exits_1() {
+    let temp_dir = repo_with_config(
+        r#"
+[hooks.fail]
+command = "exit 7"
+run_always = true
+
+[groups.pre-commit]
+includes = ["fail"]
+"#,
+    );
+
+    assert_eq!(
+        exit_code(temp_dir.path(), &["run", "pre-commit", "--all-files"]),
+        Some(1)
+    );
+    assert_eq!(exit_code(temp_dir.path(), &["lint", "fail"]), Some(1));
+}
+
+#[test]
+fn test_config_error_exits_78() {
+    let temp_dir = repo_with_config(INVALID_CONFIG);
+
+    assert_eq!(
+        exit_code(temp_dir.path(), &["run", "pre-commit", "--all-files"]),
+        Some(78)
+    );
+    assert_eq!(exit_code(temp_dir.path(), &["validate"]), Some(78));
+    assert_eq!(
+        exit_code(temp_dir.path(), &["validate", "--trace-imports"]),
+        Some(78)
+    );
+    assert_eq!(exit_code(temp_dir.path(), &["lint", "lint"]), Some(78));
+}
+
+#[test]
+fn test_invalid_fail_on_pattern_exits_2() {
+    let temp_dir = repo_with_config("[hooks.lint]\ncommand = \"echo lint\"\n");
+
+    assert_eq!(
+        exit_code(temp_dir.path(), &["run", "pre-commit", "--fail-on", "("]),
+        Some(2)
+    );
+}
+
+#[test]
+fn test_no_git_repo_exits_3() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.lint]\ncommand = \"echo lint\"\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        exit_code(temp_dir.path(), &["run", "pre-commit", "--all-files"]),
+        Some(3)
+    );
+    assert_eq!(exit_code(temp_dir.path(), &["validate"]), Some(3));
+    assert_eq!(exit_code(temp_dir.path(), &["lint", "lint"]), Some(3));
+}
+
+#[test]
+fn test_no_hooks_exits_4() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    assert_eq!(exit_code(temp_dir.path(), &["validate"]), Some(4));
+    assert_eq!(exit_code(temp_dir.path(), &["lint", "lint"]), Some(4));
+
+    let temp_dir = repo_with_config("[hooks.lint]\ncommand = \"echo lint\"\n");
+    assert_eq!(exit_code(temp_dir.path(), &["lint", "missing"]), Some(4));
+}
+
+#[test]
+fn test_run_without_hooks_for_event_succeeds() {
+    // Installed git hooks must not block commits that touch unconfigured paths
+    let temp_dir = repo_with_config("[hooks.lint]\ncommand = \"echo lint\"\n");
+
+    assert_eq!(
+        exit_code(temp_dir.path(), &["run", "pre-commit", "--all-files"]),
+        Some(0)
+    );
+}
@@ -2,6 +2,7 @@
 //! Comprehensive tests for hierarchical hook resolution
 
 use git2::Repository as Git2Repository;
+use indexmap::IndexMap;
 use peter_hook::{
     git::ChangeDetectionMode,
     hooks::{WorktreeContext, resolve_hooks_hierarchically},
@@ -30,10 +31,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result =
-        resolve_hooks_hierarchically("pre-commit", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "pre-commit",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -73,10 +81,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result =
-        resolve_hooks_hierarchically("pre-commit", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "pre-commit",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -92,10 +107,17 @@ fn test_hierarchical_no_config() {
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result =
-        resolve_hooks_hierarchically("pre-commit", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "pre-commit",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     // Should return Ok but empty groups
     assert!(result.is_ok());
@@ -129,6 +151,8 @@ files = ["*.txt"]
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
     let result = resolve_hooks_hierarchically(
@@ -163,6 +187,8 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
     let result = resolve_hooks_hierarchically(
@@ -224,9 +250,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result = resolve_hooks_hierarchically("test", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "test",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -259,10 +293,17 @@ includes = ["a", "b"]
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result =
-        resolve_hooks_hierarchically("pre-commit", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "pre-commit",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -288,10 +329,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result =
-        resolve_hooks_hierarchically("nonexistent", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "nonexistent",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     // Should return Ok with empty groups
     assert!(result.is_ok());
@@ -318,9 +366,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result = resolve_hooks_hierarchically("test", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "test",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -362,9 +418,17 @@ modifies_repository = false
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
-    let result = resolve_hooks_hierarchically("test", None, temp_dir.path(), temp_dir.path(), &worktree_context);
+    let result = resolve_hooks_hierarchically(
+        "test",
+        None,
+        temp_dir.path(),
+        temp_dir.path(),
+        &worktree_context,
+    );
 
     assert!(result.is_ok());
 }
@@ -233,10 +233,7 @@ fn test_installer_strategies_all_variants() {
 
     for strategy in strategies {
         let result = GitHookInstaller::with_strategy(strategy);
-        assert!(
-            result.is_ok(),
-            "Should create installer with {strategy:?}"
-        );
+        assert!(result.is_ok(), "Should create installer with {strategy:?}");
     }
 
     // Restore directory (ignore error if it doesn't exist)
@@ -289,3 +286,130 @@ modifies_repository = false
 
     let _ = std::env::set_current_dir(original_dir);
 }
+
+/// Repo with a custom `pre-commit` hook that `install_all` backs up
+fn repo_with_backed_up_hook() -> (TempDir, GitHookInstaller) {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo test"
+modifies_repository = false
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join(".git/hooks/pre-commit"),
+        "#!/bin/sh\necho custom\n",
+    )
+    .unwrap();
+
+    let repo = GitRepository::find_from_dir(temp_dir.path()).unwrap();
+    let installer =
+        GitHookInstaller::with_repository_and_binary(repo, "/usr/bin/peter-hook".to_string());
+    let report = installer.install_all().unwrap();
+    assert_eq!(report.backed_up.len(), 1);
+    (temp_dir, installer)
+}
+
+#[test]
+fn test_uninstall_consumes_backups_by_default() {
+    let (temp_dir, installer) = repo_with_backed_up_hook();
+    let hook = temp_dir.path().join(".git/hooks/pre-commit");
+    assert_eq!(installer.list_backups().unwrap().len(), 1);
+
+    let report = installer.uninstall_all();
+    assert!(report.is_success());
+    assert_eq!(report.restored.len(), 1);
+    assert!(!report.backups_kept);
+    assert_eq!(
+        fs::read_to_string(&hook).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+    assert!(
+        !temp_dir
+            .path()
+            .join(".git/hooks/pre-commit.backup")
+            .exists()
+    );
+    assert!(installer.list_backups().unwrap().is_empty());
+}
+
+#[test]
+fn test_uninstall_keep_backups_copies_instead_of_renaming() {
+    let (temp_dir, installer) = repo_with_backed_up_hook();
+    let installer = installer.with_backups_kept();
+    let hook = temp_dir.path().join(".git/hooks/pre-commit");
+
+    let report = installer.uninstall_all();
+    assert!(report.is_success());
+    assert_eq!(report.restored.len(), 1);
+    assert!(report.backups_kept);
+    assert_eq!(
+        fs::read_to_string(&hook).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+
+    let backups = installer.list_backups().unwrap();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(backups[0].hook, "pre-commit");
+    assert_eq!(
+        backups[0].path,
+        temp_dir.path().join(".git/hooks/pre-commit.backup")
+    );
+    assert!(backups[0].modified.is_some());
+    assert_eq!(
+        fs::read_to_string(&backups[0].path).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+}
+
+#[test]
+fn test_find_backups_lists_every_backup_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let hooks_dir = temp_dir.path();
+    for name in [
+        "pre-push.backup",
+        "pre-commit.backup.20240101T000000",
+        "pre-commit.backup",
+        "pre-commit",
+        "pre-commit.sample",
+    ] {
+        fs::write(hooks_dir.join(name), "#!/bin/sh\n").unwrap();
+    }
+
+    let backups = peter_hook::git::find_backups(hooks_dir).unwrap();
+    let listed: Vec<(&str, String)> = backups
+        .iter()
+        .map(|backup| {
+            (
+                backup.hook.as_str(),
+                backup
+                    .path
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            ("pre-commit", "pre-commit.backup".to_string()),
+            (
+                "pre-commit",
+                "pre-commit.backup.20240101T000000".to_string()
+            ),
+            ("pre-push", "pre-push.backup".to_string()),
+        ]
+    );
+
+    assert!(
+        peter_hook::git::find_backups(&hooks_dir.join("missing"))
+            .unwrap()
+            .is_empty()
+    );
+}
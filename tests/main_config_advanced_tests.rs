@@ -241,3 +241,115 @@ allow_local = false
         stdout.contains("config") || stdout.contains(".config") || stdout.contains("peter-hook")
     );
 }
+
+#[test]
+fn test_config_set_then_get() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = |args: &[&str]| {
+        Command::new(bin_path())
+            .env("HOME", temp_dir.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .arg("config")
+            .args(args)
+            .output()
+            .expect("Failed to execute")
+    };
+
+    let output = config(&["set", "defaults.remote", "upstream"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Set defaults.remote = upstream"));
+    let output = config(&["set", "security.allow_local", "true"]);
+    assert!(output.status.success());
+
+    let saved = fs::read_to_string(temp_dir.path().join(".config/peter-hook/config.toml")).unwrap();
+    assert!(saved.contains("allow_local = true"), "{saved}");
+    assert!(saved.contains("remote = \"upstream\""), "{saved}");
+
+    let output = config(&["get", "defaults.remote"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "upstream\n");
+
+    let output = config(&["get", "defaults.branch"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Unknown config key 'defaults.branch'. Valid keys: ")
+            && stderr.contains("defaults.remote"),
+        "{stderr}"
+    );
+
+    let output = config(&["set", "output.max_lines", "many"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("expected a number"));
+}
+
+#[test]
+fn test_config_show_format_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let show_json = || {
+        let output = Command::new(bin_path())
+            .env("HOME", temp_dir.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .args(["config", "show", "--format", "json"])
+            .output()
+            .expect("Failed to execute");
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    // Without a config file, the defaults are printed
+    let json = show_json();
+    assert_eq!(json["security"]["allow_local"], false);
+    assert_eq!(json["execution"]["default_strategy"], "sequential");
+
+    let config_dir = temp_dir.path().join(".config/peter-hook");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[security]\nallow_local = true\n\n[output]\nmax_lines = 50\n",
+    )
+    .unwrap();
+
+    let json = show_json();
+    assert_eq!(json["security"]["allow_local"], true);
+    assert_eq!(json["output"]["max_lines"], 50);
+}
+
+#[test]
+fn test_config_init_global_defaults_scaffolds_settings() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = |args: &[&str]| {
+        Command::new(bin_path())
+            .env("HOME", temp_dir.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .arg("config")
+            .args(args)
+            .output()
+            .expect("Failed to execute")
+    };
+    let config_path = temp_dir.path().join(".config/peter-hook/config.toml");
+
+    // Without the flag the file stays minimal
+    let output = config(&["init"]);
+    assert!(output.status.success());
+    let minimal = fs::read_to_string(&config_path).unwrap();
+    assert!(!minimal.contains('#'), "{minimal}");
+
+    let output = config(&["init", "--force", "--global-defaults", "--allow-local"]);
+    assert!(output.status.success());
+    let scaffolded = fs::read_to_string(&config_path).unwrap();
+    assert!(
+        scaffolded.contains("\nallow_local = true\n"),
+        "{scaffolded}"
+    );
+    assert!(scaffolded.contains("# max_lines = "), "{scaffolded}");
+    assert!(scaffolded.contains("# remote = "), "{scaffolded}");
+
+    // The scaffolded file is a valid configuration
+    let output = config(&["validate"]);
+    assert!(output.status.success());
+    let output = config(&["get", "security.allow_local"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+    let output = config(&["get", "execution.default_strategy"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "sequential\n");
+}
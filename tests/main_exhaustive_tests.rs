@@ -729,8 +729,10 @@ fn test_all_subcommand_help() {
         "license",
         "completions",
         "doctor",
+        "selftest",
         "update",
         "config",
+        "backups",
     ];
 
     for subcmd in subcommands {
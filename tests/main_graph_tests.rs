@@ -0,0 +1,146 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery)]
+//! Integration tests for the graph command
+
+use git2::Repository as Git2Repository;
+use std::{fs, process::Command};
+use tempfile::TempDir;
+
+fn bin_path() -> std::path::PathBuf {
+    assert_cmd::cargo::cargo_bin("peter-hook")
+}
+
+fn repo_with_config(config: &str) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(temp_dir.path().join("hooks.toml"), config).unwrap();
+    temp_dir
+}
+
+fn graph(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = Command::new(bin_path())
+        .current_dir(dir)
+        .arg("graph")
+        .args(args)
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+const DEPENDENCY_CONFIG: &str = r#"
+[hooks.fmt]
+command = "cargo fmt"
+
+[hooks.lint]
+command = "cargo clippy"
+
+[hooks.test]
+command = "cargo test"
+depends_on = ["fmt", "lint"]
+
+[hooks.package]
+command = "cargo package"
+depends_on = ["test"]
+
+[groups.pre-commit]
+includes = ["fmt", "lint", "test", "package"]
+"#;
+
+#[test]
+fn test_graph_tree_shows_dependency_phases() {
+    let temp_dir = repo_with_config(DEPENDENCY_CONFIG);
+
+    assert_eq!(
+        graph(temp_dir.path(), &["pre-commit"]),
+        "pre-commit
+├── phase 1 (parallel)
+│   ├── fmt
+│   └── lint
+├── phase 2
+│   └── test (after fmt, lint)
+└── phase 3
+    └── package (after test)
+"
+    );
+}
+
+#[test]
+fn test_graph_dot_has_phase_clusters_and_dependency_edges() {
+    let temp_dir = repo_with_config(DEPENDENCY_CONFIG);
+
+    let dot = graph(temp_dir.path(), &["pre-commit", "--format", "dot"]);
+    assert!(dot.starts_with("digraph \"pre-commit\" {\n"), "{dot}");
+    assert!(dot.trim_end().ends_with('}'), "{dot}");
+    assert!(
+        dot.contains(
+            "    subgraph \"cluster_1\" {\n        label=\"phase 1 (parallel)\";\n        \"fmt\";\n        \"lint\";\n    }\n"
+        ),
+        "{dot}"
+    );
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains("->")).collect();
+    assert_eq!(
+        edges,
+        [
+            "    \"fmt\" -> \"test\";",
+            "    \"lint\" -> \"test\";",
+            "    \"test\" -> \"package\";",
+        ]
+    );
+}
+
+#[test]
+fn test_graph_follows_execution_strategy_without_dependencies() {
+    let temp_dir = repo_with_config(
+        r#"
+[hooks.fmt]
+command = "cargo fmt"
+modifies_repository = true
+
+[hooks.lint]
+command = "cargo clippy"
+
+[hooks.docs]
+command = "cargo doc"
+
+[groups.pre-commit]
+includes = ["fmt", "lint", "docs"]
+execution = "parallel"
+
+[groups.pre-push]
+includes = ["lint", "docs"]
+"#,
+    );
+
+    // Repository-modifying hooks run after the parallel ones
+    assert_eq!(
+        graph(temp_dir.path(), &["pre-commit"]),
+        "pre-commit\n├── phase 1 (parallel)\n│   ├── lint\n│   └── docs\n└── phase 2\n    └── fmt\n"
+    );
+
+    // Sequential groups run in one phase, serialized by dashed edges
+    assert_eq!(
+        graph(temp_dir.path(), &["pre-push"]),
+        "pre-push\n└── phase 1 (sequential)\n    ├── lint\n    └── docs\n"
+    );
+    let dot = graph(temp_dir.path(), &["pre-push", "--format", "dot"]);
+    assert!(
+        dot.contains("    \"lint\" -> \"docs\" [style=dashed];\n"),
+        "{dot}"
+    );
+}
+
+#[test]
+fn test_graph_unknown_name_exits_no_hooks() {
+    let temp_dir = repo_with_config(DEPENDENCY_CONFIG);
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["graph", "pre-push"])
+        .output()
+        .expect("Failed to execute");
+    assert_eq!(output.status.code(), Some(4));
+}
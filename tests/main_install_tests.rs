@@ -2,7 +2,13 @@
 //! Comprehensive integration tests for install command
 
 use git2::Repository as Git2Repository;
-use std::{fs, process::Command};
+use peter_hook::git::RepoLock;
+use std::{
+    fs,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
 use tempfile::TempDir;
 
 fn bin_path() -> std::path::PathBuf {
@@ -707,3 +713,89 @@ modifies_repository = false
     // Exit code should be 0 or 1 (depending on actual result)
     assert!(matches!(output.status.code(), Some(0 | 1)));
 }
+
+fn repo_with_pre_commit_hook() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo pre-commit"
+"#,
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_concurrent_installs_serialize() {
+    let temp_dir = repo_with_pre_commit_hook();
+
+    let children: Vec<_> = (0..2)
+        .map(|_| {
+            Command::new(bin_path())
+                .current_dir(temp_dir.path())
+                .args(["install", "--force"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()
+                .expect("Failed to execute")
+        })
+        .collect();
+
+    for child in children {
+        let output = child.wait_with_output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let hooks_dir = temp_dir.path().join(".git/hooks");
+    let script = fs::read_to_string(hooks_dir.join("pre-commit")).unwrap();
+    assert!(script.contains("peter-hook"), "{script}");
+    let backups: Vec<_> = fs::read_dir(&hooks_dir)
+        .unwrap()
+        .filter_map(|entry| {
+            let name = entry.unwrap().file_name().to_string_lossy().into_owned();
+            name.starts_with("pre-commit.backup").then_some(name)
+        })
+        .collect();
+    assert!(
+        backups.is_empty(),
+        "the second install must see the first one's hook as managed: {backups:?}"
+    );
+}
+
+#[test]
+fn test_install_waits_for_repo_lock() {
+    let temp_dir = repo_with_pre_commit_hook();
+    let lock = RepoLock::acquire(&temp_dir.path().join(".git")).unwrap();
+
+    let mut child = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .arg("install")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute");
+
+    thread::sleep(Duration::from_millis(500));
+    assert!(
+        child.try_wait().unwrap().is_none(),
+        "install must wait while the lock is held"
+    );
+    assert!(!temp_dir.path().join(".git/hooks/pre-commit").exists());
+
+    drop(lock);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Another peter-hook operation is in progress"),
+        "{stderr}"
+    );
+    assert!(temp_dir.path().join(".git/hooks/pre-commit").exists());
+}
@@ -271,3 +271,135 @@ files = ["*.txt"]
         );
     }
 }
+
+#[cfg(unix)]
+#[test]
+fn test_lint_bail_after_stops_after_failures() {
+    let temp_dir = TempDir::new().unwrap();
+    git2::Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.a]
+command = "echo a >> ran.log; exit 1"
+modifies_repository = false
+execution_type = "other"
+
+[hooks.b]
+command = "echo b >> ran.log"
+modifies_repository = false
+execution_type = "other"
+
+[hooks.c]
+command = "echo c >> ran.log; exit 1"
+modifies_repository = false
+execution_type = "other"
+
+[hooks.d]
+command = "echo d >> ran.log; exit 1"
+modifies_repository = false
+execution_type = "other"
+
+[hooks.e]
+command = "echo e >> ran.log"
+modifies_repository = false
+execution_type = "other"
+
+[groups.sweep]
+includes = ["a", "b", "c", "d", "e"]
+execution = "sequential"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["lint", "sweep", "--bail-after", "2"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("ran.log")).unwrap(),
+        "a\nb\nc\n"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Stopped after 2 failed hooks (--bail-after 2), not run: d, e"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn test_lint_watch_reruns_matching_hooks_on_change() {
+    use nix::{
+        sys::signal::{self, Signal},
+        unistd::Pid,
+    };
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    fs::write(temp_dir.path().join("main.rs"), "fn main() {}").unwrap();
+    fs::write(temp_dir.path().join("README.md"), "# readme").unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.rust]
+command = "echo rust >> runs.log"
+execution_type = "in-place"
+files = ["**/*.rs"]
+
+[hooks.docs]
+command = "echo docs >> runs.log"
+execution_type = "in-place"
+files = ["**/*.md"]
+
+[groups.check]
+includes = ["rust", "docs"]
+"#,
+    )
+    .unwrap();
+
+    let mut child = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["lint", "check", "--watch"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to execute");
+
+    let runs = temp_dir.path().join("runs.log");
+    let wait_for = |expected: &str| {
+        let started = Instant::now();
+        loop {
+            let log = fs::read_to_string(&runs).unwrap_or_default();
+            if log == expected {
+                return;
+            }
+            assert!(
+                started.elapsed() < Duration::from_secs(20),
+                "expected runs {expected:?}, got {log:?}"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    };
+
+    // The first run covers every hook
+    wait_for("rust\ndocs\n");
+    // Give the watcher a moment to settle after the hooks' own writes
+    std::thread::sleep(Duration::from_millis(500));
+
+    // Only the hook matching the changed file runs again
+    fs::write(temp_dir.path().join("main.rs"), "fn main() { }").unwrap();
+    wait_for("rust\ndocs\nrust\n");
+
+    signal::kill(Pid::from_raw(child.id() as i32), Signal::SIGINT).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+}
@@ -209,3 +209,152 @@ fn test_list_worktrees_empty_shows_message() {
     // Should show worktree info or message
     assert!(!combined.trim().is_empty());
 }
+
+fn setup_events_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.lint]
+command = "cargo clippy"
+
+[hooks.commit-msg]
+command = "check-msg"
+
+[groups.pre-commit]
+includes = ["lint"]
+
+[groups.pre-push]
+includes = []
+placeholder = true
+
+[groups.not-an-event]
+includes = ["lint"]
+"#,
+    )
+    .unwrap();
+
+    temp_dir
+}
+
+#[test]
+fn test_list_events_shows_configured_events() {
+    let temp_dir = setup_events_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .arg("list-events")
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("pre-commit (group)"), "{stdout}");
+    assert!(stdout.contains("commit-msg (hook)"), "{stdout}");
+    assert!(stdout.contains("pre-push (placeholder)"), "{stdout}");
+    assert!(!stdout.contains("not-an-event"), "{stdout}");
+    assert!(!stdout.contains("lint"), "{stdout}");
+}
+
+#[test]
+fn test_list_events_json() {
+    let temp_dir = setup_events_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["list-events", "--json"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["events"],
+        serde_json::json!([
+            { "event": "pre-commit", "kind": "group" },
+            { "event": "commit-msg", "kind": "hook" },
+            { "event": "pre-push", "kind": "placeholder" },
+        ])
+    );
+    assert!(json["config"].as_str().unwrap().ends_with("hooks.toml"));
+}
+
+#[test]
+fn test_list_events_without_events() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.lint]\ncommand = \"cargo clippy\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .arg("list-events")
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No git events are configured"));
+}
+
+#[test]
+fn test_config_path_finds_nearest_config() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::write(root.join("hooks.toml"), "[hooks.a]\ncommand = \"true\"\n").unwrap();
+    fs::create_dir_all(root.join("backend/src")).unwrap();
+    fs::write(
+        root.join("backend/hooks.toml"),
+        "[hooks.b]\ncommand = \"true\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("docs")).unwrap();
+
+    let config_path = |dir: &std::path::Path, arg: Option<&str>| {
+        let mut command = Command::new(bin_path());
+        command.current_dir(dir).arg("config-path");
+        command.args(arg);
+        let output = command.output().expect("Failed to execute");
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string()
+    };
+
+    let nested = root.join("backend/hooks.toml").display().to_string();
+    assert_eq!(config_path(&root.join("backend/src"), None), nested);
+    assert_eq!(config_path(&root, Some("backend/src")), nested);
+    assert_eq!(
+        config_path(&root, Some("docs")),
+        root.join("hooks.toml").display().to_string()
+    );
+}
+
+#[test]
+fn test_config_path_without_config() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .arg("config-path")
+        .output()
+        .expect("Failed to execute");
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No hooks config file found"));
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["config-path", "missing"])
+        .output()
+        .expect("Failed to execute");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Not a directory"));
+}
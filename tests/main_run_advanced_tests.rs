@@ -372,3 +372,1469 @@ modifies_repository = false
     // Should show some execution output
     assert!(!combined.trim().is_empty());
 }
+
+fn setup_list_files_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+    fs::write(temp_dir.path().join("notes.txt"), "notes").unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new("src/main.rs")).unwrap();
+    index.add_path(std::path::Path::new("notes.txt")).unwrap();
+    index.write().unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.rustfmt]
+command = "touch executed"
+files = ["**/*.rs"]
+
+[hooks.markdown]
+command = "touch executed"
+files = ["**/*.md"]
+execution_type = "in-place"
+
+[groups.pre-commit]
+includes = ["rustfmt", "markdown"]
+"#,
+    )
+    .unwrap();
+
+    temp_dir
+}
+
+#[test]
+fn test_run_list_files_shows_filtered_files() {
+    let temp_dir = setup_list_files_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--list-files"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Change detection: staged"), "{stdout}");
+    assert!(stdout.contains("rustfmt (per-file) - 1 file"), "{stdout}");
+    assert!(stdout.contains("    src/main.rs"), "{stdout}");
+    assert!(!stdout.contains("notes.txt"), "{stdout}");
+    assert!(
+        stdout.contains("markdown (in-place) - skipped: no matching files"),
+        "{stdout}"
+    );
+    assert!(!temp_dir.path().join("executed").exists());
+}
+
+#[test]
+fn test_run_list_files_json() {
+    let temp_dir = setup_list_files_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--list-files", "--json"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["event"], "pre-commit");
+    assert_eq!(json["change_mode"], "staged");
+
+    let hooks = json["configs"][0]["hooks"].as_array().unwrap();
+    assert_eq!(hooks[0]["hook"], "rustfmt");
+    assert_eq!(hooks[0]["execution_type"], "per-file");
+    assert_eq!(hooks[0]["will_run"], true);
+    assert_eq!(hooks[0]["files"], serde_json::json!(["src/main.rs"]));
+    assert_eq!(hooks[1]["hook"], "markdown");
+    assert_eq!(hooks[1]["will_run"], false);
+    assert!(!temp_dir.path().join("executed").exists());
+}
+
+#[test]
+fn test_why_skipped_explains_event() {
+    let temp_dir = setup_list_files_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["why-skipped", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("rustfmt runs: 1 matching file(s): src/main.rs"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "markdown skipped: no changed files match [\"**/*.md\"] (considered: notes.txt, src/main.rs)"
+        ),
+        "{stdout}"
+    );
+    assert!(!temp_dir.path().join("executed").exists());
+}
+
+#[test]
+fn test_why_skipped_single_hook_json() {
+    let temp_dir = setup_list_files_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["why-skipped", "markdown", "--event", "pre-commit", "--json"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["event"], "pre-commit");
+    assert_eq!(json["hook"], "markdown");
+    assert_eq!(json["configured"], true);
+
+    let hooks = json["configs"][0]["hooks"].as_array().unwrap();
+    assert_eq!(hooks.len(), 1);
+    assert_eq!(hooks[0]["will_run"], false);
+    assert_eq!(hooks[0]["reason"], "no_matching_files");
+    assert_eq!(hooks[0]["patterns"], serde_json::json!(["**/*.md"]));
+}
+
+#[test]
+fn test_why_skipped_unconfigured_hook() {
+    let temp_dir = setup_list_files_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["why-skipped", "eslint", "--event", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Hook 'eslint' is not configured for event: pre-commit"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_run_log_file_records_execution() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.first]
+command = "echo {HOOK_DIR} > /dev/null"
+run_always = true
+
+[hooks.second]
+command = "exit 3"
+run_always = true
+
+[groups.pre-commit]
+includes = ["first", "second"]
+execution = "parallel"
+"#,
+    )
+    .unwrap();
+
+    let log_path = temp_dir.path().join("peter-hook.log");
+    fs::write(&log_path, "{\"msg\":\"previous run\"}\n").unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--log-file"])
+        .arg(&log_path)
+        .output()
+        .expect("Failed to execute");
+    assert!(!output.status.success());
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    let records: Vec<serde_json::Value> = log
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let messages: Vec<&str> = records
+        .iter()
+        .map(|record| record["msg"].as_str().unwrap())
+        .collect();
+
+    // Appends to the existing file, one JSON record per line
+    assert_eq!(messages[0], "previous run");
+    assert!(records[1]["ts"].as_str().unwrap().ends_with('Z'));
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.starts_with("resolved event=pre-commit")),
+        "{log}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.starts_with("exec hook=first") && !m.contains("{HOOK_DIR}")),
+        "{log}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.starts_with("hook finished name=second success=false exit_code=3")),
+        "{log}"
+    );
+    assert!(
+        messages
+            .iter()
+            .any(|m| m.starts_with("hook finished name=first success=true exit_code=0")),
+        "{log}"
+    );
+}
+
+#[test]
+fn test_run_env_injects_variables() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo \"$TOKEN $MODE {env:TOKEN}\" > env-out.txt"
+env = { MODE = "hook" }
+run_always = true
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args([
+            "run",
+            "pre-commit",
+            "--env",
+            "TOKEN=secret",
+            "--env",
+            "MODE=run",
+        ])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let written = fs::read_to_string(temp_dir.path().join("env-out.txt")).unwrap();
+    assert_eq!(written.trim(), "secret hook secret");
+}
+
+fn setup_tagged_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.fmt]
+command = "touch ran-fmt"
+tags = ["fast", "rust"]
+run_always = true
+
+[hooks.audit]
+command = "touch ran-audit"
+tags = ["security", "slow"]
+run_always = true
+
+[hooks.docs]
+command = "touch ran-docs"
+run_always = true
+
+[groups.pre-commit]
+includes = ["fmt", "audit", "docs"]
+"#,
+    )
+    .unwrap();
+
+    temp_dir
+}
+
+fn ran_hooks(temp_dir: &TempDir) -> Vec<&'static str> {
+    ["fmt", "audit", "docs"]
+        .into_iter()
+        .filter(|name| temp_dir.path().join(format!("ran-{name}")).exists())
+        .collect()
+}
+
+#[test]
+fn test_run_tag_selects_hooks() {
+    let temp_dir = setup_tagged_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--tag", "fast", "--tag", "security"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(ran_hooks(&temp_dir), vec!["fmt", "audit"]);
+}
+
+#[test]
+fn test_run_require_tag_needs_every_tag() {
+    let temp_dir = setup_tagged_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args([
+            "run",
+            "pre-commit",
+            "--require-tag",
+            "security",
+            "--require-tag",
+            "slow",
+        ])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    assert_eq!(ran_hooks(&temp_dir), vec!["audit"]);
+}
+
+#[test]
+fn test_run_tag_without_matches_succeeds() {
+    let temp_dir = setup_tagged_repo();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--tag", "nonexistent"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No hooks match the tag filter for event: pre-commit"));
+    assert!(ran_hooks(&temp_dir).is_empty());
+}
+
+#[test]
+fn test_peterhookignore_excludes_files_from_every_hook() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("vendor/dep")).unwrap();
+    fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+    fs::write(temp_dir.path().join("vendor/dep/lib.rs"), "// vendored").unwrap();
+    fs::write(temp_dir.path().join(".peterhookignore"), "vendor/\n").unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.per-file]
+command = ["sh", "-c", "printf '%s\n' \"$@\" >> seen.txt", "sh"]
+files = ["**/*.rs"]
+
+[hooks.other]
+command = "echo {CHANGED_FILES} >> seen.txt"
+execution_type = "other"
+
+[groups.pre-commit]
+includes = ["per-file", "other"]
+"#,
+    )
+    .unwrap();
+    let mut index = repo.index().unwrap();
+    for path in [
+        "src/main.rs",
+        "vendor/dep/lib.rs",
+        ".peterhookignore",
+        "hooks.toml",
+    ] {
+        index.add_path(std::path::Path::new(path)).unwrap();
+    }
+    index.write().unwrap();
+
+    for args in [["run", "pre-commit"], ["lint", "pre-commit"]] {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args(args)
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let seen = fs::read_to_string(temp_dir.path().join("seen.txt")).unwrap();
+    assert!(seen.contains("src/main.rs"), "{seen}");
+    assert!(!seen.contains("vendor"), "{seen}");
+}
+
+#[test]
+fn test_run_staged_deletion_not_passed_to_per_file_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_dir = temp_dir.path();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(repo_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "Test User"]);
+    git(&["config", "user.email", "test@example.com"]);
+    fs::write(repo_dir.join("kept.rs"), "fn kept() {}").unwrap();
+    fs::write(repo_dir.join("gone.rs"), "fn gone() {}").unwrap();
+    fs::write(
+        repo_dir.join("hooks.toml"),
+        r#"
+[hooks.per-file]
+command = ["sh", "-c", "printf '%s\n' \"$@\" >> per-file.txt", "sh"]
+files = ["**/*.rs"]
+
+[hooks.other]
+command = "echo {CHANGED_FILES} > other.txt"
+execution_type = "other"
+files = ["**/*.rs"]
+
+[groups.pre-commit]
+includes = ["per-file", "other"]
+"#,
+    )
+    .unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+
+    fs::write(repo_dir.join("kept.rs"), "fn kept() { }").unwrap();
+    git(&["add", "kept.rs"]);
+    git(&["rm", "-q", "gone.rs"]);
+
+    let output = Command::new(bin_path())
+        .current_dir(repo_dir)
+        .args(["run", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let per_file = fs::read_to_string(repo_dir.join("per-file.txt")).unwrap();
+    assert_eq!(per_file.trim(), "kept.rs");
+
+    // `other` hooks still see the deletion through {CHANGED_FILES}
+    let other = fs::read_to_string(repo_dir.join("other.txt")).unwrap();
+    assert!(other.contains("gone.rs"), "{other}");
+    assert!(other.contains("kept.rs"), "{other}");
+}
+
+#[test]
+fn test_run_from_stdin_uses_given_files() {
+    use std::io::Write as _;
+
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/a.rs"), "").unwrap();
+    fs::write(temp_dir.path().join("src/b.rs"), "").unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = ["sh", "-c", "printf '%s\n' \"$@\" >> seen.txt", "sh"]
+files = ["**/*.rs"]
+"#,
+    )
+    .unwrap();
+
+    // Nothing is staged; only the listed files count as changed
+    let mut child = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--from-stdin"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"src/a.rs\nnotes.txt\n\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let seen = fs::read_to_string(temp_dir.path().join("seen.txt")).unwrap();
+    assert_eq!(seen.trim(), "src/a.rs");
+}
+
+#[test]
+fn test_run_files_from_rejects_paths_outside_repo() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.pre-commit]\ncommand = \"true\"\n",
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("list.txt"), "../outside.rs\n").unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--files-from", "list.txt"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Path is outside the repository: ../outside.rs"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_run_output_dir_writes_hook_logs() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.greet]
+command = "echo hello; echo oops >&2"
+run_always = true
+
+[hooks.fail]
+command = "echo broken >&2; exit 3"
+run_always = true
+
+[groups.pre-commit]
+includes = ["greet", "fail"]
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--output-dir", "logs/run"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    let logs = temp_dir.path().join("logs/run");
+    let read = |file: &str| fs::read_to_string(logs.join(file)).unwrap();
+    assert_eq!(read("greet.stdout.log").trim(), "hello");
+    assert_eq!(read("greet.stderr.log").trim(), "oops");
+    assert_eq!(read("fail.stdout.log"), "");
+    assert_eq!(read("fail.stderr.log").trim(), "broken");
+}
+
+#[test]
+fn test_run_fail_on_fails_hooks_with_matching_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.lint]
+command = "echo 'WARNING: deprecated option'"
+run_always = true
+
+[groups.pre-commit]
+includes = ["lint"]
+"#,
+    )
+    .unwrap();
+    let run = |extra: &[&str]| {
+        Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args(["run", "pre-commit"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute")
+    };
+
+    assert!(run(&[]).status.success());
+
+    let output = run(&["--fail-on", "^WARNING:"]);
+    assert!(!output.status.success());
+
+    let output = run(&["--fail-on", "^WARNING:", "--allow", "deprecated"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = run(&["--fail-on", "WARNING: ("]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid --fail-on pattern"));
+}
+
+#[test]
+fn test_run_shows_hook_descriptions() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.no-debug]
+command = "exit 1"
+description = "Ensures no debug prints"
+run_always = true
+
+[hooks.plain]
+command = "true"
+run_always = true
+
+[groups.pre-commit]
+includes = ["no-debug", "plain"]
+"#,
+    )
+    .unwrap();
+    let run = |extra: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args(["run", "pre-commit", "--all-files"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute");
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let stdout = run(&[]);
+    assert!(
+        stdout.contains("[FAIL] no-debug (Ensures no debug prints): exit code 1"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("[PASS] plain: exit code 0"), "{stdout}");
+
+    let stdout = run(&["--dry-run"]);
+    assert!(
+        stdout.contains("  no-debug (Ensures no debug prints) - exit 1"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("  plain - true"), "{stdout}");
+}
+
+fn setup_color_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.greet]
+command = "echo hello"
+run_always = true
+
+[groups.pre-commit]
+includes = ["greet"]
+"#,
+    )
+    .unwrap();
+    temp_dir
+}
+
+/// Run peter-hook on a pseudo-terminal (via util-linux `script`) so TTY
+/// styling kicks in; `None` if `script` is unavailable
+#[cfg(target_os = "linux")]
+fn run_on_tty(dir: &std::path::Path, args: &str, env: &[(&str, &str)]) -> Option<String> {
+    let output = Command::new("script")
+        .current_dir(dir)
+        .args([
+            "-qec",
+            &format!("{} {args}", bin_path().display()),
+            "/dev/null",
+        ])
+        .env_remove("NO_COLOR")
+        .env_remove("FORCE_COLOR")
+        .envs(env.iter().copied())
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_color_modes_on_tty() {
+    let temp_dir = setup_color_repo();
+    let args = "run pre-commit --all-files";
+
+    let Some(styled) = run_on_tty(temp_dir.path(), args, &[]) else {
+        eprintln!("skipping: `script` is not available");
+        return;
+    };
+    assert!(
+        styled.contains('\x1b'),
+        "expected styled output: {styled:?}"
+    );
+
+    for (args, env) in [
+        (format!("--no-color {args}"), &[][..]),
+        (format!("--color never {args}"), &[][..]),
+        (args.to_string(), &[("NO_COLOR", "1")][..]),
+        // An explicit --color wins over the environment
+        (format!("--color never {args}"), &[("FORCE_COLOR", "1")][..]),
+    ] {
+        let plain = run_on_tty(temp_dir.path(), &args, env).unwrap();
+        assert!(!plain.contains('\x1b'), "{args} {env:?}: {plain:?}");
+        assert!(plain.contains("[PASS] greet"), "{args} {env:?}: {plain:?}");
+    }
+}
+
+#[test]
+fn test_color_modes_when_piped() {
+    let temp_dir = setup_color_repo();
+    let run = |color: &[&str], env: &[(&str, &str)]| {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args(color)
+            .args(["run", "pre-commit", "--all-files"])
+            .env_remove("NO_COLOR")
+            .env_remove("FORCE_COLOR")
+            .envs(env.iter().copied())
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    assert!(!run(&[], &[]).contains('\x1b'));
+    assert!(!run(&["--color", "auto"], &[]).contains('\x1b'));
+    assert!(run(&["--color", "always"], &[]).contains('\x1b'));
+    assert!(run(&[], &[("FORCE_COLOR", "1")]).contains('\x1b'));
+    assert!(!run(&[], &[("FORCE_COLOR", "0")]).contains('\x1b'));
+    assert!(!run(&[], &[("FORCE_COLOR", "1"), ("NO_COLOR", "1")]).contains('\x1b'));
+    assert!(run(&["--color", "always"], &[("NO_COLOR", "1")]).contains('\x1b'));
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["--color", "always", "--no-color", "run", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_interrupt_terminates_hooks_and_removes_temp_files() {
+    use nix::{
+        sys::signal::{self, Signal},
+        unistd::Pid,
+    };
+    use std::time::{Duration, Instant};
+
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(temp_dir.path().join("a.rs"), "").unwrap();
+    fs::write(temp_dir.path().join("list.txt"), "a.rs\n").unwrap();
+    // The hook starts a background "server" and then blocks, like a hook
+    // waiting on a service it launched
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo {CHANGED_FILES_FILE} > list-path.txt; sleep 30 & echo $! > server.pid; wait"
+execution_type = "other"
+"#,
+    )
+    .unwrap();
+
+    let mut child = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--files-from", "list.txt"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("Failed to execute");
+
+    let server_pid = temp_dir.path().join("server.pid");
+    let started = Instant::now();
+    while fs::read_to_string(&server_pid).map_or(true, |pid| pid.trim().is_empty()) {
+        assert!(
+            started.elapsed() < Duration::from_secs(20),
+            "hook never started"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let list_path = fs::read_to_string(temp_dir.path().join("list-path.txt")).unwrap();
+    let list_path = std::path::PathBuf::from(list_path.trim());
+    assert!(list_path.exists());
+
+    signal::kill(Pid::from_raw(child.id() as i32), Signal::SIGINT).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(130));
+    assert!(!list_path.exists(), "changed-files list was left behind");
+
+    // The hook's own children are terminated too
+    let server = Pid::from_raw(
+        fs::read_to_string(&server_pid)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap(),
+    );
+    let started = Instant::now();
+    while signal::kill(server, None).is_ok() {
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "hook child survived the interrupt"
+        );
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn test_run_execution_error_removes_changed_files_list() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(temp_dir.path().join("a.rs"), "").unwrap();
+    fs::write(temp_dir.path().join("list.txt"), "a.rs\n").unwrap();
+    // The command can't be spawned, so execution fails after the changed-files
+    // list has been written
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = ["./missing-tool", "{CHANGED_FILES_FILE}"]
+execution_type = "other"
+"#,
+    )
+    .unwrap();
+    let tmp = TempDir::new().unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .env("TMPDIR", tmp.path())
+        .args(["run", "pre-commit", "--files-from", "list.txt"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to execute hook command"),
+        "{stderr}"
+    );
+    let leftovers: Vec<_> = fs::read_dir(tmp.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert!(
+        leftovers.is_empty(),
+        "temp files left behind: {leftovers:?}"
+    );
+}
+
+/// Two hooks that each wait for the other to start, so they only pass when
+/// run concurrently
+fn rendezvous_repo(execution: &str, modifies_repository: bool) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    let hook = |name: &str, other: &str| {
+        format!(
+            r#"
+[hooks.{name}]
+command = "touch {name}.started; for i in $(seq 1 30); do [ -f {other}.started ] && exit 0; sleep 0.1; done; exit 1"
+modifies_repository = {modifies_repository}
+run_always = true
+"#
+        )
+    };
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        format!(
+            "{}{}\n[groups.pre-commit]\nincludes = [\"a\", \"b\"]\nexecution = \"{execution}\"\n",
+            hook("a", "b"),
+            hook("b", "a"),
+        ),
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn run_with_strategy(dir: &std::path::Path, flags: &[&str]) -> std::process::Output {
+    Command::new(bin_path())
+        .current_dir(dir)
+        .args(["run", "pre-commit", "--all-files"])
+        .args(flags)
+        .output()
+        .expect("Failed to execute")
+}
+
+#[test]
+fn test_run_strategy_override() {
+    // Without an override the group's own strategy applies
+    let temp_dir = rendezvous_repo("sequential", false);
+    assert!(!run_with_strategy(temp_dir.path(), &[]).status.success());
+
+    let temp_dir = rendezvous_repo("sequential", false);
+    let output = run_with_strategy(temp_dir.path(), &["--parallel"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let temp_dir = rendezvous_repo("parallel", false);
+    assert!(run_with_strategy(temp_dir.path(), &[]).status.success());
+
+    let temp_dir = rendezvous_repo("parallel", false);
+    assert!(
+        !run_with_strategy(temp_dir.path(), &["--sequential"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_run_force_parallel_ignores_modifies_repository() {
+    let temp_dir = rendezvous_repo("sequential", true);
+    assert!(
+        !run_with_strategy(temp_dir.path(), &["--parallel"])
+            .status
+            .success()
+    );
+
+    let temp_dir = rendezvous_repo("sequential", true);
+    let output = run_with_strategy(temp_dir.path(), &["--force-parallel"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
+
+#[test]
+fn test_run_strategy_flags_conflict() {
+    let temp_dir = rendezvous_repo("sequential", false);
+    let output = run_with_strategy(temp_dir.path(), &["--parallel", "--sequential"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+/// Repo whose `flaky` hook fails on every second invocation, next to a
+/// `stable` hook that always passes
+fn flaky_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.flaky]
+command = 'n=$(cat count 2>/dev/null || echo 0); n=$((n + 1)); echo $n > count; [ $((n % 2)) -eq 1 ]'
+modifies_repository = false
+run_always = true
+
+[hooks.stable]
+command = "true"
+modifies_repository = false
+run_always = true
+
+[groups.pre-commit]
+includes = ["flaky", "stable"]
+execution = "sequential"
+"#,
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_run_repeat_reports_flakiness() {
+    let temp_dir = flaky_repo();
+    let output = run_with_strategy(temp_dir.path(), &["--repeat", "4"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("Run 1/4: PASS"), "{stdout}");
+    assert!(stdout.contains("Run 2/4: FAIL"), "{stdout}");
+    assert!(stdout.contains("failed: flaky"), "{stdout}");
+    assert!(stdout.contains("Run 4/4: FAIL"), "{stdout}");
+    assert!(stdout.contains("flaky: 2 passed, 2 failed"), "{stdout}");
+    assert!(stdout.contains("stable: 4 passed, 0 failed"), "{stdout}");
+    assert!(
+        stdout.contains("Runs: 4 of 4, 2 failed (50.0% flaky)"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_run_repeat_until_fail_stops_early() {
+    let temp_dir = flaky_repo();
+    let output = run_with_strategy(temp_dir.path(), &["--repeat", "10", "--repeat-until-fail"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("Run 2/10: FAIL"), "{stdout}");
+    assert!(!stdout.contains("Run 3/10"), "{stdout}");
+    assert!(
+        stdout.contains("Runs: 2 of 10, 1 failed (50.0% flaky)"),
+        "{stdout}"
+    );
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("count"))
+            .unwrap()
+            .trim(),
+        "2"
+    );
+}
+
+#[test]
+fn test_lint_repeat_passes_when_every_run_passes() {
+    let temp_dir = flaky_repo();
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["lint", "stable", "--repeat", "3"])
+        .output()
+        .expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("stable: 3 passed, 0 failed"), "{stdout}");
+    assert!(
+        stdout.contains("Runs: 3 of 3, 0 failed (0.0% flaky)"),
+        "{stdout}"
+    );
+}
+
+/// Run `post-checkout` with the arguments git passes and return which
+/// marker files the hooks created
+fn post_checkout_markers(git_args: &[&str]) -> (bool, bool) {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.install-deps]
+command = "touch install-deps.ran"
+modifies_repository = false
+run_always = true
+only_on_branch_change = true
+
+[hooks.notify]
+command = "touch notify.ran"
+modifies_repository = false
+run_always = true
+
+[groups.post-checkout]
+includes = ["install-deps", "notify"]
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "post-checkout", "--all-files"])
+        .args(git_args)
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    (
+        temp_dir.path().join("install-deps.ran").exists(),
+        temp_dir.path().join("notify.ran").exists(),
+    )
+}
+
+#[test]
+fn test_run_post_checkout_only_on_branch_change() {
+    // git checkout <branch>: branch flag 1
+    assert_eq!(
+        post_checkout_markers(&["1a2b3c4d", "5e6f7a8b", "1"]),
+        (true, true)
+    );
+    // git checkout -- <file>: branch flag 0
+    assert_eq!(
+        post_checkout_markers(&["5e6f7a8b", "5e6f7a8b", "0"]),
+        (false, true)
+    );
+    // Run by hand without git's arguments
+    assert_eq!(post_checkout_markers(&[]), (true, true));
+}
+
+#[test]
+fn test_run_container_reports_missing_runtime() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "touch ran.txt"
+run_always = true
+container = { image = "alpine:3", runtime = "peter-hook-no-such-runtime" }
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("Container runtime 'peter-hook-no-such-runtime' for image 'alpine:3' was not found on PATH"),
+        "{combined}"
+    );
+    assert!(!temp_dir.path().join("ran.txt").exists());
+}
+
+/// A container runtime that can start `alpine:3` without pulling it
+fn local_container_runtime() -> Option<&'static str> {
+    ["docker", "podman"].into_iter().find(|runtime| {
+        Command::new(runtime)
+            .args(["image", "inspect", "alpine:3"])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+#[test]
+fn test_run_container_mounts_repository_and_changed_files() {
+    let Some(runtime) = local_container_runtime() else {
+        eprintln!("skipping: no docker or podman with the alpine:3 image available");
+        return;
+    };
+
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::create_dir(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src/a.rs"), "").unwrap();
+    fs::write(temp_dir.path().join("list.txt"), "src/a.rs\n").unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        format!(
+            r#"
+[hooks.pre-commit]
+command = "cat {{CHANGED_FILES_FILE}} > seen.txt; cat /etc/alpine-release > release.txt"
+execution_type = "other"
+container = {{ image = "alpine:3", runtime = "{runtime}" }}
+"#
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--files-from", "list.txt"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let seen = fs::read_to_string(temp_dir.path().join("seen.txt")).unwrap();
+    assert_eq!(seen.trim(), "src/a.rs");
+    assert!(temp_dir.path().join("release.txt").exists());
+}
+
+/// Run `pre-commit` in a monorepo with `api` (Rust) and `web` (TypeScript)
+/// subprojects where only a Rust file and the web README changed, and return
+/// the marker files the hooks created
+fn affected_markers(extra_args: &[&str]) -> Vec<String> {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    for (project, pattern) in [("api", "**/*.rs"), ("web", "**/*.ts")] {
+        let dir = temp_dir.path().join(project);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("hooks.toml"),
+            format!(
+                r#"
+[hooks.check]
+command = "touch check.ran"
+execution_type = "in-place"
+files = ["{pattern}"]
+
+[hooks.notify]
+command = "touch notify.ran"
+run_always = true
+
+[groups.pre-commit]
+includes = ["check", "notify"]
+"#
+            ),
+        )
+        .unwrap();
+    }
+    fs::write(temp_dir.path().join("api/src/lib.rs"), "").unwrap();
+    fs::write(temp_dir.path().join("web/README.md"), "").unwrap();
+    fs::write(
+        temp_dir.path().join("list.txt"),
+        "api/src/lib.rs\nweb/README.md\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--files-from", "list.txt"])
+        .args(extra_args)
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let mut markers = Vec::new();
+    for project in ["api", "web"] {
+        for marker in ["check.ran", "notify.ran"] {
+            if temp_dir.path().join(project).join(marker).exists() {
+                markers.push(format!("{project}/{marker}"));
+            }
+        }
+    }
+    markers
+}
+
+#[test]
+fn test_run_affected_only_skips_untouched_subprojects() {
+    assert_eq!(
+        affected_markers(&[]),
+        ["api/check.ran", "api/notify.ran", "web/notify.ran"]
+    );
+    assert_eq!(
+        affected_markers(&["--affected-only"]),
+        ["api/check.ran", "api/notify.ran"]
+    );
+}
+
+#[test]
+fn test_run_affected_only_without_changes_runs_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "touch ran.txt"
+run_always = true
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--affected-only"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("No subprojects affected by the changed files for event: pre-commit")
+    );
+    assert!(!temp_dir.path().join("ran.txt").exists());
+}
+
+#[test]
+fn test_run_max_output_truncates_summary_but_not_logs() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "printf 'line%s\\n' 1 2 3 4 5"
+run_always = true
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args([
+            "run",
+            "pre-commit",
+            "--max-output",
+            "2",
+            "--output-dir",
+            "logs",
+        ])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("  stdout: line1\nline2\n  ... (3 lines truncated)\n"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("line3"), "{stdout}");
+    let log = fs::read_to_string(temp_dir.path().join("logs/pre-commit.stdout.log")).unwrap();
+    assert_eq!(log, "line1\nline2\nline3\nline4\nline5\n");
+
+    // 0 turns truncation off
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--max-output", "0"])
+        .output()
+        .expect("Failed to execute");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("line5"));
+}
+
+#[test]
+fn test_run_skips_hooks_disabled_in_local_override() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.slow]
+command = "touch slow-ran.txt"
+run_always = true
+
+[hooks.check]
+command = "printf '%s' \"$MODE\" > check-ran.txt"
+run_always = true
+depends_on = ["slow"]
+env = { MODE = "shared" }
+
+[groups.pre-commit]
+includes = ["slow", "check"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.local.toml"),
+        r#"
+[hooks.slow]
+enabled = false
+
+[hooks.check.env]
+MODE = "local"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!temp_dir.path().join("slow-ran.txt").exists());
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("slow: skipped (disabled)"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    // The disabled dependency doesn't block the hook depending on it
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("check-ran.txt")).unwrap(),
+        "local"
+    );
+}
+
+#[test]
+fn test_run_pre_push_gates_hooks_on_pushed_refs() {
+    let temp_dir = TempDir::new().unwrap();
+    let _repo = Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.integration]
+command = "echo integration >> ran.log"
+execution_type = "other"
+push_refs = ["refs/heads/main", "refs/heads/release/*"]
+
+[hooks.lint]
+command = "echo lint >> ran.log"
+execution_type = "other"
+
+[groups.pre-push]
+includes = ["integration", "lint"]
+"#,
+    )
+    .unwrap();
+
+    let push = |remote_ref: &str| {
+        let mut child = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args([
+                "run",
+                "pre-push",
+                "--all-files",
+                "origin",
+                "git@example.com:repo.git",
+            ])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .expect("Failed to execute");
+        let refs = format!(
+            "refs/heads/work 1111111111111111111111111111111111111111 {remote_ref} \
+             0000000000000000000000000000000000000000\n"
+        );
+        std::io::Write::write_all(&mut child.stdin.take().unwrap(), refs.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let ran = fs::read_to_string(temp_dir.path().join("ran.log")).unwrap();
+        fs::remove_file(temp_dir.path().join("ran.log")).unwrap();
+        (ran, String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+
+    let (ran, stdout) = push("refs/heads/feature/login");
+    assert_eq!(ran, "lint\n");
+    assert!(
+        stdout.contains("integration: skipped (no pushed ref matches push_refs)"),
+        "{stdout}"
+    );
+
+    let (ran, _) = push("refs/heads/release/2.0");
+    assert_eq!(ran, "integration\nlint\n");
+
+    // Run by hand (no refs from git): the gate doesn't apply
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-push", "--all-files"])
+        .output()
+        .expect("Failed to execute");
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("ran.log")).unwrap(),
+        "integration\nlint\n"
+    );
+}
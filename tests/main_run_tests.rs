@@ -365,6 +365,66 @@ modifies_repository = false
     assert!(output.status.code().is_some());
 }
 
+#[test]
+fn test_run_root_resolves_from_repository_root() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    let subdir = temp_dir.path().join("sub");
+    fs::create_dir(&subdir).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.root-check]
+command = "touch root-ran"
+modifies_repository = false
+execution_type = "other"
+
+[groups.pre-commit]
+includes = ["root-check"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        subdir.join("hooks.toml"),
+        r#"
+[hooks.sub-check]
+command = "touch sub-ran"
+modifies_repository = false
+execution_type = "other"
+
+[groups.pre-commit]
+includes = ["sub-check"]
+"#,
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(&subdir)
+            .args(["run", "pre-commit", "--all-files"])
+            .args(args)
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+
+    // Without --root the nearest config wins
+    run(&[]);
+    assert!(subdir.join("sub-ran").exists());
+    assert!(!temp_dir.path().join("root-ran").exists());
+    fs::remove_file(subdir.join("sub-ran")).unwrap();
+
+    run(&["--root"]);
+    assert!(temp_dir.path().join("root-ran").exists());
+    assert!(!subdir.join("sub-ran").exists());
+}
+
 #[test]
 fn test_run_all_files_and_dry_run_together() {
     let temp_dir = TempDir::new().unwrap();
@@ -687,3 +747,865 @@ modifies_repository = false
     // May return non-zero on hook failure
     assert!(output.status.code().is_some());
 }
+
+#[test]
+fn test_run_dump_env_shows_resolved_env_without_executing() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "touch ran.txt"
+run_always = true
+env = { CONFIG_DIR = "{HOOK_DIR}/conf", API_TOKEN = "s3cr3t" }
+"#,
+    )
+    .unwrap();
+
+    let dump = |extra: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .args([
+                "run",
+                "pre-commit",
+                "--all-files",
+                "--dump-env",
+                "--env",
+                "MODE=ci",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let stdout = dump(&[]);
+    let hook_dir = temp_dir.path().canonicalize().unwrap();
+    assert!(
+        stdout.contains(&format!("CONFIG_DIR={}/conf", hook_dir.display())),
+        "{stdout}"
+    );
+    assert!(stdout.contains("MODE=ci"), "{stdout}");
+    assert!(stdout.contains("API_TOKEN=<redacted>"), "{stdout}");
+    assert!(!stdout.contains("s3cr3t"), "{stdout}");
+    assert!(!temp_dir.path().join("ran.txt").exists());
+
+    let stdout = dump(&["--no-redact"]);
+    assert!(stdout.contains("API_TOKEN=s3cr3t"), "{stdout}");
+}
+
+#[test]
+fn test_run_since_last_run_uses_marker() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+    let run = |extra: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(repo)
+            .args(["run", "pre-commit", "--since-last-run"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo checked"
+files = ["*.rs"]
+"#,
+    )
+    .unwrap();
+    fs::write(repo.join("a.rs"), "fn a() {}\n").unwrap();
+    fs::write(repo.join("b.rs"), "fn b() {}\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+
+    let marker = repo.join(".git/peter-hook/last-run/pre-commit.json");
+    let stdout = run(&[]);
+    assert!(
+        stdout.contains("No earlier successful run of pre-commit"),
+        "first run falls back to the event's changed files: {stdout}"
+    );
+    assert!(marker.exists(), "a successful run leaves a marker");
+
+    fs::write(repo.join("a.rs"), "fn a() { todo!() }\n").unwrap();
+    git(&["commit", "-q", "-am", "change a"]);
+    fs::write(repo.join("c.rs"), "fn c() {}\n").unwrap();
+
+    let stdout = run(&["--list-files"]);
+    assert!(
+        stdout.contains("a.rs"),
+        "committed since the marker: {stdout}"
+    );
+    assert!(stdout.contains("c.rs"), "untracked: {stdout}");
+    assert!(
+        !stdout.contains("b.rs"),
+        "unchanged since the marker: {stdout}"
+    );
+    assert!(!stdout.contains("No earlier successful run"), "{stdout}");
+}
+
+#[test]
+fn test_run_github_report_annotates_failures() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo 'src/lib.rs:7:3: unused import'; exit 2"
+run_always = true
+"#,
+    )
+    .unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = Command::new(bin_path())
+            .current_dir(temp_dir.path())
+            .env("GITHUB_ACTIONS", "true")
+            .env_remove("GITHUB_WORKSPACE")
+            .args(["run", "pre-commit"])
+            .args(extra)
+            .output()
+            .expect("Failed to execute");
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+
+    let stdout = run(&[]);
+    assert!(
+        stdout.contains(
+            "::error title=peter-hook%3A pre-commit::Hook 'pre-commit' failed with exit code 2"
+        ),
+        "enabled by GITHUB_ACTIONS: {stdout}"
+    );
+    assert!(
+        stdout.contains("::error file=src/lib.rs,line=7,col=3,title=pre-commit::unused import"),
+        "{stdout}"
+    );
+
+    let stdout = run(&["--report-format", "text"]);
+    assert!(!stdout.contains("::error"), "{stdout}");
+}
+
+#[test]
+fn test_run_changed_files_output_matches_staged_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "touch hook-ran"
+run_always = true
+"#,
+    )
+    .unwrap();
+    fs::write(repo.join("committed.rs"), "fn c() {}\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "initial"]);
+
+    fs::create_dir(repo.join("docs")).unwrap();
+    fs::write(repo.join("docs/guide.md"), "# Guide\n").unwrap();
+    fs::write(repo.join("b.rs"), "fn b() {}\n").unwrap();
+    git(&["add", "b.rs", "docs/guide.md"]);
+    fs::write(repo.join("committed.rs"), "fn c() { todo!() }\n").unwrap();
+    fs::write(repo.join("untracked.rs"), "fn u() {}\n").unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "pre-commit", "--changed-files-output", "changed.txt"])
+        .arg("--changed-files-only")
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("changed.txt")).unwrap(),
+        "b.rs\ndocs/guide.md\n",
+        "only the staged files, sorted"
+    );
+    assert!(
+        !repo.join("hook-ran").exists(),
+        "--changed-files-only skips the hooks"
+    );
+
+    let output = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "pre-commit", "--changed-files-output", "-"])
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("b.rs\ndocs/guide.md\n"), "{stdout}");
+    assert!(
+        repo.join("hook-ran").exists(),
+        "hooks still run without --changed-files-only"
+    );
+}
+
+#[test]
+fn test_run_workdir_glob_runs_once_per_marker_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+
+    fs::create_dir_all(repo.join("packages/web/src")).unwrap();
+    fs::create_dir_all(repo.join("packages/api")).unwrap();
+    fs::write(repo.join("packages/web/package.json"), "{}").unwrap();
+    fs::write(repo.join("packages/api/package.json"), "{}").unwrap();
+    fs::write(repo.join("packages/web/src/app.ts"), "").unwrap();
+    fs::write(repo.join("packages/api/server.ts"), "").unwrap();
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo \"in $(basename \"$PWD\"): {CHANGED_FILES}\""
+execution_type = "other"
+workdir_glob = "packages/*/package.json"
+files = ["*.ts"]
+"#,
+    )
+    .unwrap();
+    let status = Command::new("git")
+        .args(["add", "."])
+        .current_dir(repo)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "pre-commit"])
+        .output()
+        .expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("pre-commit:packages/web"), "{stdout}");
+    assert!(stdout.contains("in web: src/app.ts"), "{stdout}");
+    assert!(stdout.contains("pre-commit:packages/api"), "{stdout}");
+    assert!(stdout.contains("in api: server.ts"), "{stdout}");
+}
+
+#[test]
+fn test_run_show_files_and_report_list_hook_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.rust]
+command = "true"
+files = ["*.rs"]
+
+[hooks.docs]
+command = "true"
+files = ["*.md"]
+execution_type = "in-place"
+
+[groups.pre-commit]
+includes = ["rust", "docs"]
+"#,
+    )
+    .unwrap();
+    fs::write(repo.join("main.rs"), "fn main() {}\n").unwrap();
+    fs::write(repo.join("README.md"), "# Readme\n").unwrap();
+    fs::write(repo.join("data.json"), "{}\n").unwrap();
+    let status = Command::new("git")
+        .args(["add", "main.rs", "README.md", "data.json"])
+        .current_dir(repo)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let logs = repo.join("logs");
+    let output = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "pre-commit", "--show-files", "--output-dir"])
+        .arg(&logs)
+        .output()
+        .expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("files: main.rs\n"), "{stdout}");
+    assert!(stdout.contains("files: README.md\n"), "{stdout}");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(logs.join("report.json")).unwrap()).unwrap();
+    assert_eq!(
+        report["results"]["rust"]["files"],
+        serde_json::json!(["main.rs"])
+    );
+    assert_eq!(
+        report["results"]["docs"]["files"],
+        serde_json::json!(["README.md"])
+    );
+}
+
+#[test]
+fn test_run_always_if_no_changes_runs_without_matching_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+    let run = || {
+        let output = Command::new(bin_path())
+            .current_dir(repo)
+            .args(["run", "pre-commit"])
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let read = |log: &str| fs::read_to_string(repo.join(log)).unwrap_or_default();
+        let logs = (read("forced.log"), read("gated.log"));
+        let _ = fs::remove_file(repo.join("forced.log"));
+        let _ = fs::remove_file(repo.join("gated.log"));
+        logs
+    };
+
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.forced]
+command = ["sh", "-c", "echo \"files:$#\" >> forced.log", "sh"]
+files = ["tests/**"]
+run_always_if_no_changes = true
+
+[hooks.gated]
+command = ["sh", "-c", "echo \"files:$#\" >> gated.log", "sh"]
+files = ["tests/**"]
+
+[groups.pre-commit]
+includes = ["forced", "gated"]
+"#,
+    )
+    .unwrap();
+    fs::write(repo.join(".gitignore"), "*.log\n").unwrap();
+    git(&["add", "hooks.toml", ".gitignore"]);
+    git(&["commit", "-q", "-m", "initial"]);
+
+    // Nothing staged: only the forced hook runs, without files
+    assert_eq!(run(), ("files:0\n".to_string(), String::new()));
+
+    // Changes that don't match `files`
+    fs::write(repo.join("lib.rs"), "fn lib() {}\n").unwrap();
+    git(&["add", "lib.rs"]);
+    assert_eq!(run(), ("files:0\n".to_string(), String::new()));
+
+    // Matching changes: both run for them
+    fs::create_dir(repo.join("tests")).unwrap();
+    fs::write(repo.join("tests/it.rs"), "fn it() {}\n").unwrap();
+    git(&["add", "tests/it.rs"]);
+    assert_eq!(run(), ("files:1\n".to_string(), "files:1\n".to_string()));
+}
+
+#[test]
+fn test_run_post_rewrite_exposes_rewritten_commits() {
+    use std::io::Write;
+
+    // The hook writes next to the repository, so its output isn't committed
+    let temp_dir = TempDir::new().unwrap();
+    let repo = &temp_dir.path().join("repo");
+    Git2Repository::init(repo).unwrap();
+    let git = |args: &[&str]| {
+        let output = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.post-rewrite]
+command = "printf '%s' \"$PETER_HOOK_REWRITES\" > ../rewrites.txt; printf '%s' \"$PETER_HOOK_CHANGED_FILES\" > ../files.txt"
+execution_type = "other"
+"#,
+    )
+    .unwrap();
+    git(&["add", "hooks.toml"]);
+    git(&["commit", "-q", "-m", "initial"]);
+    let mut rewritten = Vec::new();
+    for file in ["b.txt", "c.txt"] {
+        fs::write(repo.join(file), file).unwrap();
+        git(&["add", file]);
+        git(&["commit", "-q", "-m", file]);
+        rewritten.push(git(&["rev-parse", "HEAD"]));
+    }
+
+    // As after a rebase that rewrote the last two commits
+    let input = format!(
+        "1111111111111111111111111111111111111111 {}\n\
+         2222222222222222222222222222222222222222 {}\n",
+        rewritten[0], rewritten[1]
+    );
+    let mut child = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "post-rewrite", "rebase"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("Failed to execute");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let outside = temp_dir.path();
+    assert_eq!(
+        fs::read_to_string(outside.join("rewrites.txt")).unwrap(),
+        input.trim_end()
+    );
+    // Every rewritten commit's files, not only HEAD's
+    let mut files: Vec<String> = fs::read_to_string(outside.join("files.txt"))
+        .unwrap()
+        .lines()
+        .map(ToString::to_string)
+        .collect();
+    files.sort();
+    assert_eq!(files, ["b.txt", "c.txt"]);
+}
+
+#[test]
+fn test_run_only_modified_configs_reads_changed_subtrees_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path();
+    Git2Repository::init(repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    // The root only wires up the event; every package has its own hooks
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[groups.pre-commit]
+includes = []
+placeholder = true
+"#,
+    )
+    .unwrap();
+    for package in 0..100 {
+        let dir = repo.join(format!("packages/pkg{package:02}"));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(
+            dir.join("hooks.toml"),
+            r#"
+[hooks.check]
+command = "basename \"$PWD\" >> ../../runs.log"
+modifies_repository = false
+execution_type = "other"
+
+[groups.pre-commit]
+includes = ["check"]
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+    }
+    fs::write(repo.join("README.md"), "readme").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-qm", "init"]);
+
+    // A config nobody touched may even be broken
+    fs::write(repo.join("packages/pkg99/hooks.toml"), "not [valid toml").unwrap();
+    for file in 0..50 {
+        fs::write(repo.join(format!("packages/pkg42/src/mod{file}.rs")), "").unwrap();
+    }
+    fs::write(repo.join("README.md"), "changed").unwrap();
+    git(&["add", "packages/pkg42", "README.md"]);
+
+    let output = Command::new(bin_path())
+        .current_dir(repo)
+        .args(["run", "pre-commit", "--only-modified-configs"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("runs.log")).unwrap(),
+        "pkg42\n"
+    );
+}
+
+#[test]
+fn test_run_worktree_isolation_hides_other_hooks_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    let out = temp_dir.path().join("out");
+    fs::create_dir_all(&repo).unwrap();
+    fs::create_dir_all(&out).unwrap();
+    Git2Repository::init(&repo).unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&repo)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    };
+
+    let config = |isolation: &str| {
+        format!(
+            r#"
+[hooks.writer]
+command = "echo writer >> a.txt"
+modifies_repository = true
+execution_type = "other"
+
+[hooks.reader]
+command = "cp a.txt \"$OUT/seen-$LABEL.txt\""
+modifies_repository = false
+execution_type = "other"
+
+[groups.pre-commit]
+includes = ["writer", "reader"]
+execution = "sequential"
+{isolation}
+"#
+        )
+    };
+    fs::write(repo.join("hooks.toml"), config("")).unwrap();
+    fs::write(repo.join("a.txt"), "base\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-qm", "init"]);
+
+    let run = |label: &str| {
+        fs::write(repo.join("a.txt"), "staged\n").unwrap();
+        git(&["add", "a.txt"]);
+        let output = Command::new(bin_path())
+            .current_dir(&repo)
+            .args(["run", "pre-commit"])
+            .args(["--env", &format!("OUT={}", out.display())])
+            .args(["--env", &format!("LABEL={label}")])
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        fs::read_to_string(out.join(format!("seen-{label}.txt"))).unwrap()
+    };
+
+    // Sharing the working directory, the reader sees the writer's change
+    assert_eq!(run("shared"), "staged\nwriter\n");
+
+    fs::write(repo.join("hooks.toml"), config("isolation = \"worktree\"")).unwrap();
+    assert_eq!(run("isolated"), "staged\n");
+    // The writer's change stayed in its own worktree, which is gone
+    assert_eq!(fs::read_to_string(repo.join("a.txt")).unwrap(), "staged\n");
+    let output = Command::new("git")
+        .args(["worktree", "list"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 1);
+}
+
+#[test]
+fn test_run_typoed_event_suggests_closest_hook() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.precommit]
+command = "echo ran-precommit"
+execution_type = "other"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "precommit", "--all-files"])
+        .output()
+        .expect("Failed to execute");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(2), "{stderr}");
+    assert!(
+        stderr.contains("'precommit' is not a git hook event"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("Did you mean 'pre-commit'?"), "{stderr}");
+    assert!(stderr.contains("--allow-custom-event"), "{stderr}");
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("ran-precommit"));
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "precommit", "--all-files", "--allow-custom-event"])
+        .output()
+        .expect("Failed to execute");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ran-precommit"));
+}
+
+#[test]
+fn test_run_known_but_unconfigured_event_is_not_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.pre-commit]
+command = "echo pre-commit"
+execution_type = "other"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "post-merge", "--all-files"])
+        .output()
+        .expect("Failed to execute");
+    assert_eq!(output.status.code(), Some(0));
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        combined.contains("No hooks found for event: post-merge"),
+        "{combined}"
+    );
+    assert!(!combined.contains("not a git hook event"), "{combined}");
+}
+
+#[test]
+fn test_run_stream_tags_lines_of_concurrent_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.alpha]
+command = "echo a1; sleep 0.5; echo a2; echo a-err >&2"
+execution_type = "other"
+run_always = true
+
+[hooks.beta]
+command = "sleep 0.25; echo b1; sleep 0.5; echo b2"
+execution_type = "other"
+run_always = true
+
+[groups.pre-commit]
+includes = ["alpha", "beta"]
+execution = "parallel"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--all-files", "--stream"])
+        .output()
+        .expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stdout}{stderr}");
+
+    // Lines arrive tagged, in the order the hooks wrote them
+    let position = |line: &str| {
+        stdout
+            .find(&format!("{line}\n"))
+            .unwrap_or_else(|| panic!("missing {line:?} in {stdout}"))
+    };
+    assert!(position("[alpha] a1") < position("[beta] b1"));
+    assert!(position("[beta] b1") < position("[alpha] a2"));
+    assert!(position("[alpha] a2") < position("[beta] b2"));
+    assert!(stderr.contains("[alpha] a-err\n"), "{stderr}");
+
+    // The summary doesn't print the output a second time
+    assert_eq!(stdout.matches("a1").count(), 1, "{stdout}");
+    assert!(!stdout.contains("stdout:"), "{stdout}");
+}
+
+#[test]
+fn test_run_records_timing_history_only_when_enabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(&home).unwrap();
+    Git2Repository::init(&repo).unwrap();
+    fs::write(
+        repo.join("hooks.toml"),
+        r#"
+[hooks.check]
+command = "echo checked"
+execution_type = "other"
+run_always = true
+
+[hooks.never]
+command = "echo never"
+enabled = false
+
+[groups.pre-commit]
+includes = ["check", "never"]
+"#,
+    )
+    .unwrap();
+    let history = repo.join(".git").join("peter-hook").join("timings.json");
+    let run = || {
+        let output = Command::new(bin_path())
+            .current_dir(&repo)
+            .env("HOME", &home)
+            .env_remove("XDG_CONFIG_HOME")
+            .args(["run", "pre-commit", "--all-files"])
+            .output()
+            .expect("Failed to execute");
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+
+    // Off by default
+    run();
+    assert!(!history.exists());
+
+    let config_dir = home.join(".config").join("peter-hook");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[execution]\ntiming_history = 2\n",
+    )
+    .unwrap();
+    for _ in 0..3 {
+        run();
+    }
+    let recorded: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&history).unwrap()).unwrap();
+    // Only the last two runs are kept, and skipped hooks aren't timed
+    assert_eq!(recorded["hooks"]["check"].as_array().unwrap().len(), 2);
+    assert!(recorded["hooks"].get("never").is_none(), "{recorded}");
+}
+
+#[test]
+fn test_run_with_only_advisory_failures_exits_zero() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.spell]
+command = "echo 'teh: did you mean the?'; exit 1"
+execution_type = "other"
+run_always = true
+allow_failure = true
+
+[hooks.lint]
+command = "echo linted"
+execution_type = "other"
+run_always = true
+
+[groups.pre-commit]
+includes = ["spell", "lint"]
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["run", "pre-commit", "--all-files"])
+        .output()
+        .expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(0), "{stdout}");
+    assert!(
+        stdout.contains("[WARN] spell: exit code 1 (allowed to fail)"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("teh: did you mean the?"), "{stdout}");
+    assert!(stdout.contains("Overall: SUCCESS"), "{stdout}");
+    assert!(
+        stdout.contains("Warnings: 1 hook(s) failed but are allowed to: spell"),
+        "{stdout}"
+    );
+}
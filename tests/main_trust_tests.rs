@@ -0,0 +1,170 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery)]
+//! Integration tests for the trust check before running hooks
+
+use git2::Repository as Git2Repository;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+use tempfile::TempDir;
+
+fn bin_path() -> PathBuf {
+    assert_cmd::cargo::cargo_bin("peter-hook")
+}
+
+/// A repository with a hook config, and a home directory whose global
+/// config requires trust
+fn setup() -> (TempDir, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir(&repo).unwrap();
+    Git2Repository::init(&repo).unwrap();
+    write_config(&repo, "echo ran-lint");
+
+    let config_dir = temp_dir.path().join("home/.config/peter-hook");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[security]\nrequire_trust = true\n",
+    )
+    .unwrap();
+    (temp_dir, repo)
+}
+
+fn write_config(repo: &Path, command: &str) {
+    fs::write(
+        repo.join("hooks.toml"),
+        format!(
+            "[hooks.pre-commit]\ncommand = \"{command}\"\nexecution_type = \"other\"\nrun_always = true\n"
+        ),
+    )
+    .unwrap();
+}
+
+fn peter_hook(temp_dir: &TempDir, repo: &Path, args: &[&str]) -> Output {
+    Command::new(bin_path())
+        .current_dir(repo)
+        .env("HOME", temp_dir.path().join("home"))
+        .env_remove("XDG_CONFIG_HOME")
+        .args(args)
+        .output()
+        .expect("Failed to execute")
+}
+
+fn ran(output: &Output) -> bool {
+    String::from_utf8_lossy(&output.stdout).contains("ran-lint")
+}
+
+#[test]
+fn test_first_run_refuses_untrusted_config() {
+    let (temp_dir, repo) = setup();
+
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(!ran(&output));
+    assert!(stderr.contains("hooks.toml (new)"), "{stderr}");
+    assert!(stderr.contains("peter-hook trust"), "{stderr}");
+
+    // CI can skip the check
+    let output = peter_hook(
+        &temp_dir,
+        &repo,
+        &["run", "pre-commit", "--all-files", "--no-trust-check"],
+    );
+    assert!(output.status.success());
+    assert!(ran(&output));
+
+    // Dry runs don't execute anything, so they aren't checked
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--dry-run"]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_trusted_config_runs_until_it_changes() {
+    let (temp_dir, repo) = setup();
+
+    let output = peter_hook(&temp_dir, &repo, &["trust"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Trusted hook configs"), "{stdout}");
+    assert!(stdout.contains("hooks.toml"), "{stdout}");
+
+    // Unchanged: runs without asking
+    for _ in 0..2 {
+        let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(ran(&output));
+    }
+
+    // Changed: refused again until trusted again
+    write_config(&repo, "echo ran-lint changed");
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(!ran(&output));
+    assert!(
+        stderr.contains("hooks.toml (changed since it was trusted)"),
+        "{stderr}"
+    );
+
+    assert!(peter_hook(&temp_dir, &repo, &["trust"]).status.success());
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+    assert!(output.status.success());
+    assert!(ran(&output));
+
+    // Untrusting brings back the check
+    let output = peter_hook(&temp_dir, &repo, &["untrust"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Removed trust"));
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+    assert!(!output.status.success());
+    let output = peter_hook(&temp_dir, &repo, &["untrust"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No trusted hook configs"));
+}
+
+#[test]
+fn test_trust_not_required_by_default() {
+    let (temp_dir, repo) = setup();
+    fs::remove_file(temp_dir.path().join("home/.config/peter-hook/config.toml")).unwrap();
+
+    let output = peter_hook(&temp_dir, &repo, &["run", "pre-commit", "--all-files"]);
+    assert!(output.status.success());
+    assert!(ran(&output));
+}
+
+#[test]
+fn test_lint_refuses_untrusted_config() {
+    let (temp_dir, repo) = setup();
+
+    let output = peter_hook(&temp_dir, &repo, &["lint", "pre-commit"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(!ran(&output));
+    assert!(stderr.contains("hooks.toml (new)"), "{stderr}");
+
+    let output = peter_hook(
+        &temp_dir,
+        &repo,
+        &["lint", "pre-commit", "--no-trust-check"],
+    );
+    assert!(output.status.success());
+    assert!(ran(&output));
+
+    let output = peter_hook(&temp_dir, &repo, &["lint", "pre-commit", "--dry-run"]);
+    assert!(output.status.success());
+
+    assert!(peter_hook(&temp_dir, &repo, &["trust"]).status.success());
+    let output = peter_hook(&temp_dir, &repo, &["lint", "pre-commit"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(ran(&output));
+}
@@ -259,3 +259,263 @@ modifies_repository = false
     // Output should contain JSON or be valid
     assert!(!combined.is_empty());
 }
+
+#[test]
+fn test_validate_explain_prints_merged_config() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    let lib = temp_dir.path().join("lib");
+    fs::create_dir(&lib).unwrap();
+    fs::write(
+        lib.join("base.toml"),
+        r#"
+[hooks.fmt]
+command = "cargo fmt"
+modifies_repository = true
+
+[hooks.lint]
+command = ["cargo", "clippy"]
+env = { RUSTFLAGS = "-D warnings", CARGO_TERM_COLOR = "never" }
+
+[groups.pre-commit]
+includes = ["fmt", "lint"]
+parallel = true
+"#,
+    )
+    .unwrap();
+    fs::write(
+        lib.join("extra.toml"),
+        r#"
+[hooks.test]
+command = "cargo test"
+files = ["**/*.rs"]
+tags = ["slow"]
+"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+imports = ["lib/base.toml", "lib/extra.toml"]
+
+[hooks.lint]
+command = "cargo clippy --all-targets"
+description = "local override"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["validate", "--explain"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let explained: String = stdout
+        .lines()
+        .skip_while(|line| line.starts_with('#') || line.is_empty())
+        .map(|line| format!("{line}\n"))
+        .collect();
+
+    // Imported hooks keep their order, the local `lint` replaces the imported
+    // one entirely, and the deprecated `parallel` flag becomes `execution`
+    assert_eq!(
+        explained,
+        r#"[hooks.fmt]
+command = "cargo fmt"
+modifies_repository = true
+run_always = false
+run_always_if_no_changes = false
+include_deleted = false
+only_on_branch_change = false
+execution_type = "per-file"
+run_at_root = false
+priority = 0
+combined_output = false
+allow_failure = false
+
+[hooks.lint]
+command = "cargo clippy --all-targets"
+description = "local override"
+modifies_repository = false
+run_always = false
+run_always_if_no_changes = false
+include_deleted = false
+only_on_branch_change = false
+execution_type = "per-file"
+run_at_root = false
+priority = 0
+combined_output = false
+allow_failure = false
+
+[hooks.test]
+command = "cargo test"
+modifies_repository = false
+files = ["**/*.rs"]
+run_always = false
+run_always_if_no_changes = false
+include_deleted = false
+only_on_branch_change = false
+execution_type = "per-file"
+run_at_root = false
+tags = ["slow"]
+priority = 0
+combined_output = false
+allow_failure = false
+
+[groups.pre-commit]
+includes = [
+    "fmt",
+    "lint",
+]
+execution = "parallel"
+"#
+    );
+
+    // The explained config is a standalone hooks.toml
+    let reparsed = peter_hook::HookConfig::parse(&explained).unwrap();
+    assert_eq!(reparsed.to_toml().unwrap(), explained);
+}
+
+#[test]
+fn test_validate_explain_conflicts_with_trace_imports() {
+    let output = Command::new(bin_path())
+        .args(["validate", "--explain", "--trace-imports"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn test_validate_trace_imports_reports_local_override() {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        "[hooks.lint]\ncommand = \"echo lint\"\n\n[hooks.test]\ncommand = \"echo test\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("hooks.local.toml"),
+        "[hooks.lint]\nenabled = false\n\n[hooks.test]\nenv = { RUST_LOG = \"debug\" }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(bin_path())
+        .current_dir(temp_dir.path())
+        .args(["validate", "--trace-imports"])
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Local override (not shared): ")
+            && stdout
+                .contains("hooks.local.toml\n  hook lint: disabled\n  hook test: env RUST_LOG\n"),
+        "{stdout}"
+    );
+}
+
+/// Run git in `dir`, returning its trimmed stdout
+fn git(dir: &std::path::Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "git {args:?} failed");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn test_validate_git_import_requires_allowed_host_and_works_offline() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    fs::create_dir(&home).unwrap();
+
+    // A bare repository whose v1 tag holds a shared hook config
+    let work = temp_dir.path().join("shared");
+    fs::create_dir(&work).unwrap();
+    git(&work, &["init", "--quiet"]);
+    git(&work, &["config", "user.name", "Test"]);
+    git(&work, &["config", "user.email", "test@test.com"]);
+    fs::write(
+        work.join("hooks.toml"),
+        "[hooks.shared-lint]\ncommand = \"echo shared\"\nmodifies_repository = false\n",
+    )
+    .unwrap();
+    git(&work, &["add", "hooks.toml"]);
+    git(&work, &["commit", "--quiet", "-m", "shared hooks"]);
+    git(&work, &["tag", "v1"]);
+    let bare = temp_dir.path().join("shared.git");
+    git(
+        &work,
+        &["clone", "--quiet", "--bare", ".", bare.to_str().unwrap()],
+    );
+
+    let repo = temp_dir.path().join("repo");
+    Git2Repository::init(&repo).unwrap();
+    fs::write(
+        repo.join("hooks.toml"),
+        format!(
+            "imports = [{{ git = \"file://{}\", ref = \"v1\" }}]\n\n[hooks.main]\ncommand = \
+             \"echo main\"\nmodifies_repository = false\n",
+            bare.display()
+        ),
+    )
+    .unwrap();
+
+    let validate = || {
+        Command::new(bin_path())
+            .current_dir(&repo)
+            .env("HOME", &home)
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_CACHE_HOME")
+            .arg("validate")
+            .output()
+            .unwrap()
+    };
+
+    // Git imports are refused until their host is allowed
+    let output = validate();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Git import host not allowed: file"),
+        "{stderr}"
+    );
+
+    let config_dir = home.join(".config").join("peter-hook");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("config.toml"),
+        "[security]\nallowed_import_hosts = [\"file\"]\n",
+    )
+    .unwrap();
+    let output = validate();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{stdout}{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("- shared-lint"), "{stdout}");
+    assert!(
+        home.join(".cache")
+            .join("peter-hook")
+            .join("imports")
+            .is_dir()
+    );
+
+    // The cached checkout is used once the repository is out of reach
+    fs::remove_dir_all(&bare).unwrap();
+    let output = validate();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("- shared-lint"));
+}
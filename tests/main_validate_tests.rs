@@ -316,3 +316,106 @@ modifies_repository = false
     // Should show hook information
     assert!(!stdout.is_empty());
 }
+
+/// Run `validate` with a `--match` for each path and return the marks in
+/// each hook's row of the grid
+fn validate_matches(paths: &[&str]) -> Vec<(String, Vec<String>)> {
+    let temp_dir = TempDir::new().unwrap();
+    Git2Repository::init(temp_dir.path()).unwrap();
+
+    fs::write(
+        temp_dir.path().join("hooks.toml"),
+        r#"
+[hooks.rust]
+command = "cargo fmt --check"
+modifies_repository = false
+files = ["**/*.rs"]
+
+[hooks.manifest]
+command = "cargo verify-project"
+modifies_repository = false
+files = ["Cargo.toml"]
+
+[hooks.lib]
+command = "cargo doc"
+modifies_repository = false
+files = ["src/lib.rs"]
+
+[hooks.everything]
+command = "true"
+modifies_repository = false
+"#,
+    )
+    .unwrap();
+
+    let mut command = Command::new(bin_path());
+    command.current_dir(temp_dir.path()).arg("validate");
+    for path in paths {
+        command.args(["--match", path]);
+    }
+    let output = command.output().expect("Failed to execute");
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("File pattern matches:"), "{stdout}");
+
+    stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("File pattern matches:"))
+        .skip(2)
+        .map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next().unwrap().to_string();
+            let marks = fields.take(paths.len()).map(str::to_string).collect();
+            (name, marks)
+        })
+        .collect()
+}
+
+#[test]
+fn test_validate_match_globstar_pattern() {
+    let rows = validate_matches(&["src/deep/nested/mod.rs", "main.rs", "README.md"]);
+    assert_eq!(
+        rows[0],
+        ("rust".to_string(), vec!["✓".into(), "✓".into(), "✗".into()])
+    );
+    assert_eq!(
+        rows[3],
+        (
+            "everything".to_string(),
+            vec!["✓".into(), "✓".into(), "✓".into()]
+        )
+    );
+}
+
+#[test]
+fn test_validate_match_exact_pattern() {
+    let rows = validate_matches(&[
+        "Cargo.toml",
+        "./src/lib.rs",
+        "other/lib.rs",
+        "src/lib.rs.bak",
+    ]);
+    assert_eq!(
+        rows[1],
+        (
+            "manifest".to_string(),
+            vec!["✓".into(), "✗".into(), "✗".into(), "✗".into()]
+        )
+    );
+    assert_eq!(
+        rows[2],
+        (
+            "lib".to_string(),
+            vec!["✗".into(), "✓".into(), "✗".into(), "✗".into()]
+        )
+    );
+}
+
+#[test]
+fn test_validate_match_conflicts_with_json() {
+    let output = Command::new(bin_path())
+        .args(["validate", "--trace-imports", "--json", "--match", "a.rs"])
+        .output()
+        .expect("Failed to execute");
+    assert_eq!(output.status.code(), Some(2));
+}
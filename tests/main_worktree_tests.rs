@@ -121,3 +121,94 @@ fn test_list_worktrees_output_format() {
     );
     assert!(!combined.trim().is_empty());
 }
+
+/// Create a repository with an initial commit and a linked `feature` worktree
+fn create_repo_with_worktree(temp_dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+    let main_dir = temp_dir.path().join("main");
+    let worktree_dir = temp_dir.path().join("feature");
+    std::fs::create_dir_all(&main_dir).unwrap();
+
+    let repo = Git2Repository::init(&main_dir).unwrap();
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "Initial", &tree, &[])
+        .unwrap();
+    repo.worktree("feature", &worktree_dir, None).unwrap();
+
+    (main_dir, worktree_dir)
+}
+
+fn write_hook(path: &std::path::Path, content: &str, executable: bool) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = if executable { 0o755 } else { 0o644 };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+}
+
+fn list_worktrees_stdout(dir: &std::path::Path) -> String {
+    let output = Command::new(bin_path())
+        .current_dir(dir)
+        .arg("list-worktrees")
+        .output()
+        .expect("Failed to execute");
+
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+#[cfg(unix)]
+fn test_list_worktrees_annotates_shared_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, _worktree_dir) = create_repo_with_worktree(&temp_dir);
+
+    let hooks_dir = main_dir.join(".git/hooks");
+    write_hook(
+        &hooks_dir.join("pre-commit"),
+        "#!/bin/sh\n# Generated by peter-hook\nexit 0\n",
+        true,
+    );
+    write_hook(&hooks_dir.join("post-merge"), "#!/bin/sh\nexit 0\n", false);
+
+    let stdout = list_worktrees_stdout(&main_dir);
+
+    assert!(stdout.contains("Hooks (shared"), "{stdout}");
+    assert!(
+        stdout.contains("✅ pre-commit 🔧 managed (executable: yes)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("❌ post-merge 📄 custom (executable: no)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Hooks: shared with main worktree"),
+        "{stdout}"
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn test_list_worktrees_annotates_worktree_specific_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, worktree_dir) = create_repo_with_worktree(&temp_dir);
+
+    write_hook(
+        &main_dir.join(".git/worktrees/feature/hooks/pre-push"),
+        "#!/bin/sh\n# Generated by peter-hook\nexit 0\n",
+        true,
+    );
+
+    let stdout = list_worktrees_stdout(&worktree_dir);
+
+    assert!(stdout.contains("Hooks (worktree-specific"), "{stdout}");
+    assert!(
+        stdout.contains("✅ pre-push 🔧 managed (executable: yes)"),
+        "{stdout}"
+    );
+}
@@ -130,3 +130,190 @@ fn test_run_update_no_install_dir_specified() {
     // Should return 2 (already up to date)
     assert_eq!(exit_code, 2);
 }
+
+#[test]
+fn test_fetch_latest_release_parses_tag_and_changelog() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/releases/latest")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"tag_name": "peter-hook-v3.0.5", "html_url": "https://example.com/releases/3.0.5"}"#,
+        )
+        .create();
+
+    let release = update::fetch_latest_release(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Stable,
+    )
+    .unwrap();
+
+    assert_eq!(release.version, "3.0.5");
+    assert_eq!(release.changelog_url, "https://example.com/releases/3.0.5");
+}
+
+#[test]
+fn test_fetch_latest_release_missing_tag_name() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/releases/latest")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"name": "no tag"}"#)
+        .create();
+
+    let result = update::fetch_latest_release(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Stable,
+    );
+
+    assert_eq!(result, Err("No tag_name in response".to_string()));
+}
+
+#[test]
+fn test_run_check_reports_update_available() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/releases/latest")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"tag_name": "peter-hook-v999.0.0"}"#)
+        .create();
+
+    let exit_code = update::run_check_against(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Stable,
+    );
+
+    assert_eq!(exit_code, update::EXIT_UPDATE_AVAILABLE);
+}
+
+#[test]
+fn test_run_check_up_to_date() {
+    let mut server = Server::new();
+    let body = format!(
+        r#"{{"tag_name": "peter-hook-v{}"}}"#,
+        env!("CARGO_PKG_VERSION")
+    );
+    let _mock = server
+        .mock("GET", "/releases/latest")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .create();
+
+    let exit_code = update::run_check_against(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Stable,
+    );
+
+    assert_eq!(exit_code, 0);
+}
+
+#[test]
+fn test_run_check_older_release_is_up_to_date() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/releases/latest")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"tag_name": "peter-hook-v0.0.1"}"#)
+        .create();
+
+    let exit_code = update::run_check_against(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Stable,
+    );
+
+    assert_eq!(exit_code, 0);
+}
+
+#[test]
+fn test_is_newer_version_uses_semver() {
+    assert_eq!(update::is_newer_version("3.10.0", "3.9.0"), Ok(true));
+    assert_eq!(update::is_newer_version("3.1.1", "3.1.1"), Ok(false));
+    assert_eq!(update::is_newer_version("3.1.1-rc.1", "3.1.1"), Ok(false));
+    assert!(update::is_newer_version("not-a-version", "3.1.1").is_err());
+}
+
+/// Release list mixing stable, pre-release and draft entries
+const MIXED_RELEASES: &str = r#"[
+    {"tag_name": "peter-hook-v4.0.0", "prerelease": false, "draft": true},
+    {"tag_name": "peter-hook-v3.2.0-rc.1", "prerelease": true, "draft": false},
+    {"tag_name": "peter-hook-v3.1.1", "prerelease": false, "draft": false},
+    {"tag_name": "peter-hook-v3.1.0", "prerelease": false, "draft": false}
+]"#;
+
+#[test]
+fn test_fetch_latest_release_prerelease_channel_uses_mixed_list() {
+    let mut server = Server::new();
+    let _mock = server
+        .mock("GET", "/releases")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(MIXED_RELEASES)
+        .create();
+
+    let release = update::fetch_latest_release(
+        &format!("{}/releases", server.url()),
+        update::ReleaseChannel::Prerelease,
+    )
+    .unwrap();
+
+    // Drafts are never offered, pre-releases are
+    assert_eq!(release.version, "3.2.0-rc.1");
+    assert!(release.prerelease);
+}
+
+#[test]
+fn test_select_latest_release_by_channel() {
+    let release = |version: &str, prerelease: bool| update::ReleaseInfo {
+        version: version.to_string(),
+        changelog_url: String::new(),
+        prerelease,
+    };
+    let releases = vec![
+        release("3.1.0", false),
+        release("3.2.0-rc.1", true),
+        release("3.1.1", false),
+        release("not-semver", false),
+    ];
+
+    let stable = update::select_latest_release(&releases, update::ReleaseChannel::Stable);
+    assert_eq!(stable.map(|r| r.version.as_str()), Some("3.1.1"));
+
+    let pre = update::select_latest_release(&releases, update::ReleaseChannel::Prerelease);
+    assert_eq!(pre.map(|r| r.version.as_str()), Some("3.2.0-rc.1"));
+
+    assert!(update::select_latest_release(&[], update::ReleaseChannel::Stable).is_none());
+}
+
+#[test]
+fn test_release_channel_from_str() {
+    let parse = |s: &str| s.parse::<update::ReleaseChannel>().ok();
+    assert_eq!(parse("stable"), Some(update::ReleaseChannel::Stable));
+    assert_eq!(
+        parse("prerelease"),
+        Some(update::ReleaseChannel::Prerelease)
+    );
+    assert_eq!(
+        parse("pre-release"),
+        Some(update::ReleaseChannel::Prerelease)
+    );
+    assert_eq!(parse("nightly"), None);
+    assert_eq!(
+        update::ReleaseChannel::default(),
+        update::ReleaseChannel::Stable
+    );
+}
+
+#[test]
+fn test_run_update_refuses_downgrade_without_force() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let exit_code = update::run_update(Some("0.0.1"), false, Some(temp_dir.path()));
+
+    assert_eq!(exit_code, 1);
+    assert!(!temp_dir.path().join("peter-hook").exists());
+}
@@ -0,0 +1,116 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery)]
+//! Backup and rollback tests for the self-updater using dummy binaries
+
+use peter_hook::update;
+use std::fs;
+use tempfile::TempDir;
+
+#[cfg(unix)]
+fn mode(path: &std::path::Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn test_replace_binary_saves_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    let new_binary = temp_dir.path().join("new-peter-hook");
+    fs::write(&install_path, b"v1").unwrap();
+    fs::write(&new_binary, b"v2").unwrap();
+
+    let backup = update::replace_binary(&new_binary, &install_path).unwrap();
+
+    assert_eq!(backup, Some(update::backup_path(&install_path)));
+    assert_eq!(fs::read(&install_path).unwrap(), b"v2");
+    assert_eq!(fs::read(update::backup_path(&install_path)).unwrap(), b"v1");
+}
+
+#[test]
+fn test_replace_binary_fresh_install_has_no_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    let new_binary = temp_dir.path().join("new-peter-hook");
+    fs::write(&new_binary, b"v2").unwrap();
+
+    let backup = update::replace_binary(&new_binary, &install_path).unwrap();
+
+    assert_eq!(backup, None);
+    assert_eq!(fs::read(&install_path).unwrap(), b"v2");
+    #[cfg(unix)]
+    assert_eq!(mode(&install_path), 0o755);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_replace_and_rollback_preserve_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    let new_binary = temp_dir.path().join("new-peter-hook");
+    fs::write(&install_path, b"v1").unwrap();
+    fs::set_permissions(&install_path, fs::Permissions::from_mode(0o750)).unwrap();
+    fs::write(&new_binary, b"v2").unwrap();
+    fs::set_permissions(&new_binary, fs::Permissions::from_mode(0o644)).unwrap();
+
+    update::replace_binary(&new_binary, &install_path).unwrap();
+    assert_eq!(mode(&install_path), 0o750);
+    assert_eq!(mode(&update::backup_path(&install_path)), 0o750);
+
+    update::rollback_binary(&install_path).unwrap();
+    assert_eq!(fs::read(&install_path).unwrap(), b"v1");
+    assert_eq!(mode(&install_path), 0o750);
+}
+
+#[test]
+fn test_rollback_swaps_with_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    let new_binary = temp_dir.path().join("new-peter-hook");
+    fs::write(&install_path, b"v1").unwrap();
+    fs::write(&new_binary, b"v2").unwrap();
+    update::replace_binary(&new_binary, &install_path).unwrap();
+
+    update::rollback_binary(&install_path).unwrap();
+    assert_eq!(fs::read(&install_path).unwrap(), b"v1");
+    assert_eq!(fs::read(update::backup_path(&install_path)).unwrap(), b"v2");
+
+    // Rolling back again returns to the updated version
+    update::rollback_binary(&install_path).unwrap();
+    assert_eq!(fs::read(&install_path).unwrap(), b"v2");
+    assert_eq!(fs::read(update::backup_path(&install_path)).unwrap(), b"v1");
+
+    // No staging files are left behind
+    let entries: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(".peter-hook."))
+        .collect();
+    assert!(entries.is_empty(), "leftover staging files: {entries:?}");
+}
+
+#[test]
+fn test_rollback_without_backup_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    fs::write(&install_path, b"v1").unwrap();
+
+    let err = update::rollback_binary(&install_path).unwrap_err();
+
+    assert!(err.contains("No previous version found"), "{err}");
+    assert_eq!(fs::read(&install_path).unwrap(), b"v1");
+}
+
+#[test]
+fn test_run_rollback_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    fs::write(&install_path, b"v2").unwrap();
+
+    assert_eq!(update::run_rollback(Some(temp_dir.path())), 1);
+
+    fs::write(update::backup_path(&install_path), b"v1").unwrap();
+    assert_eq!(update::run_rollback(Some(temp_dir.path())), 0);
+    assert_eq!(fs::read(&install_path).unwrap(), b"v1");
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::all, clippy::pedantic, clippy::nursery)]
+//! Checksum verification tests for self-update against a fixture server
+
+use mockito::{Server, ServerGuard};
+use peter_hook::update;
+use sha2::{Digest, Sha256};
+use std::fs;
+use tempfile::TempDir;
+
+const VERSION: &str = "9.9.9";
+const BINARY_CONTENT: &[u8] = b"#!/bin/sh\necho new peter-hook\n";
+
+/// Build a release archive containing a `peter-hook` binary
+fn build_archive() -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(BINARY_CONTENT.len() as u64);
+    header.set_mode(0o755);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "peter-hook", BINARY_CONTENT)
+        .unwrap();
+
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+fn asset_path(suffix: &str) -> String {
+    format!(
+        "/peter-hook-v{VERSION}/peter-hook-{}{suffix}",
+        update::get_platform_string()
+    )
+}
+
+fn serve_archive(server: &mut ServerGuard, archive: &[u8]) -> mockito::Mock {
+    server
+        .mock("GET", asset_path(".tar.gz").as_str())
+        .with_status(200)
+        .with_body(archive)
+        .create()
+}
+
+#[test]
+fn test_install_release_with_valid_checksum() {
+    let mut server = Server::new();
+    let archive = build_archive();
+    let digest = hex::encode(Sha256::digest(&archive));
+
+    let _archive_mock = serve_archive(&mut server, &archive);
+    let _checksum_mock = server
+        .mock("GET", asset_path(".sha256").as_str())
+        .with_status(200)
+        .with_body(format!("{digest}  peter-hook.tar.gz\n"))
+        .create();
+
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+
+    let result = update::install_release(&server.url(), VERSION, &install_path, false);
+
+    assert!(result.is_ok(), "install failed: {result:?}");
+    assert_eq!(fs::read(&install_path).unwrap(), BINARY_CONTENT);
+}
+
+#[test]
+fn test_install_release_aborts_on_checksum_mismatch() {
+    let mut server = Server::new();
+    let archive = build_archive();
+
+    let _archive_mock = serve_archive(&mut server, &archive);
+    let _checksum_mock = server
+        .mock("GET", asset_path(".sha256").as_str())
+        .with_status(200)
+        .with_body(format!("{}  peter-hook.tar.gz\n", "0".repeat(64)))
+        .create();
+
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+    fs::write(&install_path, b"old binary").unwrap();
+
+    let result = update::install_release(&server.url(), VERSION, &install_path, false);
+
+    let err = result.unwrap_err();
+    assert!(err.contains("Checksum verification failed"), "{err}");
+    // Existing binary must be left untouched
+    assert_eq!(fs::read(&install_path).unwrap(), b"old binary");
+    assert!(!update::backup_path(&install_path).exists());
+}
+
+#[test]
+fn test_install_release_aborts_when_checksum_missing() {
+    let mut server = Server::new();
+    let archive = build_archive();
+
+    let _archive_mock = serve_archive(&mut server, &archive);
+    let _checksum_mock = server
+        .mock("GET", asset_path(".sha256").as_str())
+        .with_status(404)
+        .create();
+
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+
+    let result = update::install_release(&server.url(), VERSION, &install_path, false);
+
+    let err = result.unwrap_err();
+    assert!(err.contains("--skip-verify"), "{err}");
+    assert!(!install_path.exists());
+}
+
+#[test]
+fn test_install_release_skip_verify() {
+    let mut server = Server::new();
+    let archive = build_archive();
+
+    let _archive_mock = serve_archive(&mut server, &archive);
+    let checksum_mock = server
+        .mock("GET", asset_path(".sha256").as_str())
+        .expect(0)
+        .create();
+
+    let temp_dir = TempDir::new().unwrap();
+    let install_path = temp_dir.path().join("peter-hook");
+
+    let result = update::install_release(&server.url(), VERSION, &install_path, true);
+
+    assert!(result.is_ok(), "install failed: {result:?}");
+    assert_eq!(fs::read(&install_path).unwrap(), BINARY_CONTENT);
+    checksum_mock.assert();
+}
+
+#[test]
+fn test_verify_checksum_accepts_bare_and_uppercase_digest() {
+    let data = b"payload";
+    let digest = hex::encode(Sha256::digest(data));
+
+    assert!(update::verify_checksum(data, &digest).is_ok());
+    assert!(update::verify_checksum(data, &digest.to_uppercase()).is_ok());
+}
+
+#[test]
+fn test_verify_checksum_rejects_malformed_file() {
+    assert_eq!(
+        update::verify_checksum(b"payload", "not-a-digest  file.tar.gz"),
+        Err("Invalid checksum format".to_string())
+    );
+    assert!(update::verify_checksum(b"payload", "").is_err());
+}
@@ -2,6 +2,7 @@
 //! Integration tests for worktree functionality
 
 use git2::Repository as Git2Repository;
+use indexmap::IndexMap;
 use peter_hook::{
     config::TemplateResolver,
     git::{GitHookInstaller, GitRepository, WorktreeHookStrategy},
@@ -22,6 +23,8 @@ fn test_worktree_template_variables() {
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
     let resolver =
@@ -32,7 +35,14 @@ fn test_worktree_template_variables() {
 
     assert_eq!(variables.get("IS_WORKTREE").unwrap(), "true");
     assert_eq!(variables.get("WORKTREE_NAME").unwrap(), "feature-branch");
-    assert!(variables.contains_key("COMMON_DIR"));
+    assert_eq!(
+        variables.get("WORKTREE_ROOT").unwrap(),
+        &temp_dir.path().display().to_string()
+    );
+    assert_eq!(
+        variables.get("COMMON_DIR").unwrap(),
+        &temp_dir.path().join(".git").display().to_string()
+    );
     assert!(variables.contains_key("REPO_ROOT"));
 
     // Test template resolution in strings
@@ -56,6 +66,8 @@ fn test_main_repository_template_variables() {
         repo_root: temp_dir.path().to_path_buf(),
         common_dir: temp_dir.path().join(".git"),
         working_dir: temp_dir.path().to_path_buf(),
+        run_env: IndexMap::new(),
+        push_refs: None,
     };
 
     let resolver =
@@ -65,7 +77,11 @@ fn test_main_repository_template_variables() {
     let variables = resolver.get_available_variables();
 
     assert_eq!(variables.get("IS_WORKTREE").unwrap(), "false");
-    assert!(!variables.contains_key("WORKTREE_NAME"));
+    assert_eq!(variables.get("WORKTREE_NAME").unwrap(), "");
+    assert_eq!(
+        variables.get("WORKTREE_ROOT").unwrap(),
+        &temp_dir.path().display().to_string()
+    );
     assert!(variables.contains_key("COMMON_DIR"));
     assert!(variables.contains_key("REPO_ROOT"));
 
@@ -74,6 +90,15 @@ fn test_main_repository_template_variables() {
     let resolved_text = resolver.resolve_string(test_command).unwrap();
 
     assert!(resolved_text.contains("false"));
+
+    // WORKTREE_NAME resolves to an empty string rather than failing
+    let cache_dir = resolver
+        .resolve_string("{COMMON_DIR}/cache/{WORKTREE_NAME}")
+        .unwrap();
+    assert_eq!(
+        cache_dir,
+        format!("{}/cache/", temp_dir.path().join(".git").display())
+    );
 }
 
 #[test]
@@ -167,3 +192,172 @@ fn test_git_hook_installer_with_strategy() {
     drop(installer_per_worktree);
     drop(installer_detect);
 }
+
+/// Create a repository with an initial commit and a linked worktree
+fn create_repo_with_worktree(
+    temp_dir: &std::path::Path,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    let main_dir = temp_dir.join("main");
+    let repo = Git2Repository::init(&main_dir).unwrap();
+    let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+        .unwrap();
+
+    let worktree_dir = temp_dir.join("feature");
+    repo.worktree("feature", &worktree_dir, None).unwrap();
+
+    (main_dir, worktree_dir)
+}
+
+fn git_config_get(dir: &std::path::Path, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("config")
+        .args(args)
+        .output()
+        .unwrap();
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[test]
+fn test_per_worktree_install_sets_worktree_hooks_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, worktree_dir) = create_repo_with_worktree(temp_dir.path());
+    std::fs::write(
+        worktree_dir.join("hooks.toml"),
+        "[hooks.pre-commit]\ncommand = \"echo test\"\n",
+    )
+    .unwrap();
+
+    let repo = GitRepository::find_from_dir(&worktree_dir).unwrap();
+    assert!(repo.is_worktree);
+    let worktree_hooks_dir = repo.get_worktree_hooks_dir();
+
+    let installer = GitHookInstaller::with_repository_binary_and_strategy(
+        repo.clone(),
+        "peter-hook".to_string(),
+        WorktreeHookStrategy::PerWorktree,
+    );
+    let report = installer.install_all().unwrap();
+    assert!(report.is_success(), "errors: {:?}", report.errors);
+    assert!(worktree_hooks_dir.join("pre-commit").exists());
+
+    // core.hooksPath is scoped to the worktree and points at its hooks dir
+    assert_eq!(
+        git_config_get(&worktree_dir, &["--worktree", "--get", "core.hooksPath"]),
+        Some(worktree_hooks_dir.display().to_string())
+    );
+    // The repository still opens with extensions.worktreeConfig enabled
+    let reopened = GitRepository::find_from_dir(&worktree_dir).unwrap();
+    assert_eq!(
+        reopened.get_worktree_hooks_path().unwrap(),
+        Some(worktree_hooks_dir.clone())
+    );
+    assert_eq!(
+        git_config_get(&main_dir, &["--get", "core.hooksPath"]),
+        None
+    );
+
+    // Uninstall removes the worktree hooks and the worktree config
+    let report = installer.uninstall_all();
+    assert!(report.errors.is_empty(), "errors: {:?}", report.errors);
+    assert_eq!(report.removed, vec!["pre-commit".to_string()]);
+    assert!(!worktree_hooks_dir.join("pre-commit").exists());
+    assert_eq!(repo.get_worktree_hooks_path().unwrap(), None);
+    assert_eq!(
+        git_config_get(&worktree_dir, &["--worktree", "--get", "core.hooksPath"]),
+        None
+    );
+}
+
+#[test]
+fn test_worktree_hooks_path_rejected_in_main_repo() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, _) = create_repo_with_worktree(temp_dir.path());
+
+    let repo = GitRepository::find_from_dir(&main_dir).unwrap();
+    assert!(repo.set_worktree_hooks_path(&repo.hooks_dir).is_err());
+    assert_eq!(repo.get_worktree_hooks_path().unwrap(), None);
+    assert!(!repo.unset_worktree_hooks_path().unwrap());
+}
+
+#[test]
+fn test_install_all_worktrees_per_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, worktree_dir) = create_repo_with_worktree(temp_dir.path());
+    let config = "[hooks.pre-commit]\ncommand = \"echo test\"\n";
+    std::fs::write(main_dir.join("hooks.toml"), config).unwrap();
+    std::fs::write(worktree_dir.join("hooks.toml"), config).unwrap();
+
+    let repo = GitRepository::find_from_dir(&main_dir).unwrap();
+    let installer = GitHookInstaller::with_repository_binary_and_strategy(
+        repo.clone(),
+        "peter-hook".to_string(),
+        WorktreeHookStrategy::PerWorktree,
+    );
+
+    let report = installer.install_all_worktrees(false).unwrap();
+    assert!(report.is_success(), "errors: {:?}", report.errors);
+    let names: Vec<_> = report.worktrees.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["main", "feature"]);
+    for (_, worktree_report) in &report.worktrees {
+        assert_eq!(worktree_report.installed, vec!["pre-commit".to_string()]);
+    }
+    assert!(repo.hooks_dir.join("pre-commit").exists());
+    let worktree_repo = GitRepository::find_from_dir(&worktree_dir).unwrap();
+    assert!(
+        worktree_repo
+            .get_worktree_hooks_dir()
+            .join("pre-commit")
+            .exists()
+    );
+
+    // Second run skips worktrees that already have managed hooks
+    let report = installer.install_all_worktrees(false).unwrap();
+    assert!(report.worktrees.is_empty());
+    assert_eq!(report.skipped.len(), 2);
+
+    // --force reinstalls everywhere
+    let report = installer.install_all_worktrees(true).unwrap();
+    assert_eq!(report.worktrees.len(), 2);
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn test_install_all_worktrees_skips_unmanaged_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    let (main_dir, worktree_dir) = create_repo_with_worktree(temp_dir.path());
+    let config = "[hooks.pre-commit]\ncommand = \"echo test\"\n";
+    std::fs::write(main_dir.join("hooks.toml"), config).unwrap();
+    std::fs::write(worktree_dir.join("hooks.toml"), config).unwrap();
+
+    let repo = GitRepository::find_from_dir(&main_dir).unwrap();
+    std::fs::create_dir_all(&repo.hooks_dir).unwrap();
+    std::fs::write(
+        repo.hooks_dir.join("pre-commit"),
+        "#!/bin/sh\necho custom\n",
+    )
+    .unwrap();
+
+    let installer = GitHookInstaller::with_repository_binary_and_strategy(
+        repo.clone(),
+        "peter-hook".to_string(),
+        WorktreeHookStrategy::PerWorktree,
+    );
+    let report = installer.install_all_worktrees(false).unwrap();
+
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].0, "main");
+    assert_eq!(report.worktrees.len(), 1);
+    assert_eq!(report.worktrees[0].0, "feature");
+    assert_eq!(
+        std::fs::read_to_string(repo.hooks_dir.join("pre-commit")).unwrap(),
+        "#!/bin/sh\necho custom\n"
+    );
+}